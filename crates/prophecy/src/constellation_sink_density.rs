@@ -1,4 +1,7 @@
 use futures::StreamExt;
+use futures::channel::oneshot;
+use futures::future::Either;
+use futures::future::select;
 use gloo_net::websocket::Message;
 use gloo_net::websocket::futures::WebSocket;
 use leptos::html::Canvas;
@@ -7,21 +10,282 @@ use leptos::prelude::*;
 use leptos::task::spawn_local;
 use leptos::wasm_bindgen::prelude::*;
 use num_complex::Complex32;
+use std::cell::Cell;
 use std::cell::RefCell;
 use std::rc::Rc;
+use std::time::Duration;
 use web_sys::HtmlCanvasElement;
 use web_sys::WebGl2RenderingContext as GL;
+use web_sys::WebGlBuffer;
+use web_sys::WebGlProgram;
+use web_sys::WebGlUniformLocation;
 
 use crate::ArrayView;
 
 pub const DEFAULT_BINS: usize = 256;
 
+/// Half the length of a reference marker arm in clip space
+const MARKER_SIZE: f32 = 0.03;
+
+/// Size of a labeled record: real and imaginary part as little-endian `f32`, label as `u8`
+const LABELED_RECORD_SIZE: usize = 9;
+
+/// Number of labels mapped onto the hue circle (one per 802.11a data subcarrier)
+const LABEL_HUES: f32 = 48.0;
+
+/// Delay before reconnecting after the WebSocket closed
+const RECONNECT_DELAY: Duration = Duration::from_millis(500);
+
+/// Smallest coordinate range used for binning and markers, to avoid dividing by zero
+const MIN_WIDTH: f32 = 1e-6;
+
+/// Factor per rendered frame by which the auto-scale maximum follows a falling texture maximum
+const AUTO_SCALE_RELEASE: f32 = 0.98;
+
+/// Shown instead of the density map if the browser cannot create a WebGL2 context
+const WEBGL2_UNSUPPORTED: &str = "WebGL2 not supported";
+
 struct RenderState {
     canvas: HtmlCanvasElement,
     gl: GL,
     width: Signal<f32>,
     bins: usize,
     texture: Vec<f32>,
+    /// Per-frame histogram in peak-hold mode (empty otherwise)
+    hits: Vec<f32>,
+    /// Hue of the last label per bin in labeled mode (empty otherwise)
+    labels: Vec<f32>,
+    /// Interleaved density and label texels for the upload in labeled mode
+    texels: Vec<f32>,
+    peak_hold: bool,
+    /// Bin only every `decimate`-th symbol
+    decimate: usize,
+    reset: Option<Signal<u32>>,
+    reset_seen: u32,
+    /// Set by the WebSocket task when data arrives on a new connection that should clear the map
+    reconnected: Rc<Cell<bool>>,
+    density: Pass,
+    overlay: Option<Overlay>,
+    /// Text of the statistics overlay, if shown
+    stats: Option<RwSignal<String>>,
+    /// Ideal symbol locations for the EVM (empty without `reference`)
+    stats_reference: Vec<Complex32>,
+    /// Normalization of the colormap input in auto-scale mode
+    auto_scale: Option<AutoScale>,
+    scale_location: Option<WebGlUniformLocation>,
+}
+
+/// Running maximum of the density texture, by which the colormap input is divided
+///
+/// The maximum jumps up with the texture and follows it down by [`AUTO_SCALE_RELEASE`] per
+/// frame, so that the coloring adapts to the symbol rate without flickering between frames.
+#[derive(Clone, Copy, Debug, Default)]
+struct AutoScale {
+    max: f32,
+}
+
+impl AutoScale {
+    /// Update the maximum with the texture of the current frame and return the scale
+    fn update(&mut self, texture: &[f32]) -> f32 {
+        let frame_max = texture
+            .iter()
+            .copied()
+            .filter(|v| v.is_finite())
+            .fold(0.0f32, f32::max);
+        self.max = frame_max.max(self.max * AUTO_SCALE_RELEASE);
+        if self.max > 0.0 { self.max } else { 1.0 }
+    }
+}
+
+/// Statistics of the symbols binned in a frame
+#[derive(Clone, Copy, Debug, Default)]
+struct Stats {
+    n: usize,
+    /// Sum of the squared distances to the nearest reference point
+    error: f32,
+    /// Sum of the magnitudes
+    magnitude: f32,
+    /// Sum of the phase differences to the nearest reference point
+    phase: f32,
+}
+
+impl Stats {
+    fn add(&mut self, s: Complex32, reference: &[Complex32]) {
+        self.n += 1;
+        self.magnitude += s.norm();
+        let nearest = reference
+            .iter()
+            .min_by(|a, b| (s - **a).norm_sqr().total_cmp(&(s - **b).norm_sqr()));
+        if let Some(r) = nearest {
+            self.error += (s - r).norm_sqr();
+            if r.norm_sqr() > 0.0 {
+                self.phase += (s / r).arg();
+            }
+        }
+    }
+
+    /// RMS EVM as a ratio
+    ///
+    /// The RMS error vector to the nearest reference point, normalized to the RMS magnitude of
+    /// the reference points, i.e., `sqrt(mean |s - r|² / mean |r|²)`.
+    fn evm(&self, reference: &[Complex32]) -> Option<f32> {
+        let power = reference.iter().map(|r| r.norm_sqr()).sum::<f32>() / reference.len() as f32;
+        (self.n > 0 && power > 0.0).then(|| (self.error / self.n as f32 / power).sqrt())
+    }
+
+    fn text(&self, reference: &[Complex32]) -> String {
+        if self.n == 0 {
+            return String::new();
+        }
+        let magnitude = format!("|s| {:.3}", self.magnitude / self.n as f32);
+        match self.evm(reference) {
+            Some(evm) => format!(
+                "EVM {:.1} % ({:.1} dB)  {}  phase {:+.1}°",
+                evm * 100.0,
+                20.0 * evm.log10(),
+                magnitude,
+                (self.phase / self.n as f32).to_degrees()
+            ),
+            None => magnitude,
+        }
+    }
+}
+
+/// Texel data and format of the density texture
+///
+/// In labeled mode, density and label hue are interleaved into a two-channel texture.
+fn texels<'a>(texture: &'a [f32], labels: &[f32], texels: &'a mut Vec<f32>) -> (&'a [f32], u32) {
+    if labels.is_empty() {
+        (texture, GL::RED)
+    } else {
+        texels.clear();
+        texels.extend(texture.iter().zip(labels).flat_map(|(v, l)| [*v, *l]));
+        (texels, GL::RG)
+    }
+}
+
+/// Texture bin of a sample for the coordinate range `-width..width`, or `None` if it is outside
+///
+/// Widths below [`MIN_WIDTH`] (e.g., from a slider at 0) are clamped to it. Samples that are not
+/// finite are not binned.
+fn bin_index(s: Complex32, width: f32, bins: usize) -> Option<usize> {
+    let width = width.max(MIN_WIDTH);
+    let index = |v: f32| {
+        let i = ((v + width) / (2.0 * width) * bins as f32).round();
+        (i.is_finite() && i >= 0.0 && i < bins as f32).then_some(i as usize)
+    };
+    Some(index(s.im)? * bins + index(s.re)?)
+}
+
+/// WebGL2 context from the result of `getContext("webgl2")`, or why there is none
+///
+/// Browsers return `null` (or throw) if WebGL2 is disabled or not supported by the hardware.
+fn webgl2_context(context: Result<Option<js_sys::Object>, JsValue>) -> Result<GL, String> {
+    match context {
+        Ok(Some(context)) => context
+            .dyn_into()
+            .map_err(|_| format!("{WEBGL2_UNSUPPORTED} (unexpected context type)")),
+        Ok(None) => Err(WEBGL2_UNSUPPORTED.to_string()),
+        Err(e) => Err(format!("{WEBGL2_UNSUPPORTED} ({e:?})")),
+    }
+}
+
+/// Shader program with its vertex buffer
+struct Pass {
+    program: WebGlProgram,
+    buffer: WebGlBuffer,
+    loc: u32,
+}
+
+impl Pass {
+    fn bind(&self, gl: &GL) {
+        gl.use_program(Some(&self.program));
+        gl.bind_buffer(GL::ARRAY_BUFFER, Some(&self.buffer));
+        gl.enable_vertex_attrib_array(self.loc);
+        gl.vertex_attrib_pointer_with_i32(self.loc, 2, GL::FLOAT, false, 0, 0);
+    }
+}
+
+/// Crosshair markers at the ideal symbol locations
+struct Overlay {
+    pass: Pass,
+    points: Vec<Complex32>,
+    vertexes: Vec<f32>,
+}
+
+impl Overlay {
+    /// Update the marker vertexes for the current `width` and draw them
+    fn draw(&mut self, gl: &GL, width: f32) {
+        let width = width.max(MIN_WIDTH);
+        self.vertexes.clear();
+        for p in self.points.iter() {
+            let x = p.re / width;
+            let y = p.im / width;
+            self.vertexes.extend_from_slice(&[
+                x - MARKER_SIZE,
+                y,
+                x + MARKER_SIZE,
+                y,
+                x,
+                y - MARKER_SIZE,
+                x,
+                y + MARKER_SIZE,
+            ]);
+        }
+
+        self.pass.bind(gl);
+        let view = unsafe { f32::view(&self.vertexes) };
+        gl.buffer_data_with_array_buffer_view(GL::ARRAY_BUFFER, &view, GL::DYNAMIC_DRAW);
+        gl.draw_arrays(GL::LINES, 0, (self.vertexes.len() / 2) as i32);
+    }
+}
+
+fn compile_program(gl: &GL, vert_code: &str, frag_code: &str) -> WebGlProgram {
+    let vert_shader = gl.create_shader(GL::VERTEX_SHADER).unwrap();
+    gl.shader_source(&vert_shader, vert_code);
+    gl.compile_shader(&vert_shader);
+
+    let frag_shader = gl.create_shader(GL::FRAGMENT_SHADER).unwrap();
+    gl.shader_source(&frag_shader, frag_code);
+    gl.compile_shader(&frag_shader);
+
+    let program = gl.create_program().unwrap();
+    gl.attach_shader(&program, &vert_shader);
+    gl.attach_shader(&program, &frag_shader);
+    gl.link_program(&program);
+    program
+}
+
+fn create_overlay(gl: &GL, points: Vec<Complex32>) -> Overlay {
+    let vert_code = r"
+        attribute vec2 position;
+
+        void main(void) {
+            gl_Position = vec4(position, 0, 1);
+        }
+    ";
+
+    let frag_code = r"
+        precision mediump float;
+
+        void main(void) {
+            gl_FragColor = vec4(1.0, 1.0, 1.0, 1.0);
+        }
+    ";
+
+    let program = compile_program(gl, vert_code, frag_code);
+    let buffer = gl.create_buffer().unwrap();
+    let loc = gl.get_attrib_location(&program, "position") as u32;
+
+    Overlay {
+        pass: Pass {
+            program,
+            buffer,
+            loc,
+        },
+        vertexes: Vec::with_capacity(points.len() * 8),
+        points,
+    }
 }
 
 #[component]
@@ -33,31 +297,98 @@ struct RenderState {
 /// - `width`: The coordinate range for the constellation (e.g., 2.0 means -2 to +2)
 /// - `bins`: Number of bins per dimension for the density map (default: 256). Higher = more detail.
 /// - `decay`: Decay factor per sample (default: 0.999). Lower = faster fade.
+/// - `peak_hold`: Keep the per-bin maximum of all frames instead of decaying (default: false),
+///   so that short bursts remain visible after they stop.
+/// - `reset`: Clears the density map whenever its value changes.
 /// - `intensity`: Intensity increment per sample hit (default: 0.1).
-/// - `websocket`: WebSocket URL for receiving constellation data.
+/// - `decimate`: Bin only every Nth symbol (default: 1), to save CPU at high symbol rates. The
+///   binned symbols count N times, and the decay still applies per received symbol, so
+///   brightness and fade timing are unchanged on average. The price is a noisier map, in which
+///   rare outliers may not show up.
+/// - `websocket`: WebSocket URL for receiving constellation data. When the socket closes, e.g.,
+///   because the backend switched flowgraphs, the component reconnects.
+/// - `clear_on_reconnect`: Clear the density map when data arrives on a new connection
+///   (default: true). Otherwise, the map keeps accumulating, so a brief switch does not blank
+///   the display.
+/// - `labeled`: Expect records of a symbol and a `u8` label (e.g., the OFDM subcarrier) in the
+///   layout of [`LABELED_RECORD_SIZE`] instead of plain `Complex32` samples, and color each bin by
+///   the label of its last hit, with the density as brightness.
+/// - `reference`: Ideal symbol locations (e.g., the four QPSK points), drawn as crosshair
+///   markers on top of the density map to judge the EVM.
+/// - `show_stats`: Show statistics of the symbols binned in the last frame as a text overlay
+///   (default: false): the mean magnitude and, with `reference`, the RMS EVM and the mean
+///   phase offset to the nearest reference point. The EVM is normalized to the RMS magnitude
+///   of the reference points.
+/// - `auto_scale`: Normalize the colormap input by the running maximum of the density map
+///   (default: false), so that the full color range is used independent of the symbol rate,
+///   `intensity`, and `decay`. Otherwise, the density is mapped as is, saturating at 1.
 pub fn ConstellationSinkDensity(
     #[prop(into)] width: Signal<f32>,
     #[prop(optional, default = DEFAULT_BINS)] bins: usize,
     #[prop(optional, default = 0.999f32)] decay: f32,
+    #[prop(optional, default = false)] peak_hold: bool,
+    #[prop(optional, into)] reset: Option<Signal<u32>>,
     #[prop(optional, default = 0.1f32)] intensity: f32,
+    #[prop(optional, default = 1)] decimate: usize,
     #[prop(optional, into, default = "ws://127.0.0.1:9002".to_string())] websocket: String,
+    #[prop(optional, default = true)] clear_on_reconnect: bool,
+    #[prop(optional)] reference: Option<Vec<Complex32>>,
+    #[prop(optional)] labeled: bool,
+    #[prop(optional, default = false)] show_stats: bool,
+    #[prop(optional, default = false)] auto_scale: bool,
 ) -> impl IntoView {
+    let stats = show_stats.then(|| RwSignal::new(String::new()));
+    // reason the density map cannot be drawn, shown instead
+    let unsupported = RwSignal::new(None::<String>);
     let data = Rc::new(RefCell::new(None));
+    let reconnected = Rc::new(Cell::new(false));
     {
         let data = data.clone();
+        let reconnected = reconnected.clone();
+        // ends the WebSocket task when the component is unmounted, so that a remounted
+        // component does not compete with it for the data
+        let (cancel_tx, mut cancel) = oneshot::channel::<()>();
+        on_cleanup(move || {
+            let _ = cancel_tx.send(());
+        });
         spawn_local(async move {
-            let mut ws = WebSocket::open(&websocket).unwrap();
-            while let Some(msg) = ws.next().await {
-                match msg {
-                    Ok(Message::Bytes(b)) => {
-                        *data.borrow_mut() = Some(b);
+            // whether any earlier connection delivered data
+            let mut streamed = false;
+            loop {
+                match WebSocket::open(&websocket) {
+                    Ok(mut ws) => {
+                        let mut fresh = true;
+                        loop {
+                            match select(ws.next(), &mut cancel).await {
+                                Either::Left((Some(Ok(Message::Bytes(b))), _)) => {
+                                    if fresh {
+                                        fresh = false;
+                                        if streamed && clear_on_reconnect {
+                                            reconnected.set(true);
+                                        }
+                                        streamed = true;
+                                    }
+                                    *data.borrow_mut() = Some(b);
+                                }
+                                Either::Left((Some(msg), _)) => {
+                                    log!("ConstellationSinkDensity: WebSocket {:?}", msg);
+                                }
+                                Either::Left((None, _)) => break,
+                                Either::Right(_) => return,
+                            }
+                        }
+                        log!("ConstellationSinkDensity: WebSocket Closed");
                     }
-                    _ => {
-                        log!("ConstellationSinkDensity: WebSocket {:?}", msg);
+                    Err(e) => {
+                        log!("ConstellationSinkDensity: WebSocket {:?}", e);
                     }
                 }
+
+                let delay = gloo_timers::future::sleep(RECONNECT_DELAY);
+                if let Either::Right(_) = select(delay, &mut cancel).await {
+                    return;
+                }
             }
-            log!("ConstellationSinkDensity: WebSocket Closed");
         });
     }
 
@@ -72,12 +403,15 @@ pub fn ConstellationSinkDensity(
             )
             .expect("Cannot create context options");
 
-            let gl: GL = canvas
-                .get_context_with_context_options("webgl2", &context_options)
-                .unwrap()
-                .unwrap()
-                .dyn_into()
-                .unwrap();
+            let context = canvas.get_context_with_context_options("webgl2", &context_options);
+            let gl = match webgl2_context(context) {
+                Ok(gl) => gl,
+                Err(e) => {
+                    warn!("ConstellationSinkDensity: {}", e);
+                    unsupported.set(Some(e));
+                    return;
+                }
+            };
 
             let vert_code = r"
                 attribute vec2 texCoord;
@@ -89,15 +423,18 @@ pub fn ConstellationSinkDensity(
                 }
             ";
 
-            let vert_shader = gl.create_shader(GL::VERTEX_SHADER).unwrap();
-            gl.shader_source(&vert_shader, vert_code);
-            gl.compile_shader(&vert_shader);
-
             let frag_code = r"
                 precision mediump float;
 
                 varying vec2 coord;
                 uniform sampler2D sampler;
+                uniform bool labeled;
+                uniform float scale;
+
+                // Fully saturated color with hue h in [0, 1)
+                vec3 hue_map(float h) {
+                    return clamp(abs(mod(h * 6.0 + vec3(0.0, 4.0, 2.0), 6.0) - 3.0) - 1.0, 0.0, 1.0);
+                }
 
                 // Rainbow colormap: sky blue (low) -> cyan -> green -> yellow -> orange -> red (high)
                 vec3 color_map(float t) {
@@ -131,22 +468,25 @@ pub fn ConstellationSinkDensity(
 
                 void main(void) {
                     vec4 sample = texture2D(sampler, vec2(coord.x * 0.5 + 0.5, coord.y * 0.5 - 0.5));
-                    float value = clamp(sample.r, 0.0, 1.0);
+                    float value = clamp(sample.r / scale, 0.0, 1.0);
                     // Solid color (alpha = 1.0) when there's any sample, black background otherwise
                     float alpha = value > 0.001 ? 1.0 : 0.0;
-                    gl_FragColor = vec4(color_map(value), alpha);
+                    if (labeled) {
+                        gl_FragColor = vec4(hue_map(sample.g) * (0.4 + 0.6 * value), alpha);
+                    } else {
+                        gl_FragColor = vec4(color_map(value), alpha);
+                    }
                 }
             ";
 
-            let frag_shader = gl.create_shader(GL::FRAGMENT_SHADER).unwrap();
-            gl.shader_source(&frag_shader, frag_code);
-            gl.compile_shader(&frag_shader);
-
-            let shader = gl.create_program().unwrap();
-            gl.attach_shader(&shader, &vert_shader);
-            gl.attach_shader(&shader, &frag_shader);
-            gl.link_program(&shader);
+            let shader = compile_program(&gl, vert_code, frag_code);
             gl.use_program(Some(&shader));
+            gl.uniform1i(
+                gl.get_uniform_location(&shader, "labeled").as_ref(),
+                labeled as i32,
+            );
+            let scale_location = gl.get_uniform_location(&shader, "scale");
+            gl.uniform1f(scale_location.as_ref(), 1.0);
 
             let texture = gl.create_texture().unwrap();
             gl.bind_texture(GL::TEXTURE_2D, Some(&texture));
@@ -156,15 +496,19 @@ pub fn ConstellationSinkDensity(
             gl.tex_parameteri(GL::TEXTURE_2D, GL::TEXTURE_MAG_FILTER, GL::NEAREST as i32);
 
             let texture = vec![0.0f32; bins * bins];
-            let view = unsafe { f32::view(&texture) };
+            let labels = if labeled { vec![0.0f32; bins * bins] } else { Vec::new() };
+            let mut scratch = Vec::new();
+            let (texel_data, format) = texels(&texture, &labels, &mut scratch);
+            let internal_format = if format == GL::RG { GL::RG32F } else { GL::R32F };
+            let view = unsafe { f32::view(texel_data) };
             gl.tex_image_2d_with_i32_and_i32_and_i32_and_format_and_type_and_array_buffer_view_and_src_offset(
                 GL::TEXTURE_2D,
                 0,
-                GL::R32F as i32,
+                internal_format as i32,
                 bins as i32,
                 bins as i32,
                 0,
-                GL::RED,
+                format,
                 GL::FLOAT,
                 &view,
                 0
@@ -186,18 +530,57 @@ pub fn ConstellationSinkDensity(
             gl.enable_vertex_attrib_array(loc);
             gl.vertex_attrib_pointer_with_i32(loc, 2, GL::FLOAT, false, 0, 0);
 
+            let density = Pass {
+                program: shader,
+                buffer: vertex_buffer,
+                loc,
+            };
+            let overlay = reference.clone().map(|points| create_overlay(&gl, points));
+            let stats_reference = reference.clone().unwrap_or_default();
+
+            let hits = if peak_hold { vec![0.0f32; bins * bins] } else { Vec::new() };
+            let reset_seen = reset.map(|r| r.get_untracked()).unwrap_or_default();
+
             let state = Rc::new(RefCell::new(RenderState {
                 canvas,
                 gl,
                 texture,
+                hits,
+                labels,
+                texels: scratch,
+                peak_hold,
+                decimate: decimate.max(1),
+                reset,
+                reset_seen,
+                reconnected: reconnected.clone(),
                 width,
                 bins,
+                density,
+                overlay,
+                stats,
+                stats_reference,
+                auto_scale: auto_scale.then(AutoScale::default),
+                scale_location,
             }));
             request_animation_frame(render(state, data.clone(), decay, intensity))
         }
     });
 
-    view! { <canvas node_ref=canvas_ref style="width: 100%; height: 100%" /> }
+    view! {
+        <div style="position: relative; width: 100%; height: 100%">
+            <canvas node_ref=canvas_ref style="width: 100%; height: 100%" />
+            {move || unsupported.get().map(|e| view! {
+                <div style="position: absolute; inset: 0; display: flex; align-items: center; justify-content: center; color: white; font: 14px sans-serif">
+                    {e}
+                </div>
+            })}
+            {stats.map(|text| view! {
+                <div style="position: absolute; top: 4px; left: 8px; color: white; font: 12px monospace; pointer-events: none">
+                    {move || text.get()}
+                </div>
+            })}
+        </div>
+    }
 }
 
 fn render(
@@ -212,8 +595,22 @@ fn render(
                 canvas,
                 gl,
                 texture,
+                hits,
+                labels,
+                texels: scratch,
+                peak_hold,
+                decimate,
+                reset,
+                reset_seen,
+                reconnected,
                 width,
                 bins,
+                density,
+                overlay,
+                stats,
+                stats_reference,
+                auto_scale,
+                scale_location,
             } = &mut (*state.borrow_mut());
             let bins = *bins;
 
@@ -228,28 +625,100 @@ fn render(
                 gl.viewport(0, 0, display_width as i32, display_height as i32);
             }
 
+            let mut dirty = false;
+            if let Some(reset) = reset {
+                let value = reset.get_untracked();
+                if value != *reset_seen {
+                    *reset_seen = value;
+                    texture.fill(0.0);
+                    if let Some(auto_scale) = auto_scale {
+                        *auto_scale = AutoScale::default();
+                    }
+                    dirty = true;
+                }
+            }
+            // clear before binning the first data of the new connection
+            if reconnected.take() {
+                texture.fill(0.0);
+                if let Some(auto_scale) = auto_scale {
+                    *auto_scale = AutoScale::default();
+                }
+                dirty = true;
+            }
+
+            let width = width.get_untracked();
             if let Some(bytes) = data.borrow_mut().take() {
-                let samples = unsafe {
-                    let s = bytes.len() / 8;
-                    let p = bytes.as_ptr();
-                    std::slice::from_raw_parts(p as *const Complex32, s)
+                let labeled = !labels.is_empty();
+                let n_samples = if labeled {
+                    bytes.len() / LABELED_RECORD_SIZE
+                } else {
+                    bytes.len() / 8
                 };
 
-                let decay_factor = decay.powi(samples.len() as i32);
-                texture.iter_mut().for_each(|v| *v *= decay_factor);
+                // the decay covers all received samples, while only every `decimate`-th one is
+                // binned, with a correspondingly higher weight
+                let decimate = *decimate;
+                let weight = intensity * decimate as f32;
 
-                let width = width.get_untracked();
-                for s in samples.iter() {
-                    let w = ((s.re + width) / (2.0 * width) * bins as f32).round() as i64;
-                    if w >= 0 && w < bins as i64 {
-                        let h = ((s.im + width) / (2.0 * width) * bins as f32).round() as i64;
-                        if h >= 0 && h < bins as i64 {
-                            texture[h as usize * bins + w as usize] += intensity;
+                // in peak-hold mode, the frame is binned separately and max-accumulated
+                let bin_target = if *peak_hold {
+                    hits.fill(0.0);
+                    &mut *hits
+                } else {
+                    let decay_factor = decay.powi(n_samples as i32);
+                    texture.iter_mut().for_each(|v| *v *= decay_factor);
+                    &mut *texture
+                };
+
+                let mut frame_stats = Stats::default();
+                let collect_stats = stats.is_some();
+                let mut hit = |s: Complex32, label: Option<u8>| {
+                    if collect_stats {
+                        frame_stats.add(s, stats_reference);
+                    }
+                    if let Some(bin) = bin_index(s, width, bins) {
+                        bin_target[bin] += weight;
+                        if let Some(label) = label {
+                            labels[bin] = (label as f32 / LABEL_HUES).fract();
                         }
                     }
+                };
+
+                if labeled {
+                    for r in bytes.chunks_exact(LABELED_RECORD_SIZE).step_by(decimate) {
+                        let re = f32::from_le_bytes(r[0..4].try_into().unwrap());
+                        let im = f32::from_le_bytes(r[4..8].try_into().unwrap());
+                        hit(Complex32::new(re, im), Some(r[8]));
+                    }
+                } else {
+                    let samples = unsafe {
+                        let p = bytes.as_ptr();
+                        std::slice::from_raw_parts(p as *const Complex32, n_samples)
+                    };
+                    for s in samples.iter().step_by(decimate) {
+                        hit(*s, None);
+                    }
+                }
+
+                if let Some(stats) = stats {
+                    stats.set(frame_stats.text(stats_reference));
+                }
+
+                if *peak_hold {
+                    texture
+                        .iter_mut()
+                        .zip(hits.iter())
+                        .for_each(|(v, h)| *v = v.max(*h));
                 }
+                dirty = true;
+            }
 
-                let view = unsafe { f32::view(texture) };
+            if dirty {
+                if let Some(auto_scale) = auto_scale {
+                    gl.uniform1f(scale_location.as_ref(), auto_scale.update(texture));
+                }
+                let (texel_data, format) = texels(texture, labels, scratch);
+                let view = unsafe { f32::view(texel_data) };
                 gl.tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_array_buffer_view_and_src_offset(
                     GL::TEXTURE_2D,
                     0,
@@ -257,7 +726,7 @@ fn render(
                     0,
                     bins as i32,
                     bins as i32,
-                    GL::RED,
+                    format,
                     GL::FLOAT,
                     &view,
                     0,
@@ -265,8 +734,96 @@ fn render(
                 .unwrap();
 
                 gl.draw_elements_with_i32(GL::TRIANGLES, 6, GL::UNSIGNED_SHORT, 0);
+
+                if let Some(overlay) = overlay {
+                    overlay.draw(gl, width);
+                    density.bind(gl);
+                }
             }
         }
         request_animation_frame(render(state, data, decay, intensity))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_webgl2_context() {
+        assert_eq!(webgl2_context(Ok(None)).unwrap_err(), WEBGL2_UNSUPPORTED);
+    }
+
+    #[test]
+    fn bin_index_bounds() {
+        let c = Complex32::new;
+        assert_eq!(bin_index(c(-1.0, -1.0), 1.0, 256), Some(0));
+        assert_eq!(bin_index(c(0.0, 0.0), 1.0, 256), Some(128 * 256 + 128));
+        assert_eq!(bin_index(c(1.0, 0.0), 1.0, 256), None);
+        assert_eq!(bin_index(c(0.5, -2.0), 1.0, 256), None);
+
+        // zero and tiny widths are clamped, not divided by
+        for width in [0.0, -1.0, 1e-9, f32::MIN_POSITIVE] {
+            assert_eq!(bin_index(c(0.0, 0.0), width, 256), Some(128 * 256 + 128));
+            assert_eq!(bin_index(c(0.5, 0.0), width, 256), None);
+        }
+        assert_eq!(bin_index(c(f32::NAN, 0.0), 1.0, 256), None);
+        assert_eq!(bin_index(c(0.0, f32::INFINITY), 1.0, 256), None);
+        assert_eq!(bin_index(c(0.0, 0.0), f32::INFINITY, 256), None);
+    }
+
+    #[test]
+    fn auto_scale_independent_of_rate() {
+        // steady state of the same symbol distribution at two symbol rates
+        let shape = [0.0, 1.0, 4.0, 2.0, 0.5];
+        let normalized = |rate: f32| {
+            let texture: Vec<f32> = shape.iter().map(|v| v * rate * 0.1).collect();
+            let mut auto_scale = AutoScale::default();
+            let mut scale = 0.0;
+            for _ in 0..10 {
+                scale = auto_scale.update(&texture);
+            }
+            texture.iter().map(|v| v / scale).collect::<Vec<_>>()
+        };
+        let slow = normalized(1.0);
+        let fast = normalized(10.0);
+        for (s, f) in slow.iter().zip(fast.iter()) {
+            assert!((s - f).abs() < 1e-6);
+        }
+        assert!((slow[2] - 1.0).abs() < 1e-6);
+
+        // after a burst, the scale follows the texture down instead of staying at its peak
+        let mut auto_scale = AutoScale::default();
+        assert_eq!(auto_scale.update(&[10.0]), 10.0);
+        let mut scale = 0.0;
+        for _ in 0..500 {
+            scale = auto_scale.update(&[1.0, f32::NAN]);
+        }
+        assert_eq!(scale, 1.0);
+        assert_eq!(AutoScale::default().update(&[0.0; 4]), 1.0);
+    }
+
+    #[test]
+    fn evm_of_ideal_qpsk() {
+        let level = std::f32::consts::FRAC_1_SQRT_2;
+        let qpsk = [
+            Complex32::new(-level, -level),
+            Complex32::new(level, -level),
+            Complex32::new(-level, level),
+            Complex32::new(level, level),
+        ];
+        let mut stats = Stats::default();
+        for s in qpsk.iter().cycle().take(100) {
+            stats.add(*s, &qpsk);
+        }
+        assert!(stats.evm(&qpsk).unwrap() < 1e-4);
+        assert!((stats.magnitude / stats.n as f32 - 1.0).abs() < 1e-4);
+        assert!(stats.phase.abs() < 1e-3);
+
+        // an error of 10 % of the RMS magnitude
+        let mut stats = Stats::default();
+        stats.add(qpsk[3] + Complex32::new(0.1, 0.0), &qpsk);
+        assert!((stats.evm(&qpsk).unwrap() - 0.1).abs() < 1e-4);
+        assert!(Stats::default().evm(&qpsk).is_none());
+    }
+}
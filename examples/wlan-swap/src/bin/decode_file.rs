@@ -0,0 +1,90 @@
+//! Decode a capture file and print frame statistics as JSON, e.g., for regression tests
+//!
+//! The RX graph has to read the capture with a `FileSource` without repeat, so it runs to EOF.
+//! The decoder's frame status port (`frame_ok` of `zigbee::Decoder`, `frame_status` of
+//! `wifi::Decoder`) is connected to a [`FrameStats`] block, whose summary is printed at the end.
+//! Set `FUTURESDR_LOG_LEVEL=warn` to keep the runtime's log out of stdout.
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+use clap::Parser;
+use futuresdr::async_io::block_on;
+use futuresdr::blocks::MessagePipe;
+use futuresdr::futures::StreamExt;
+use futuresdr::prelude::*;
+use wlan::blocks::FrameStats;
+use wlan::loader::FlowgraphLoader;
+
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Args {
+    /// RX graph
+    #[clap(default_value = "captures/zigbee_rx_file.toml")]
+    graph: String,
+    /// Capture file, overriding the `path` of the source block
+    input: Option<String>,
+    /// Name of the source block
+    #[clap(long, default_value = "src")]
+    source: String,
+    /// Name of the decoder block
+    #[clap(long, default_value = "decoder")]
+    decoder: String,
+    /// Frame status port of the decoder (default depends on the decoder type)
+    #[clap(long)]
+    status_port: Option<String>,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    eprintln!("Loading flowgraph from: {}", args.graph);
+
+    let mut loader = FlowgraphLoader::from_file(&args.graph)?;
+    if let Some(input) = &args.input {
+        loader.set_parameter(&args.source, "path", toml::Value::String(input.clone()))?;
+        loader.set_parameter(&args.source, "repeat", toml::Value::Boolean(false))?;
+    }
+
+    let status_port = match &args.status_port {
+        Some(port) => port.clone(),
+        None => {
+            let decoder = loader
+                .config()
+                .blocks
+                .iter()
+                .find(|b| b.name == args.decoder)
+                .with_context(|| format!("Block '{}' not found", args.decoder))?;
+            match decoder.block_type.as_str() {
+                "zigbee::Decoder" => "frame_ok".to_string(),
+                "wifi::Decoder" => "frame_status".to_string(),
+                t => bail!("no default status port for {t}, use --status-port"),
+            }
+        }
+    };
+
+    let mut fg = Flowgraph::new();
+    loader.build(&mut fg)?;
+    let decoder = loader
+        .get_block(&args.decoder)
+        .with_context(|| format!("Block '{}' not found", args.decoder))?;
+
+    // the summary is posted once the decoder finished
+    let stats = fg.add_block(FrameStats::new());
+    fg.connect_message(decoder, status_port.as_str(), &stats, "in")?;
+    let (tx, mut rx) = mpsc::channel::<Pmt>(1);
+    let pipe = fg.add_block(MessagePipe::new(tx));
+    fg.connect_message(&stats, "summary", &pipe, "in")?;
+
+    // no control port, so several decoders can run in parallel
+    futuresdr::runtime::config::set("ctrlport_enable", false);
+    let rt = Runtime::new();
+    let (task, mut handle) = rt.start_sync(fg)?;
+    block_on(rx.next()).context("flowgraph terminated without a summary")?;
+    // other blocks of the graph (e.g., a controller) might not finish on their own
+    let _ = block_on(handle.terminate());
+    block_on(task)?;
+
+    let summary = stats.get()?.summary().clone();
+    println!("{}", serde_json::to_string_pretty(&summary)?);
+
+    Ok(())
+}
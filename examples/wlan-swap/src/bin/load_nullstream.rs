@@ -3,6 +3,7 @@ use clap::Parser;
 use futuresdr::prelude::*;
 use futuresdr::runtime;
 use wlan::loader::load_flowgraph;
+use wlan::loader::run_with_ctrlc;
 
 #[derive(Parser, Debug)]
 #[clap(version)]
@@ -18,9 +19,10 @@ fn main() -> Result<()> {
     let fg = load_flowgraph(&args.file)?;
     println!("Flowgraph loaded successfully!");
     
-    println!("Starting runtime...");
+    println!("Starting runtime... (Ctrl-C to stop)");
     runtime::init();
-    Runtime::new().run(fg)?;
+    run_with_ctrlc(&Runtime::new(), fg)?;
+    println!("Flowgraph terminated");
 
     Ok(())
 }
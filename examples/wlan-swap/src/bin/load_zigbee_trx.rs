@@ -30,12 +30,10 @@ fn main() -> Result<()> {
     let task = runtime_config.async_tasks.first()
         .context("No async tasks configured")?;
     
-    // Get the block ID for the MAC
-    let mac_block_id = loader.get_block(&task.block)
-        .with_context(|| format!("Block '{}' not found", task.block))?;
+    // Get the port of the MAC
+    let port = loader.port_handle(&task.block, &task.port)?;
     
     let interval = task.interval_secs.unwrap_or(0.06);
-    let port = task.port.clone();
     let message_pattern = task.message_pattern.clone();
     
     println!("Starting runtime...");
@@ -48,12 +46,7 @@ fn main() -> Result<()> {
         loop {
             Timer::after(Duration::from_secs_f32(interval)).await;
             let message = message_pattern.replace("{seq}", &seq.to_string());
-            handle
-                .call(
-                    mac_block_id,
-                    port.as_str(),
-                    Pmt::Blob(message.as_bytes().to_vec()),
-                )
+            port.call(&mut handle, Pmt::Blob(message.as_bytes().to_vec()))
                 .await
                 .unwrap();
             seq += 1;
@@ -1,13 +1,30 @@
 use clap::Parser;
 use anyhow::Result;
-use futuresdr::runtime::{Runtime, FlowgraphHandle};
+use futuresdr::runtime::{Flowgraph, Runtime};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
 use std::sync::mpsc;
 use std::thread;
 use std::time::Duration;
 use wlan::loader::{
-    load_flowgraph_with_loader,
+    check_flowgraphs,
+    set_control_bind,
     write_control_file,
+    receive_commands,
+    ControlCommand,
+    FlowgraphInstances,
+    DEFAULT_CONTROL_PORT,
+    DEFAULT_LABEL,
+    BlockRegistry,
+    FlowgraphConfig,
+    FlowgraphLoader,
 };
+use wlan::loader::FlowgraphController;
+use wlan::loader::flowgraph_controller::set_runtime_status;
+
+/// Label of the controller-only flowgraph that runs while no other flowgraph is loaded
+const IDLE_LABEL: &str = "__idle__";
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about = "FutureSDR Radio Frontend - Switchable WiFi/ZigBee TX/RX")]
@@ -19,11 +36,91 @@ struct Args {
     /// Mode: wifi_tx, wifi_rx, zigbee_tx, zigbee_rx
     #[clap(short, long)]
     mode: Option<String>,
+
+    /// Validate and dry-run every flowgraph in flowgraphs/ and exit (no hardware is opened, SDR
+    /// blocks need the seify_dummy feature)
+    #[clap(long)]
+    check_all: bool,
+
+    /// Print the block types available in flowgraph files with their parameters and exit
+    #[clap(long)]
+    list_blocks: bool,
+
+    /// Print the --list-blocks output as JSON
+    #[clap(long, requires = "list_blocks")]
+    json: bool,
+
+    /// Address the runtime's control server binds to
+    #[clap(long, default_value_t = IpAddr::V4(Ipv4Addr::LOCALHOST))]
+    control_addr: IpAddr,
+
+    /// Port of the runtime's control server (the web GUI's `rt_url` has to use the same port)
+    #[clap(long, default_value_t = DEFAULT_CONTROL_PORT)]
+    control_port: u16,
+}
+
+/// Check all flowgraphs, print a pass/fail table, and return whether all passed
+fn check_all() -> Result<bool> {
+    let results = check_flowgraphs("flowgraphs")?;
+    let width = results.iter().map(|(p, _)| p.len()).max().unwrap_or(0);
+    let mut ok = true;
+
+    println!("{:<width$}  RESULT", "FLOWGRAPH");
+    for (path, res) in &results {
+        match res {
+            Ok(()) => println!("{:<width$}  PASS", path),
+            Err(e) => {
+                ok = false;
+                println!("{:<width$}  FAIL  {}", path, e);
+            }
+        }
+    }
+    let failed = results.iter().filter(|(_, r)| r.is_err()).count();
+    println!("\n{} flowgraphs, {} failed", results.len(), failed);
+    Ok(ok)
+}
+
+/// Print what changed compared to the config last loaded as `label`
+fn log_changes(configs: &mut HashMap<String, FlowgraphConfig>, label: &str, path: &str) {
+    let Ok(loader) = FlowgraphLoader::from_file(path) else {
+        return;
+    };
+    let config = loader.config().clone();
+    if let Some(old) = configs.get(label) {
+        println!(">>> Changes in '{}':\n{}", label, old.diff(&config));
+    }
+    configs.insert(label.to_string(), config);
+}
+
+/// Print all registered block types with their parameters
+fn list_blocks(json: bool) -> Result<()> {
+    let registry = BlockRegistry::new();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&registry.block_schemas())?);
+        return Ok(());
+    }
+    for block_type in registry.registered_types() {
+        if let Some(text) = registry.describe_block(block_type) {
+            println!("{}", text);
+        }
+    }
+    Ok(())
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
 
+    if args.list_blocks {
+        return list_blocks(args.json);
+    }
+
+    if args.check_all {
+        if !check_all()? {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // Determine initial file from mode or file argument
     let initial_file = if let Some(ref mode) = args.mode {
         format!("flowgraphs/{}.toml", mode)
@@ -39,100 +136,91 @@ fn main() -> Result<()> {
     println!("=== FutureSDR Radio Frontend ===");
     println!("Initial flowgraph: {}", initial_file);
     println!("Hot-reload: Web GUI can switch flowgraphs via control message");
+    println!("Multiple flowgraphs: send \"load:label:/path\" and \"stop:label\" to run them side by side");
     println!();
     
     // Create channel for reload signals
     let (reload_tx, reload_rx) = mpsc::channel::<String>();
     
     // Set the global reload channel for FlowgraphController
+    let finished_tx = reload_tx.clone();
     wlan::loader::flowgraph_controller::set_reload_channel(reload_tx);
     
     // Create Runtime once
+    let control_bind = set_control_bind(args.control_addr, args.control_port);
     let rt = Runtime::new();
-    println!(">>> Runtime started at http://127.0.0.1:1337");
+    println!(">>> Runtime started at http://{}", control_bind);
     
-    // Spawn dedicated listener thread that owns the flowgraph handle
+    // Spawn dedicated listener thread that owns the flowgraph handles
     thread::spawn(move || {
-        use futuresdr::async_io::block_on;
-        let mut current_file = initial_file;
-        let mut fg_handle_opt: Option<FlowgraphHandle> = None;
+        let mut instances = FlowgraphInstances::new(&rt);
+        // Flowgraphs that finish on their own (e.g., at the end of a file) arrive as commands, too
+        instances.notify_finished(finished_tx);
+        let mut next = vec![ControlCommand::Load {
+            label: DEFAULT_LABEL.to_string(),
+            path: initial_file,
+        }];
+        let mut last_reload = None;
+        let mut configs = HashMap::new();
         
         loop {
-            println!("\n>>> Loading flowgraph: {}", current_file);
-            
-            // First, terminate the old flowgraph if it exists
-            if let Some(mut old_handle) = fg_handle_opt.take() {
-                println!(">>> Terminating old flowgraph...");
-                block_on(async {
-                    if let Err(e) = old_handle.terminate_and_wait().await {
-                        eprintln!("Error during old flowgraph termination: {}", e);
+            for cmd in next.drain(..) {
+                if matches!(cmd, ControlCommand::Load { .. } | ControlCommand::Stop { .. })
+                    && let Some(frames) = instances.frame_count(cmd.label())
+                {
+                    println!(">>> Graph '{}' handled {} frames", cmd.label(), frames);
+                }
+                match &cmd {
+                    ControlCommand::Load { label, path } => {
+                        println!("\n>>> Loading flowgraph: {} as '{}'", path, label);
                     }
-                });
-                println!(">>> Old flowgraph fully terminated");
-            }
-            
-            // Now load and start the new flowgraph
-            match load_flowgraph_with_loader(&current_file) {
-                Ok((fg, loader)) => {
-                    println!(">>> Flowgraph loaded successfully!");
-                    
-                    // Debug: print controller block ID
-                    if let Some(controller_id) = loader.get_block("flowgraph_controller") {
-                        println!(">>> FlowgraphController is at block ID: {:?}", controller_id);
-                    } else {
-                        println!(">>> WARNING: FlowgraphController not found in block_map!");
+                    ControlCommand::Stop { label } => {
+                        println!("\n>>> Stopping flowgraph '{}'", label);
                     }
-                    
-                    // Start new flowgraph
-                    let (_fg_task, mut new_fg_handle) = match rt.start_sync(fg) {
-                        Ok(h) => h,
-                        Err(e) => {
-                            eprintln!(">>> Failed to start flowgraph: {}", e);
-                            thread::sleep(Duration::from_secs(1));
-                            continue;
-                        }
-                    };
-                    
-                    println!(">>> Flowgraph running. Listening for reload signals...");
-                    
-                    // Send 'initialized' message to FlowgraphController TX port to notify frontend
-                    if let Some(controller_id) = loader.get_block("flowgraph_controller") {
-                        use futuresdr::runtime::Pmt;
-                        let _ = new_fg_handle.call(controller_id, "tx", Pmt::String("initialized".to_string()));
-                        println!(">>> Sent initialization signal to frontend via FlowgraphController");
-                        
-                        // Also send reload message to RX port for any UI state resets
-                        let _ = new_fg_handle.call(controller_id, "rx", Pmt::String("reload".to_string()));
+                    ControlCommand::Finished { label } => {
+                        println!("\n>>> Flowgraph '{}' finished", label);
                     }
-
-                    // Keep the new handle for next iteration
-                    fg_handle_opt = Some(new_fg_handle);
-                    
-                    // Wait for reload signal from channel
-                    match reload_rx.recv_timeout(Duration::from_secs(3600)) {
-                        Ok(new_file) => {
-                            println!("\n>>> Reload signal received!");
-                            println!(">>> Switching from {} to {}", current_file, new_file);
-                            
-                            // Update to new flowgraph file and loop will handle termination + reload
-                            current_file = new_file;
-                        }
-                        Err(mpsc::RecvTimeoutError::Timeout) => {
-                            // Continue running - just checking channel periodically
-                        }
-                        Err(mpsc::RecvTimeoutError::Disconnected) => {
-                            println!(">>> Reload channel disconnected, exiting...");
-                            break;
+                }
+                match instances.apply(&cmd) {
+                    Ok(()) => {
+                        if let ControlCommand::Load { label, path } = &cmd {
+                            log_changes(&mut configs, label, path);
+                            last_reload = Some(path.clone());
                         }
                     }
+                    Err(e) => eprintln!(">>> {}", e),
+                }
+            }
+            
+            // Keep a controller-only flowgraph running while nothing else is, so the GUI can load the next one
+            if instances.is_empty() {
+                let mut idle_fg = Flowgraph::new();
+                idle_fg.add_block(FlowgraphController::new());
+                if let Err(e) = instances.start(IDLE_LABEL, "", idle_fg) {
+                    eprintln!(">>> Failed to start idle flowgraph: {}", e);
                 }
-                Err(e) => {
-                    eprintln!(">>> Failed to load flowgraph: {}", e);
-                    eprintln!(">>> Retrying in 2 seconds...");
-                    thread::sleep(Duration::from_secs(2));
+                println!(">>> No flowgraph running. Waiting for next flowgraph...");
+            } else if instances.len() > 1 && instances.contains(IDLE_LABEL) {
+                instances.stop(IDLE_LABEL);
+            }
+            
+            for label in instances.labels() {
+                println!(">>> Running '{}': {}", label, instances.path(&label).unwrap_or(""));
+            }
+            let active = instances.labels().iter().filter(|l| *l != IDLE_LABEL).count();
+            set_runtime_status(active, last_reload.as_deref());
+            
+            // Wait for the next control messages; requests queued during a switch are coalesced
+            match receive_commands(&reload_rx) {
+                Some(cmds) => next = cmds,
+                None => {
+                    println!(">>> Reload channel disconnected, exiting...");
+                    break;
                 }
             }
         }
+        
+        instances.stop_all();
     });
     
     // Main thread keeps runtime alive
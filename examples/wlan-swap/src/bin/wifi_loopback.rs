@@ -1,4 +1,5 @@
 use anyhow::Result;
+use clap::Parser;
 use futuresdr::async_io::Timer;
 use futuresdr::blocks::Apply;
 use futuresdr::blocks::BlobToUdp;
@@ -14,6 +15,7 @@ use rand_distr::Distribution;
 use rand_distr::Normal;
 use std::time::Duration;
 
+use wlan::blocks::PowerMeter;
 use wlan::wifi::Decoder;
 use wlan::wifi::Encoder;
 use wlan::wifi::FrameEqualizer;
@@ -25,13 +27,29 @@ use wlan::wifi::Prefix;
 use wlan::wifi::SyncLong;
 use wlan::wifi::SyncShort;
 
-const PAD_FRONT: usize = 10000;
-const PAD_TAIL: usize = 10000;
+#[derive(Parser, Debug)]
+#[clap(version)]
+struct Args {
+    /// Zero samples before each frame
+    #[clap(long, default_value_t = 10000)]
+    pad_front: usize,
+    /// Zero samples after each frame
+    #[clap(long, default_value_t = 10000)]
+    pad_tail: usize,
+    /// Modulation and coding scheme of the frames (e.g., bpsk_1_2, qpsk_3_4, qam16_1_2)
+    #[clap(long, default_value = "qam16_1_2")]
+    mcs: Mcs,
+}
+
+fn prefix(args: &Args) -> Prefix {
+    Prefix::new(args.pad_front, args.pad_tail)
+}
 
 fn main() -> Result<()> {
+    let args = Args::parse();
     let mut fg = Flowgraph::new();
     let mac = Mac::new([0x42; 6], [0x23; 6], [0xff; 6]);
-    let encoder: Encoder = Encoder::new(Mcs::Qpsk_1_2);
+    let encoder: Encoder = Encoder::new(args.mcs);
     connect!(fg, mac.tx | tx.encoder);
     let mapper: Mapper = Mapper::new();
     connect!(fg, encoder > mapper);
@@ -42,7 +60,7 @@ fn main() -> Result<()> {
         Some((1.0f32 / 52.0).sqrt()),
     );
     connect!(fg, mapper > fft);
-    let prefix: Prefix = Prefix::new(PAD_FRONT, PAD_TAIL);
+    let prefix = prefix(&args);
     connect!(fg, fft > prefix);
 
     // add noise
@@ -54,7 +72,14 @@ fn main() -> Result<()> {
     });
     connect!(fg, prefix > noise);
 
-    let src = noise;
+    // report the RX power once per second
+    let power_meter: PowerMeter = PowerMeter::new(Duration::from_secs(1), 0.999);
+    connect!(fg, noise > power_meter);
+    let (tx_power, mut rx_power) = mpsc::channel::<Pmt>(10);
+    let power_pipe = MessagePipe::new(tx_power);
+    connect!(fg, power_meter.power | power_pipe);
+
+    let src = power_meter;
 
     // ========================================
     // Receiver
@@ -102,6 +127,8 @@ fn main() -> Result<()> {
     let blob_to_udp = BlobToUdp::new("127.0.0.1:55556");
     connect!(fg, decoder.rftap | blob_to_udp);
     let mac = mac.get()?.id;
+    let mcs = args.mcs;
+    println!("MCS: {mcs:?}");
 
     let rt = Runtime::new();
     let (_fg, mut handle) = rt.start_sync(fg)?;
@@ -116,7 +143,7 @@ fn main() -> Result<()> {
                     "tx",
                     Pmt::Any(Box::new((
                         format!("FutureSDR {seq}").as_bytes().to_vec(),
-                        Mcs::Qam16_1_2,
+                        mcs,
                     ))),
                 )
                 .await
@@ -125,6 +152,12 @@ fn main() -> Result<()> {
         }
     });
 
+    rt.spawn_background(async move {
+        while let Some(Pmt::F64(db)) = rx_power.next().await {
+            println!("rx power {db:.1} dBFS");
+        }
+    });
+
     rt.block_on(async move {
         while let Some(x) = rx_frame.next().await {
             match x {
@@ -138,3 +171,33 @@ fn main() -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pad_args() {
+        let args = Args::try_parse_from(["wifi_loopback"]).unwrap();
+        assert_eq!((args.pad_front, args.pad_tail), (10000, 10000));
+
+        let args = Args::try_parse_from(["wifi_loopback", "--pad-front", "400", "--pad-tail", "800"]).unwrap();
+        let prefix = prefix(&args);
+        assert_eq!((prefix.pad_front(), prefix.pad_tail()), (400, 800));
+
+        assert!(Args::try_parse_from(["wifi_loopback", "--pad-tail", "-5"]).is_err());
+    }
+
+    #[test]
+    fn mcs_arg() {
+        let args = Args::try_parse_from(["wifi_loopback"]).unwrap();
+        assert_eq!(args.mcs, Mcs::Qam16_1_2);
+
+        let args = Args::try_parse_from(["wifi_loopback", "--mcs", "qpsk_3_4"]).unwrap();
+        assert_eq!(args.mcs, Mcs::Qpsk_3_4);
+        let args = Args::try_parse_from(["wifi_loopback", "--mcs", "BPSK-1-2"]).unwrap();
+        assert_eq!(args.mcs, Mcs::Bpsk_1_2);
+
+        assert!(Args::try_parse_from(["wifi_loopback", "--mcs", "qam256"]).is_err());
+    }
+}
@@ -32,10 +32,20 @@ struct Args {
     /// WLAN Channel Number
     #[clap(short, long, value_parser = parse_channel, default_value = "1")]
     channel: f64,
+    /// Zero samples before each frame
+    #[clap(long, default_value_t = 5000)]
+    pad_front: usize,
+    /// Zero samples after each frame
+    #[clap(long, default_value_t = 5000)]
+    pad_tail: usize,
+    /// Modulation and coding scheme of the frames (e.g., bpsk_1_2, qpsk_3_4, qam16_1_2)
+    #[clap(long, default_value = "qam16_1_2")]
+    mcs: Mcs,
 }
 
-const PAD_FRONT: usize = 5000;
-const PAD_TAIL: usize = 5000;
+fn prefix(args: &Args) -> Prefix {
+    Prefix::new(args.pad_front, args.pad_tail)
+}
 
 fn main() -> Result<()> {
     let args = Args::parse();
@@ -44,7 +54,7 @@ fn main() -> Result<()> {
 
     let mut fg = Flowgraph::new();
     let mac = Mac::new([0x42; 6], [0x23; 6], [0xff; 6]);
-    let encoder: Encoder = Encoder::new(Mcs::Qpsk_1_2);
+    let encoder: Encoder = Encoder::new(args.mcs);
     connect!(fg, mac.tx | tx.encoder);
     let mapper: Mapper = Mapper::new();
     connect!(fg, encoder > mapper);
@@ -55,7 +65,7 @@ fn main() -> Result<()> {
         Some((1.0f32 / 52.0).sqrt()),
     );
     connect!(fg, mapper > fft);
-    let prefix: Prefix = Prefix::new(PAD_FRONT, PAD_TAIL);
+    let prefix = prefix(&args);
     connect!(fg, fft > prefix);
     let snk = Builder::new(args.args)?
         .frequency(args.channel)
@@ -68,6 +78,8 @@ fn main() -> Result<()> {
     connect!(fg, prefix > throttle > inputs[0].snk);
 
     let mac = mac.get()?.id;
+    let mcs = args.mcs;
+    println!("MCS: {mcs:?}");
 
     let rt = Runtime::new();
     let (_fg, mut handle) = rt.start_sync(fg)?;
@@ -82,7 +94,7 @@ fn main() -> Result<()> {
                     "tx",
                     Pmt::Any(Box::new((
                         format!("FutureSDR {seq}xxxxxxxxxxxxxxxxxxxxxxxxxxxxxx").as_bytes().to_vec(),
-                        Mcs::Qam16_1_2,
+                        mcs,
                     ))),
                 )
                 .await
@@ -92,4 +104,34 @@ fn main() -> Result<()> {
     });
 
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pad_args() {
+        let args = Args::try_parse_from(["wifi_tx"]).unwrap();
+        assert_eq!((args.pad_front, args.pad_tail), (5000, 5000));
+
+        let args = Args::try_parse_from(["wifi_tx", "--pad-front", "2000", "--pad-tail", "0"]).unwrap();
+        let prefix = prefix(&args);
+        assert_eq!((prefix.pad_front(), prefix.pad_tail()), (2000, 0));
+
+        assert!(Args::try_parse_from(["wifi_tx", "--pad-front", "-1"]).is_err());
+    }
+
+    #[test]
+    fn mcs_arg() {
+        let args = Args::try_parse_from(["wifi_tx"]).unwrap();
+        assert_eq!(args.mcs, Mcs::Qam16_1_2);
+
+        let args = Args::try_parse_from(["wifi_tx", "--mcs", "qpsk_3_4"]).unwrap();
+        assert_eq!(args.mcs, Mcs::Qpsk_3_4);
+        let args = Args::try_parse_from(["wifi_tx", "--mcs", "BPSK-1-2"]).unwrap();
+        assert_eq!(args.mcs, Mcs::Bpsk_1_2);
+
+        assert!(Args::try_parse_from(["wifi_tx", "--mcs", "qam256"]).is_err());
+    }
+}
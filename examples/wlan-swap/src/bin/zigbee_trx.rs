@@ -59,7 +59,8 @@ fn main() -> Result<()> {
         ClockRecoveryMm::new(omega, gain_omega, mu, gain_mu, omega_relative_limit);
     let mm = fg.add_block(mm);
 
-    let decoder: Decoder = Decoder::new(12);  // Increased threshold for software loopback
+    let decoder: Decoder = Decoder::new(12)  // Increased threshold for software loopback
+        .with_dedup_consecutive(true);
     let decoder = fg.add_block(decoder);
 
     // Perfect loopback: iq_delay -> receiver chain
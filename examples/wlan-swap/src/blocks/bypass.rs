@@ -0,0 +1,296 @@
+use futuresdr::blocks::Apply;
+use futuresdr::channel::mpsc::Sender;
+use futuresdr::prelude::*;
+use futuresdr::runtime::BlockMessage;
+use futuresdr::runtime::Error;
+use futuresdr::runtime::KernelInterface;
+use futuresdr::runtime::PortId;
+use futuresdr::runtime::buffer::BufferReader;
+use std::any::TypeId;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use crate::blocks::FreqXlate;
+
+/// Block that [`Bypass`] can wrap: one stream input and one stream output of the same type
+pub trait Bypassable: Kernel + KernelInterface + Send + 'static {
+    type Item: CpuSample;
+    type Input: CpuBufferReader<Item = Self::Item>;
+    type Output: CpuBufferWriter<Item = Self::Item>;
+
+    /// Stream input, which is copied to the output while bypassed
+    fn bypass_input(&mut self) -> &mut Self::Input;
+    /// Stream output
+    fn bypass_output(&mut self) -> &mut Self::Output;
+}
+
+impl<F, A, I, O> Bypassable for Apply<F, A, A, I, O>
+where
+    F: FnMut(&A) -> A + Send + 'static,
+    A: CpuSample,
+    I: CpuBufferReader<Item = A> + 'static,
+    O: CpuBufferWriter<Item = A> + 'static,
+{
+    type Item = A;
+    type Input = I;
+    type Output = O;
+
+    fn bypass_input(&mut self) -> &mut I {
+        self.input()
+    }
+    fn bypass_output(&mut self) -> &mut O {
+        self.output()
+    }
+}
+
+impl<I, O> Bypassable for FreqXlate<I, O>
+where
+    I: CpuBufferReader<Item = Complex32> + 'static,
+    O: CpuBufferWriter<Item = Complex32> + 'static,
+{
+    type Item = Complex32;
+    type Input = I;
+    type Output = O;
+
+    fn bypass_input(&mut self) -> &mut I {
+        self.input()
+    }
+    fn bypass_output(&mut self) -> &mut O {
+        self.output()
+    }
+}
+
+/// Wrap a processing stage, which can be switched off at runtime.
+///
+/// Works like the inner block, but copies the input to the output while bypassed, e.g., for
+/// A/B comparisons of DC offset removal without rebuilding the flowgraph. The `bypass` message
+/// port takes a `Pmt::Bool` and returns the current state when called with `Pmt::Null`. The
+/// inner block is not called while bypassed, i.e., its state (a DC estimate, an oscillator
+/// phase) is frozen and processing resumes from there once the bypass is switched off again.
+///
+/// The message inputs of the inner block are available next to `bypass`. While bypassed,
+/// messages to them are buffered and answered with `Pmt::Ok`. They are delivered in order
+/// when the bypass is switched off, before the next sample is processed. The message outputs
+/// are those of the inner block.
+pub struct Bypass<K: Bypassable> {
+    inner: K,
+    bypassed: bool,
+    pending: Vec<(PortId, Pmt)>,
+    /// Samples on their way from the input to the output of the inner block while bypassed
+    scratch: Vec<K::Item>,
+}
+
+impl<K: Bypassable> Bypass<K> {
+    pub fn new(inner: K, bypassed: bool) -> Self {
+        Self {
+            inner,
+            bypassed,
+            pending: Vec::new(),
+            scratch: Vec::new(),
+        }
+    }
+
+    /// Whether the inner block is bypassed
+    pub fn bypassed(&self) -> bool {
+        self.bypassed
+    }
+
+    async fn bypass(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt, Error> {
+        match p {
+            Pmt::Bool(b) => {
+                self.bypassed = b;
+                if !b {
+                    for (id, p) in std::mem::take(&mut self.pending) {
+                        self.inner.call_handler(io, mio, meta, id, p).await?;
+                    }
+                }
+                Ok(Pmt::Ok)
+            }
+            Pmt::Null => Ok(Pmt::Bool(self.bypassed)),
+            _ => Ok(Pmt::InvalidValue),
+        }
+    }
+}
+
+impl<K: Bypassable> Kernel for Bypass<K> {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        meta: &mut BlockMeta,
+    ) -> Result<()> {
+        if !self.bypassed {
+            return self.inner.work(io, mio, meta).await;
+        }
+
+        // the ports are only reachable through the inner block, one at a time
+        let o_len = self.inner.bypass_output().slice().len();
+        let input = self.inner.bypass_input();
+        let i = input.slice();
+        let i_len = i.len();
+        let n = std::cmp::min(i_len, o_len);
+        self.scratch.clear();
+        self.scratch.extend_from_slice(&i[..n]);
+        input.consume(n);
+        let finished = input.finished() && n == i_len;
+
+        let output = self.inner.bypass_output();
+        output.slice()[..n].clone_from_slice(&self.scratch);
+        output.produce(n);
+
+        if finished {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+
+    async fn init(&mut self, mio: &mut MessageOutputs, meta: &mut BlockMeta) -> Result<()> {
+        self.inner.init(mio, meta).await
+    }
+
+    async fn deinit(&mut self, mio: &mut MessageOutputs, meta: &mut BlockMeta) -> Result<()> {
+        self.inner.deinit(mio, meta).await
+    }
+}
+
+impl<K: Bypassable> KernelInterface for Bypass<K> {
+    fn is_blocking() -> bool {
+        K::is_blocking()
+    }
+    fn type_name() -> &'static str {
+        "Bypass"
+    }
+    fn stream_inputs(&self) -> Vec<String> {
+        self.inner.stream_inputs()
+    }
+    fn stream_outputs(&self) -> Vec<String> {
+        self.inner.stream_outputs()
+    }
+    fn stream_ports_init(&mut self, block_id: BlockId, inbox: Sender<BlockMessage>) {
+        self.inner.stream_ports_init(block_id, inbox)
+    }
+    fn stream_ports_validate(&self) -> Result<(), Error> {
+        self.inner.stream_ports_validate()
+    }
+    fn stream_input_finish(&mut self, port_id: PortId) -> Result<(), Error> {
+        self.inner.stream_input_finish(port_id)
+    }
+    async fn stream_ports_notify_finished(&mut self) {
+        self.inner.stream_ports_notify_finished().await
+    }
+    fn stream_input(&mut self, name: &str) -> Option<&mut dyn BufferReader> {
+        self.inner.stream_input(name)
+    }
+    fn connect_stream_output(
+        &mut self,
+        name: &str,
+        reader: &mut dyn BufferReader,
+    ) -> Result<(), Error> {
+        self.inner.connect_stream_output(name, reader)
+    }
+
+    fn message_inputs() -> &'static [&'static str] {
+        // one list per inner type, which lives as long as the program like the derived ones
+        static PORTS: Mutex<BTreeMap<TypeId, &'static [&'static str]>> =
+            Mutex::new(BTreeMap::new());
+        let mut ports = PORTS.lock().unwrap();
+        ports.entry(TypeId::of::<K>()).or_insert_with(|| {
+            let mut names = vec!["bypass"];
+            names.extend(K::message_inputs().iter().filter(|n| **n != "bypass"));
+            Box::leak(names.into_boxed_slice())
+        })
+    }
+    fn message_outputs() -> &'static [&'static str] {
+        K::message_outputs()
+    }
+    async fn call_handler(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        meta: &mut BlockMeta,
+        id: PortId,
+        p: Pmt,
+    ) -> Result<Pmt, Error> {
+        if id.name() == "bypass" {
+            return self.bypass(io, mio, meta, p).await;
+        }
+        if self.bypassed && K::message_inputs().contains(&id.name()) {
+            self.pending.push((id, p));
+            return Ok(Pmt::Ok);
+        }
+        self.inner.call_handler(io, mio, meta, id, p).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    #[test]
+    fn toggle_mid_stream() {
+        let mut count = 0.0f32;
+        let f = move |x: &f32| {
+            count += 1.0;
+            x * 10.0 + count
+        };
+        let mut apply = Apply::<_, f32, f32, Reader<_>, Writer<_>>::new(f);
+        apply.input().set(vec![1.0; 4]);
+        apply.output().reserve(12);
+        let mut mocker = Mocker::new(Bypass::new(apply, false));
+
+        mocker.run();
+        assert_eq!(mocker.post("bypass", Pmt::Null).unwrap(), Pmt::Bool(false));
+        assert_eq!(mocker.post("bypass", Pmt::Bool(true)).unwrap(), Pmt::Ok);
+        mocker.inner.input().set(vec![2.0; 4]);
+        mocker.run();
+        assert_eq!(mocker.post("bypass", Pmt::Bool(false)).unwrap(), Pmt::Ok);
+        mocker.inner.input().set(vec![3.0; 4]);
+        mocker.run();
+
+        let (output, _) = mocker.inner.output().get();
+        // the function is not called while bypassed, so its counter resumes at 5
+        assert_eq!(
+            output,
+            vec![11.0, 12.0, 13.0, 14.0, 2.0, 2.0, 2.0, 2.0, 35.0, 36.0, 37.0, 38.0]
+        );
+        assert_eq!(mocker.post("bypass", Pmt::U32(1)).unwrap(), Pmt::InvalidValue);
+    }
+
+    #[test]
+    fn buffers_inner_messages() {
+        let mut xlate = FreqXlate::<Reader<_>, Writer<_>>::new(1000.0, 8000.0);
+        xlate.output().reserve(8);
+        let mut mocker = Mocker::new(Bypass::new(xlate, true));
+        assert_eq!(Bypass::<FreqXlate>::message_inputs(), ["bypass", "freq"]);
+
+        assert_eq!(mocker.post("freq", Pmt::F64(2000.0)).unwrap(), Pmt::Ok);
+        assert_eq!(mocker.post("freq", Pmt::F64(-500.0)).unwrap(), Pmt::Ok);
+        let input = vec![Complex32::new(1.0, 0.0); 4];
+        mocker.inner.input().set(input.clone());
+        mocker.run();
+        assert_eq!(mocker.inner.output().get().0, input);
+
+        // delivered in order once the bypass is switched off
+        assert_eq!(mocker.post("bypass", Pmt::Bool(false)).unwrap(), Pmt::Ok);
+        assert_eq!(mocker.post("freq", Pmt::Null).unwrap(), Pmt::F64(-500.0));
+        mocker.inner.input().set(input);
+        mocker.run();
+        let (output, _) = mocker.inner.output().get();
+        assert_eq!(output.len(), 8);
+        // oscillator at -500 Hz, i.e., +pi/8 per sample
+        let step = (output[5] * output[4].conj()).arg();
+        assert!((step - std::f32::consts::PI / 8.0).abs() < 1e-4, "{step}");
+
+        assert!(mocker.post("nope", Pmt::Null).is_err());
+    }
+}
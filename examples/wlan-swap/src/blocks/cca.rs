@@ -0,0 +1,206 @@
+use futuresdr::prelude::*;
+use rand::Rng;
+use std::collections::VecDeque;
+
+/// Channel state and backoff of the CCA
+struct Assessment {
+    threshold_db: f32,
+    defer: u64,
+    max_backoff: u64,
+    /// Consecutive samples below the threshold
+    clear: u64,
+    /// Samples left until the channel is assessed again
+    backoff: u64,
+    deferred: u64,
+}
+
+impl Assessment {
+    /// Update the channel state with one power estimate and return whether the pending
+    /// request, if any, may be sent.
+    fn update(&mut self, power_db: f32, pending: bool) -> bool {
+        let busy = power_db >= self.threshold_db;
+        self.clear = if busy { 0 } else { self.clear + 1 };
+
+        if self.backoff > 0 {
+            self.backoff -= 1;
+            return false;
+        }
+        if !pending {
+            return false;
+        }
+        if self.clear >= self.defer {
+            // the next request waits for another full window
+            self.clear = 0;
+            return true;
+        }
+        if busy {
+            self.backoff = rand::rng().random_range(0..=self.max_backoff);
+            self.deferred += 1;
+        }
+        false
+    }
+}
+
+/// Clear channel assessment (CCA) gate for TX requests.
+///
+/// Consumes a stream of RX power estimates in dB, one per sample (e.g., the averaged sample
+/// power of the receive path), and queues the messages received on `tx`. A request is forwarded
+/// on `tx` (to the MAC) once the power has stayed below `threshold_db` for `defer_us`. If the
+/// channel is busy when a request is due, the gate backs off for a random interval of up to
+/// `backoff_us` before it assesses the channel again. Times are converted to samples with
+/// `sample_rate`, so the timing follows the RX stream rather than the wall clock.
+///
+/// Consecutive requests are spaced by a full `defer_us` window. `Pmt::Finished` terminates the
+/// block once the queue is drained.
+#[derive(Block)]
+#[message_inputs(tx)]
+#[message_outputs(tx)]
+pub struct Cca<I = DefaultCpuReader<f32>>
+where
+    I: CpuBufferReader<Item = f32>,
+{
+    #[input]
+    input: I,
+    assessment: Assessment,
+    queue: VecDeque<Pmt>,
+    finished: bool,
+}
+
+impl<I> Cca<I>
+where
+    I: CpuBufferReader<Item = f32>,
+{
+    pub fn new(threshold_db: f32, defer_us: f64, backoff_us: f64, sample_rate: f64) -> Self {
+        assert!(sample_rate > 0.0 && sample_rate.is_finite());
+        assert!(defer_us >= 0.0 && defer_us.is_finite());
+        assert!(backoff_us >= 0.0 && backoff_us.is_finite());
+        let samples = |us: f64| (us * 1e-6 * sample_rate).round() as u64;
+        Self {
+            input: I::default(),
+            assessment: Assessment {
+                threshold_db,
+                defer: samples(defer_us),
+                max_backoff: samples(backoff_us),
+                clear: 0,
+                backoff: 0,
+                deferred: 0,
+            },
+            queue: VecDeque::new(),
+            finished: false,
+        }
+    }
+
+    /// Number of times a request found the channel busy and backed off
+    pub fn deferred(&self) -> u64 {
+        self.assessment.deferred
+    }
+
+    /// Number of queued requests
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    async fn tx(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Finished => self.finished = true,
+            p => self.queue.push_back(p),
+        }
+        io.call_again = true;
+        Ok(Pmt::Ok)
+    }
+}
+
+impl<I> Kernel for Cca<I>
+where
+    I: CpuBufferReader<Item = f32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let n = i.len();
+
+        let mut send = 0;
+        for p in i.iter() {
+            if self.assessment.update(*p, send < self.queue.len()) {
+                send += 1;
+            }
+        }
+        self.input.consume(n);
+
+        for _ in 0..send {
+            let p = self.queue.pop_front().unwrap();
+            mio.post("tx", p).await?;
+        }
+
+        if (self.finished && self.queue.is_empty()) || self.input.finished() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+
+    const BUSY: f32 = -40.0;
+    const CLEAR: f32 = -90.0;
+
+    /// Run the gate on `n` power estimates and return the number of forwarded requests
+    fn feed(mocker: &mut Mocker<Cca<Reader<f32>>>, power: f32, n: usize) -> usize {
+        mocker.input.set(vec![power; n]);
+        mocker.run();
+        mocker.messages()[0].len()
+    }
+
+    #[test]
+    fn defers_while_busy() {
+        // 1 sample per µs: defer for 10 samples, back off for up to 5
+        let mut mocker = Mocker::new(Cca::<Reader<f32>>::new(-70.0, 10.0, 5.0, 1e6));
+        mocker.post("tx", Pmt::U32(1)).unwrap();
+        mocker.post("tx", Pmt::U32(2)).unwrap();
+
+        assert_eq!(feed(&mut mocker, BUSY, 100), 0);
+        assert!(mocker.deferred() > 0);
+        assert_eq!(mocker.queued(), 2);
+
+        // clear, but shorter than the defer window
+        assert_eq!(feed(&mut mocker, CLEAR, 8), 0);
+        assert_eq!(feed(&mut mocker, BUSY, 1), 0);
+        // the window restarts with the busy sample, and the backoff ends within it
+        assert_eq!(feed(&mut mocker, CLEAR, 9), 0);
+        assert_eq!(feed(&mut mocker, CLEAR, 1), 1);
+        assert_eq!(mocker.messages()[0], vec![Pmt::U32(1)]);
+
+        // the second request waits for another window
+        assert_eq!(feed(&mut mocker, CLEAR, 9), 1);
+        assert_eq!(feed(&mut mocker, CLEAR, 1), 2);
+        assert_eq!(mocker.messages()[0], vec![Pmt::U32(1), Pmt::U32(2)]);
+        assert_eq!(mocker.queued(), 0);
+    }
+
+    #[test]
+    fn forwards_on_clear_channel() {
+        let mut mocker = Mocker::new(Cca::<Reader<f32>>::new(-70.0, 10.0, 5.0, 1e6));
+        assert_eq!(feed(&mut mocker, CLEAR, 20), 0);
+
+        // the channel has been clear for long enough already
+        mocker.post("tx", Pmt::Blob(vec![1, 2, 3])).unwrap();
+        assert_eq!(feed(&mut mocker, CLEAR, 1), 1);
+        assert_eq!(mocker.messages()[0], vec![Pmt::Blob(vec![1, 2, 3])]);
+        assert_eq!(mocker.deferred(), 0);
+    }
+}
@@ -0,0 +1,91 @@
+use futuresdr::prelude::*;
+
+/// Convert complex samples to interleaved `re, im` floats.
+///
+/// Produces two output items per input item.
+#[derive(Block)]
+pub struct ComplexToFloat<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<f32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+}
+
+impl<I, O> ComplexToFloat<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    pub fn new() -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+        }
+    }
+}
+
+impl<I, O> Default for ComplexToFloat<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I, O> Kernel for ComplexToFloat<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = f32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _m: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let o = self.output.slice();
+        let i_len = i.len();
+        let n = std::cmp::min(i_len, o.len() / 2);
+
+        for (c, out) in i[0..n].iter().zip(o.chunks_exact_mut(2)) {
+            out[0] = c.re;
+            out[1] = c.im;
+        }
+
+        self.input.consume(n);
+        self.output.produce(2 * n);
+
+        if self.input.finished() && n == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    #[test]
+    fn interleaves() {
+        let mut block = ComplexToFloat::<Reader<_>, Writer<_>>::new();
+        block.input().set(vec![Complex32::new(1.0, 2.0), Complex32::new(-3.0, 4.5)]);
+        block.output().reserve(4);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let (output, _) = mocker.output.get();
+
+        assert_eq!(output, vec![1.0, 2.0, -3.0, 4.5]);
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::bail;
+use futuresdr::prelude::*;
+use std::collections::HashMap;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Append [`Pmt::MapStrPmt`] messages as rows to a CSV file.
+///
+/// Columns are taken from the (sorted) keys of the first message. Later messages with a
+/// different set of keys are an error, unless the sink is `flexible`, in which case missing
+/// columns are left empty and unknown keys are ignored.
+#[derive(Block)]
+#[message_inputs(r#in)]
+pub struct CsvSink {
+    path: PathBuf,
+    flexible: bool,
+    file: Option<File>,
+    columns: Option<Vec<String>>,
+    n_rows: u64,
+}
+
+impl CsvSink {
+    pub fn new(path: impl Into<PathBuf>, flexible: bool) -> Self {
+        Self {
+            path: path.into(),
+            flexible,
+            file: None,
+            columns: None,
+            n_rows: 0,
+        }
+    }
+
+    /// Number of rows written
+    pub fn n_rows(&self) -> u64 {
+        self.n_rows
+    }
+
+    fn escape(field: &str) -> String {
+        if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+
+    fn format_value(p: &Pmt) -> String {
+        match p {
+            Pmt::String(s) => Self::escape(s),
+            p => Self::escape(&p.to_string()),
+        }
+    }
+
+    fn write_row(&mut self, map: HashMap<String, Pmt>) -> Result<()> {
+        if self.columns.is_none() {
+            let mut columns: Vec<String> = map.keys().cloned().collect();
+            columns.sort();
+
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            if file.metadata()?.len() == 0 {
+                let header: Vec<String> = columns.iter().map(|c| Self::escape(c)).collect();
+                writeln!(file, "{}", header.join(","))?;
+            }
+            self.file = Some(file);
+            self.columns = Some(columns);
+        }
+
+        let columns = self.columns.as_ref().unwrap();
+        if !self.flexible
+            && (map.len() != columns.len() || columns.iter().any(|c| !map.contains_key(c)))
+        {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            bail!(
+                "CsvSink: message keys {:?} do not match columns {:?}",
+                keys,
+                columns
+            );
+        }
+
+        let row: Vec<String> = columns
+            .iter()
+            .map(|c| map.get(c).map(Self::format_value).unwrap_or_default())
+            .collect();
+        let file = self.file.as_mut().unwrap();
+        writeln!(file, "{}", row.join(","))?;
+        file.flush()?;
+        self.n_rows += 1;
+        Ok(())
+    }
+
+    async fn r#in(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::MapStrPmt(map) => {
+                self.write_row(map)?;
+            }
+            Pmt::Finished => {
+                io.finished = true;
+            }
+            _ => {
+                warn!("CsvSink: received wrong PMT type (expected Pmt::MapStrPmt). {:?}", p);
+            }
+        }
+        Ok(Pmt::Ok)
+    }
+}
+
+impl Kernel for CsvSink {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+
+    fn row(per: f64, received: u64) -> Pmt {
+        Pmt::MapStrPmt(HashMap::from([
+            ("per".to_string(), Pmt::F64(per)),
+            ("received".to_string(), Pmt::U64(received)),
+        ]))
+    }
+
+    #[test]
+    fn writes_header_and_rows() {
+        let path = std::env::temp_dir().join(format!("csv_sink_rows_{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut mocker = Mocker::new(CsvSink::new(&path, false));
+        mocker.post("in", row(0.5, 10)).unwrap();
+        mocker.post("in", row(0.25, 20)).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(content, "per,received\n0.5,10\n0.25,20\n");
+    }
+
+    #[test]
+    fn rejects_different_keys() {
+        let path = std::env::temp_dir().join(format!("csv_sink_keys_{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let other = Pmt::MapStrPmt(HashMap::from([("snr".to_string(), Pmt::F32(3.0))]));
+
+        let mut mocker = Mocker::new(CsvSink::new(&path, false));
+        mocker.post("in", row(0.5, 10)).unwrap();
+        assert!(mocker.post("in", other.clone()).is_err());
+
+        let _ = std::fs::remove_file(&path);
+        let mut mocker = Mocker::new(CsvSink::new(&path, true));
+        mocker.post("in", row(0.5, 10)).unwrap();
+        mocker.post("in", other).unwrap();
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(content, "per,received\n0.5,10\n,\n");
+    }
+}
@@ -0,0 +1,167 @@
+use futuresdr::prelude::*;
+use regex::bytes::Regex;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+/// Drop frames with a sequence number that was seen recently.
+///
+/// Extracts the sequence number of each frame on `in` (`Pmt::Blob` or `Pmt::String`) with a
+/// regex, whose first matching capture group has to be a decimal number. Frames with one of
+/// the last `window` distinct sequence numbers are dropped, and the number of dropped frames
+/// so far is posted on `duplicates` (`Pmt::U64`). Unique frames, including frames without a
+/// sequence number, are forwarded to `out`, as is `Pmt::Finished`, which also terminates the
+/// block.
+///
+/// [`Dedup::FUTURESDR`] matches the `FutureSDR {seq}` payloads of the TX examples and
+/// [`Dedup::LOAD`] the `loadGGSSSS` payloads of the PER tests (keyed by `GGSSSS`, so frames of
+/// different groups are kept apart). [`Dedup::DEFAULT`] matches both.
+#[derive(Block)]
+#[message_inputs(r#in)]
+#[message_outputs(out, duplicates)]
+#[null_kernel]
+pub struct Dedup {
+    pattern: Regex,
+    window: usize,
+    recent: VecDeque<u64>,
+    seen: HashSet<u64>,
+    duplicates: u64,
+}
+
+impl Dedup {
+    /// `FutureSDR {seq}`
+    pub const FUTURESDR: &str = r"FutureSDR (\d+)";
+    /// `loadGGSSSS`, with group `GG` and sequence number `SSSS`
+    pub const LOAD: &str = r"load(\d{6})";
+    /// [`Dedup::FUTURESDR`] or [`Dedup::LOAD`]
+    pub const DEFAULT: &str = r"FutureSDR (\d+)|load(\d{6})";
+
+    /// Create a block remembering the last `window` sequence numbers, extracted with `pattern`
+    pub fn new(window: usize, pattern: &str) -> std::result::Result<Self, regex::Error> {
+        assert!(window >= 1);
+        Ok(Self {
+            pattern: Regex::new(pattern)?,
+            window,
+            recent: VecDeque::with_capacity(window),
+            seen: HashSet::with_capacity(window),
+            duplicates: 0,
+        })
+    }
+
+    /// Sequence number of a frame, if the pattern matches
+    fn sequence(&self, frame: &[u8]) -> Option<u64> {
+        let captures = self.pattern.captures(frame)?;
+        let seq = captures.iter().skip(1).flatten().next()?;
+        std::str::from_utf8(seq.as_bytes()).ok()?.parse().ok()
+    }
+
+    /// Remember a sequence number, `false` if it is a duplicate
+    fn insert(&mut self, seq: u64) -> bool {
+        if !self.seen.insert(seq) {
+            return false;
+        }
+        self.recent.push_back(seq);
+        if self.recent.len() > self.window {
+            let old = self.recent.pop_front().unwrap();
+            self.seen.remove(&old);
+        }
+        true
+    }
+
+    async fn r#in(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        let seq = match &p {
+            Pmt::Finished => {
+                mio.post("out", Pmt::Finished).await?;
+                io.finished = true;
+                return Ok(Pmt::Ok);
+            }
+            Pmt::Blob(b) => self.sequence(b),
+            Pmt::String(s) => self.sequence(s.as_bytes()),
+            _ => return Ok(Pmt::InvalidValue),
+        };
+
+        if let Some(seq) = seq
+            && !self.insert(seq)
+        {
+            self.duplicates += 1;
+            mio.post("duplicates", Pmt::U64(self.duplicates)).await?;
+            return Ok(Pmt::Ok);
+        }
+        mio.post("out", p).await?;
+        Ok(Pmt::Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+
+    fn frame(payload: &str) -> Pmt {
+        // MAC header in front of the payload
+        let mut frame = vec![0x41, 0x88, 0x07, 0xaa, 0x1a];
+        frame.extend_from_slice(payload.as_bytes());
+        Pmt::Blob(frame)
+    }
+
+    #[test]
+    fn drops_duplicates() {
+        let mut mocker = Mocker::new(Dedup::new(2, Dedup::DEFAULT).unwrap());
+        for payload in [
+            "FutureSDR 1",
+            "FutureSDR 1",
+            "FutureSDR 2",
+            "load010002",
+            "load020002",
+            "load010002",
+            // 1 dropped out of the window
+            "FutureSDR 1",
+            "no sequence",
+            "no sequence",
+        ] {
+            assert_eq!(mocker.post("in", frame(payload)).unwrap(), Pmt::Ok);
+        }
+        mocker
+            .post("in", Pmt::String("FutureSDR 1".to_string()))
+            .unwrap();
+        assert_eq!(mocker.post("in", Pmt::U32(1)).unwrap(), Pmt::InvalidValue);
+        mocker.post("in", Pmt::Finished).unwrap();
+        mocker.run();
+
+        let [out, duplicates] = mocker.messages().try_into().unwrap();
+        let expected: Vec<Pmt> = [
+            "FutureSDR 1",
+            "FutureSDR 2",
+            "load010002",
+            "load020002",
+            "FutureSDR 1",
+            "no sequence",
+            "no sequence",
+        ]
+        .into_iter()
+        .map(frame)
+        .chain([Pmt::Finished])
+        .collect();
+        assert_eq!(out, expected);
+        assert_eq!(duplicates, vec![Pmt::U64(1), Pmt::U64(2), Pmt::U64(3)]);
+    }
+
+    #[test]
+    fn custom_pattern() {
+        let mut mocker = Mocker::new(Dedup::new(8, r"seq=(\d+)").unwrap());
+        mocker.post("in", frame("a seq=7")).unwrap();
+        mocker.post("in", frame("b seq=7")).unwrap();
+        mocker.post("in", frame("FutureSDR 7")).unwrap();
+        mocker.run();
+        let [out, duplicates] = mocker.messages().try_into().unwrap();
+        assert_eq!(out, vec![frame("a seq=7"), frame("FutureSDR 7")]);
+        assert_eq!(duplicates, vec![Pmt::U64(1)]);
+
+        assert!(Dedup::new(8, "seq=(").is_err());
+    }
+}
@@ -0,0 +1,193 @@
+use futuresdr::prelude::*;
+use std::collections::HashMap;
+use std::time::Duration;
+use web_time::Instant;
+
+/// Largest number of fragments of a frame, as the 802.11 fragment number has 4 bits
+const MAX_FRAGMENTS: usize = 16;
+
+/// Fragments of a frame received so far
+struct Partial {
+    fragments: Vec<Option<Vec<u8>>>,
+    received: usize,
+    first: Instant,
+}
+
+/// Reassemble fragmented frames.
+///
+/// Expects fragments on `in` as `Pmt::MapStrPmt` with `seq`, `frag_index`, and `frag_total`
+/// (integers) and `payload` (`Pmt::Blob`). Fragments are buffered per sequence number, in any
+/// order, and once all `frag_total` fragments of a sequence number arrived, their payloads are
+/// posted on `out` as one `Pmt::Blob`, in fragment order. A repeated fragment replaces the
+/// earlier copy. Fragments of sets with more than 16 fragments, the limit of the 4-bit 802.11
+/// fragment number, are rejected with `Pmt::InvalidValue`.
+///
+/// Sets that are still incomplete `timeout` after their first fragment are dropped when the
+/// next fragment arrives, as are all incomplete sets on `Pmt::Finished`, which is forwarded
+/// and terminates the block. The number of dropped sets so far is posted on `incomplete`
+/// (`Pmt::U64`).
+#[derive(Block)]
+#[message_inputs(r#in)]
+#[message_outputs(out, incomplete)]
+#[null_kernel]
+pub struct Defragment {
+    timeout: Duration,
+    partial: HashMap<u64, Partial>,
+    incomplete: u64,
+}
+
+impl Defragment {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            partial: HashMap::new(),
+            incomplete: 0,
+        }
+    }
+
+    /// Number of sets that are waiting for fragments
+    pub fn pending(&self) -> usize {
+        self.partial.len()
+    }
+
+    /// Fragment fields `(seq, frag_index, frag_total, payload)`
+    fn fragment(p: &Pmt) -> Option<(u64, usize, usize, &Vec<u8>)> {
+        let Pmt::MapStrPmt(m) = p else {
+            return None;
+        };
+        let int = |key: &str| match m.get(key)? {
+            Pmt::U32(v) => Some(*v as u64),
+            Pmt::U64(v) => Some(*v),
+            Pmt::Usize(v) => Some(*v as u64),
+            _ => None,
+        };
+        let Some(Pmt::Blob(payload)) = m.get("payload") else {
+            return None;
+        };
+        let index = usize::try_from(int("frag_index")?).ok()?;
+        let total = usize::try_from(int("frag_total")?).ok()?;
+        (index < total && total <= MAX_FRAGMENTS).then_some((int("seq")?, index, total, payload))
+    }
+
+    /// Drop incomplete sets older than the timeout (all if `all`) and report them
+    async fn expire(&mut self, mio: &mut MessageOutputs, all: bool) -> Result<()> {
+        let timeout = self.timeout;
+        let before = self.partial.len();
+        self.partial.retain(|_, p| !all && p.first.elapsed() < timeout);
+        let dropped = before - self.partial.len();
+        if dropped > 0 {
+            self.incomplete += dropped as u64;
+            mio.post("incomplete", Pmt::U64(self.incomplete)).await?;
+        }
+        Ok(())
+    }
+
+    async fn r#in(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if let Pmt::Finished = p {
+            self.expire(mio, true).await?;
+            mio.post("out", Pmt::Finished).await?;
+            io.finished = true;
+            return Ok(Pmt::Ok);
+        }
+        let Some((seq, index, total, payload)) = Self::fragment(&p) else {
+            return Ok(Pmt::InvalidValue);
+        };
+
+        self.expire(mio, false).await?;
+
+        let partial = self.partial.entry(seq).or_insert_with(|| Partial {
+            fragments: vec![None; total],
+            received: 0,
+            first: Instant::now(),
+        });
+        if partial.fragments.len() != total {
+            return Ok(Pmt::InvalidValue);
+        }
+        if partial.fragments[index].replace(payload.clone()).is_none() {
+            partial.received += 1;
+        }
+
+        if partial.received == total {
+            let partial = self.partial.remove(&seq).unwrap();
+            let frame = partial.fragments.into_iter().flatten().flatten().collect();
+            mio.post("out", Pmt::Blob(frame)).await?;
+        }
+        Ok(Pmt::Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+
+    fn fragment(seq: u64, index: usize, total: usize, payload: &[u8]) -> Pmt {
+        Pmt::MapStrPmt(HashMap::from([
+            ("seq".to_string(), Pmt::U64(seq)),
+            ("frag_index".to_string(), Pmt::Usize(index)),
+            ("frag_total".to_string(), Pmt::Usize(total)),
+            ("payload".to_string(), Pmt::Blob(payload.to_vec())),
+        ]))
+    }
+
+    #[test]
+    fn reassembles_out_of_order() {
+        let mut mocker = Mocker::new(Defragment::new(Duration::from_secs(10)));
+        for f in [
+            fragment(1, 2, 3, b"ghi"),
+            fragment(2, 1, 2, b"23"),
+            fragment(1, 0, 3, b"abc"),
+            fragment(2, 0, 2, b"01"),
+            fragment(3, 0, 1, b"x"),
+            fragment(1, 1, 3, b"def"),
+        ] {
+            assert_eq!(mocker.post("in", f).unwrap(), Pmt::Ok);
+        }
+        assert_eq!(mocker.post("in", fragment(4, 2, 2, b"")).unwrap(), Pmt::InvalidValue);
+        assert_eq!(
+            mocker.post("in", fragment(5, 0, usize::MAX, b"")).unwrap(),
+            Pmt::InvalidValue
+        );
+        assert_eq!(
+            mocker.post("in", fragment(6, 0, 17, b"")).unwrap(),
+            Pmt::InvalidValue
+        );
+        assert_eq!(mocker.pending(), 0);
+        assert_eq!(mocker.post("in", Pmt::Blob(vec![1])).unwrap(), Pmt::InvalidValue);
+        mocker.run();
+
+        let [out, incomplete] = mocker.messages().try_into().unwrap();
+        assert_eq!(
+            out,
+            vec![
+                Pmt::Blob(b"0123".to_vec()),
+                Pmt::Blob(b"x".to_vec()),
+                Pmt::Blob(b"abcdefghi".to_vec()),
+            ]
+        );
+        assert!(incomplete.is_empty());
+    }
+
+    #[test]
+    fn drops_incomplete_sets() {
+        let mut mocker = Mocker::new(Defragment::new(Duration::from_millis(20)));
+        mocker.post("in", fragment(1, 0, 2, b"ab")).unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        // expires the first set, whose second fragment then starts a new one
+        mocker.post("in", fragment(2, 0, 2, b"cd")).unwrap();
+        mocker.post("in", fragment(1, 1, 2, b"ef")).unwrap();
+        mocker.post("in", fragment(2, 1, 2, b"gh")).unwrap();
+        mocker.post("in", Pmt::Finished).unwrap();
+        mocker.run();
+
+        let [out, incomplete] = mocker.messages().try_into().unwrap();
+        assert_eq!(out, vec![Pmt::Blob(b"cdgh".to_vec()), Pmt::Finished]);
+        assert_eq!(incomplete, vec![Pmt::U64(1), Pmt::U64(2)]);
+    }
+}
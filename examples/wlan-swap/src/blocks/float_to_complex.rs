@@ -0,0 +1,132 @@
+use futuresdr::prelude::*;
+
+/// Convert interleaved `re, im` floats to complex samples.
+///
+/// Pairs consecutive input items. If a buffer ends in the middle of a pair, the trailing
+/// sample is kept until the next call.
+#[derive(Block)]
+pub struct FloatToComplex<I = DefaultCpuReader<f32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    pending: Option<f32>,
+}
+
+impl<I, O> FloatToComplex<I, O>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    pub fn new() -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            pending: None,
+        }
+    }
+}
+
+impl<I, O> Default for FloatToComplex<I, O>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I, O> Kernel for FloatToComplex<I, O>
+where
+    I: CpuBufferReader<Item = f32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _m: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let o = self.output.slice();
+        let i_len = i.len();
+
+        let mut consumed = 0;
+        let mut produced = 0;
+
+        if let Some(re) = self.pending
+            && i_len > 0
+            && !o.is_empty()
+        {
+            o[0] = Complex32::new(re, i[0]);
+            self.pending = None;
+            consumed = 1;
+            produced = 1;
+        }
+
+        if self.pending.is_none() {
+            let n = std::cmp::min((i_len - consumed) / 2, o.len() - produced);
+            for k in 0..n {
+                o[produced + k] = Complex32::new(i[consumed + 2 * k], i[consumed + 2 * k + 1]);
+            }
+            consumed += 2 * n;
+            produced += n;
+
+            if i_len - consumed == 1 {
+                self.pending = Some(i[consumed]);
+                consumed += 1;
+            }
+        }
+
+        self.input.consume(consumed);
+        self.output.produce(produced);
+
+        if self.input.finished() && consumed == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blocks::ComplexToFloat;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    #[test]
+    fn round_trip() {
+        let input: Vec<Complex32> = (0..16)
+            .map(|k| Complex32::new(k as f32, -(k as f32) / 2.0))
+            .collect();
+
+        let mut block = ComplexToFloat::<Reader<_>, Writer<_>>::new();
+        block.input().set(input.clone());
+        block.output().reserve(2 * input.len());
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let (floats, _) = mocker.output().get();
+        assert_eq!(floats.len(), 2 * input.len());
+
+        // split in the middle of a pair to exercise the buffered trailing sample
+        let mut block = FloatToComplex::<Reader<_>, Writer<_>>::new();
+        block.output().reserve(input.len());
+        let mut mocker = Mocker::new(block);
+        mocker.input.set(floats[0..7].to_vec());
+        mocker.run();
+        assert_eq!(mocker.output.get().0.len(), 3);
+        mocker.input.set(floats[7..].to_vec());
+        mocker.run();
+        let (output, _) = mocker.output.get();
+
+        assert_eq!(output, input);
+    }
+}
@@ -0,0 +1,122 @@
+use futuresdr::prelude::*;
+use std::collections::HashMap;
+
+/// Count the frames passing through a message connection.
+///
+/// Forwards each message from `in` to `out` and counts it, along with the bytes of
+/// `Pmt::Blob` frames. The `summary` port returns the counts as `Pmt::MapStrPmt` with `frames`
+/// and `bytes` (`Pmt::U64`). The totals are logged when the block terminates, e.g., when the
+/// flowgraph is stopped for a switch. `Pmt::Finished` terminates the block.
+#[derive(Block)]
+#[message_inputs(r#in, summary)]
+#[message_outputs(out)]
+pub struct FrameCounter {
+    frames: u64,
+    bytes: u64,
+}
+
+impl FrameCounter {
+    pub fn new() -> Self {
+        Self {
+            frames: 0,
+            bytes: 0,
+        }
+    }
+
+    /// Number of forwarded frames
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Number of bytes in forwarded `Pmt::Blob` frames
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    async fn r#in(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Finished => {
+                io.finished = true;
+            }
+            p => {
+                self.frames += 1;
+                if let Pmt::Blob(b) = &p {
+                    self.bytes += b.len() as u64;
+                }
+                mio.post("out", p).await?;
+            }
+        }
+        Ok(Pmt::Ok)
+    }
+
+    async fn summary(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => Ok(Pmt::MapStrPmt(HashMap::from([
+                ("frames".to_string(), Pmt::U64(self.frames)),
+                ("bytes".to_string(), Pmt::U64(self.bytes)),
+            ]))),
+            _ => Ok(Pmt::InvalidValue),
+        }
+    }
+}
+
+impl Default for FrameCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Kernel for FrameCounter {
+    async fn deinit(&mut self, _mio: &mut MessageOutputs, meta: &mut BlockMeta) -> Result<()> {
+        info!(
+            "{}: {} frames ({} bytes)",
+            meta.instance_name().unwrap_or("FrameCounter"),
+            self.frames,
+            self.bytes
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+
+    #[test]
+    fn counts_frames() {
+        let mut mocker = Mocker::new(FrameCounter::new());
+        mocker.post("in", Pmt::Blob(vec![1, 2, 3])).unwrap();
+        mocker.post("in", Pmt::Blob(vec![4])).unwrap();
+        mocker.post("in", Pmt::String("frame".to_string())).unwrap();
+
+        let Pmt::MapStrPmt(summary) = mocker.post("summary", Pmt::Null).unwrap() else {
+            panic!("expected a map");
+        };
+        assert_eq!(summary["frames"], Pmt::U64(3));
+        assert_eq!(summary["bytes"], Pmt::U64(4));
+        assert_eq!(mocker.post("summary", Pmt::U32(1)).unwrap(), Pmt::InvalidValue);
+
+        mocker.run();
+        assert_eq!(
+            mocker.messages()[0],
+            vec![
+                Pmt::Blob(vec![1, 2, 3]),
+                Pmt::Blob(vec![4]),
+                Pmt::String("frame".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use futuresdr::prelude::*;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+/// Frame counts of a [`FrameStats`] block
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FrameSummary {
+    /// Decoded frames, i.e., `fcs_ok + fcs_fail`
+    pub frames: u64,
+    pub fcs_ok: u64,
+    pub fcs_fail: u64,
+    /// Reports that could not be interpreted
+    pub invalid: u64,
+    /// Decoded frames per MCS, if the decoder reports it
+    pub mcs: BTreeMap<String, u64>,
+}
+
+impl FrameSummary {
+    pub fn to_pmt(&self) -> Pmt {
+        let mcs = self
+            .mcs
+            .iter()
+            .map(|(m, n)| (m.clone(), Pmt::U64(*n)))
+            .collect();
+        Pmt::MapStrPmt(HashMap::from([
+            ("frames".to_string(), Pmt::U64(self.frames)),
+            ("fcs_ok".to_string(), Pmt::U64(self.fcs_ok)),
+            ("fcs_fail".to_string(), Pmt::U64(self.fcs_fail)),
+            ("invalid".to_string(), Pmt::U64(self.invalid)),
+            ("mcs".to_string(), Pmt::MapStrPmt(mcs)),
+        ]))
+    }
+}
+
+/// Frame statistics of a receiver.
+///
+/// Counts the per-frame reports of a decoder on `in`, either `Pmt::Bool` (FCS valid, e.g., the
+/// ZigBee decoder's `frame_ok`) or `Pmt::MapStrPmt` with `crc_ok` and an optional `mcs` string
+/// (the WiFi decoder's `frame_status`). The `report` port returns the [`FrameSummary`] as
+/// `Pmt::MapStrPmt`. Once `in` finishes, the final report is posted on `summary` and the block
+/// terminates.
+#[derive(Block)]
+#[message_inputs(r#in, report)]
+#[message_outputs(summary)]
+#[null_kernel]
+pub struct FrameStats {
+    summary: FrameSummary,
+}
+
+impl FrameStats {
+    pub fn new() -> Self {
+        Self {
+            summary: FrameSummary::default(),
+        }
+    }
+
+    pub fn summary(&self) -> &FrameSummary {
+        &self.summary
+    }
+
+    fn add(&mut self, crc_ok: bool, mcs: Option<&str>) {
+        self.summary.frames += 1;
+        if crc_ok {
+            self.summary.fcs_ok += 1;
+        } else {
+            self.summary.fcs_fail += 1;
+        }
+        if let Some(mcs) = mcs {
+            *self.summary.mcs.entry(mcs.to_string()).or_default() += 1;
+        }
+    }
+
+    async fn r#in(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Finished => {
+                mio.post("summary", self.summary.to_pmt()).await?;
+                io.finished = true;
+            }
+            Pmt::Bool(crc_ok) => self.add(crc_ok, None),
+            Pmt::MapStrPmt(map) => match (map.get("crc_ok"), map.get("mcs")) {
+                (Some(Pmt::Bool(crc_ok)), Some(Pmt::String(mcs))) => self.add(*crc_ok, Some(mcs)),
+                (Some(Pmt::Bool(crc_ok)), None) => self.add(*crc_ok, None),
+                _ => self.summary.invalid += 1,
+            },
+            _ => self.summary.invalid += 1,
+        }
+        Ok(Pmt::Ok)
+    }
+
+    async fn report(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(self.summary.to_pmt())
+    }
+}
+
+impl Default for FrameStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+
+    fn status(crc_ok: bool, mcs: &str) -> Pmt {
+        Pmt::MapStrPmt(HashMap::from([
+            ("crc_ok".to_string(), Pmt::Bool(crc_ok)),
+            ("mcs".to_string(), Pmt::String(mcs.to_string())),
+            ("psdu_size".to_string(), Pmt::Usize(100)),
+        ]))
+    }
+
+    #[test]
+    fn counts_frames() {
+        let mut mocker = Mocker::new(FrameStats::new());
+        mocker.post("in", Pmt::Bool(true)).unwrap();
+        mocker.post("in", Pmt::Bool(false)).unwrap();
+        mocker.post("in", status(true, "Qpsk_1_2")).unwrap();
+        mocker.post("in", status(true, "Qpsk_1_2")).unwrap();
+        mocker.post("in", status(false, "Qam16_3_4")).unwrap();
+        mocker.post("in", Pmt::U32(1)).unwrap();
+        mocker.post("in", Pmt::Finished).unwrap();
+
+        let expected = FrameSummary {
+            frames: 5,
+            fcs_ok: 3,
+            fcs_fail: 2,
+            invalid: 1,
+            mcs: BTreeMap::from([("Qam16_3_4".to_string(), 1), ("Qpsk_1_2".to_string(), 2)]),
+        };
+        assert_eq!(mocker.summary(), &expected);
+        assert_eq!(mocker.post("report", Pmt::Null).unwrap(), expected.to_pmt());
+        mocker.run();
+        let [summary] = mocker.messages().try_into().unwrap();
+        assert_eq!(summary, vec![expected.to_pmt()]);
+    }
+}
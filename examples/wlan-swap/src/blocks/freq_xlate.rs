@@ -0,0 +1,183 @@
+use futuresdr::prelude::*;
+use std::f64::consts::PI;
+
+/// Frequency shift (numerically-controlled oscillator mixer).
+///
+/// Multiplies the input with `exp(-j*2*pi*freq_hz/sample_rate*n)`, i.e., a signal at
+/// `freq_hz` is moved to baseband. The oscillator phase is kept across work calls. The `freq`
+/// message port retunes the oscillator at runtime (`Pmt::F64` or `Pmt::F32` in Hz) and returns
+/// the current frequency when called with `Pmt::Null`.
+#[derive(Block)]
+#[message_inputs(freq)]
+pub struct FreqXlate<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    freq_hz: f64,
+    sample_rate: f64,
+    phase: f64,
+    phase_inc: f64,
+}
+
+impl<I, O> FreqXlate<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    pub fn new(freq_hz: f64, sample_rate: f64) -> Self {
+        assert!(sample_rate > 0.0 && sample_rate.is_finite());
+        assert!(freq_hz.is_finite());
+        Self {
+            input: I::default(),
+            output: O::default(),
+            freq_hz,
+            sample_rate,
+            phase: 0.0,
+            phase_inc: Self::phase_inc(freq_hz, sample_rate),
+        }
+    }
+
+    fn phase_inc(freq_hz: f64, sample_rate: f64) -> f64 {
+        -2.0 * PI * freq_hz / sample_rate
+    }
+
+    async fn freq(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        let freq_hz = match p {
+            Pmt::F64(f) if f.is_finite() => f,
+            Pmt::F32(f) if f.is_finite() => f as f64,
+            Pmt::Null => return Ok(Pmt::F64(self.freq_hz)),
+            _ => return Ok(Pmt::InvalidValue),
+        };
+        self.freq_hz = freq_hz;
+        self.phase_inc = Self::phase_inc(freq_hz, self.sample_rate);
+        Ok(Pmt::Ok)
+    }
+}
+
+impl<I, O> Kernel for FreqXlate<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _m: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let o = self.output.slice();
+        let i_len = i.len();
+        let n = std::cmp::min(i_len, o.len());
+
+        for (x, y) in i[0..n].iter().zip(o[0..n].iter_mut()) {
+            let (sin, cos) = self.phase.sin_cos();
+            *y = x * Complex32::new(cos as f32, sin as f32);
+            self.phase = (self.phase + self.phase_inc) % (2.0 * PI);
+        }
+
+        self.input.consume(n);
+        self.output.produce(n);
+
+        if self.input.finished() && n == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    const FS: f64 = 8000.0;
+    const N: usize = 80;
+
+    fn tone(freq: f64, len: usize) -> Vec<Complex32> {
+        (0..len)
+            .map(|k| Complex32::from_polar(1.0, (2.0 * PI * freq / FS * k as f64) as f32))
+            .collect()
+    }
+
+    /// Index of the strongest DFT bin
+    fn peak_bin(x: &[Complex32]) -> usize {
+        (0..x.len())
+            .max_by(|a, b| {
+                let power = |bin: usize| {
+                    x.iter()
+                        .enumerate()
+                        .map(|(k, s)| {
+                            let phase = -2.0 * PI * (bin * k) as f64 / x.len() as f64;
+                            s * Complex32::from_polar(1.0, phase as f32)
+                        })
+                        .sum::<Complex32>()
+                        .norm()
+                };
+                power(*a).total_cmp(&power(*b))
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn shifts_tone() {
+        let input = tone(2000.0, N);
+        assert_eq!(peak_bin(&input), 20);
+
+        let mut block = FreqXlate::<Reader<_>, Writer<_>>::new(1500.0, FS);
+        block.input().set(input);
+        block.output().reserve(N);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let (output, _) = mocker.output.get();
+
+        // 100 Hz per bin
+        assert_eq!(peak_bin(&output), 5);
+    }
+
+    #[test]
+    fn phase_continuity() {
+        let input = tone(1000.0, N);
+
+        let mut block = FreqXlate::<Reader<_>, Writer<_>>::new(250.0, FS);
+        block.input().set(input[0..37].to_vec());
+        block.output().reserve(N);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        mocker.input.set(input[37..].to_vec());
+        mocker.run();
+        let (output, _) = mocker.output.get();
+
+        let expected = tone(750.0, N);
+        assert_eq!(output.len(), N);
+        for (o, e) in output.iter().zip(expected.iter()) {
+            assert!((o - e).norm() < 1e-4, "{o} != {e}");
+        }
+    }
+
+    #[test]
+    fn retune() {
+        let mut mocker = Mocker::new(FreqXlate::<Reader<_>, Writer<_>>::new(250.0, FS));
+        assert!(matches!(mocker.post("freq", Pmt::F64(-100.0)).unwrap(), Pmt::Ok));
+        assert!(matches!(mocker.post("freq", Pmt::Null).unwrap(), Pmt::F64(f) if f == -100.0));
+        assert!(matches!(
+            mocker.post("freq", Pmt::F64(f64::NAN)).unwrap(),
+            Pmt::InvalidValue
+        ));
+        assert!(matches!(mocker.post("freq", Pmt::Null).unwrap(), Pmt::F64(f) if f == -100.0));
+    }
+}
@@ -0,0 +1,116 @@
+use futuresdr::async_io::Timer;
+use futuresdr::prelude::*;
+use std::time::Duration;
+use web_time::Instant;
+use web_time::SystemTime;
+use web_time::UNIX_EPOCH;
+
+/// Periodically post a heartbeat.
+///
+/// Posts `{"type":"heartbeat","ts":...}` as `Pmt::String` on `out` once per `interval`, with
+/// `ts` the wall-clock time in milliseconds since the Unix epoch (see
+/// [`RxMessage::Heartbeat`](crate::loader::RxMessage::Heartbeat)). Connected to the RX
+/// WebSocket sink, it lets the GUI tell an idle backend from a dead one.
+#[derive(Block)]
+#[message_outputs(out)]
+pub struct Heartbeat {
+    interval: Duration,
+    t_next: Instant,
+}
+
+impl Heartbeat {
+    pub fn new(interval: Duration) -> Self {
+        assert!(!interval.is_zero());
+        Self {
+            interval,
+            t_next: Instant::now(),
+        }
+    }
+}
+
+/// Heartbeat envelope for the given time stamp
+fn heartbeat_message(ts: u64) -> String {
+    format!(r#"{{"type":"heartbeat","ts":{}}}"#, ts)
+}
+
+impl Kernel for Heartbeat {
+    async fn init(&mut self, _mio: &mut MessageOutputs, _meta: &mut BlockMeta) -> Result<()> {
+        self.t_next = Instant::now();
+        Ok(())
+    }
+
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let now = Instant::now();
+        if now >= self.t_next {
+            let ts = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+            mio.post("out", Pmt::String(heartbeat_message(ts))).await?;
+            self.t_next += self.interval;
+            // do not catch up on missed heartbeats, e.g., after the process was suspended
+            if self.t_next < now {
+                self.t_next = now + self.interval;
+            }
+        }
+
+        let wait = self.t_next.saturating_duration_since(Instant::now());
+        io.block_on(async move {
+            Timer::after(wait).await;
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loader::RxMessage;
+    use futuresdr::runtime::mocker::Mocker;
+
+    #[test]
+    fn emits_at_interval() {
+        let interval = Duration::from_millis(20);
+        let mut mocker = Mocker::new(Heartbeat::new(interval));
+        mocker.init();
+        let start = Instant::now();
+        while mocker.messages()[0].len() < 4 {
+            assert!(start.elapsed() < Duration::from_secs(5), "no heartbeats");
+            mocker.run();
+            std::thread::sleep(Duration::from_millis(2));
+        }
+        // the first heartbeat is immediate, the others may be late but are never early
+        assert!(start.elapsed() >= 3 * interval);
+
+        let ts: Vec<u64> = mocker.messages()[0]
+            .iter()
+            .map(|p| match p {
+                Pmt::String(s) => match RxMessage::parse(s) {
+                    Some(RxMessage::Heartbeat(ts)) => ts,
+                    m => panic!("expected a heartbeat, got {:?}", m),
+                },
+                p => panic!("expected a string, got {:?}", p),
+            })
+            .collect();
+        assert!(ts.windows(2).all(|w| w[0] <= w[1]), "{ts:?}");
+    }
+
+    #[test]
+    fn does_not_catch_up() {
+        let interval = Duration::from_millis(20);
+        let mut mocker = Mocker::new(Heartbeat::new(interval));
+        mocker.init();
+        // like after the process was suspended for ten intervals
+        mocker.t_next = Instant::now().checked_sub(10 * interval).unwrap();
+
+        let before = Instant::now();
+        mocker.run();
+        assert_eq!(mocker.messages()[0].len(), 1);
+        assert!(mocker.t_next >= before + interval);
+    }
+}
@@ -0,0 +1,179 @@
+use anyhow::bail;
+use futuresdr::async_io::Timer;
+use futuresdr::prelude::*;
+use std::path::PathBuf;
+use std::time::Duration;
+use web_time::Instant;
+
+/// Post frames read from a file of hex lines.
+///
+/// Each line of the file holds one frame as hexadecimal digits, e.g., `41880701aa`, which is
+/// posted as `Pmt::Blob` on `out`, one frame per `interval`. Whitespace within a line is
+/// ignored, as are empty lines and lines starting with `#`. The file is read when the block is
+/// initialized, so a missing file or a malformed line fails the flowgraph on startup.
+///
+/// Without `repeat`, the block finishes after the last frame, which posts `Pmt::Finished` to
+/// the connected blocks. With `repeat`, it starts over with the first frame.
+#[derive(Block)]
+#[message_outputs(out)]
+pub struct HexFileSource {
+    path: PathBuf,
+    interval: Duration,
+    repeat: bool,
+    frames: Vec<Vec<u8>>,
+    index: usize,
+    t_next: Instant,
+}
+
+impl HexFileSource {
+    pub fn new(path: impl Into<PathBuf>, interval: Duration, repeat: bool) -> Self {
+        Self {
+            path: path.into(),
+            interval,
+            repeat,
+            frames: Vec::new(),
+            index: 0,
+            t_next: Instant::now(),
+        }
+    }
+
+    /// Number of frames read from the file
+    pub fn n_frames(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+/// Parse one frame per line, see [`HexFileSource`]
+fn parse_hex_lines(text: &str) -> std::result::Result<Vec<Vec<u8>>, String> {
+    let mut frames = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let digits: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+        if digits.is_empty() || digits.starts_with('#') {
+            continue;
+        }
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("line {}: '{}' is not a hexadecimal string", n + 1, line));
+        }
+        if !digits.len().is_multiple_of(2) {
+            return Err(format!("line {}: odd number of hex digits", n + 1));
+        }
+        frames.push(
+            (0..digits.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+                .collect(),
+        );
+    }
+    Ok(frames)
+}
+
+impl Kernel for HexFileSource {
+    async fn init(&mut self, _mio: &mut MessageOutputs, _meta: &mut BlockMeta) -> Result<()> {
+        let text = match std::fs::read_to_string(&self.path) {
+            Ok(text) => text,
+            Err(e) => bail!("HexFileSource: failed to read {:?}: {}", self.path, e),
+        };
+        self.frames = match parse_hex_lines(&text) {
+            Ok(frames) => frames,
+            Err(e) => bail!("HexFileSource: {:?}: {}", self.path, e),
+        };
+        self.index = 0;
+        self.t_next = Instant::now();
+        Ok(())
+    }
+
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        if self.frames.is_empty() {
+            io.finished = true;
+            return Ok(());
+        }
+
+        let now = Instant::now();
+        if now >= self.t_next {
+            mio.post("out", Pmt::Blob(self.frames[self.index].clone()))
+                .await?;
+            self.index += 1;
+            self.t_next = now + self.interval;
+            if self.index == self.frames.len() {
+                if !self.repeat {
+                    io.finished = true;
+                    return Ok(());
+                }
+                self.index = 0;
+            }
+        }
+
+        if self.interval.is_zero() {
+            io.call_again = true;
+        } else {
+            let wait = self.t_next.saturating_duration_since(Instant::now());
+            io.block_on(async move {
+                Timer::after(wait).await;
+            });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+
+    fn write_file(name: &str, text: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}_{}.txt", name, std::process::id()));
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn posts_frames() {
+        let path = write_file(
+            "hex_file_source_frames",
+            "# MAC payloads\n41 88 07 aa\n\nDEADbeef\n00\n",
+        );
+
+        let mut mocker = Mocker::new(HexFileSource::new(&path, Duration::ZERO, false));
+        mocker.init();
+        mocker.run();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            mocker.messages()[0],
+            vec![
+                Pmt::Blob(vec![0x41, 0x88, 0x07, 0xaa]),
+                Pmt::Blob(vec![0xde, 0xad, 0xbe, 0xef]),
+                Pmt::Blob(vec![0x00]),
+            ]
+        );
+    }
+
+    #[test]
+    fn repeats() {
+        let path = write_file("hex_file_source_repeat", "01\n02\n");
+
+        let mut mocker = Mocker::new(HexFileSource::new(&path, Duration::from_millis(1), true));
+        mocker.init();
+        std::fs::remove_file(&path).unwrap();
+        for _ in 0..5 {
+            mocker.run();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let frames: Vec<Pmt> = [1, 2, 1, 2, 1].map(|b| Pmt::Blob(vec![b])).into();
+        assert_eq!(mocker.messages()[0], frames);
+    }
+
+    #[test]
+    fn malformed_lines() {
+        let err = parse_hex_lines("0102\n01x2\n").unwrap_err();
+        assert!(err.starts_with("line 2:"), "{err}");
+        let err = parse_hex_lines("012\n").unwrap_err();
+        assert!(err.contains("odd number"), "{err}");
+    }
+}
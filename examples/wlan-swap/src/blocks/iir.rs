@@ -0,0 +1,193 @@
+use futuresdr::prelude::*;
+use std::ops::Add;
+use std::ops::Mul;
+use std::ops::Sub;
+
+/// Sample type of an [`Iir`] filter with real coefficients
+pub trait IirSample:
+    Copy + Default + Send + 'static + Add<Output = Self> + Sub<Output = Self> + Mul<f32, Output = Self>
+{
+}
+
+impl<T> IirSample for T where
+    T: Copy
+        + Default
+        + Send
+        + 'static
+        + Add<Output = T>
+        + Sub<Output = T>
+        + Mul<f32, Output = T>
+{
+}
+
+/// Normalized coefficients and state of an [`Iir`] filter
+struct IirCore<T> {
+    b: Vec<f32>,
+    a: Vec<f32>,
+    state: Vec<T>,
+}
+
+impl<T: IirSample> IirCore<T> {
+    fn filter(&mut self, x: T) -> T {
+        let y = x * self.b[0] + self.state.first().copied().unwrap_or_default();
+        let n = self.state.len();
+        for k in 0..n {
+            let next = if k + 1 < n {
+                self.state[k + 1]
+            } else {
+                T::default()
+            };
+            self.state[k] = x * self.b[k + 1] - y * self.a[k + 1] + next;
+        }
+        y
+    }
+}
+
+/// IIR filter with arbitrary coefficients, e.g., a biquad.
+///
+/// Computes `a[0] y[n] = b[0] x[n] + b[1] x[n-1] + ... - a[1] y[n-1] - a[2] y[n-2] - ...`
+/// (transposed direct form II), i.e., the transfer function `B(z) / A(z)` with the coefficient
+/// convention of SciPy and MATLAB. The coefficients are normalized by `a[0]`; `a` and `b` may
+/// have different lengths.
+#[derive(Block)]
+pub struct Iir<T, I = DefaultCpuReader<T>, O = DefaultCpuWriter<T>>
+where
+    T: IirSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    core: IirCore<T>,
+}
+
+impl<T, I, O> Iir<T, I, O>
+where
+    T: IirSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    /// Create a filter with feed-forward coefficients `b` and feedback coefficients `a`
+    ///
+    /// Both have to be non-empty and `a[0]` non-zero.
+    pub fn new(b: Vec<f32>, a: Vec<f32>) -> Self {
+        assert!(!b.is_empty() && !a.is_empty());
+        assert!(a[0] != 0.0);
+
+        let order = std::cmp::max(a.len(), b.len());
+        let mut b: Vec<f32> = b.iter().map(|c| c / a[0]).collect();
+        let mut a: Vec<f32> = a.iter().map(|c| c / a[0]).collect();
+        b.resize(order, 0.0);
+        a.resize(order, 0.0);
+
+        Self {
+            input: I::default(),
+            output: O::default(),
+            core: IirCore {
+                b,
+                a,
+                state: vec![T::default(); order - 1],
+            },
+        }
+    }
+}
+
+impl<T, I, O> Kernel for Iir<T, I, O>
+where
+    T: IirSample,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _m: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let o = self.output.slice();
+        let i_len = i.len();
+        let n = std::cmp::min(i_len, o.len());
+
+        for (x, y) in i[0..n].iter().zip(o.iter_mut()) {
+            *y = self.core.filter(*x);
+        }
+
+        self.input.consume(n);
+        self.output.produce(n);
+
+        if self.input.finished() && n == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    const N: usize = 200;
+
+    /// Low-pass biquad (RBJ cookbook, 1 kHz at 48 kHz, Q = 0.707), not normalized
+    fn biquad() -> ([f64; 3], [f64; 3]) {
+        let w0 = 2.0 * std::f64::consts::PI * 1000.0 / 48000.0;
+        let alpha = w0.sin() / (2.0 * 0.707);
+        let b1 = 1.0 - w0.cos();
+        (
+            [b1 / 2.0, b1, b1 / 2.0],
+            [1.0 + alpha, -2.0 * w0.cos(), 1.0 - alpha],
+        )
+    }
+
+    /// Step response in direct form I
+    fn reference(b: [f64; 3], a: [f64; 3]) -> Vec<f64> {
+        let mut x = [0.0; 3];
+        let mut y = [0.0; 3];
+        let mut out = Vec::new();
+        for _ in 0..N {
+            x = [1.0, x[0], x[1]];
+            let v = (b[0] * x[0] + b[1] * x[1] + b[2] * x[2] - a[1] * y[0] - a[2] * y[1]) / a[0];
+            y = [v, y[0], y[1]];
+            out.push(v);
+        }
+        out
+    }
+
+    #[test]
+    fn step_response() {
+        let (b, a) = biquad();
+        let expected = reference(b, a);
+        let taps = |c: [f64; 3]| c.iter().map(|v| *v as f32).collect::<Vec<f32>>();
+
+        let mut block = Iir::<f32, Reader<_>, Writer<_>>::new(taps(b), taps(a));
+        block.input().set(vec![1.0; N]);
+        block.output().reserve(N);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let (output, _) = mocker.output.get();
+        assert_eq!(output.len(), N);
+        for (y, e) in output.iter().zip(expected.iter()) {
+            assert!((*y as f64 - e).abs() < 1e-4, "{y} != {e}");
+        }
+        // unity DC gain
+        assert!((output[N - 1] - 1.0).abs() < 1e-3);
+
+        let mut block = Iir::<Complex32, Reader<_>, Writer<_>>::new(taps(b), taps(a));
+        block.input().set(vec![Complex32::new(1.0, -2.0); N]);
+        block.output().reserve(N);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let (output, _) = mocker.output.get();
+        for (y, e) in output.iter().zip(expected.iter()) {
+            assert!((y.re as f64 - e).abs() < 1e-4);
+            assert!((y.im as f64 + 2.0 * e).abs() < 1e-4);
+        }
+    }
+}
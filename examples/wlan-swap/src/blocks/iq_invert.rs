@@ -0,0 +1,190 @@
+use futuresdr::prelude::*;
+use std::marker::PhantomData;
+
+/// Sample transform of an [`IqInvert`] block
+pub trait IqTransform: Send + 'static {
+    fn apply(c: Complex32) -> Complex32;
+}
+
+/// Complex conjugate, `re - j im`
+pub struct Conjugate;
+
+impl IqTransform for Conjugate {
+    fn apply(c: Complex32) -> Complex32 {
+        c.conj()
+    }
+}
+
+/// Swap I and Q, `im + j re`
+pub struct Swap;
+
+impl IqTransform for Swap {
+    fn apply(c: Complex32) -> Complex32 {
+        Complex32::new(c.im, c.re)
+    }
+}
+
+/// Fix spectrally inverted IQ by conjugating the samples.
+pub type ConjugateIq<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>> =
+    IqInvert<Conjugate, I, O>;
+
+/// Fix spectrally inverted IQ by swapping I and Q.
+///
+/// Unlike [`ConjugateIq`], this also rotates the samples by 90°.
+pub type SwapIq<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>> =
+    IqInvert<Swap, I, O>;
+
+/// Spectrum inversion, see [`ConjugateIq`] and [`SwapIq`].
+///
+/// Mirrors the spectrum at DC, e.g., for SDRs that deliver inverted IQ. When disabled, samples
+/// are copied unchanged. The `enabled` message port toggles the inversion at runtime
+/// (`Pmt::Bool`) and returns the current state when called with `Pmt::Null`.
+#[derive(Block)]
+#[message_inputs(enabled)]
+pub struct IqInvert<T, I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    T: IqTransform,
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    enabled: bool,
+    transform: PhantomData<T>,
+}
+
+impl<T, I, O> IqInvert<T, I, O>
+where
+    T: IqTransform,
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            input: I::default(),
+            output: O::default(),
+            enabled,
+            transform: PhantomData,
+        }
+    }
+
+    async fn enabled(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Bool(b) => {
+                self.enabled = b;
+                Ok(Pmt::Ok)
+            }
+            Pmt::Null => Ok(Pmt::Bool(self.enabled)),
+            _ => Ok(Pmt::InvalidValue),
+        }
+    }
+}
+
+impl<T, I, O> Kernel for IqInvert<T, I, O>
+where
+    T: IqTransform,
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _m: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let o = self.output.slice();
+        let i_len = i.len();
+        let n = std::cmp::min(i_len, o.len());
+
+        if self.enabled {
+            for (x, y) in i[0..n].iter().zip(o.iter_mut()) {
+                *y = T::apply(*x);
+            }
+        } else {
+            o[0..n].copy_from_slice(&i[0..n]);
+        }
+
+        self.input.consume(n);
+        self.output.produce(n);
+
+        if self.input.finished() && n == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    fn samples() -> Vec<Complex32> {
+        vec![
+            Complex32::new(1.0, 2.0),
+            Complex32::new(-3.0, 0.5),
+            Complex32::new(0.0, -4.0),
+        ]
+    }
+
+    #[test]
+    fn conjugates() {
+        let mut block = ConjugateIq::<Reader<_>, Writer<_>>::new(true);
+        block.input().set(samples());
+        block.output().reserve(3);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let (output, _) = mocker.output.get();
+        assert_eq!(
+            output,
+            vec![
+                Complex32::new(1.0, -2.0),
+                Complex32::new(-3.0, -0.5),
+                Complex32::new(0.0, 4.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn swaps() {
+        let mut block = SwapIq::<Reader<_>, Writer<_>>::new(true);
+        block.input().set(samples());
+        block.output().reserve(3);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let (output, _) = mocker.output.get();
+        assert_eq!(
+            output,
+            vec![
+                Complex32::new(2.0, 1.0),
+                Complex32::new(0.5, -3.0),
+                Complex32::new(-4.0, 0.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn disabled() {
+        let mut block = SwapIq::<Reader<_>, Writer<_>>::new(true);
+        block.input().set(samples());
+        block.output().reserve(3);
+        let mut mocker = Mocker::new(block);
+        assert_eq!(mocker.post("enabled", Pmt::Bool(false)).unwrap(), Pmt::Ok);
+        assert_eq!(mocker.post("enabled", Pmt::Null).unwrap(), Pmt::Bool(false));
+        mocker.run();
+        let (output, _) = mocker.output.get();
+        assert_eq!(output, samples());
+    }
+}
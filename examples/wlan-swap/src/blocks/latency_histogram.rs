@@ -0,0 +1,144 @@
+use futuresdr::prelude::*;
+use std::collections::HashMap;
+
+/// Histogram of end-to-end latencies.
+///
+/// Expects a `Pmt::MapStrPmt` on `in` per frame with the transmit and receive timestamps in
+/// nanoseconds, `tx_time_ns` and `rx_time_ns` (`Pmt::U64` or `Pmt::Usize`), and counts
+/// `rx_time_ns - tx_time_ns` in the bin `[edges_ns[k], edges_ns[k + 1])` it falls into.
+/// Latencies below the first edge (including negative ones, e.g., from unsynchronized clocks)
+/// and at or above the last edge are counted separately, as are messages without valid
+/// timestamps.
+///
+/// The `report` port returns the histogram as `Pmt::MapStrPmt` with `edges_ns` and `counts`
+/// (`Pmt::VecU64`, one count per bin), and `below`, `above`, `invalid`, and `total`
+/// (`Pmt::U64`). `Pmt::Finished` on `in` terminates the block.
+#[derive(Block)]
+#[message_inputs(r#in, report)]
+#[null_kernel]
+pub struct LatencyHistogram {
+    edges_ns: Vec<u64>,
+    counts: Vec<u64>,
+    below: u64,
+    above: u64,
+    invalid: u64,
+}
+
+impl LatencyHistogram {
+    /// Create a histogram with the given bin edges, which have to be strictly increasing
+    pub fn new(edges_ns: Vec<u64>) -> Self {
+        assert!(edges_ns.len() >= 2);
+        assert!(edges_ns.windows(2).all(|w| w[0] < w[1]));
+        Self {
+            counts: vec![0; edges_ns.len() - 1],
+            edges_ns,
+            below: 0,
+            above: 0,
+            invalid: 0,
+        }
+    }
+
+    /// Latency of a frame in nanoseconds
+    fn latency(p: &Pmt) -> Option<i128> {
+        let Pmt::MapStrPmt(map) = p else {
+            return None;
+        };
+        let time = |name: &str| match map.get(name) {
+            Some(Pmt::U64(t)) => Some(*t as i128),
+            Some(Pmt::Usize(t)) => Some(*t as i128),
+            _ => None,
+        };
+        Some(time("rx_time_ns")? - time("tx_time_ns")?)
+    }
+
+    fn add(&mut self, latency: i128) {
+        let first = self.edges_ns[0] as i128;
+        let last = self.edges_ns[self.edges_ns.len() - 1] as i128;
+        if latency < first {
+            self.below += 1;
+        } else if latency >= last {
+            self.above += 1;
+        } else {
+            // index of the first edge above the latency, at least 1
+            let bin = self.edges_ns.partition_point(|e| *e as i128 <= latency) - 1;
+            self.counts[bin] += 1;
+        }
+    }
+
+    fn report_pmt(&self) -> Pmt {
+        let total = self.counts.iter().sum::<u64>() + self.below + self.above + self.invalid;
+        Pmt::MapStrPmt(HashMap::from([
+            ("edges_ns".to_string(), Pmt::VecU64(self.edges_ns.clone())),
+            ("counts".to_string(), Pmt::VecU64(self.counts.clone())),
+            ("below".to_string(), Pmt::U64(self.below)),
+            ("above".to_string(), Pmt::U64(self.above)),
+            ("invalid".to_string(), Pmt::U64(self.invalid)),
+            ("total".to_string(), Pmt::U64(total)),
+        ]))
+    }
+
+    async fn r#in(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if matches!(p, Pmt::Finished) {
+            io.finished = true;
+            return Ok(Pmt::Ok);
+        }
+
+        match Self::latency(&p) {
+            Some(latency) => self.add(latency),
+            None => self.invalid += 1,
+        }
+        Ok(Pmt::Ok)
+    }
+
+    async fn report(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(self.report_pmt())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+
+    fn frame(tx: u64, rx: u64) -> Pmt {
+        Pmt::MapStrPmt(HashMap::from([
+            ("tx_time_ns".to_string(), Pmt::U64(tx)),
+            ("rx_time_ns".to_string(), Pmt::Usize(rx as usize)),
+        ]))
+    }
+
+    #[test]
+    fn buckets_latencies() {
+        let mut mocker = Mocker::new(LatencyHistogram::new(vec![1_000, 2_000, 5_000]));
+        let base = 1_700_000_000_000_000_000;
+        for delta in [1_000, 1_999, 2_000, 4_999, 4_000, 999, 5_000, 70_000] {
+            mocker.post("in", frame(base, base + delta)).unwrap();
+        }
+        // receive timestamp before the transmit timestamp
+        mocker.post("in", frame(base, base - 10)).unwrap();
+        mocker.post("in", Pmt::MapStrPmt(HashMap::new())).unwrap();
+        mocker.post("in", Pmt::Blob(vec![1, 2])).unwrap();
+
+        let Pmt::MapStrPmt(report) = mocker.post("report", Pmt::Null).unwrap() else {
+            panic!("report is not a map");
+        };
+        assert_eq!(report["counts"], Pmt::VecU64(vec![2, 3]));
+        assert_eq!(report["below"], Pmt::U64(2));
+        assert_eq!(report["above"], Pmt::U64(2));
+        assert_eq!(report["invalid"], Pmt::U64(2));
+        assert_eq!(report["total"], Pmt::U64(11));
+        assert_eq!(report["edges_ns"], Pmt::VecU64(vec![1_000, 2_000, 5_000]));
+    }
+}
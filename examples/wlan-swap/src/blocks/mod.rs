@@ -0,0 +1,125 @@
+
+mod bypass;
+pub use bypass::Bypass;
+pub use bypass::Bypassable;
+
+mod cca;
+pub use cca::Cca;
+
+mod complex_to_float;
+pub use complex_to_float::ComplexToFloat;
+
+mod csv_sink;
+pub use csv_sink::CsvSink;
+
+mod dedup;
+pub use dedup::Dedup;
+
+mod defragment;
+pub use defragment::Defragment;
+
+mod float_to_complex;
+pub use float_to_complex::FloatToComplex;
+
+mod frame_counter;
+pub use frame_counter::FrameCounter;
+
+mod frame_stats;
+pub use frame_stats::FrameStats;
+pub use frame_stats::FrameSummary;
+
+mod freq_xlate;
+pub use freq_xlate::FreqXlate;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod heartbeat;
+#[cfg(not(target_arch = "wasm32"))]
+pub use heartbeat::Heartbeat;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod hex_file_source;
+#[cfg(not(target_arch = "wasm32"))]
+pub use hex_file_source::HexFileSource;
+
+mod iir;
+pub use iir::Iir;
+pub use iir::IirSample;
+
+mod iq_invert;
+pub use iq_invert::ConjugateIq;
+pub use iq_invert::IqInvert;
+pub use iq_invert::IqTransform;
+pub use iq_invert::SwapIq;
+
+mod latency_histogram;
+pub use latency_histogram::LatencyHistogram;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod mqtt_sink;
+#[cfg(not(target_arch = "wasm32"))]
+pub use mqtt_sink::MqttSink;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod msg_rate_limit;
+#[cfg(not(target_arch = "wasm32"))]
+pub use msg_rate_limit::MsgRateLimit;
+#[cfg(not(target_arch = "wasm32"))]
+pub use msg_rate_limit::RateLimitPolicy;
+
+mod pattern_source;
+pub use pattern_source::Pattern;
+pub use pattern_source::PatternSource;
+pub use pattern_source::PatternType;
+
+mod pmt_merge;
+pub use pmt_merge::PmtMerge;
+
+mod power_meter;
+pub use power_meter::PowerMeter;
+
+mod prbs;
+pub use prbs::BerSink;
+pub use prbs::Polynomial;
+pub use prbs::PrbsSource;
+
+mod pre_trigger_recorder;
+pub use pre_trigger_recorder::PreTriggerRecorder;
+
+mod rssi_annotate;
+pub use rssi_annotate::RssiAnnotate;
+
+mod sro_correct;
+pub use sro_correct::SroCorrect;
+
+mod squelch;
+pub use squelch::Squelch;
+pub use squelch::SquelchMode;
+
+mod stream_tap;
+pub use stream_tap::StreamTap;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod symbol_csv_sink;
+#[cfg(not(target_arch = "wasm32"))]
+pub use symbol_csv_sink::SymbolCsvSink;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod udp_framing;
+#[cfg(not(target_arch = "wasm32"))]
+pub use udp_framing::BlobToUdpFramed;
+#[cfg(not(target_arch = "wasm32"))]
+pub use udp_framing::BlobToUdpWithMeta;
+#[cfg(not(target_arch = "wasm32"))]
+pub use udp_framing::FrameMeta;
+#[cfg(not(target_arch = "wasm32"))]
+pub use udp_framing::decode_meta_frame;
+#[cfg(not(target_arch = "wasm32"))]
+pub use udp_framing::UdpToBlob;
+
+mod upsample;
+pub use upsample::Upsample;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod watchdog;
+#[cfg(not(target_arch = "wasm32"))]
+pub use watchdog::Watchdog;
@@ -0,0 +1,379 @@
+use futuresdr::async_io::Timer;
+use futuresdr::async_net::TcpStream;
+use futuresdr::futures::AsyncReadExt;
+use futuresdr::futures::AsyncWriteExt;
+use futuresdr::futures::future::Either;
+use futuresdr::futures::future::select;
+use futuresdr::prelude::*;
+use std::pin::pin;
+use std::time::Duration;
+use std::time::Instant;
+
+/// Time to wait for the TCP connection to the broker
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Time to wait for the broker to acknowledge a connection or a QoS 1 message
+const ACK_TIMEOUT: Duration = Duration::from_secs(5);
+/// Delay before the first reconnection attempt, doubled after each failed attempt
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+/// Upper bound of the delay between reconnection attempts
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Append an MQTT string: length as big-endian `u16`, followed by the bytes
+fn put_string(b: &mut Vec<u8>, s: &[u8]) {
+    b.extend_from_slice(&(s.len() as u16).to_be_bytes());
+    b.extend_from_slice(s);
+}
+
+/// MQTT packet with the fixed header: packet type and flags, then the remaining length
+fn packet(header: u8, body: &[u8]) -> Vec<u8> {
+    let mut b = vec![header];
+    let mut len = body.len();
+    loop {
+        let byte = (len % 128) as u8;
+        len /= 128;
+        if len > 0 {
+            b.push(byte | 0x80);
+        } else {
+            b.push(byte);
+            break;
+        }
+    }
+    b.extend_from_slice(body);
+    b
+}
+
+/// MQTT 3.1.1 CONNECT packet with a clean session and keep alive disabled
+fn connect_packet(client_id: &str) -> Vec<u8> {
+    let mut body = Vec::new();
+    put_string(&mut body, b"MQTT");
+    // protocol level 4 (3.1.1), clean session, keep alive 0
+    body.extend_from_slice(&[4, 0x02, 0, 0]);
+    put_string(&mut body, client_id.as_bytes());
+    packet(0x10, &body)
+}
+
+/// MQTT PUBLISH packet, with the packet identifier `id` for QoS 1
+fn publish_packet(topic: &str, qos: u8, id: u16, payload: &[u8]) -> Vec<u8> {
+    let mut body = Vec::new();
+    put_string(&mut body, topic.as_bytes());
+    if qos > 0 {
+        body.extend_from_slice(&id.to_be_bytes());
+    }
+    body.extend_from_slice(payload);
+    packet(0x30 | (qos << 1), &body)
+}
+
+/// Read `buf.len()` bytes, giving up after [`ACK_TIMEOUT`]
+async fn read_ack(stream: &mut TcpStream, buf: &mut [u8]) -> Result<()> {
+    let read = pin!(stream.read_exact(buf));
+    match select(read, Timer::after(ACK_TIMEOUT)).await {
+        Either::Left((res, _)) => Ok(res?),
+        Either::Right(_) => anyhow::bail!("no acknowledgement from broker"),
+    }
+}
+
+/// Publish messages to an MQTT broker.
+///
+/// Posts each `Pmt::Blob` (as is) and `Pmt::String` (as UTF-8) received on `in` as an MQTT
+/// 3.1.1 message on `topic`, with QoS 0 (at most once) or 1 (at least once, waiting for the
+/// broker's acknowledgement). `Pmt::Finished` terminates the block.
+///
+/// The block connects to the broker when the flowgraph starts. If the broker is not reachable
+/// or the connection breaks, it retries in the background, waiting 1 s before the first attempt
+/// and doubling the delay up to 60 s after each failed one. Messages received while there is no
+/// connection are dropped, so that an unavailable broker does not stop the flowgraph.
+#[derive(Block)]
+#[message_inputs(r#in)]
+pub struct MqttSink {
+    broker: String,
+    topic: String,
+    qos: u8,
+    client_id: String,
+    stream: Option<TcpStream>,
+    /// Time of the next connection attempt, if not connected
+    reconnect_at: Instant,
+    /// Delay of the connection attempt after the next one
+    backoff: Duration,
+    /// Identifier of the last QoS 1 message
+    packet_id: u16,
+    published: u64,
+    dropped: u64,
+}
+
+impl MqttSink {
+    /// Publish to `topic` on `broker` (e.g., `127.0.0.1:1883`) with QoS 0 or 1
+    pub fn new(broker: impl Into<String>, topic: impl Into<String>, qos: u8) -> Self {
+        assert!(qos <= 1, "only QoS 0 and 1 are supported");
+        Self {
+            broker: broker.into(),
+            topic: topic.into(),
+            qos,
+            client_id: format!("futuresdr-{}", std::process::id()),
+            stream: None,
+            reconnect_at: Instant::now(),
+            backoff: MIN_BACKOFF,
+            packet_id: 0,
+            published: 0,
+            dropped: 0,
+        }
+    }
+
+    /// Number of messages accepted by the broker (QoS 1) or sent to it (QoS 0)
+    pub fn published(&self) -> u64 {
+        self.published
+    }
+
+    /// Number of messages dropped because the broker was not connected or publishing failed
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    async fn connect(&mut self) -> Result<()> {
+        let connect = pin!(TcpStream::connect(self.broker.as_str()));
+        let mut stream = match select(connect, Timer::after(CONNECT_TIMEOUT)).await {
+            Either::Left((res, _)) => res?,
+            Either::Right(_) => anyhow::bail!("connection timed out"),
+        };
+        stream.write_all(&connect_packet(&self.client_id)).await?;
+        let mut connack = [0u8; 4];
+        read_ack(&mut stream, &mut connack).await?;
+        if connack[0] != 0x20 || connack[3] != 0 {
+            anyhow::bail!("broker refused connection (CONNACK {:?})", connack);
+        }
+        self.stream = Some(stream);
+        self.backoff = MIN_BACKOFF;
+        Ok(())
+    }
+
+    /// Close the connection and schedule the next connection attempt
+    fn disconnect(&mut self) {
+        self.stream = None;
+        self.reconnect_at = Instant::now() + self.backoff;
+        self.backoff = (2 * self.backoff).min(MAX_BACKOFF);
+    }
+
+    async fn publish(&mut self, payload: &[u8]) -> Result<()> {
+        self.packet_id = self.packet_id.checked_add(1).unwrap_or(1);
+        let id = self.packet_id;
+        let stream = self.stream.as_mut().unwrap();
+        stream
+            .write_all(&publish_packet(&self.topic, self.qos, id, payload))
+            .await?;
+        if self.qos == 1 {
+            let mut puback = [0u8; 4];
+            read_ack(stream, &mut puback).await?;
+            if puback[0] != 0x40 || puback[2..4] != id.to_be_bytes() {
+                anyhow::bail!("unexpected acknowledgement {:?}", puback);
+            }
+        }
+        self.published += 1;
+        Ok(())
+    }
+
+    async fn r#in(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        let payload = match p {
+            Pmt::Blob(b) => b,
+            Pmt::String(s) => s.into_bytes(),
+            Pmt::Finished => {
+                io.finished = true;
+                return Ok(Pmt::Ok);
+            }
+            _ => return Ok(Pmt::InvalidValue),
+        };
+
+        if self.stream.is_none() {
+            debug!(
+                "MqttSink: not connected to {}, dropping message",
+                self.broker
+            );
+            self.dropped += 1;
+            return Ok(Pmt::Ok);
+        }
+        if let Err(e) = self.publish(&payload).await {
+            // the connection may have been closed by the broker, reconnect in the background
+            warn!("MqttSink: dropping message for {}: {}", self.broker, e);
+            self.dropped += 1;
+            self.disconnect();
+        }
+        Ok(Pmt::Ok)
+    }
+}
+
+impl Kernel for MqttSink {
+    async fn init(&mut self, _mio: &mut MessageOutputs, _meta: &mut BlockMeta) -> Result<()> {
+        if let Err(e) = self.connect().await {
+            warn!("MqttSink: cannot connect to {}: {}", self.broker, e);
+            self.disconnect();
+        }
+        Ok(())
+    }
+
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        if self.stream.is_some() || io.finished {
+            return Ok(());
+        }
+
+        if Instant::now() >= self.reconnect_at {
+            match self.connect().await {
+                Ok(()) => {
+                    info!("MqttSink: reconnected to {}", self.broker);
+                    return Ok(());
+                }
+                Err(e) => {
+                    debug!("MqttSink: cannot reconnect to {}: {}", self.broker, e);
+                    self.disconnect();
+                }
+            }
+        }
+
+        // messages in the meantime are dropped, the attempt is made on wake-up
+        let wait = self.reconnect_at.saturating_duration_since(Instant::now());
+        io.block_on(async move {
+            Timer::after(wait).await;
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use std::io::Read;
+    use std::io::Write;
+    use std::net::TcpListener;
+
+    /// Read one MQTT packet: header byte and body
+    fn read_packet(stream: &mut std::net::TcpStream) -> (u8, Vec<u8>) {
+        let mut byte = [0u8; 1];
+        stream.read_exact(&mut byte).unwrap();
+        let header = byte[0];
+        let (mut len, mut shift) = (0usize, 0);
+        loop {
+            stream.read_exact(&mut byte).unwrap();
+            len |= ((byte[0] & 0x7f) as usize) << shift;
+            shift += 7;
+            if byte[0] & 0x80 == 0 {
+                break;
+            }
+        }
+        let mut body = vec![0u8; len];
+        stream.read_exact(&mut body).unwrap();
+        (header, body)
+    }
+
+    #[test]
+    fn remaining_length() {
+        let p = publish_packet("t", 0, 0, &[0xab; 200]);
+        // 2 + 1 bytes of topic and 200 bytes of payload
+        assert_eq!(&p[..3], &[0x30, 0x80 | 75, 1]);
+        assert_eq!(p.len(), 3 + 203);
+        assert_eq!(
+            publish_packet("ab", 1, 7, b"x"),
+            [0x32, 7, 0, 2, b'a', b'b', 0, 7, b'x']
+        );
+    }
+
+    #[test]
+    fn reconnects_with_backoff() {
+        // a broker that is not up yet
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let broker = listener.local_addr().unwrap();
+        drop(listener);
+
+        let mut mocker = Mocker::new(MqttSink::new(broker.to_string(), "wlan/frames", 0));
+        mocker.init();
+        assert!(mocker.stream.is_none());
+        assert_eq!(mocker.backoff, 2 * MIN_BACKOFF);
+
+        // messages are dropped without waiting for a connection
+        let start = Instant::now();
+        assert_eq!(mocker.post("in", Pmt::Blob(vec![1])).unwrap(), Pmt::Ok);
+        assert!(start.elapsed() < MIN_BACKOFF);
+        assert_eq!(mocker.dropped(), 1);
+
+        let listener = TcpListener::bind(broker).unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            assert_eq!(read_packet(&mut stream).0, 0x10);
+            stream.write_all(&[0x20, 2, 0, 0]).unwrap();
+            read_packet(&mut stream)
+        });
+
+        // no attempt before the backoff passed
+        mocker.run();
+        assert!(mocker.stream.is_none());
+        while mocker.stream.is_none() {
+            assert!(start.elapsed() < 10 * MIN_BACKOFF, "not reconnected");
+            std::thread::sleep(Duration::from_millis(50));
+            mocker.run();
+        }
+        assert!(start.elapsed() >= MIN_BACKOFF);
+        assert_eq!(mocker.backoff, MIN_BACKOFF);
+
+        mocker.post("in", Pmt::Blob(vec![2])).unwrap();
+        assert_eq!(mocker.published(), 1);
+        let (header, body) = server.join().unwrap();
+        assert_eq!(header, 0x30);
+        assert_eq!(body.last(), Some(&2));
+    }
+
+    #[test]
+    fn publishes_to_broker() {
+        // a broker that accepts one client and acknowledges its QoS 1 messages
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let broker = listener.local_addr().unwrap().to_string();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let (header, connect) = read_packet(&mut stream);
+            assert_eq!(header, 0x10);
+            assert_eq!(&connect[..7], b"\x00\x04MQTT\x04");
+            stream.write_all(&[0x20, 2, 0, 0]).unwrap();
+
+            let mut messages = Vec::new();
+            for _ in 0..2 {
+                let (header, body) = read_packet(&mut stream);
+                assert_eq!(header, 0x32);
+                let topic_len = u16::from_be_bytes([body[0], body[1]]) as usize;
+                let topic = String::from_utf8(body[2..2 + topic_len].to_vec()).unwrap();
+                let id = &body[2 + topic_len..4 + topic_len];
+                stream.write_all(&[0x40, 2, id[0], id[1]]).unwrap();
+                messages.push((topic, body[4 + topic_len..].to_vec()));
+            }
+            messages
+        });
+
+        let mut mocker = Mocker::new(MqttSink::new(broker, "wlan/frames", 1));
+        mocker.init();
+        assert_eq!(
+            mocker.post("in", Pmt::Blob(vec![0xff, 0, 1])).unwrap(),
+            Pmt::Ok
+        );
+        assert_eq!(
+            mocker.post("in", Pmt::String("hello".to_string())).unwrap(),
+            Pmt::Ok
+        );
+        assert_eq!(mocker.post("in", Pmt::U32(1)).unwrap(), Pmt::InvalidValue);
+        assert_eq!(mocker.published(), 2);
+
+        let messages = server.join().unwrap();
+        assert_eq!(
+            messages,
+            vec![
+                ("wlan/frames".to_string(), vec![0xff, 0, 1]),
+                ("wlan/frames".to_string(), b"hello".to_vec()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use futuresdr::async_io::Timer;
+use futuresdr::prelude::*;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::time::Duration;
+use web_time::Instant;
+
+/// Minimum time between two `stats` messages
+const STATS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What [`MsgRateLimit`] does with messages above the rate
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RateLimitPolicy {
+    /// Drop them
+    Drop,
+    /// Queue them (up to the queue capacity) and forward them as the rate allows
+    Block,
+}
+
+impl std::str::FromStr for RateLimitPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "drop" => Ok(RateLimitPolicy::Drop),
+            "block" => Ok(RateLimitPolicy::Block),
+            _ => Err(format!("unknown rate limit policy `{s}` (expected `drop` or `block`)")),
+        }
+    }
+}
+
+/// Message rate limiter.
+///
+/// Forwards messages from `in` to `out` at most `rate` messages per second, spaced by at least
+/// `1 / rate`. Excess messages are dropped or, with [`RateLimitPolicy::Block`], queued up to
+/// `capacity` messages and dropped once the queue is full. The number of dropped messages is
+/// posted as `Pmt::MapStrPmt` with a `dropped` field (`Pmt::U64`) on `stats`, at most once per
+/// second. `Pmt::Finished` terminates the block once the queue is drained.
+#[derive(Block)]
+#[message_inputs(r#in)]
+#[message_outputs(out, stats)]
+pub struct MsgRateLimit {
+    rate: f64,
+    policy: RateLimitPolicy,
+    capacity: usize,
+    queue: VecDeque<Pmt>,
+    tokens: f64,
+    last_refill: Instant,
+    dropped: u64,
+    reported: u64,
+    last_stats: Option<Instant>,
+    finished: bool,
+}
+
+impl MsgRateLimit {
+    pub fn new(rate: f64, policy: RateLimitPolicy, capacity: usize) -> Self {
+        assert!(rate > 0.0);
+        Self {
+            rate,
+            policy,
+            capacity,
+            queue: VecDeque::with_capacity(capacity),
+            tokens: 1.0,
+            last_refill: Instant::now(),
+            dropped: 0,
+            reported: 0,
+            last_stats: None,
+            finished: false,
+        }
+    }
+
+    /// Number of dropped messages
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Number of queued messages
+    pub fn queued(&self) -> usize {
+        self.queue.len()
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(1.0);
+        self.last_refill = now;
+    }
+
+    /// Time until the pending `stats` message may be posted
+    fn stats_due(&self) -> Option<Duration> {
+        if self.dropped == self.reported {
+            return None;
+        }
+        Some(match self.last_stats {
+            Some(t) => STATS_INTERVAL.saturating_sub(t.elapsed()),
+            None => Duration::ZERO,
+        })
+    }
+
+    async fn post_stats(&mut self, mio: &mut MessageOutputs) -> Result<()> {
+        if self.stats_due() == Some(Duration::ZERO) {
+            let stats = HashMap::from([("dropped".to_string(), Pmt::U64(self.dropped))]);
+            mio.post("stats", Pmt::MapStrPmt(stats)).await?;
+            self.reported = self.dropped;
+            self.last_stats = Some(Instant::now());
+        }
+        Ok(())
+    }
+
+    async fn r#in(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if matches!(p, Pmt::Finished) {
+            self.finished = true;
+            io.call_again = true;
+            return Ok(Pmt::Ok);
+        }
+
+        self.refill();
+        if self.queue.is_empty() && self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            mio.post("out", p).await?;
+        } else if self.policy == RateLimitPolicy::Block && self.queue.len() < self.capacity {
+            self.queue.push_back(p);
+            io.call_again = true;
+        } else {
+            self.dropped += 1;
+            self.post_stats(mio).await?;
+            io.call_again = true;
+        }
+        Ok(Pmt::Ok)
+    }
+}
+
+impl Kernel for MsgRateLimit {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        self.refill();
+        while self.tokens >= 1.0 {
+            let Some(p) = self.queue.pop_front() else {
+                break;
+            };
+            self.tokens -= 1.0;
+            mio.post("out", p).await?;
+        }
+        self.post_stats(mio).await?;
+
+        if self.queue.is_empty() && self.finished {
+            io.finished = true;
+            return Ok(());
+        }
+
+        let next_token = (!self.queue.is_empty())
+            .then(|| Duration::from_secs_f64((1.0 - self.tokens) / self.rate));
+        let wakeup = match (next_token, self.stats_due()) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        if let Some(wakeup) = wakeup {
+            io.block_on(async move {
+                Timer::after(wakeup).await;
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+
+    fn dropped(p: &Pmt) -> u64 {
+        match p {
+            Pmt::MapStrPmt(m) => match m["dropped"] {
+                Pmt::U64(n) => n,
+                _ => panic!("unexpected stats {p:?}"),
+            },
+            _ => panic!("unexpected stats {p:?}"),
+        }
+    }
+
+    #[test]
+    fn drops_burst() {
+        let mut mocker = Mocker::new(MsgRateLimit::new(10.0, RateLimitPolicy::Drop, 0));
+        for i in 0..25 {
+            assert_eq!(mocker.post("in", Pmt::U32(i)).unwrap(), Pmt::Ok);
+        }
+        mocker.run();
+
+        // one message per 100 ms
+        assert_eq!(mocker.dropped(), 24);
+        let [out, stats] = mocker.messages().try_into().unwrap();
+        assert_eq!(out, vec![Pmt::U32(0)]);
+        // the first drop is reported immediately, later ones after STATS_INTERVAL
+        assert_eq!(stats.iter().map(dropped).collect::<Vec<_>>(), vec![1]);
+    }
+
+    #[test]
+    fn queues_burst() {
+        let rate = 20.0;
+        let mut mocker = Mocker::new(MsgRateLimit::new(rate, RateLimitPolicy::Block, 5));
+        let start = Instant::now();
+        for i in 0..10 {
+            assert_eq!(mocker.post("in", Pmt::U32(i)).unwrap(), Pmt::Ok);
+        }
+        assert_eq!(mocker.queued(), 5);
+        assert_eq!(mocker.dropped(), 4);
+
+        let mut times = Vec::new();
+        while mocker.queued() > 0 {
+            mocker.run();
+            let released = mocker.messages()[0].len();
+            while times.len() < released {
+                times.push(start.elapsed());
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(start.elapsed() < Duration::from_secs(2));
+
+        let [out, _] = mocker.messages().try_into().unwrap();
+        assert_eq!(out, (0..6).map(Pmt::U32).collect::<Vec<_>>());
+        // queued messages are spaced by 1 / rate
+        for (k, t) in times.iter().enumerate().skip(1) {
+            assert!(t.as_secs_f64() >= k as f64 / rate, "message {k} after {t:?}");
+        }
+    }
+}
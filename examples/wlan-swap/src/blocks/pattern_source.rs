@@ -0,0 +1,166 @@
+use futuresdr::prelude::*;
+
+/// Sample types supported by [`PatternSource`]
+pub trait PatternType: CpuSample + Copy {
+    /// Zero value
+    fn zero() -> Self;
+    /// Value of the counter at index `n` (wrapping for integer types)
+    fn from_index(n: u64) -> Self;
+    /// Add `step`, wrapping for integer types
+    fn step(self, step: Self) -> Self;
+}
+
+impl PatternType for u8 {
+    fn zero() -> Self {
+        0
+    }
+    fn from_index(n: u64) -> Self {
+        n as u8
+    }
+    fn step(self, step: Self) -> Self {
+        self.wrapping_add(step)
+    }
+}
+
+impl PatternType for u32 {
+    fn zero() -> Self {
+        0
+    }
+    fn from_index(n: u64) -> Self {
+        n as u32
+    }
+    fn step(self, step: Self) -> Self {
+        self.wrapping_add(step)
+    }
+}
+
+impl PatternType for f32 {
+    fn zero() -> Self {
+        0.0
+    }
+    fn from_index(n: u64) -> Self {
+        n as f32
+    }
+    fn step(self, step: Self) -> Self {
+        self + step
+    }
+}
+
+impl PatternType for Complex32 {
+    fn zero() -> Self {
+        Complex32::new(0.0, 0.0)
+    }
+    fn from_index(n: u64) -> Self {
+        Complex32::new(n as f32, 0.0)
+    }
+    fn step(self, step: Self) -> Self {
+        self + step
+    }
+}
+
+/// Data emitted by [`PatternSource`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pattern<T> {
+    /// All zeros, like `NullSource`
+    Zero,
+    /// Sample index: 0, 1, 2, ...
+    Counter,
+    /// A constant value
+    Const(T),
+    /// Start at zero and add `step` for every sample
+    Ramp(T),
+}
+
+/// Generate a stream with an identifiable pattern.
+///
+/// Useful to check that data actually flows through a flowgraph.
+#[derive(Block)]
+pub struct PatternSource<T: PatternType, O: CpuBufferWriter<Item = T> = DefaultCpuWriter<T>> {
+    #[output]
+    output: O,
+    pattern: Pattern<T>,
+    index: u64,
+    current: T,
+}
+
+impl<T, O> PatternSource<T, O>
+where
+    T: PatternType,
+    O: CpuBufferWriter<Item = T>,
+{
+    pub fn new(pattern: Pattern<T>) -> Self {
+        Self {
+            output: O::default(),
+            pattern,
+            index: 0,
+            current: T::zero(),
+        }
+    }
+}
+
+impl<T, O> Kernel for PatternSource<T, O>
+where
+    T: PatternType,
+    O: CpuBufferWriter<Item = T>,
+{
+    async fn work(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let o = self.output.slice();
+        let o_len = o.len();
+
+        match self.pattern {
+            Pattern::Zero => o.fill(T::zero()),
+            Pattern::Const(v) => o.fill(v),
+            Pattern::Counter => {
+                for (k, v) in o.iter_mut().enumerate() {
+                    *v = T::from_index(self.index + k as u64);
+                }
+            }
+            Pattern::Ramp(step) => {
+                for v in o.iter_mut() {
+                    *v = self.current;
+                    self.current = self.current.step(step);
+                }
+            }
+        }
+
+        self.index += o_len as u64;
+        self.output.produce(o_len);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Writer;
+
+    #[test]
+    fn counter() {
+        let mut block = PatternSource::<u8, Writer<_>>::new(Pattern::Counter);
+        block.output().reserve(300);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let (output, _) = mocker.output().get();
+
+        let expected: Vec<u8> = (0..300).map(|n: u32| n as u8).collect();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn constant() {
+        let mut block = PatternSource::<f32, Writer<_>>::new(Pattern::Const(0.5));
+        block.output().reserve(16);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let (output, _) = mocker.output().get();
+
+        assert_eq!(output, vec![0.5; 16]);
+    }
+}
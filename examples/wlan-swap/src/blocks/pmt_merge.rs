@@ -0,0 +1,100 @@
+use futuresdr::prelude::*;
+
+/// Generate the handlers of the `in<k>` message inputs
+macro_rules! inputs {
+    ($($name:ident => $index:expr),* $(,)?) => {
+        $(
+            async fn $name(
+                &mut self,
+                io: &mut WorkIo,
+                mio: &mut MessageOutputs,
+                _meta: &mut BlockMeta,
+                p: Pmt,
+            ) -> Result<Pmt> {
+                self.forward($index, io, mio, p).await
+            }
+        )*
+    };
+}
+
+/// Merge several message sources into one.
+///
+/// Forwards every message received on the first `n_inputs` inputs (`in0`, `in1`, ..., up to
+/// [`PmtMerge::MAX_INPUTS`]) to `out`, e.g., to feed a MAC from both the GUI console and a PER
+/// block. Messages are not buffered: handlers run one at a time, in the order the messages
+/// reach the block's inbox, so the output preserves the arrival order across all inputs and the
+/// order of each input. A busy input cannot starve another one, since every message is
+/// forwarded before the next is handled. Messages on unused inputs are rejected with
+/// `Pmt::InvalidValue`. `Pmt::Finished` is forwarded, and terminates the block, once all used
+/// inputs finished.
+#[derive(Block)]
+#[message_inputs(in0, in1, in2, in3, in4, in5, in6, in7)]
+#[message_outputs(out)]
+#[null_kernel]
+pub struct PmtMerge {
+    n_inputs: usize,
+    finished: [bool; PmtMerge::MAX_INPUTS],
+}
+
+impl PmtMerge {
+    /// Number of message inputs
+    pub const MAX_INPUTS: usize = 8;
+
+    pub fn new(n_inputs: usize) -> Self {
+        assert!((1..=Self::MAX_INPUTS).contains(&n_inputs));
+        Self {
+            n_inputs,
+            finished: [false; Self::MAX_INPUTS],
+        }
+    }
+
+    async fn forward(
+        &mut self,
+        index: usize,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if index >= self.n_inputs {
+            return Ok(Pmt::InvalidValue);
+        }
+        if matches!(p, Pmt::Finished) {
+            self.finished[index] = true;
+            if self.finished[..self.n_inputs].iter().all(|f| *f) {
+                mio.post("out", Pmt::Finished).await?;
+                io.finished = true;
+            }
+            return Ok(Pmt::Ok);
+        }
+
+        mio.post("out", p).await?;
+        Ok(Pmt::Ok)
+    }
+
+    inputs!(in0 => 0, in1 => 1, in2 => 2, in3 => 3, in4 => 4, in5 => 5, in6 => 6, in7 => 7);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+
+    #[test]
+    fn merges_inputs() {
+        let mut mocker = Mocker::new(PmtMerge::new(2));
+        assert_eq!(mocker.post("in0", Pmt::U32(0)).unwrap(), Pmt::Ok);
+        assert_eq!(mocker.post("in1", Pmt::U32(1)).unwrap(), Pmt::Ok);
+        assert_eq!(mocker.post("in0", Pmt::U32(2)).unwrap(), Pmt::Ok);
+        assert_eq!(mocker.post("in2", Pmt::U32(3)).unwrap(), Pmt::InvalidValue);
+        assert_eq!(mocker.post("in1", Pmt::Finished).unwrap(), Pmt::Ok);
+        assert_eq!(mocker.post("in0", Pmt::U32(4)).unwrap(), Pmt::Ok);
+        assert_eq!(mocker.post("in0", Pmt::Finished).unwrap(), Pmt::Ok);
+        mocker.run();
+
+        let [out] = mocker.messages().try_into().unwrap();
+        assert_eq!(
+            out,
+            vec![Pmt::U32(0), Pmt::U32(1), Pmt::U32(2), Pmt::U32(4), Pmt::Finished]
+        );
+    }
+}
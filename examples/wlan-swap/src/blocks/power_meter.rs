@@ -0,0 +1,129 @@
+use futuresdr::prelude::*;
+use std::time::Duration;
+use web_time::Instant;
+
+/// Pass-through power meter.
+///
+/// Tracks an exponentially decaying average of the sample power and posts it in dBFS as
+/// [`Pmt::F64`] on the `power` message port, at most once every `interval`. A `decay` close to
+/// one averages over more samples.
+#[derive(Block)]
+#[message_outputs(power)]
+pub struct PowerMeter<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    interval: Duration,
+    decay: f32,
+    average: Option<f32>,
+    last_post: Instant,
+}
+
+impl<I, O> PowerMeter<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    pub fn new(interval: Duration, decay: f32) -> Self {
+        assert!((0.0..1.0).contains(&decay));
+        Self {
+            input: I::default(),
+            output: O::default(),
+            interval,
+            decay,
+            average: None,
+            last_post: Instant::now(),
+        }
+    }
+
+    /// Current power estimate in dBFS
+    pub fn power_db(&self) -> Option<f64> {
+        self.average.map(|a| 10.0 * (a as f64).log10())
+    }
+}
+
+impl<I, O> Kernel for PowerMeter<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let o = self.output.slice();
+        let i_len = i.len();
+        let n = std::cmp::min(i_len, o.len());
+
+        for s in &i[0..n] {
+            let power = s.norm_sqr();
+            // start from the first sample instead of ramping up from zero
+            let average = self.average.get_or_insert(power);
+            *average = self.decay * *average + (1.0 - self.decay) * power;
+        }
+        o[0..n].copy_from_slice(&i[0..n]);
+
+        self.input.consume(n);
+        self.output.produce(n);
+
+        if n > 0 && self.last_post.elapsed() >= self.interval {
+            if let Some(db) = self.power_db() {
+                mio.post("power", Pmt::F64(db)).await?;
+            }
+            self.last_post = Instant::now();
+        }
+
+        if self.input.finished() && n == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    #[test]
+    fn constant_amplitude() {
+        let input: Vec<Complex32> = (0..1000)
+            .map(|k| Complex32::from_polar(0.5, k as f32 * 0.1))
+            .collect();
+
+        let mut block = PowerMeter::<Reader<_>, Writer<_>>::new(Duration::ZERO, 0.9);
+        block.input().set(input.clone());
+        block.output().reserve(3 * input.len());
+        let mut mocker = Mocker::new(block);
+
+        for _ in 0..3 {
+            mocker.input.set(input.clone());
+            mocker.run();
+        }
+
+        let (output, _) = mocker.output.get();
+        assert_eq!(output.len(), 3 * input.len());
+        assert_eq!(&output[0..input.len()], &input[..]);
+
+        let messages = mocker.messages();
+        assert_eq!(messages[0].len(), 3);
+        let expected = 20.0 * 0.5f64.log10();
+        for m in &messages[0] {
+            match m {
+                Pmt::F64(db) => assert!((db - expected).abs() < 1e-3, "{db} != {expected}"),
+                p => panic!("unexpected message {p:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,353 @@
+use futuresdr::prelude::*;
+use std::collections::HashMap;
+
+/// Feedback polynomial `x^degree + x^tap + 1` of a PRBS
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Polynomial {
+    pub degree: u32,
+    pub tap: u32,
+}
+
+impl Polynomial {
+    /// `x^7 + x^6 + 1`
+    pub const PRBS7: Self = Self::new(7, 6);
+    /// `x^9 + x^5 + 1`
+    pub const PRBS9: Self = Self::new(9, 5);
+    /// `x^11 + x^9 + 1`
+    pub const PRBS11: Self = Self::new(11, 9);
+    /// `x^15 + x^14 + 1`
+    pub const PRBS15: Self = Self::new(15, 14);
+    /// `x^23 + x^18 + 1`
+    pub const PRBS23: Self = Self::new(23, 18);
+    /// `x^31 + x^28 + 1`
+    pub const PRBS31: Self = Self::new(31, 28);
+
+    pub const fn new(degree: u32, tap: u32) -> Self {
+        assert!(degree >= 2 && degree <= 31);
+        assert!(tap >= 1 && tap < degree);
+        Self { degree, tap }
+    }
+
+    /// Polynomial by name, e.g., `prbs7`
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "prbs7" => Some(Self::PRBS7),
+            "prbs9" => Some(Self::PRBS9),
+            "prbs11" => Some(Self::PRBS11),
+            "prbs15" => Some(Self::PRBS15),
+            "prbs23" => Some(Self::PRBS23),
+            "prbs31" => Some(Self::PRBS31),
+            _ => None,
+        }
+    }
+}
+
+/// Fibonacci LFSR, the most recent bit in the LSB
+#[derive(Clone, Copy, Debug)]
+struct Lfsr {
+    polynomial: Polynomial,
+    state: u32,
+}
+
+impl Lfsr {
+    fn new(polynomial: Polynomial) -> Self {
+        let mut lfsr = Self {
+            polynomial,
+            state: 0,
+        };
+        lfsr.state = lfsr.mask();
+        lfsr
+    }
+
+    fn mask(&self) -> u32 {
+        (1 << self.polynomial.degree) - 1
+    }
+
+    /// Next bit of the sequence, without advancing
+    fn peek(&self) -> u8 {
+        let p = self.polynomial;
+        (((self.state >> (p.degree - 1)) ^ (self.state >> (p.tap - 1))) & 1) as u8
+    }
+
+    /// Shift a bit into the register
+    fn push(&mut self, bit: u8) {
+        self.state = ((self.state << 1) | bit as u32) & self.mask();
+    }
+
+    fn next(&mut self) -> u8 {
+        let bit = self.peek();
+        self.push(bit);
+        bit
+    }
+}
+
+/// Emit a pseudo-random bit sequence.
+///
+/// The bits are packed into bytes, LSB first, as expected by the ZigBee modulator. The LFSR
+/// starts with all ones.
+#[derive(Block)]
+pub struct PrbsSource<O: CpuBufferWriter<Item = u8> = DefaultCpuWriter<u8>> {
+    #[output]
+    output: O,
+    lfsr: Lfsr,
+}
+
+impl<O> PrbsSource<O>
+where
+    O: CpuBufferWriter<Item = u8>,
+{
+    pub fn new(polynomial: Polynomial) -> Self {
+        Self {
+            output: O::default(),
+            lfsr: Lfsr::new(polynomial),
+        }
+    }
+}
+
+impl<O> Kernel for PrbsSource<O>
+where
+    O: CpuBufferWriter<Item = u8>,
+{
+    async fn work(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let o = self.output.slice();
+        let o_len = o.len();
+
+        for v in o.iter_mut() {
+            *v = (0..8).fold(0, |byte, k| byte | (self.lfsr.next() << k));
+        }
+
+        self.output.produce(o_len);
+
+        Ok(())
+    }
+}
+
+/// Bits of a window in which more than a quarter of errors drops the lock of a [`BerSink`]
+const LOCK_WINDOW: u32 = 256;
+
+/// Synchronization and error counts of a [`BerSink`]
+struct BerTracker {
+    lfsr: Lfsr,
+    locked: bool,
+    /// Bits shifted into the LFSR while synchronizing
+    filled: u32,
+    /// Correctly predicted bits in a row while synchronizing
+    run: u32,
+    window_bits: u32,
+    window_errors: u32,
+    bits: u64,
+    errors: u64,
+}
+
+impl BerTracker {
+    fn process(&mut self, bit: u8) {
+        if self.locked {
+            self.bits += 1;
+            self.window_bits += 1;
+            if self.lfsr.next() != bit {
+                self.errors += 1;
+                self.window_errors += 1;
+            }
+            if self.window_bits == LOCK_WINDOW {
+                if self.window_errors > LOCK_WINDOW / 4 {
+                    self.locked = false;
+                    self.filled = 0;
+                    self.run = 0;
+                }
+                self.window_bits = 0;
+                self.window_errors = 0;
+            }
+            return;
+        }
+
+        let degree = self.lfsr.polynomial.degree;
+        if self.filled < degree {
+            self.filled += 1;
+        } else if self.lfsr.state != 0 && self.lfsr.peek() == bit {
+            // the all-zero state would lock onto a dead link
+            self.run += 1;
+        } else {
+            self.run = 0;
+        }
+        self.lfsr.push(bit);
+        self.locked = self.run == degree;
+    }
+}
+
+/// Measure the bit error rate of a [`PrbsSource`] sequence.
+///
+/// Takes bytes packed like the output of [`PrbsSource`] and synchronizes by loading the LFSR
+/// with the last received bits, until it predicts `degree` bits in a row. Once locked, it
+/// compares against the free-running sequence. More than a quarter of errors in a window of 256
+/// bits (e.g., after a bit slip) restarts the synchronization. Bits received while
+/// synchronizing are not counted.
+///
+/// The `ber` message port returns the counts as [`Pmt::MapStrPmt`] with `bits` and `errors`
+/// (`Pmt::U64`) and `ber` (`Pmt::F64`).
+#[derive(Block)]
+#[message_inputs(ber)]
+pub struct BerSink<I: CpuBufferReader<Item = u8> = DefaultCpuReader<u8>> {
+    #[input]
+    input: I,
+    tracker: BerTracker,
+}
+
+impl<I> BerSink<I>
+where
+    I: CpuBufferReader<Item = u8>,
+{
+    pub fn new(polynomial: Polynomial) -> Self {
+        Self {
+            input: I::default(),
+            tracker: BerTracker {
+                lfsr: Lfsr::new(polynomial),
+                locked: false,
+                filled: 0,
+                run: 0,
+                window_bits: 0,
+                window_errors: 0,
+                bits: 0,
+                errors: 0,
+            },
+        }
+    }
+
+    /// Compared bits
+    pub fn bits(&self) -> u64 {
+        self.tracker.bits
+    }
+
+    /// Bit errors
+    pub fn errors(&self) -> u64 {
+        self.tracker.errors
+    }
+
+    /// Bit error rate, zero if no bits were compared
+    pub fn rate(&self) -> f64 {
+        if self.tracker.bits == 0 {
+            0.0
+        } else {
+            self.tracker.errors as f64 / self.tracker.bits as f64
+        }
+    }
+
+    /// Whether the sink is synchronized to the sequence
+    pub fn locked(&self) -> bool {
+        self.tracker.locked
+    }
+
+    async fn ber(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => Ok(Pmt::MapStrPmt(HashMap::from([
+                ("bits".to_string(), Pmt::U64(self.bits())),
+                ("errors".to_string(), Pmt::U64(self.errors())),
+                ("ber".to_string(), Pmt::F64(self.rate())),
+            ]))),
+            _ => Ok(Pmt::InvalidValue),
+        }
+    }
+}
+
+impl<I> Kernel for BerSink<I>
+where
+    I: CpuBufferReader<Item = u8>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let n = i.len();
+
+        for byte in i.iter() {
+            for k in 0..8 {
+                self.tracker.process((byte >> k) & 1);
+            }
+        }
+
+        self.input.consume(n);
+
+        if self.input.finished() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    fn prbs(polynomial: Polynomial, n: usize) -> Vec<u8> {
+        let mut block = PrbsSource::<Writer<_>>::new(polynomial);
+        block.output().reserve(n);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        mocker.output().get().0
+    }
+
+    fn ber(polynomial: Polynomial, input: Vec<u8>) -> Pmt {
+        let mut block = BerSink::<Reader<_>>::new(polynomial);
+        block.input().set(input);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        assert!(mocker.locked());
+        mocker.post("ber", Pmt::Null).unwrap()
+    }
+
+    #[test]
+    fn sequence_period() {
+        // a maximum-length sequence repeats after 2^degree - 1 bits
+        let bytes = prbs(Polynomial::PRBS7, 2 * 127);
+        let bits: Vec<u8> = bytes
+            .iter()
+            .flat_map(|b| (0..8).map(move |k| (b >> k) & 1))
+            .collect();
+        assert_eq!(bits[0..127], bits[127..254]);
+        assert_ne!(bits[0..127], bits[1..128]);
+        assert_eq!(bits[0..127].iter().filter(|b| **b == 1).count(), 64);
+    }
+
+    #[test]
+    fn error_free() {
+        let input = prbs(Polynomial::PRBS15, 1000);
+        let Pmt::MapStrPmt(map) = ber(Polynomial::PRBS15, input) else {
+            panic!("expected a map");
+        };
+        // 15 bits to fill the register and 15 to verify the prediction
+        assert_eq!(map["bits"], Pmt::U64(8000 - 30));
+        assert_eq!(map["errors"], Pmt::U64(0));
+        assert_eq!(map["ber"], Pmt::F64(0.0));
+    }
+
+    #[test]
+    fn injected_errors() {
+        let mut input = prbs(Polynomial::PRBS9, 1000);
+        for k in 0..10 {
+            input[100 + 80 * k] ^= 1 << (k % 8);
+        }
+        let Pmt::MapStrPmt(map) = ber(Polynomial::PRBS9, input) else {
+            panic!("expected a map");
+        };
+        assert_eq!(map["bits"], Pmt::U64(8000 - 18));
+        assert_eq!(map["errors"], Pmt::U64(10));
+        assert_eq!(map["ber"], Pmt::F64(10.0 / (8000.0 - 18.0)));
+    }
+}
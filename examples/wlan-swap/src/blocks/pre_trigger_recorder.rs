@@ -0,0 +1,223 @@
+use futuresdr::prelude::*;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// IQ recorder with pre-trigger capture.
+///
+/// Keeps the last `pre_samples` samples in a ring buffer. A message on the `trigger` port
+/// starts a capture of the buffered samples plus the next `post_samples` samples, which is
+/// written to a file once complete (or when the flowgraph terminates). The `n`-th capture
+/// (starting at 0) is written to `path` with `_n` appended to the file stem, e.g.,
+/// `capture_0.cf32`, as raw little-endian `f32` I/Q pairs. Triggers while a capture is in
+/// progress are ignored.
+#[derive(Block)]
+#[message_inputs(trigger)]
+pub struct PreTriggerRecorder<I = DefaultCpuReader<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    path: PathBuf,
+    pre_samples: usize,
+    post_samples: usize,
+    ring: VecDeque<Complex32>,
+    capture: Option<Capture>,
+    n_dumps: usize,
+}
+
+/// Capture in progress
+struct Capture {
+    samples: Vec<Complex32>,
+    remaining: usize,
+}
+
+impl<I> PreTriggerRecorder<I>
+where
+    I: CpuBufferReader<Item = Complex32>,
+{
+    pub fn new(path: impl Into<PathBuf>, pre_samples: usize, post_samples: usize) -> Self {
+        Self {
+            input: I::default(),
+            path: path.into(),
+            pre_samples,
+            post_samples,
+            ring: VecDeque::with_capacity(pre_samples),
+            capture: None,
+            n_dumps: 0,
+        }
+    }
+
+    /// File the `n`-th capture is written to
+    pub fn dump_path(path: &Path, n: usize) -> PathBuf {
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match path.extension() {
+            Some(ext) => format!("{}_{}.{}", stem, n, ext.to_string_lossy()),
+            None => format!("{}_{}", stem, n),
+        };
+        path.with_file_name(name)
+    }
+
+    /// Number of captures written
+    pub fn n_dumps(&self) -> usize {
+        self.n_dumps
+    }
+
+    fn write_capture(&mut self, samples: &[Complex32]) -> Result<()> {
+        let path = Self::dump_path(&self.path, self.n_dumps);
+        let mut file = BufWriter::new(File::create(&path)?);
+        for s in samples {
+            file.write_all(&s.re.to_le_bytes())?;
+            file.write_all(&s.im.to_le_bytes())?;
+        }
+        file.flush()?;
+        info!(
+            "PreTriggerRecorder: wrote {} samples to {}",
+            samples.len(),
+            path.display()
+        );
+        self.n_dumps += 1;
+        Ok(())
+    }
+
+    async fn trigger(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if matches!(p, Pmt::Finished) || self.capture.is_some() {
+            return Ok(Pmt::Ok);
+        }
+
+        let mut samples = Vec::with_capacity(self.ring.len() + self.post_samples);
+        samples.extend(self.ring.iter());
+        if self.post_samples == 0 {
+            self.write_capture(&samples)?;
+        } else {
+            self.capture = Some(Capture {
+                samples,
+                remaining: self.post_samples,
+            });
+        }
+        Ok(Pmt::Ok)
+    }
+}
+
+impl<I> Kernel for PreTriggerRecorder<I>
+where
+    I: CpuBufferReader<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _m: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let n = i.len();
+
+        if let Some(capture) = self.capture.as_mut() {
+            let m = std::cmp::min(n, capture.remaining);
+            capture.samples.extend_from_slice(&i[0..m]);
+            capture.remaining -= m;
+        }
+
+        if self.pre_samples > 0 {
+            let keep = std::cmp::min(n, self.pre_samples);
+            let overflow = (self.ring.len() + keep).saturating_sub(self.pre_samples);
+            self.ring.drain(0..overflow);
+            self.ring.extend(i[n - keep..n].iter());
+        }
+
+        self.input.consume(n);
+
+        if let Some(capture) = self.capture.take_if(|c| c.remaining == 0) {
+            self.write_capture(&capture.samples)?;
+        }
+
+        if self.input.finished() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+
+    async fn deinit(&mut self, _m: &mut MessageOutputs, _b: &mut BlockMeta) -> Result<()> {
+        // stream ended during the post-trigger window
+        if let Some(capture) = self.capture.take() {
+            self.write_capture(&capture.samples)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+
+    fn counter(range: std::ops::Range<usize>) -> Vec<Complex32> {
+        range.map(|k| Complex32::new(k as f32, -(k as f32))).collect()
+    }
+
+    #[test]
+    fn dumps_pre_and_post_samples() {
+        let path = std::env::temp_dir().join(format!("pre_trigger_{}.cf32", std::process::id()));
+        let dump = PreTriggerRecorder::<Reader<Complex32>>::dump_path(&path, 0);
+        let _ = std::fs::remove_file(&dump);
+
+        let mut block = PreTriggerRecorder::<Reader<_>>::new(&path, 100, 50);
+        block.input().set(counter(0..500));
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        mocker.post("trigger", Pmt::Null).unwrap();
+        // the post-trigger window spans several work calls
+        mocker.input.set(counter(500..520));
+        mocker.run();
+        mocker.post("trigger", Pmt::Null).unwrap();
+        mocker.input.set(counter(520..1000));
+        mocker.run();
+        assert_eq!(mocker.n_dumps(), 1);
+
+        let bytes = std::fs::read(&dump).unwrap();
+        std::fs::remove_file(&dump).unwrap();
+        let samples: Vec<Complex32> = bytes
+            .chunks_exact(8)
+            .map(|b| {
+                Complex32::new(
+                    f32::from_le_bytes(b[0..4].try_into().unwrap()),
+                    f32::from_le_bytes(b[4..8].try_into().unwrap()),
+                )
+            })
+            .collect();
+        assert_eq!(samples, counter(400..550));
+    }
+
+    #[test]
+    fn flushes_partial_capture() {
+        let path = std::env::temp_dir().join(format!("pre_trigger_partial_{}", std::process::id()));
+        let dump = PreTriggerRecorder::<Reader<Complex32>>::dump_path(&path, 0);
+        let _ = std::fs::remove_file(&dump);
+
+        let mut block = PreTriggerRecorder::<Reader<_>>::new(&path, 10, 1000);
+        block.input().set(counter(0..5));
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        mocker.post("trigger", Pmt::Null).unwrap();
+        mocker.input.set(counter(5..8));
+        mocker.run();
+        assert_eq!(mocker.n_dumps(), 0);
+        mocker.deinit();
+
+        let len = std::fs::metadata(&dump).unwrap().len();
+        std::fs::remove_file(&dump).unwrap();
+        assert_eq!(len, 8 * 8);
+    }
+}
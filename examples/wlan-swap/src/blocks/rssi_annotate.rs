@@ -0,0 +1,166 @@
+use futuresdr::prelude::*;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+
+/// Annotate received frames with the signal strength.
+///
+/// Takes the sample power (e.g., the moving average of `|x|^2` in front of the WiFi
+/// synchronization) on the stream input and the decoded frames on `in`. Each frame is posted on
+/// `out` as `Pmt::MapStrPmt` with the original `frame` and its `rssi_dbfs` (`Pmt::F64`,
+/// negative infinity if no power was received yet).
+///
+/// Frames carry no sample index, so the block relies on their order: a decoder posts a frame
+/// once all of its samples were received, so the frame is among the most recent samples. The
+/// power is averaged over consecutive windows of `window` samples and the strongest window of
+/// the last `history` samples is taken as the RSSI, since the windows without the frame only
+/// hold noise. `history` has to cover the longest frame plus the decoder latency, but should
+/// be short enough to not include the previous frame of a stronger transmitter.
+#[derive(Block)]
+#[message_inputs(r#in)]
+#[message_outputs(out)]
+pub struct RssiAnnotate<I = DefaultCpuReader<f32>>
+where
+    I: CpuBufferReader<Item = f32>,
+{
+    #[input]
+    input: I,
+    window: usize,
+    /// Means of the last complete windows, the most recent at the back
+    means: VecDeque<f32>,
+    max_windows: usize,
+    sum: f32,
+    count: usize,
+}
+
+impl<I> RssiAnnotate<I>
+where
+    I: CpuBufferReader<Item = f32>,
+{
+    pub fn new(window: usize, history: usize) -> Self {
+        assert!(window > 0 && history >= window);
+        let max_windows = history / window;
+        Self {
+            input: I::default(),
+            window,
+            means: VecDeque::with_capacity(max_windows),
+            max_windows,
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    /// Strongest window power of the history in dBFS
+    pub fn rssi_dbfs(&self) -> f64 {
+        let partial = (self.count > 0).then(|| self.sum / self.count as f32);
+        self.means
+            .iter()
+            .copied()
+            .chain(partial)
+            .reduce(f32::max)
+            .map(|p| 10.0 * (p as f64).log10())
+            .unwrap_or(f64::NEG_INFINITY)
+    }
+
+    async fn r#in(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Finished => {
+                io.finished = true;
+            }
+            frame => {
+                let annotated = Pmt::MapStrPmt(HashMap::from([
+                    ("frame".to_string(), frame),
+                    ("rssi_dbfs".to_string(), Pmt::F64(self.rssi_dbfs())),
+                ]));
+                mio.post("out", annotated).await?;
+            }
+        }
+        Ok(Pmt::Ok)
+    }
+}
+
+impl<I> Kernel for RssiAnnotate<I>
+where
+    I: CpuBufferReader<Item = f32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let n = i.len();
+
+        for p in i.iter() {
+            self.sum += *p;
+            self.count += 1;
+            if self.count == self.window {
+                if self.means.len() == self.max_windows {
+                    self.means.pop_front();
+                }
+                self.means.push_back(self.sum / self.window as f32);
+                self.sum = 0.0;
+                self.count = 0;
+            }
+        }
+
+        self.input.consume(n);
+
+        if self.input.finished() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+
+    fn annotate(power: Vec<f32>, window: usize, history: usize) -> HashMap<String, Pmt> {
+        let mut block = RssiAnnotate::<Reader<f32>>::new(window, history);
+        block.input().set(power);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        mocker.post("in", Pmt::Blob(vec![1, 2, 3])).unwrap();
+        mocker.run();
+
+        let messages = mocker.messages();
+        let [Pmt::MapStrPmt(map)] = &messages[0][..] else {
+            panic!("expected one annotation, got {:?}", messages[0]);
+        };
+        map.clone()
+    }
+
+    #[test]
+    fn constant_power() {
+        let map = annotate(vec![0.01; 1000], 64, 512);
+        assert_eq!(map["frame"], Pmt::Blob(vec![1, 2, 3]));
+        let Pmt::F64(rssi) = map["rssi_dbfs"] else {
+            panic!("rssi is not an f64");
+        };
+        assert!((rssi + 20.0).abs() < 1e-4, "{rssi}");
+    }
+
+    #[test]
+    fn strongest_recent_window() {
+        // an old burst beyond the history, the frame, and noise after it
+        let mut power = vec![1.0; 1000];
+        power.extend(vec![1e-6; 2000]);
+        power.extend(vec![1e-3; 500]);
+        power.extend(vec![1e-6; 300]);
+        let Pmt::F64(rssi) = annotate(power, 50, 1000)["rssi_dbfs"] else {
+            panic!("rssi is not an f64");
+        };
+        assert!((rssi + 30.0).abs() < 1e-3, "{rssi}");
+    }
+}
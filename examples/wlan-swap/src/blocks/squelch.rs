@@ -0,0 +1,211 @@
+use futuresdr::prelude::*;
+use std::collections::VecDeque;
+
+/// What [`Squelch`] does with samples while it is closed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SquelchMode {
+    /// Replace samples with zeros, keeping the sample rate
+    Mute,
+    /// Drop samples
+    Gate,
+}
+
+impl std::str::FromStr for SquelchMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "mute" => Ok(SquelchMode::Mute),
+            "gate" => Ok(SquelchMode::Gate),
+            _ => Err(format!("unknown squelch mode `{s}` (expected `mute` or `gate`)")),
+        }
+    }
+}
+
+/// Moving power estimate with open/close hysteresis
+struct PowerGate {
+    threshold: f32,
+    attack: usize,
+    release: usize,
+    history: VecDeque<f32>,
+    sum: f32,
+    open: bool,
+    count: usize,
+}
+
+impl PowerGate {
+    /// Update the power estimate with one sample and return whether the squelch is open.
+    fn update(&mut self, sample: Complex32) -> bool {
+        let power = sample.norm_sqr();
+        self.sum += power - self.history.pop_front().unwrap();
+        self.sum = self.sum.max(0.0);
+        self.history.push_back(power);
+
+        let above = self.sum >= self.threshold;
+        if above == self.open {
+            self.count = 0;
+        } else {
+            self.count += 1;
+            let hold = if self.open { self.release } else { self.attack };
+            if self.count > hold {
+                self.open = above;
+                self.count = 0;
+            }
+        }
+        self.open
+    }
+}
+
+/// Power squelch.
+///
+/// Passes samples while the average power over the last `window` samples is above
+/// `threshold_db`. The squelch opens after more than `attack` consecutive samples above the
+/// threshold and closes after more than `release` consecutive samples below it.
+#[derive(Block)]
+pub struct Squelch<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    mode: SquelchMode,
+    gate: PowerGate,
+}
+
+impl<I, O> Squelch<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    pub fn new(
+        threshold_db: f32,
+        window: usize,
+        mode: SquelchMode,
+        attack: usize,
+        release: usize,
+    ) -> Self {
+        assert!(window > 0);
+        Self {
+            input: I::default(),
+            output: O::default(),
+            mode,
+            gate: PowerGate {
+                threshold: 10.0f32.powf(threshold_db / 10.0) * window as f32,
+                attack,
+                release,
+                history: VecDeque::from(vec![0.0; window]),
+                sum: 0.0,
+                open: false,
+                count: 0,
+            },
+        }
+    }
+}
+
+impl<I, O> Kernel for Squelch<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _m: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let o = self.output.slice();
+        let i_len = i.len();
+        let n = std::cmp::min(i_len, o.len());
+
+        let mut produced = 0;
+        for s in &i[0..n] {
+            if self.gate.update(*s) {
+                o[produced] = *s;
+                produced += 1;
+            } else if self.mode == SquelchMode::Mute {
+                o[produced] = Complex32::new(0.0, 0.0);
+                produced += 1;
+            }
+        }
+
+        self.input.consume(n);
+        self.output.produce(produced);
+
+        if self.input.finished() && n == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    const SEGMENT: usize = 100;
+
+    /// noise, signal, noise, signal
+    fn input() -> Vec<Complex32> {
+        let mut v = Vec::new();
+        for seg in 0..4 {
+            let amp = if seg % 2 == 0 { 0.01 } else { 1.0 };
+            for k in 0..SEGMENT {
+                v.push(Complex32::from_polar(amp, k as f32));
+            }
+        }
+        v
+    }
+
+    fn run(mode: SquelchMode, attack: usize, release: usize) -> Vec<Complex32> {
+        let input = input();
+        let mut block = Squelch::<Reader<_>, Writer<_>>::new(-20.0, 4, mode, attack, release);
+        block.input().set(input.clone());
+        block.output().reserve(input.len());
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let (output, _) = mocker.output.get();
+        output
+    }
+
+    #[test]
+    fn mute_zeros_noise() {
+        let input = input();
+        let output = run(SquelchMode::Mute, 0, 0);
+        assert_eq!(output.len(), input.len());
+
+        for (k, (o, i)) in output.iter().zip(input.iter()).enumerate() {
+            let seg = k / SEGMENT;
+            let pos = k % SEGMENT;
+            if seg % 2 == 1 {
+                assert_eq!(o, i, "signal sample {k} muted");
+            } else if pos >= 3 {
+                // the power estimate needs a full window of noise to drop
+                assert_eq!(*o, Complex32::new(0.0, 0.0), "noise sample {k} passed");
+            }
+        }
+    }
+
+    #[test]
+    fn gate_drops_noise() {
+        let output = run(SquelchMode::Gate, 0, 0);
+        // both signal segments, plus the tail of the window after the first one
+        assert_eq!(output.len(), 2 * SEGMENT + 3);
+        assert!(output.iter().take(SEGMENT).all(|s| (s.norm() - 1.0).abs() < 1e-5));
+    }
+
+    #[test]
+    fn hysteresis() {
+        let output = run(SquelchMode::Gate, 10, 20);
+        // each signal segment loses `attack` samples and the noise after the first one
+        // is held for `release` samples on top of the window tail
+        assert_eq!(output.len(), 2 * (SEGMENT - 10) + 3 + 20);
+    }
+}
@@ -0,0 +1,193 @@
+use futuresdr::prelude::*;
+
+/// Sample-rate offset (SRO) correction.
+///
+/// Compensates a receiver clock that runs `ppm` parts per million faster than the transmitter
+/// clock (negative for a slower receiver) by resampling the input with a cubic Lagrange
+/// fractional-delay interpolator, i.e., output sample `k` is interpolated at input position
+/// `1 + k * (1 + ppm * 1e-6)`. The output is delayed by one sample, since the interpolator needs
+/// one sample of history. The `ppm` message port updates the offset at runtime (`Pmt::F64` or
+/// `Pmt::F32`), e.g., with an estimate from the pilot phase drift, and returns the current offset
+/// when called with `Pmt::Null`. Offsets of -1e6 ppm or less (and non-finite ones) are rejected
+/// with `Pmt::InvalidValue`, since the input would not advance (see [`SroCorrect::valid_ppm`]).
+#[derive(Block)]
+#[message_inputs(ppm)]
+pub struct SroCorrect<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    ppm: f64,
+    sample_rate: f64,
+    /// Input samples per output sample
+    step: f64,
+    /// Position of the next output sample, relative to the start of the input buffer
+    pos: f64,
+}
+
+impl<I, O> SroCorrect<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    pub fn new(ppm: f64, sample_rate: f64) -> Self {
+        assert!(sample_rate > 0.0 && sample_rate.is_finite());
+        assert!(Self::valid_ppm(ppm), "invalid offset {ppm} ppm");
+        Self {
+            input: I::default(),
+            output: O::default(),
+            ppm,
+            sample_rate,
+            step: Self::step(ppm),
+            pos: 1.0,
+        }
+    }
+
+    fn step(ppm: f64) -> f64 {
+        1.0 + ppm * 1e-6
+    }
+
+    /// Whether the offset gives a finite, positive step through the input
+    pub fn valid_ppm(ppm: f64) -> bool {
+        let step = Self::step(ppm);
+        step > 0.0 && step.is_finite()
+    }
+
+    /// Cubic Lagrange interpolation between `x[1]` and `x[2]` at fractional offset `mu`
+    fn interpolate(x: &[Complex32], mu: f32) -> Complex32 {
+        let c0 = -mu * (mu - 1.0) * (mu - 2.0) / 6.0;
+        let c1 = (mu + 1.0) * (mu - 1.0) * (mu - 2.0) / 2.0;
+        let c2 = -(mu + 1.0) * mu * (mu - 2.0) / 2.0;
+        let c3 = (mu + 1.0) * mu * (mu - 1.0) / 6.0;
+        x[0] * c0 + x[1] * c1 + x[2] * c2 + x[3] * c3
+    }
+
+    async fn ppm(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        let ppm = match p {
+            Pmt::F64(f) => f,
+            Pmt::F32(f) => f as f64,
+            Pmt::Null => return Ok(Pmt::F64(self.ppm)),
+            _ => return Ok(Pmt::InvalidValue),
+        };
+        if !Self::valid_ppm(ppm) {
+            return Ok(Pmt::InvalidValue);
+        }
+        self.ppm = ppm;
+        self.step = Self::step(ppm);
+        debug!(
+            "SroCorrect: {} ppm ({:.1} samples/s)",
+            ppm,
+            ppm * 1e-6 * self.sample_rate
+        );
+        Ok(Pmt::Ok)
+    }
+}
+
+impl<I, O> Kernel for SroCorrect<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _m: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let o = self.output.slice();
+        let i_len = i.len();
+
+        let mut produced = 0;
+        while produced < o.len() {
+            let base = self.pos.floor() as usize;
+            if base + 2 >= i_len {
+                break;
+            }
+            let mu = (self.pos - base as f64) as f32;
+            o[produced] = Self::interpolate(&i[base - 1..base + 3], mu);
+            self.pos += self.step;
+            produced += 1;
+        }
+
+        // keep one sample of history before the next output position
+        let consumed = std::cmp::min(self.pos.floor() as usize - 1, i_len);
+        self.pos -= consumed as f64;
+
+        self.input.consume(consumed);
+        self.output.produce(produced);
+
+        if self.input.finished() && self.pos.floor() as usize + 2 >= i_len - consumed {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    #[test]
+    fn corrects_ramp() {
+        const N: usize = 10000;
+        const PPM: f64 = 1000.0;
+        let step = 1.0 + PPM * 1e-6;
+
+        // transmitted ramp s(t) = t, sampled by a receiver that is PPM too fast
+        let input: Vec<Complex32> = (0..N)
+            .map(|n| {
+                let t = n as f64 / step;
+                Complex32::new(t as f32, -t as f32)
+            })
+            .collect();
+
+        let mut block = SroCorrect::<Reader<_>, Writer<_>>::new(PPM, 20e6);
+        block.input().set(input);
+        block.output().reserve(N);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let (output, _) = mocker.output.get();
+
+        // one output per transmitted sample, i.e., ~10 samples less than the input
+        assert_eq!(output.len(), ((N - 3) as f64 / step).ceil() as usize);
+        assert!(N - output.len() >= 10);
+        // back on the transmitter's sample grid (delayed by one receiver sample)
+        let delay = 1.0 / step;
+        for (k, y) in output.iter().enumerate() {
+            let t = (k as f64 + delay) as f32;
+            assert!((y - Complex32::new(t, -t)).norm() < 1e-2, "{k}: {y}");
+        }
+    }
+
+    #[test]
+    fn update_ppm() {
+        let mut mocker = Mocker::new(SroCorrect::<Reader<_>, Writer<_>>::new(0.0, 20e6));
+        assert_eq!(mocker.post("ppm", Pmt::F32(-20.0)).unwrap(), Pmt::Ok);
+        assert_eq!(mocker.post("ppm", Pmt::Null).unwrap(), Pmt::F64(-20.0));
+        assert_eq!(mocker.post("ppm", Pmt::U32(1)).unwrap(), Pmt::InvalidValue);
+        assert_eq!(
+            mocker.post("ppm", Pmt::F64(-1e6)).unwrap(),
+            Pmt::InvalidValue
+        );
+        assert_eq!(
+            mocker.post("ppm", Pmt::F64(f64::NAN)).unwrap(),
+            Pmt::InvalidValue
+        );
+        assert_eq!(mocker.post("ppm", Pmt::Null).unwrap(), Pmt::F64(-20.0));
+    }
+}
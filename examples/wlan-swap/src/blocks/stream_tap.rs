@@ -0,0 +1,129 @@
+use futuresdr::prelude::*;
+
+/// Pass a stream through unchanged while copying samples to a message port.
+///
+/// Of every `decimate` samples, the first is tapped, starting with the first sample of the
+/// stream. The samples tapped in one `work()` call are posted together on `tap` as a
+/// `Pmt::Blob` with their raw bytes in native byte order, like a file written by `FileSink`.
+#[derive(Block)]
+#[message_outputs(tap)]
+pub struct StreamTap<T, I = DefaultCpuReader<T>, O = DefaultCpuWriter<T>>
+where
+    T: CpuSample + Copy,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    decimate: usize,
+    /// Samples to pass before the next one is tapped
+    skip: usize,
+    tapped: Vec<T>,
+}
+
+impl<T, I, O> StreamTap<T, I, O>
+where
+    T: CpuSample + Copy,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    pub fn new(decimate: usize) -> Self {
+        assert!(decimate >= 1);
+        Self {
+            input: I::default(),
+            output: O::default(),
+            decimate,
+            skip: 0,
+            tapped: Vec::new(),
+        }
+    }
+}
+
+impl<T, I, O> Kernel for StreamTap<T, I, O>
+where
+    T: CpuSample + Copy,
+    I: CpuBufferReader<Item = T>,
+    O: CpuBufferWriter<Item = T>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let o = self.output.slice();
+        let i_len = i.len();
+        let n = std::cmp::min(i_len, o.len());
+
+        o[0..n].copy_from_slice(&i[0..n]);
+        self.tapped.clear();
+        self.tapped
+            .extend(i[0..n].iter().skip(self.skip).step_by(self.decimate));
+        self.skip = if n <= self.skip {
+            self.skip - n
+        } else {
+            (self.decimate - (n - self.skip) % self.decimate) % self.decimate
+        };
+
+        self.input.consume(n);
+        self.output.produce(n);
+
+        if !self.tapped.is_empty() {
+            let bytes = unsafe {
+                std::slice::from_raw_parts(
+                    self.tapped.as_ptr() as *const u8,
+                    std::mem::size_of_val(&self.tapped[..]),
+                )
+            };
+            mio.post("tap", Pmt::Blob(bytes.to_vec())).await?;
+        }
+
+        if self.input.finished() && n == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    #[test]
+    fn pass_through_and_tap() {
+        let input: Vec<f32> = (0..25).map(|k| k as f32).collect();
+
+        let mut block = StreamTap::<f32, Reader<_>, Writer<_>>::new(4);
+        block.output().reserve(input.len());
+        let mut mocker = Mocker::new(block);
+        // chunks that do not align with the decimation
+        for chunk in input.chunks(7) {
+            mocker.input.set(chunk.to_vec());
+            mocker.run();
+        }
+
+        let (output, _) = mocker.output.get();
+        assert_eq!(output, input);
+
+        let tapped: Vec<f32> = mocker.messages()[0]
+            .iter()
+            .flat_map(|p| match p {
+                Pmt::Blob(b) => b
+                    .chunks(4)
+                    .map(|c| f32::from_ne_bytes(c.try_into().unwrap()))
+                    .collect::<Vec<_>>(),
+                p => panic!("unexpected {p:?}"),
+            })
+            .collect();
+        assert_eq!(tapped, [0.0, 4.0, 8.0, 12.0, 16.0, 20.0, 24.0]);
+        // one message per work call with tapped samples
+        assert_eq!(mocker.messages()[0].len(), 4);
+    }
+}
@@ -0,0 +1,217 @@
+use futuresdr::prelude::*;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Open CSV file and the limits for splitting the recording
+struct Recorder {
+    path: PathBuf,
+    max_rows: Option<u64>,
+    rotate: bool,
+    index: bool,
+    file: Option<BufWriter<File>>,
+    file_index: usize,
+    rows_in_file: u64,
+    n_rows: u64,
+    full: bool,
+}
+
+impl Recorder {
+    /// Path of the `k`-th file: `path` itself for the first, `stem.k.ext` for later ones
+    fn file_path(&self, k: usize) -> PathBuf {
+        if k == 0 {
+            return self.path.clone();
+        }
+        let stem = self.path.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match self.path.extension() {
+            Some(ext) => format!("{}.{}.{}", stem, k, ext.to_string_lossy()),
+            None => format!("{}.{}", stem, k),
+        };
+        self.path.with_file_name(name)
+    }
+
+    fn open(&mut self) -> Result<&mut BufWriter<File>> {
+        if self.file.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.file_path(self.file_index))?;
+            let empty = file.metadata()?.len() == 0;
+            let mut file = BufWriter::new(file);
+            if empty {
+                writeln!(file, "{}", if self.index { "index,re,im" } else { "re,im" })?;
+            }
+            self.file = Some(file);
+        }
+        Ok(self.file.as_mut().unwrap())
+    }
+
+    fn write(&mut self, s: &Complex32) -> Result<()> {
+        if self.max_rows.is_some_and(|max| self.rows_in_file >= max) {
+            if !self.rotate {
+                if !self.full {
+                    warn!(
+                        "SymbolCsvSink: {} full, dropping further symbols",
+                        self.path.display()
+                    );
+                    self.full = true;
+                }
+                return Ok(());
+            }
+            self.flush()?;
+            self.file = None;
+            self.file_index += 1;
+            self.rows_in_file = 0;
+        }
+
+        let (index, n_rows) = (self.index, self.n_rows);
+        let file = self.open()?;
+        if index {
+            writeln!(file, "{},{},{}", n_rows, s.re, s.im)?;
+        } else {
+            writeln!(file, "{},{}", s.re, s.im)?;
+        }
+        self.rows_in_file += 1;
+        self.n_rows += 1;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        if let Some(file) = self.file.as_mut() {
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Record symbols as `re,im` rows to a CSV file.
+///
+/// For offline analysis of what the constellation view shows live. Rows are appended to
+/// `path`, with a `re,im` header if the file is new or empty. With `index`, each row starts with
+/// the number of the symbol, counted from the start of the recording.
+///
+/// `max_rows` limits the rows written to one file. Once reached, the sink drops further
+/// symbols, or, with `rotate`, continues in a new file, named like `path` with the number of
+/// the file before the extension (e.g., `symbols.csv`, `symbols.1.csv`, `symbols.2.csv`).
+#[derive(Block)]
+pub struct SymbolCsvSink<I = DefaultCpuReader<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    recorder: Recorder,
+}
+
+impl<I> SymbolCsvSink<I>
+where
+    I: CpuBufferReader<Item = Complex32>,
+{
+    pub fn new(path: impl Into<PathBuf>, max_rows: Option<u64>, rotate: bool, index: bool) -> Self {
+        Self {
+            input: I::default(),
+            recorder: Recorder {
+                path: path.into(),
+                max_rows,
+                rotate,
+                index,
+                file: None,
+                file_index: 0,
+                rows_in_file: 0,
+                n_rows: 0,
+                full: false,
+            },
+        }
+    }
+
+    /// Number of symbols written
+    pub fn n_rows(&self) -> u64 {
+        self.recorder.n_rows
+    }
+}
+
+impl<I> Kernel for SymbolCsvSink<I>
+where
+    I: CpuBufferReader<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let n = i.len();
+
+        for s in i.iter() {
+            self.recorder.write(s)?;
+        }
+        self.recorder.flush()?;
+        self.input.consume(n);
+
+        if self.input.finished() {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+
+    fn symbols(n: usize) -> Vec<Complex32> {
+        (0..n)
+            .map(|k| Complex32::new(k as f32, -0.5 * k as f32))
+            .collect()
+    }
+
+    #[test]
+    fn writes_symbols() {
+        let path = std::env::temp_dir().join(format!("symbol_csv_{}.csv", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut mocker = Mocker::new(SymbolCsvSink::<Reader<_>>::new(
+            &path,
+            Some(3),
+            false,
+            false,
+        ));
+        mocker.input.set(symbols(2));
+        mocker.run();
+        mocker.input.set(symbols(2));
+        mocker.run();
+        assert_eq!(mocker.n_rows(), 3);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(content, "re,im\n0,-0\n1,-0.5\n0,-0\n");
+    }
+
+    #[test]
+    fn rotates_files() {
+        let dir = std::env::temp_dir().join(format!("symbol_csv_rotate_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("symbols.csv");
+
+        let mut mocker = Mocker::new(SymbolCsvSink::<Reader<_>>::new(&path, Some(2), true, true));
+        mocker.input.set(symbols(5));
+        mocker.run();
+
+        let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+        let files = [
+            read("symbols.csv"),
+            read("symbols.1.csv"),
+            read("symbols.2.csv"),
+        ];
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(files[0], "index,re,im\n0,0,-0\n1,1,-0.5\n");
+        assert_eq!(files[1], "index,re,im\n2,2,-1\n3,3,-1.5\n");
+        assert_eq!(files[2], "index,re,im\n4,4,-2\n");
+    }
+}
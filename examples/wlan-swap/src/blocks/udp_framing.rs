@@ -0,0 +1,489 @@
+use futuresdr::async_io::Timer;
+use futuresdr::async_net::SocketAddr;
+use futuresdr::async_net::UdpSocket;
+use futuresdr::futures::future::Either;
+use futuresdr::futures::future::select;
+use futuresdr::prelude::*;
+use std::net::ToSocketAddrs;
+use std::pin::pin;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use crate::wifi::Mcs;
+
+/// Size of the frame header: payload length (`u32`) and type tag (`u16`), both big-endian
+pub const FRAME_HEADER_SIZE: usize = 6;
+
+/// Size of the metadata header of [`BlobToUdpWithMeta`], see [`encode_meta_frame`]
+pub const META_HEADER_SIZE: usize = 14;
+
+/// Version of the metadata header layout
+pub const META_VERSION: u8 = 1;
+
+/// MCS index in the metadata header if the frame has no (valid) MCS
+pub const MCS_UNKNOWN: u8 = 0xff;
+
+/// Largest UDP payload
+const MAX_DATAGRAM: usize = 65507;
+
+/// Time [`UdpToBlob`] waits for a datagram before it checks its inbox
+const RECV_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Prepend the frame header to a payload
+pub fn encode_frame(tag: u16, payload: &[u8]) -> Vec<u8> {
+    let mut b = Vec::with_capacity(FRAME_HEADER_SIZE + payload.len());
+    b.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    b.extend_from_slice(&tag.to_be_bytes());
+    b.extend_from_slice(payload);
+    b
+}
+
+/// Split a frame into type tag and payload
+///
+/// Returns `None` if the frame is shorter than the header or the length does not match.
+pub fn decode_frame(b: &[u8]) -> Option<(u16, &[u8])> {
+    let len = u32::from_be_bytes(b.get(0..4)?.try_into().unwrap()) as usize;
+    let tag = u16::from_be_bytes(b.get(4..6)?.try_into().unwrap());
+    let payload = &b[FRAME_HEADER_SIZE..];
+    (payload.len() == len).then_some((tag, payload))
+}
+
+/// Metadata of a frame, sent by [`BlobToUdpWithMeta`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FrameMeta {
+    /// Index of the MCS in [`Mcs::all`], or [`MCS_UNKNOWN`]
+    pub mcs: u8,
+    /// Received signal strength in dB, NaN if unknown
+    pub rssi_db: f32,
+    /// Time of reception in microseconds since the Unix epoch
+    pub timestamp_us: u64,
+}
+
+/// Prepend the metadata header to a payload
+///
+/// The header has a fixed size of [`META_HEADER_SIZE`] bytes, with all fields big-endian:
+///
+/// | Offset | Type  | Field                           |
+/// |--------|-------|---------------------------------|
+/// | 0      | `u8`  | version ([`META_VERSION`])      |
+/// | 1      | `u8`  | MCS index or [`MCS_UNKNOWN`]    |
+/// | 2      | `f32` | RSSI in dB (NaN if unknown)     |
+/// | 6      | `u64` | timestamp in µs since the epoch |
+///
+/// The payload follows up to the end of the datagram.
+pub fn encode_meta_frame(meta: &FrameMeta, payload: &[u8]) -> Vec<u8> {
+    let mut b = Vec::with_capacity(META_HEADER_SIZE + payload.len());
+    b.push(META_VERSION);
+    b.push(meta.mcs);
+    b.extend_from_slice(&meta.rssi_db.to_be_bytes());
+    b.extend_from_slice(&meta.timestamp_us.to_be_bytes());
+    b.extend_from_slice(payload);
+    b
+}
+
+/// Split a datagram of [`BlobToUdpWithMeta`] into metadata and payload
+///
+/// Returns `None` if it is shorter than the header or has another version.
+pub fn decode_meta_frame(b: &[u8]) -> Option<(FrameMeta, &[u8])> {
+    if b.len() < META_HEADER_SIZE || b[0] != META_VERSION {
+        return None;
+    }
+    let meta = FrameMeta {
+        mcs: b[1],
+        rssi_db: f32::from_be_bytes(b[2..6].try_into().unwrap()),
+        timestamp_us: u64::from_be_bytes(b[6..14].try_into().unwrap()),
+    };
+    Some((meta, &b[META_HEADER_SIZE..]))
+}
+
+/// Metadata and payload of a frame given as `Pmt::MapStrPmt`, `None` without a `payload` blob
+fn frame_meta(p: &Pmt) -> Option<(FrameMeta, &[u8])> {
+    let Pmt::MapStrPmt(map) = p else {
+        return None;
+    };
+    let Some(Pmt::Blob(payload)) = map.get("payload") else {
+        return None;
+    };
+    let mcs = match map.get("mcs") {
+        Some(Pmt::String(s)) => Mcs::parse(s)
+            .ok()
+            .and_then(|m| Mcs::all().iter().position(|a| *a == m)),
+        Some(Pmt::U32(i)) => Some(*i as usize),
+        Some(Pmt::Usize(i)) => Some(*i),
+        _ => None,
+    };
+    let rssi_db = match map.get("rssi") {
+        Some(Pmt::F32(r)) => *r,
+        Some(Pmt::F64(r)) => *r as f32,
+        _ => f32::NAN,
+    };
+    let timestamp_us = match map.get("timestamp") {
+        Some(Pmt::U64(t)) => *t,
+        _ => SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_micros() as u64)
+            .unwrap_or(0),
+    };
+    let meta = FrameMeta {
+        mcs: mcs
+            .filter(|i| *i < Mcs::all().len())
+            .map(|i| i as u8)
+            .unwrap_or(MCS_UNKNOWN),
+        rssi_db,
+        timestamp_us,
+    };
+    Some((meta, payload))
+}
+
+fn resolve(address: &str) -> Result<SocketAddr> {
+    address
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("could not resolve {address}"))
+}
+
+/// Send [Blobs](Pmt::Blob) as framed UDP datagrams.
+///
+/// Like [`BlobToUdp`](futuresdr::blocks::BlobToUdp), but each datagram starts with a header in
+/// the layout of [`encode_frame`]: the payload length as big-endian `u32` and the type `tag`
+/// as big-endian `u16`. Receivers can check that a datagram is complete and tell different
+/// streams (e.g., frames and RFtap) apart. `Pmt::Finished` terminates the block.
+#[derive(Block)]
+#[message_inputs(r#in)]
+pub struct BlobToUdpFramed {
+    socket: Option<UdpSocket>,
+    remote: SocketAddr,
+    tag: u16,
+}
+
+impl BlobToUdpFramed {
+    /// Send to `remote` (e.g., `127.0.0.1:55555`), with `tag` in every header
+    pub fn new(remote: &str, tag: u16) -> Result<Self> {
+        Ok(Self {
+            socket: None,
+            remote: resolve(remote)?,
+            tag,
+        })
+    }
+
+    async fn r#in(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Blob(v) => {
+                let socket = self
+                    .socket
+                    .as_ref()
+                    .ok_or_else(|| anyhow::anyhow!("no socket"))?;
+                socket
+                    .send_to(&encode_frame(self.tag, &v), self.remote)
+                    .await?;
+                Ok(Pmt::Ok)
+            }
+            Pmt::Finished => {
+                io.finished = true;
+                Ok(Pmt::Ok)
+            }
+            _ => Ok(Pmt::InvalidValue),
+        }
+    }
+}
+
+impl Kernel for BlobToUdpFramed {
+    async fn init(&mut self, _mio: &mut MessageOutputs, _meta: &mut BlockMeta) -> Result<()> {
+        let local = if self.remote.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        self.socket = Some(UdpSocket::bind(local).await?);
+        Ok(())
+    }
+}
+
+/// Send frames with their metadata as UDP datagrams.
+///
+/// Like [`BlobToUdp`](futuresdr::blocks::BlobToUdp), but for analysis pipelines that need to
+/// know how a frame was received. Expects each frame on `in` as `Pmt::MapStrPmt` with the
+/// fields
+/// - `payload` (`Pmt::Blob`): the frame itself,
+/// - `mcs` (`Pmt::String` with the MCS name, e.g., `Qpsk_1_2`, or its index in [`Mcs::all`]
+///   as an integer),
+/// - `rssi` (`Pmt::F32` or `Pmt::F64`, in dB), and
+/// - `timestamp` (`Pmt::U64`, in µs since the Unix epoch).
+///
+/// Only `payload` is required; missing fields are sent as unknown (see [`FrameMeta`]), and a
+/// missing timestamp is replaced by the time of sending. Each datagram carries the header in
+/// the layout of [`encode_meta_frame`], followed by the payload. Messages without a payload,
+/// or with a payload that does not fit into a datagram with the header, are rejected with
+/// `Pmt::InvalidValue`. `Pmt::Finished` terminates the block.
+#[derive(Block)]
+#[message_inputs(r#in)]
+pub struct BlobToUdpWithMeta {
+    socket: Option<UdpSocket>,
+    remote: SocketAddr,
+}
+
+impl BlobToUdpWithMeta {
+    /// Send to `remote` (e.g., `127.0.0.1:55555`)
+    pub fn new(remote: &str) -> Result<Self> {
+        Ok(Self {
+            socket: None,
+            remote: resolve(remote)?,
+        })
+    }
+
+    async fn r#in(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if matches!(p, Pmt::Finished) {
+            io.finished = true;
+            return Ok(Pmt::Ok);
+        }
+        let Some((meta, payload)) = frame_meta(&p) else {
+            return Ok(Pmt::InvalidValue);
+        };
+        if payload.len() > MAX_DATAGRAM - META_HEADER_SIZE {
+            warn!(
+                "BlobToUdpWithMeta: dropping {} byte payload, too large for a datagram",
+                payload.len()
+            );
+            return Ok(Pmt::InvalidValue);
+        }
+        let socket = self
+            .socket
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("no socket"))?;
+        socket
+            .send_to(&encode_meta_frame(&meta, payload), self.remote)
+            .await?;
+        Ok(Pmt::Ok)
+    }
+}
+
+impl Kernel for BlobToUdpWithMeta {
+    async fn init(&mut self, _mio: &mut MessageOutputs, _meta: &mut BlockMeta) -> Result<()> {
+        let local = if self.remote.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        self.socket = Some(UdpSocket::bind(local).await?);
+        Ok(())
+    }
+}
+
+/// Receive framed UDP datagrams as [Blobs](Pmt::Blob).
+///
+/// The counterpart of [`BlobToUdpFramed`]: binds to `address` and posts the payload of each
+/// datagram with a valid header on `out`. Datagrams with a length that does not match the
+/// header, or, if a `tag` is set, with another tag, are dropped.
+#[derive(Block)]
+#[message_outputs(out)]
+pub struct UdpToBlob {
+    bind: String,
+    tag: Option<u16>,
+    socket: Option<UdpSocket>,
+    buf: Vec<u8>,
+}
+
+impl UdpToBlob {
+    /// Listen on `bind` (e.g., `127.0.0.1:55555`), accepting frames with any tag if `tag` is `None`
+    pub fn new(bind: impl Into<String>, tag: Option<u16>) -> Self {
+        Self {
+            bind: bind.into(),
+            tag,
+            socket: None,
+            buf: vec![0; MAX_DATAGRAM],
+        }
+    }
+
+    /// Local address of the socket, once the block is initialized
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.socket.as_ref()?.local_addr().ok()
+    }
+}
+
+impl Kernel for UdpToBlob {
+    async fn init(&mut self, _mio: &mut MessageOutputs, _meta: &mut BlockMeta) -> Result<()> {
+        self.socket = Some(UdpSocket::bind(self.bind.as_str()).await?);
+        Ok(())
+    }
+
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        let socket = self
+            .socket
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("no socket"))?;
+        // wake up regularly, so that the block can be terminated without traffic
+        let n = {
+            let recv = pin!(socket.recv_from(&mut self.buf));
+            match select(recv, Timer::after(RECV_TIMEOUT)).await {
+                Either::Left((res, _)) => res?.0,
+                Either::Right(_) => {
+                    io.call_again = true;
+                    return Ok(());
+                }
+            }
+        };
+
+        match decode_frame(&self.buf[..n]) {
+            Some((tag, payload)) if self.tag.is_none_or(|t| t == tag) => {
+                mio.post("out", Pmt::Blob(payload.to_vec())).await?;
+            }
+            Some(_) => {}
+            None => warn!("UdpToBlob: dropping malformed datagram of {} bytes", n),
+        }
+        io.call_again = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::BlockMessage;
+    use futuresdr::runtime::WrappedKernel;
+    use futuresdr::runtime::mocker::Mocker;
+    use std::collections::HashMap;
+
+    #[test]
+    fn frame_layout() {
+        let b = encode_frame(0x0102, b"abc");
+        assert_eq!(b, [0, 0, 0, 3, 1, 2, b'a', b'b', b'c']);
+        assert_eq!(decode_frame(&b), Some((0x0102, &b"abc"[..])));
+        assert_eq!(decode_frame(&b[..8]), None);
+        assert_eq!(decode_frame(&b[..5]), None);
+        assert_eq!(decode_frame(&encode_frame(7, b"")), Some((7, &b""[..])));
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rx = Mocker::new(UdpToBlob::new("127.0.0.1:0", Some(3)));
+        rx.init();
+        let addr = rx.local_addr().unwrap().to_string();
+        let (out_tx, mut out_rx) = futuresdr::futures::channel::mpsc::channel(10);
+        rx.mio
+            .connect(&"out".into(), out_tx, &"input".into())
+            .unwrap();
+
+        let mut tx = Mocker::new(BlobToUdpFramed::new(&addr, 3).unwrap());
+        tx.init();
+        let mut other = Mocker::new(BlobToUdpFramed::new(&addr, 4).unwrap());
+        other.init();
+
+        assert_eq!(
+            tx.post("in", Pmt::Blob(b"hello".to_vec())).unwrap(),
+            Pmt::Ok
+        );
+        assert_eq!(
+            other.post("in", Pmt::Blob(b"other".to_vec())).unwrap(),
+            Pmt::Ok
+        );
+        assert_eq!(
+            tx.post("in", Pmt::Blob(vec![0xff, 0, 0x80])).unwrap(),
+            Pmt::Ok
+        );
+        assert_eq!(tx.post("in", Pmt::U32(1)).unwrap(), Pmt::InvalidValue);
+
+        // each call receives at most one datagram
+        let mut received = Vec::new();
+        let mut io = WorkIo {
+            call_again: false,
+            finished: false,
+            block_on: None,
+        };
+        let WrappedKernel {
+            meta, mio, kernel, ..
+        } = &mut rx.block;
+        for _ in 0..10 {
+            futuresdr::async_io::block_on(kernel.work(&mut io, mio, meta)).unwrap();
+            assert!(io.call_again);
+            while let Ok(BlockMessage::Call { data, .. }) = out_rx.try_recv() {
+                received.push(data);
+            }
+            if received.len() == 2 {
+                break;
+            }
+        }
+        assert_eq!(
+            received,
+            vec![Pmt::Blob(b"hello".to_vec()), Pmt::Blob(vec![0xff, 0, 0x80])]
+        );
+    }
+
+    #[test]
+    fn meta_header() {
+        let receiver = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let addr = receiver.local_addr().unwrap().to_string();
+
+        let mut tx = Mocker::new(BlobToUdpWithMeta::new(&addr).unwrap());
+        tx.init();
+        let frame = Pmt::MapStrPmt(HashMap::from([
+            ("payload".to_string(), Pmt::Blob(b"frame".to_vec())),
+            ("mcs".to_string(), Pmt::String("Qam16_1_2".to_string())),
+            ("rssi".to_string(), Pmt::F64(-42.5)),
+            ("timestamp".to_string(), Pmt::U64(1_700_000_000_123_456)),
+        ]));
+        assert_eq!(tx.post("in", frame).unwrap(), Pmt::Ok);
+        // only the payload is required
+        let minimal = Pmt::MapStrPmt(HashMap::from([(
+            "payload".to_string(),
+            Pmt::Blob(vec![0xff]),
+        )]));
+        assert_eq!(tx.post("in", minimal).unwrap(), Pmt::Ok);
+        assert_eq!(
+            tx.post("in", Pmt::Blob(b"frame".to_vec())).unwrap(),
+            Pmt::InvalidValue
+        );
+        let oversized = Pmt::MapStrPmt(HashMap::from([(
+            "payload".to_string(),
+            Pmt::Blob(vec![0; MAX_DATAGRAM - META_HEADER_SIZE + 1]),
+        )]));
+        assert_eq!(tx.post("in", oversized).unwrap(), Pmt::InvalidValue);
+
+        let mut buf = [0u8; 64];
+        let n = receiver.recv(&mut buf).unwrap();
+        assert_eq!(
+            &buf[..META_HEADER_SIZE],
+            [
+                1, 4, 0xc2, 0x2a, 0, 0, 0, 0x06, 0x0a, 0x24, 0x18, 0x20, 0x22, 0x40
+            ]
+        );
+        let (meta, payload) = decode_meta_frame(&buf[..n]).unwrap();
+        assert_eq!(
+            meta,
+            FrameMeta {
+                mcs: 4,
+                rssi_db: -42.5,
+                timestamp_us: 1_700_000_000_123_456,
+            }
+        );
+        assert_eq!(payload, b"frame");
+
+        let n = receiver.recv(&mut buf).unwrap();
+        let (meta, payload) = decode_meta_frame(&buf[..n]).unwrap();
+        assert_eq!(meta.mcs, MCS_UNKNOWN);
+        assert!(meta.rssi_db.is_nan());
+        assert!(meta.timestamp_us > 1_700_000_000_000_000);
+        assert_eq!(payload, [0xff]);
+
+        assert_eq!(decode_meta_frame(&buf[..META_HEADER_SIZE - 1]), None);
+    }
+}
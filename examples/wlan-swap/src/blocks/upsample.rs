@@ -0,0 +1,102 @@
+use futuresdr::prelude::*;
+
+/// Upsample by an integer factor, inserting zeros.
+///
+/// Each input sample is followed by `factor - 1` zeros, so the output has exactly `factor`
+/// times as many samples as the input. Followed by an interpolation filter, e.g., a [`Fir`]
+/// with root-raised-cosine taps for pulse shaping, this raises the sample rate before the DAC.
+/// Zero-stuffing scales the signal power by `1 / factor`, which the filter's gain has to make
+/// up for.
+///
+/// [`Fir`]: super::Fir
+#[derive(Block)]
+pub struct Upsample<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<Complex32>>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    #[input]
+    input: I,
+    #[output]
+    output: O,
+    factor: usize,
+}
+
+impl<I, O> Upsample<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    pub fn new(factor: usize) -> Self {
+        assert!(factor >= 1);
+        Self {
+            input: I::default(),
+            output: O::default(),
+            factor,
+        }
+    }
+}
+
+impl<I, O> Kernel for Upsample<I, O>
+where
+    I: CpuBufferReader<Item = Complex32>,
+    O: CpuBufferWriter<Item = Complex32>,
+{
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        _m: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let i = self.input.slice();
+        let o = self.output.slice();
+        let i_len = i.len();
+        let n = std::cmp::min(i_len, o.len() / self.factor);
+
+        for (s, out) in i[0..n].iter().zip(o.chunks_exact_mut(self.factor)) {
+            out[0] = *s;
+            out[1..].fill(Complex32::new(0.0, 0.0));
+        }
+
+        self.input.consume(n);
+        self.output.produce(n * self.factor);
+
+        if self.input.finished() && n == i_len {
+            io.finished = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    #[test]
+    fn zero_stuffing() {
+        let input: Vec<Complex32> = (1..=10).map(|k| Complex32::new(k as f32, -1.0)).collect();
+
+        let mut block = Upsample::<Reader<_>, Writer<_>>::new(4);
+        block.output().reserve(4 * input.len());
+        let mut mocker = Mocker::new(block);
+        for chunk in input.chunks(3) {
+            mocker.input.set(chunk.to_vec());
+            mocker.run();
+        }
+
+        let (output, _) = mocker.output.get();
+        assert_eq!(output.len(), 4 * input.len());
+        for (k, s) in output.iter().enumerate() {
+            let expected = if k % 4 == 0 {
+                input[k / 4]
+            } else {
+                Complex32::new(0.0, 0.0)
+            };
+            assert_eq!(*s, expected, "sample {k}");
+        }
+    }
+}
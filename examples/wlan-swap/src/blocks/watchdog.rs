@@ -0,0 +1,130 @@
+use futuresdr::async_io::Timer;
+use futuresdr::prelude::*;
+use std::time::Duration;
+use web_time::Instant;
+
+/// Terminate when no messages arrive for a while.
+///
+/// Every message on `kick` (e.g., the decoded frames) restarts the timer. If the block is not
+/// kicked for `timeout`, e.g., because the SDR was unplugged, it sets `io.finished` and posts
+/// the idle time in seconds as `Pmt::F64` on `timeout`. A block finishing on its own does not
+/// stop the other blocks, so the receiver flowgraphs connect `timeout` to the `stop` port of the
+/// `FlowgraphController`, which tears the flowgraph down. `Pmt::Finished` on `kick` terminates
+/// the block without a timeout.
+#[derive(Block)]
+#[message_inputs(kick)]
+#[message_outputs(timeout)]
+pub struct Watchdog {
+    timeout: Duration,
+    last_kick: Instant,
+    timed_out: bool,
+}
+
+impl Watchdog {
+    pub fn new(timeout: Duration) -> Self {
+        assert!(!timeout.is_zero());
+        Self {
+            timeout,
+            last_kick: Instant::now(),
+            timed_out: false,
+        }
+    }
+
+    /// Whether the block was not kicked in time
+    pub fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    async fn kick(
+        &mut self,
+        io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Finished => io.finished = true,
+            _ => self.last_kick = Instant::now(),
+        }
+        Ok(Pmt::Ok)
+    }
+}
+
+impl Kernel for Watchdog {
+    async fn init(&mut self, _mio: &mut MessageOutputs, _meta: &mut BlockMeta) -> Result<()> {
+        self.last_kick = Instant::now();
+        Ok(())
+    }
+
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+    ) -> Result<()> {
+        if self.timed_out {
+            return Ok(());
+        }
+
+        let idle = self.last_kick.elapsed();
+        if idle >= self.timeout {
+            warn!(
+                "Watchdog: not kicked for {:.1} s, terminating",
+                idle.as_secs_f64()
+            );
+            self.timed_out = true;
+            io.finished = true;
+            mio.post("timeout", Pmt::F64(idle.as_secs_f64())).await?;
+            return Ok(());
+        }
+
+        // kicks in the meantime only move the deadline, which is checked again on wake-up
+        let wait = self.timeout - idle;
+        io.block_on(async move {
+            Timer::after(wait).await;
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+
+    #[test]
+    fn terminates_without_kicks() {
+        let timeout = Duration::from_millis(100);
+        let mut mocker = Mocker::new(Watchdog::new(timeout));
+        mocker.init();
+
+        // kicked well within the timeout for several timeouts
+        let start = Instant::now();
+        let mut last_kick = start;
+        while start.elapsed() < 3 * timeout {
+            last_kick = Instant::now();
+            mocker.post("kick", Pmt::Blob(vec![0; 8])).unwrap();
+            mocker.run();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(mocker.messages()[0].is_empty());
+        assert!(!mocker.timed_out());
+
+        while mocker.messages()[0].is_empty() {
+            assert!(last_kick.elapsed() < 10 * timeout, "no timeout");
+            mocker.run();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        // the timeout runs from the last kick, which the block saw after `last_kick`
+        assert!(last_kick.elapsed() >= timeout);
+        assert!(mocker.timed_out());
+        match mocker.messages()[0].as_slice() {
+            [Pmt::F64(secs)] => assert!(*secs >= timeout.as_secs_f64(), "{secs}"),
+            m => panic!("expected the idle time, got {m:?}"),
+        }
+
+        // the timeout is posted once
+        mocker.run();
+        assert_eq!(mocker.messages()[0].len(), 1);
+    }
+}
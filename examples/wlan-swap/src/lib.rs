@@ -4,13 +4,16 @@ pub mod wifi;
 // ZigBee library module
 pub mod zigbee;
 
+// Generic helper blocks shared by both stacks
+pub mod blocks;
+
 // TOML-based flowgraph loader
 pub mod loader;
 
 // Re-export commonly used WLAN items for convenience
 pub use wifi::{
     channel_to_freq, parse_channel, Decoder, Encoder, FrameEqualizer, Mac, Mapper,
-    MovingAverage, Prefix, SyncLong, SyncShort, ViterbiDecoder, Modulation,
+    McsDemux, MovingAverage, Prefix, SyncLong, SyncShort, ViterbiDecoder, Modulation,
     MAX_PAYLOAD_SIZE, MAX_PSDU_SIZE, MAX_SYM, MAX_ENCODED_BITS,
     Mcs, FrameParam, LONG, POLARITY,
 };
@@ -0,0 +1,179 @@
+//! Block Parameter Metadata
+//!
+//! Describes the blocks of a loaded flowgraph together with their parameters and configured
+//! values, so that a GUI can show them and change those that have a runtime message port.
+//! The description is exchanged as a [`Pmt`] through the `describe` port of the
+//! FlowgraphController.
+
+use futuresdr::runtime::Pmt;
+use std::collections::HashMap;
+
+/// A parameter of a block in a loaded flowgraph
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamInfo {
+    /// Parameter name
+    pub name: String,
+    /// TOML type of the value (e.g., `f64`, `usize`, `string`, `bool`)
+    pub param_type: String,
+    /// Configured value, or the default if the parameter was omitted
+    pub value: Option<String>,
+    /// Message port that changes the parameter at runtime. Parameters without a port are
+    /// construction-only.
+    pub runtime_port: Option<String>,
+}
+
+impl ParamInfo {
+    /// Whether the parameter can be changed while the flowgraph is running
+    pub fn is_runtime(&self) -> bool {
+        self.runtime_port.is_some()
+    }
+
+    /// Convert user input to the [`Pmt`] expected by the runtime port
+    pub fn parse_value(&self, s: &str) -> Option<Pmt> {
+        let s = s.trim();
+        match self.param_type.as_str() {
+            "f64" => s.parse().ok().map(Pmt::F64),
+            "f32" => s.parse().ok().map(Pmt::F32),
+            "u32" => s.parse().ok().map(Pmt::U32),
+            "u64" => s.parse().ok().map(Pmt::U64),
+            "usize" => s.parse().ok().map(Pmt::Usize),
+            "bool" => s.parse().ok().map(Pmt::Bool),
+            "string" => Some(Pmt::String(s.to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// A block of a loaded flowgraph
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockInfo {
+    /// Block name in the configuration
+    pub name: String,
+    /// Registered block type
+    pub block_type: String,
+    /// Block ID in the flowgraph
+    pub id: usize,
+    /// Parameters, in schema order followed by configured parameters the schema does not know
+    pub params: Vec<ParamInfo>,
+}
+
+fn get_string(map: &HashMap<String, Pmt>, key: &str) -> Option<String> {
+    match map.get(key)? {
+        Pmt::String(s) => Some(s.clone()),
+        _ => None,
+    }
+}
+
+fn opt_string(s: &Option<String>) -> Pmt {
+    s.as_ref().map(|s| Pmt::String(s.clone())).unwrap_or(Pmt::Null)
+}
+
+impl From<&ParamInfo> for Pmt {
+    fn from(p: &ParamInfo) -> Self {
+        Pmt::MapStrPmt(HashMap::from([
+            ("name".to_string(), Pmt::String(p.name.clone())),
+            ("type".to_string(), Pmt::String(p.param_type.clone())),
+            ("value".to_string(), opt_string(&p.value)),
+            ("port".to_string(), opt_string(&p.runtime_port)),
+        ]))
+    }
+}
+
+impl TryFrom<&Pmt> for ParamInfo {
+    type Error = String;
+
+    fn try_from(p: &Pmt) -> Result<Self, Self::Error> {
+        let Pmt::MapStrPmt(map) = p else {
+            return Err(format!("expected Pmt::MapStrPmt, got {:?}", p));
+        };
+        Ok(ParamInfo {
+            name: get_string(map, "name").ok_or("parameter without name")?,
+            param_type: get_string(map, "type").ok_or("parameter without type")?,
+            value: get_string(map, "value"),
+            runtime_port: get_string(map, "port"),
+        })
+    }
+}
+
+impl From<&BlockInfo> for Pmt {
+    fn from(b: &BlockInfo) -> Self {
+        Pmt::MapStrPmt(HashMap::from([
+            ("name".to_string(), Pmt::String(b.name.clone())),
+            ("type".to_string(), Pmt::String(b.block_type.clone())),
+            ("id".to_string(), Pmt::Usize(b.id)),
+            (
+                "params".to_string(),
+                Pmt::VecPmt(b.params.iter().map(Pmt::from).collect()),
+            ),
+        ]))
+    }
+}
+
+impl TryFrom<&Pmt> for BlockInfo {
+    type Error = String;
+
+    fn try_from(p: &Pmt) -> Result<Self, Self::Error> {
+        let Pmt::MapStrPmt(map) = p else {
+            return Err(format!("expected Pmt::MapStrPmt, got {:?}", p));
+        };
+        let id = match map.get("id") {
+            Some(Pmt::Usize(id)) => *id,
+            _ => return Err("block without id".to_string()),
+        };
+        let params = match map.get("params") {
+            Some(Pmt::VecPmt(v)) => v.iter().map(ParamInfo::try_from).collect::<Result<_, _>>()?,
+            _ => Vec::new(),
+        };
+        Ok(BlockInfo {
+            name: get_string(map, "name").ok_or("block without name")?,
+            block_type: get_string(map, "type").ok_or("block without type")?,
+            id,
+            params,
+        })
+    }
+}
+
+/// Encode a flowgraph description
+pub fn blocks_to_pmt(blocks: &[BlockInfo]) -> Pmt {
+    Pmt::VecPmt(blocks.iter().map(Pmt::from).collect())
+}
+
+/// Decode a flowgraph description
+pub fn blocks_from_pmt(p: &Pmt) -> Result<Vec<BlockInfo>, String> {
+    match p {
+        Pmt::VecPmt(v) => v.iter().map(BlockInfo::try_from).collect(),
+        _ => Err(format!("expected Pmt::VecPmt, got {:?}", p)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pmt_round_trip() {
+        let blocks = vec![BlockInfo {
+            name: "src".to_string(),
+            block_type: "SeifySource".to_string(),
+            id: 3,
+            params: vec![
+                ParamInfo {
+                    name: "frequency".to_string(),
+                    param_type: "f64".to_string(),
+                    value: Some("2480000000".to_string()),
+                    runtime_port: Some("freq".to_string()),
+                },
+                ParamInfo {
+                    name: "antenna".to_string(),
+                    param_type: "string".to_string(),
+                    value: None,
+                    runtime_port: None,
+                },
+            ],
+        }];
+
+        assert_eq!(blocks_from_pmt(&blocks_to_pmt(&blocks)).unwrap(), blocks);
+        assert!(matches!(blocks[0].params[0].parse_value(" 2.4e9 "), Some(Pmt::F64(f)) if f == 2.4e9));
+        assert!(blocks[0].params[0].parse_value("fast").is_none());
+    }
+}
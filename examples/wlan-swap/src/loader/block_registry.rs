@@ -2,25 +2,137 @@
 //! 
 //! Provides block factories and registration for instantiating blocks from TOML configs.
 
-use anyhow::{Result, Context, bail};
 use futuresdr::prelude::*;
-use futuresdr::blocks::{Apply, NullSource, NullSink, Delay, Fft, Combine, Throttle};
+use futuresdr::blocks::{Apply, NullSource, NullSink, Delay, Fft, FirBuilder, Combine, PfbArbResampler, Throttle};
+use futuresdr::futuredsp::{firdes, windows};
 #[cfg(not(target_arch = "wasm32"))]
 use futuresdr::blocks::{WebsocketPmtSink, FileSource, BlobToUdp};
 #[cfg(not(target_arch = "wasm32"))]
 use futuresdr::blocks::seify::Builder;
 use crate::zigbee::{Mac, IqDelay, ClockRecoveryMm, Decoder, modulator};
 use crate::wifi;
+use crate::blocks::{BerSink, Bypass, Cca, ComplexToFloat, ConjugateIq, Dedup, Defragment, FloatToComplex, FrameCounter, FrameStats, FreqXlate, Iir, LatencyHistogram, Pattern, PatternSource, PatternType, PmtMerge, Polynomial, PowerMeter, PrbsSource, RssiAnnotate, Squelch, SquelchMode, SroCorrect, StreamTap, SwapIq, Upsample};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::blocks::{BlobToUdpFramed, BlobToUdpWithMeta, CsvSink, Heartbeat, HexFileSource, MqttSink, MsgRateLimit, PreTriggerRecorder, RateLimitPolicy, SymbolCsvSink, UdpToBlob, Watchdog};
+use super::error::LoaderError;
 use super::toml_loader::{BlockConfig, ParameterConfig};
+use serde::Serialize;
+
+type Result<T> = std::result::Result<T, LoaderError>;
+
+/// Description of a block parameter, as expected in the `[[blocks.parameters]]` of a config
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ParamSpec {
+    /// Parameter name
+    pub name: &'static str,
+    /// TOML type of the value (e.g., `f64`, `usize`, `string`, `bool`)
+    pub param_type: &'static str,
+    /// Whether the block can be created without this parameter
+    pub required: bool,
+    /// Value used when an optional parameter is omitted
+    pub default: Option<&'static str>,
+    /// Alternative names accepted for this parameter
+    pub aliases: &'static [&'static str],
+    /// Message port that changes the parameter while the flowgraph runs (`None` if the
+    /// parameter is construction-only)
+    pub runtime_port: Option<&'static str>,
+}
+
+impl ParamSpec {
+    /// Required parameter
+    pub const fn required(name: &'static str, param_type: &'static str) -> Self {
+        Self {
+            name,
+            param_type,
+            required: true,
+            default: None,
+            aliases: &[],
+            runtime_port: None,
+        }
+    }
+
+    /// Optional parameter with an optional default value
+    pub const fn optional(
+        name: &'static str,
+        param_type: &'static str,
+        default: Option<&'static str>,
+    ) -> Self {
+        Self {
+            name,
+            param_type,
+            required: false,
+            default,
+            aliases: &[],
+            runtime_port: None,
+        }
+    }
+
+    /// Accept alternative names for the parameter
+    pub const fn with_aliases(mut self, aliases: &'static [&'static str]) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Parameter can be changed at runtime through a message port of the block
+    pub const fn with_runtime_port(mut self, port: &'static str) -> Self {
+        self.runtime_port = Some(port);
+        self
+    }
+
+    /// Check whether a configured parameter name refers to this parameter
+    pub fn matches(&self, name: &str) -> bool {
+        self.name == name || self.aliases.contains(&name)
+    }
+}
+
+impl std::fmt::Display for ParamSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.name, self.param_type)?;
+        match (self.required, self.default) {
+            (true, _) => write!(f, " (required)")?,
+            (false, Some(d)) => write!(f, " (optional, default: {})", d)?,
+            (false, None) => write!(f, " (optional)")?,
+        }
+        match self.runtime_port {
+            Some(port) => write!(f, " [runtime: {}]", port),
+            None => Ok(()),
+        }
+    }
+}
 
 /// Block factory trait
 pub trait BlockFactory: Send + Sync {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId>;
+
+    /// Parameters understood by [`create`](Self::create)
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        Vec::new()
+    }
+
+    /// Create the block for a dry run, without opening hardware
+    ///
+    /// Factories for hardware blocks override this to validate their parameters and add a
+    /// stand-in with the same ports.
+    fn create_dry(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        self.create(fg, config)
+    }
+}
+
+/// A registered block type with its parameters, see [`BlockRegistry::block_schemas`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BlockSchema {
+    #[serde(rename = "type")]
+    pub block_type: String,
+    /// Whether the block is only available natively (hardware, files, and sockets), not in wasm
+    pub native_only: bool,
+    pub parameters: Vec<ParamSpec>,
 }
 
 /// Block registry that maps block types to factories
 pub struct BlockRegistry {
     factories: std::collections::HashMap<String, Box<dyn BlockFactory>>,
+    /// Block types that are not registered on wasm32
+    native_only: std::collections::HashSet<String>,
 }
 
 impl BlockRegistry {
@@ -28,6 +140,7 @@ impl BlockRegistry {
     pub fn new() -> Self {
         let mut registry = Self {
             factories: std::collections::HashMap::new(),
+            native_only: std::collections::HashSet::new(),
         };
         
         // Register ZigBee blocks
@@ -44,14 +157,62 @@ impl BlockRegistry {
         registry.register("Delay", Box::new(DelayFactory));
         registry.register("Fft", Box::new(FftFactory));
         registry.register("Throttle", Box::new(ThrottleFactory));
+        registry.register("StreamTap", Box::new(StreamTapFactory));
+        registry.register("Squelch", Box::new(SquelchFactory));
+        registry.register("Bypass", Box::new(BypassFactory));
+        registry.register("PowerMeter", Box::new(PowerMeterFactory));
+        registry.register("RssiAnnotate", Box::new(RssiAnnotateFactory));
+        registry.register("Cca", Box::new(CcaFactory));
+        registry.register("FreqXlate", Box::new(FreqXlateFactory));
+        registry.register("SroCorrect", Box::new(SroCorrectFactory));
+        registry.register("ComplexToFloat", Box::new(ComplexToFloatFactory));
+        registry.register("FloatToComplex", Box::new(FloatToComplexFactory));
+        registry.register("ConjugateIq", Box::new(ConjugateIqFactory));
+        registry.register("SwapIq", Box::new(SwapIqFactory));
+        registry.register("Decimate", Box::new(DecimateFactory));
+        registry.register("Upsample", Box::new(UpsampleFactory));
+        registry.register("ArbResampler", Box::new(ArbResamplerFactory));
+        registry.register("Iir", Box::new(IirFactory));
+        registry.register("Fir", Box::new(FirFactory));
+        registry.register("Dedup", Box::new(DedupFactory));
+        registry.register("Defragment", Box::new(DefragmentFactory));
+        registry.register("PmtMerge", Box::new(PmtMergeFactory));
+        registry.register("LatencyHistogram", Box::new(LatencyHistogramFactory));
+        registry.register("FrameStats", Box::new(FrameStatsFactory));
+        registry.register("FrameCounter", Box::new(FrameCounterFactory));
+        #[cfg(not(target_arch = "wasm32"))]
+        registry.register_native_only("WebsocketPmtSink", Box::new(WebsocketPmtSinkFactory));
+        #[cfg(not(target_arch = "wasm32"))]
+        registry.register_native_only("FileSource", Box::new(FileSourceFactory));
+        #[cfg(not(target_arch = "wasm32"))]
+        registry.register_native_only("BlobToUdp", Box::new(BlobToUdpFactory));
+        #[cfg(not(target_arch = "wasm32"))]
+        registry.register_native_only("BlobToUdpFramed", Box::new(BlobToUdpFramedFactory));
+        #[cfg(not(target_arch = "wasm32"))]
+        registry.register_native_only("BlobToUdpWithMeta", Box::new(BlobToUdpWithMetaFactory));
+        #[cfg(not(target_arch = "wasm32"))]
+        registry.register_native_only("UdpToBlob", Box::new(UdpToBlobFactory));
         #[cfg(not(target_arch = "wasm32"))]
-        registry.register("WebsocketPmtSink", Box::new(WebsocketPmtSinkFactory));
+        registry.register_native_only("MqttSink", Box::new(MqttSinkFactory));
         #[cfg(not(target_arch = "wasm32"))]
-        registry.register("FileSource", Box::new(FileSourceFactory));
+        registry.register_native_only("CsvSink", Box::new(CsvSinkFactory));
         #[cfg(not(target_arch = "wasm32"))]
-        registry.register("BlobToUdp", Box::new(BlobToUdpFactory));
+        registry.register_native_only("SymbolCsvSink", Box::new(SymbolCsvSinkFactory));
+        #[cfg(not(target_arch = "wasm32"))]
+        registry.register_native_only("PreTriggerRecorder", Box::new(PreTriggerRecorderFactory));
+        #[cfg(not(target_arch = "wasm32"))]
+        registry.register_native_only("MsgRateLimit", Box::new(MsgRateLimitFactory));
+        #[cfg(not(target_arch = "wasm32"))]
+        registry.register_native_only("HexFileSource", Box::new(HexFileSourceFactory));
+        #[cfg(not(target_arch = "wasm32"))]
+        registry.register_native_only("Heartbeat", Box::new(HeartbeatFactory));
+        #[cfg(not(target_arch = "wasm32"))]
+        registry.register_native_only("Watchdog", Box::new(WatchdogFactory));
         registry.register("NullSource", Box::new(NullSourceFactory));
         registry.register("NullSink", Box::new(NullSinkFactory));
+        registry.register("PatternSource", Box::new(PatternSourceFactory));
+        registry.register("PrbsSource", Box::new(PrbsSourceFactory));
+        registry.register("BerSink", Box::new(BerSinkFactory));
         
         // Register WiFi blocks
         registry.register("wifi::Mac", Box::new(WifiMacFactory));
@@ -63,12 +224,13 @@ impl BlockRegistry {
         registry.register("wifi::SyncLong", Box::new(WifiSyncLongFactory));
         registry.register("wifi::FrameEqualizer", Box::new(WifiFrameEqualizerFactory));
         registry.register("wifi::Decoder", Box::new(WifiDecoderFactory));
+        registry.register("wifi::McsDemux", Box::new(WifiMcsDemuxFactory));
         
         // Register SDR hardware blocks (seify)
         #[cfg(not(target_arch = "wasm32"))]
-        registry.register("seify::Source", Box::new(SeifySourceFactory));
+        registry.register_native_only("seify::Source", Box::new(SeifySourceFactory));
         #[cfg(not(target_arch = "wasm32"))]
-        registry.register("seify::Sink", Box::new(SeifySinkFactory));
+        registry.register_native_only("seify::Sink", Box::new(SeifySinkFactory));
         
         // Register control blocks
         registry.register("FlowgraphController", Box::new(FlowgraphControllerFactory));
@@ -81,13 +243,69 @@ impl BlockRegistry {
         self.factories.insert(block_type.to_string(), factory);
     }
     
+    /// Register a factory for a block type that is not available on wasm32
+    #[cfg(not(target_arch = "wasm32"))]
+    fn register_native_only(&mut self, block_type: &str, factory: Box<dyn BlockFactory>) {
+        self.native_only.insert(block_type.to_string());
+        self.register(block_type, factory);
+    }
+    
+    /// Registered block types, sorted by name
+    pub fn registered_types(&self) -> Vec<&str> {
+        let mut types: Vec<&str> = self.factories.keys().map(|s| s.as_str()).collect();
+        types.sort_unstable();
+        types
+    }
+    
+    /// All registered block types with their parameters, sorted by name
+    pub fn block_schemas(&self) -> Vec<BlockSchema> {
+        self.registered_types()
+            .into_iter()
+            .map(|t| BlockSchema {
+                block_type: t.to_string(),
+                native_only: self.native_only.contains(t),
+                parameters: self.factories[t].parameter_schema(),
+            })
+            .collect()
+    }
+    
     /// Create a block from configuration
     pub fn create_block(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
         let factory = self.factories.get(&config.block_type)
-            .with_context(|| format!("No factory registered for block type: {}", config.block_type))?;
+            .ok_or_else(|| LoaderError::UnknownBlockType(config.block_type.clone()))?;
         
         factory.create(fg, config)
     }
+    
+    /// Create a block from configuration for a dry run (see [`BlockFactory::create_dry`])
+    pub fn create_block_dry(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let factory = self.factories.get(&config.block_type)
+            .ok_or_else(|| LoaderError::UnknownBlockType(config.block_type.clone()))?;
+        
+        factory.create_dry(fg, config)
+    }
+    
+    /// Parameters expected by a block type, or `None` if the type is not registered
+    pub fn schema_for(&self, block_type: &str) -> Option<Vec<ParamSpec>> {
+        self.factories.get(block_type).map(|f| f.parameter_schema())
+    }
+    
+    /// Human-readable description of the parameters of a block type
+    pub fn describe_block(&self, block_type: &str) -> Option<String> {
+        let schema = self.schema_for(block_type)?;
+        let mut out = if self.native_only.contains(block_type) {
+            format!("{} (native only)\n", block_type)
+        } else {
+            format!("{}\n", block_type)
+        };
+        if schema.is_empty() {
+            out.push_str("  (no parameters)\n");
+        }
+        for spec in schema {
+            out.push_str(&format!("  {}\n", spec));
+        }
+        Some(out)
+    }
 }
 
 impl Default for BlockRegistry {
@@ -97,35 +315,118 @@ impl Default for BlockRegistry {
 }
 
 // Helper functions to extract parameters
-fn get_param_u32(params: &[ParameterConfig], name: &str) -> Result<u32> {
-    params.iter()
-        .find(|p| p.name == name)
-        .and_then(|p| p.value.as_integer())
-        .map(|v| v as u32)
-        .with_context(|| format!("Parameter '{}' not found or invalid", name))
+fn find_param<'a>(config: &'a BlockConfig, name: &str) -> Option<&'a ParameterConfig> {
+    config.parameters.iter().find(|p| p.name == name)
 }
 
-fn get_param_f32(params: &[ParameterConfig], name: &str) -> Result<f32> {
-    params.iter()
-        .find(|p| p.name == name)
-        .and_then(|p| p.value.as_float())
-        .map(|v| v as f32)
-        .with_context(|| format!("Parameter '{}' not found or invalid", name))
+fn require_param<'a>(config: &'a BlockConfig, name: &str) -> Result<&'a ParameterConfig> {
+    find_param(config, name).ok_or_else(|| LoaderError::missing(&config.name, name))
 }
 
-fn get_param_f64(params: &[ParameterConfig], name: &str) -> Result<f64> {
-    params.iter()
-        .find(|p| p.name == name)
-        .and_then(|p| p.value.as_float())
-        .with_context(|| format!("Parameter '{}' not found or invalid", name))
+/// Parse an integer literal: decimal, or hexadecimal/binary with a `0x`/`0b` prefix
+///
+/// Digits may be separated by `_`, like in TOML, e.g., `0b1010_0111`.
+pub(crate) fn parse_int_literal(s: &str) -> std::result::Result<i64, String> {
+    let digits = s.replace('_', "");
+    let (radix, digits) = match digits.get(..2) {
+        Some("0x" | "0X") => (16, &digits[2..]),
+        Some("0b" | "0B") => (2, &digits[2..]),
+        _ => (10, &digits[..]),
+    };
+    if radix != 10 && digits.starts_with(['+', '-']) {
+        return Err(format!("malformed integer literal '{}': sign after the prefix", s));
+    }
+    i64::from_str_radix(digits, radix)
+        .map_err(|e| format!("malformed integer literal '{}': {}", s, e))
 }
 
-fn get_param_string(params: &[ParameterConfig], name: &str) -> Result<String> {
-    params.iter()
-        .find(|p| p.name == name)
-        .and_then(|p| p.value.as_str())
+/// Integer of a TOML value, given natively or as a string literal (see [`parse_int_literal`])
+fn as_integer(value: &toml::Value) -> Option<i64> {
+    match value {
+        toml::Value::String(s) => parse_int_literal(s).ok(),
+        v => v.as_integer(),
+    }
+}
+
+/// Integer parameter
+///
+/// A string that starts like a number but is not a valid literal (e.g., `"0xZZ"`) is an invalid
+/// parameter; other strings, and values out of range for `T`, are a type mismatch.
+fn get_param_int<T: TryFrom<i64>>(config: &BlockConfig, name: &str, expected: &str) -> Result<T> {
+    let value = match &require_param(config, name)?.value {
+        toml::Value::String(s) if s.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+') => {
+            parse_int_literal(s).map_err(|e| LoaderError::invalid(&config.name, name, e))?
+        }
+        v => v
+            .as_integer()
+            .ok_or_else(|| LoaderError::mismatch(&config.name, name, expected))?,
+    };
+    T::try_from(value).map_err(|_| LoaderError::mismatch(&config.name, name, expected))
+}
+
+fn get_param_u32(config: &BlockConfig, name: &str) -> Result<u32> {
+    get_param_int(config, name, "u32")
+}
+
+fn get_param_isize(config: &BlockConfig, name: &str) -> Result<isize> {
+    get_param_int(config, name, "isize")
+}
+
+fn get_param_f32(config: &BlockConfig, name: &str) -> Result<f32> {
+    get_param_f64(config, name).map(|v| v as f32)
+}
+
+fn get_param_f64(config: &BlockConfig, name: &str) -> Result<f64> {
+    let value = &require_param(config, name)?.value;
+    value
+        .as_float()
+        .or_else(|| value.as_integer().map(|v| v as f64))
+        .ok_or_else(|| LoaderError::mismatch(&config.name, name, "float"))
+}
+
+fn get_param_string(config: &BlockConfig, name: &str) -> Result<String> {
+    require_param(config, name)?
+        .value
+        .as_str()
         .map(|v| v.to_string())
-        .with_context(|| format!("Parameter '{}' not found or invalid", name))
+        .ok_or_else(|| LoaderError::mismatch(&config.name, name, "string"))
+}
+
+fn get_param_f32_array(config: &BlockConfig, param: &ParameterConfig) -> Result<Vec<f32>> {
+    param
+        .value
+        .as_array()
+        .and_then(|a| {
+            a.iter()
+                .map(|v| v.as_float().or_else(|| v.as_integer().map(|i| i as f64)))
+                .map(|v| v.map(|f| f as f32))
+                .collect::<Option<Vec<f32>>>()
+        })
+        .ok_or_else(|| LoaderError::mismatch(&config.name, &param.name, "array of floats"))
+}
+
+fn get_param_complex_array(config: &BlockConfig, param: &ParameterConfig) -> Result<Vec<Complex32>> {
+    let float = |v: &toml::Value| v.as_float().or_else(|| v.as_integer().map(|i| i as f64));
+    param
+        .value
+        .as_array()
+        .and_then(|a| {
+            a.iter()
+                .map(|v| match v.as_array()?.as_slice() {
+                    [re, im] => Some(Complex32::new(float(re)? as f32, float(im)? as f32)),
+                    _ => None,
+                })
+                .collect::<Option<Vec<Complex32>>>()
+        })
+        .ok_or_else(|| LoaderError::mismatch(&config.name, &param.name, "array of [re, im] pairs"))
+}
+
+fn unsupported_dtype(config: &BlockConfig, dtype: &str) -> LoaderError {
+    LoaderError::invalid(
+        &config.name,
+        "dtype",
+        format!("unsupported dtype for {}: {}", config.block_type, dtype),
+    )
 }
 
 // ============================================================================
@@ -169,15 +470,25 @@ struct ClockRecoveryMmFactory;
 
 impl BlockFactory for ClockRecoveryMmFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
-        let omega = get_param_f32(&config.parameters, "omega")?;
-        let gain_omega = get_param_f32(&config.parameters, "gain_omega")?;
-        let mu = get_param_f32(&config.parameters, "mu")?;
-        let gain_mu = get_param_f32(&config.parameters, "gain_mu")?;
-        let omega_relative_limit = get_param_f32(&config.parameters, "omega_relative_limit")?;
+        let omega = get_param_f32(config, "omega")?;
+        let gain_omega = get_param_f32(config, "gain_omega")?;
+        let mu = get_param_f32(config, "mu")?;
+        let gain_mu = get_param_f32(config, "gain_mu")?;
+        let omega_relative_limit = get_param_f32(config, "omega_relative_limit")?;
         
         let mm: ClockRecoveryMm = ClockRecoveryMm::new(omega, gain_omega, mu, gain_mu, omega_relative_limit);
         Ok(fg.add_block(mm).into())
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("omega", "f64"),
+            ParamSpec::required("gain_omega", "f64"),
+            ParamSpec::required("mu", "f64"),
+            ParamSpec::required("gain_mu", "f64"),
+            ParamSpec::required("omega_relative_limit", "f64"),
+        ]
+    }
 }
 
 /// Factory for zigbee::Decoder
@@ -185,11 +496,36 @@ struct DecoderFactory;
 
 impl BlockFactory for DecoderFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
-        let threshold = get_param_u32(&config.parameters, "threshold")?;
+        let threshold = get_param_u32(config, "threshold")?;
+        let max_window = <Decoder>::MAX_WINDOW;
+        let window = match find_param(config, "window") {
+            None => max_window,
+            Some(_) => get_param_u32(config, "window")? as usize,
+        };
+        if !(1..=max_window).contains(&window) {
+            return Err(LoaderError::invalid(
+                &config.name,
+                "window",
+                format!("must be in [1, {}] chips", max_window),
+            ));
+        }
         
-        let decoder: Decoder = Decoder::new(threshold);
+        let dedup_consecutive = find_param(config, "dedup_consecutive")
+            .and_then(|p| p.value.as_bool())
+            .unwrap_or(false);
+        
+        let decoder: Decoder =
+            Decoder::with_params(threshold, window).with_dedup_consecutive(dedup_consecutive);
         Ok(fg.add_block(decoder).into())
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("threshold", "usize"),
+            ParamSpec::optional("window", "usize", Some("30")),
+            ParamSpec::optional("dedup_consecutive", "bool", Some("false")),
+        ]
+    }
 }
 
 /// Factory for Apply blocks with predefined closures
@@ -198,12 +534,12 @@ struct ApplyFactory;
 impl BlockFactory for ApplyFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
         // Get the closure name from parameters
-        let closure_name = get_param_string(&config.parameters, "function")?;
+        let closure_name = get_param_string(config, "function")?;
         
         match closure_name.as_str() {
             "phase_detector_iir" => {
                 // Create the phase detector with IIR filter
-                let alpha = get_param_f32(&config.parameters, "alpha")
+                let alpha = get_param_f32(config, "alpha")
                     .unwrap_or(0.00016);
                 
                 let mut last = Complex32::new(0.0, 0.0);
@@ -224,21 +560,84 @@ impl BlockFactory for ApplyFactory {
                 Ok(fg.add_block(block).into())
             }
             "dc_offset_removal" => {
-                // DC offset removal using IIR filter
-                let ratio = 1.0e-5f32;
-                let mut avg_real = 0.0f32;
-                let mut avg_img = 0.0f32;
-                
-                let block = Apply::<_, _, _>::new(move |c: &Complex32| -> Complex32 {
-                    avg_real = ratio * (c.re - avg_real) + avg_real;
-                    avg_img = ratio * (c.im - avg_img) + avg_img;
-                    Complex32::new(c.re - avg_real, c.im - avg_img)
-                });
+                let block = Apply::<_, _, _>::new(dc_offset_removal());
                 Ok(fg.add_block(block).into())
             }
-            _ => bail!("Unknown closure type for Apply block: {}", closure_name),
+            _ => Err(LoaderError::invalid(
+                &config.name,
+                "function",
+                format!("unknown closure type for Apply block: {}", closure_name),
+            )),
+        }
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("function", "closure"),
+            ParamSpec::optional("alpha", "f64", Some("0.00016")),
+        ]
+    }
+}
+
+/// DC offset removal using IIR filter
+fn dc_offset_removal() -> impl FnMut(&Complex32) -> Complex32 + Send + 'static {
+    let ratio = 1.0e-5f32;
+    let mut avg_real = 0.0f32;
+    let mut avg_img = 0.0f32;
+    
+    move |c: &Complex32| -> Complex32 {
+        avg_real = ratio * (c.re - avg_real) + avg_real;
+        avg_img = ratio * (c.im - avg_img) + avg_img;
+        Complex32::new(c.re - avg_real, c.im - avg_img)
+    }
+}
+
+/// Factory for Bypass, wrapping the stage given in `[blocks.inner]`
+///
+/// Stages with one input and one output of the same type can be bypassed, currently the
+/// `dc_offset_removal` Apply closure and FreqXlate.
+struct BypassFactory;
+
+impl BlockFactory for BypassFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let inner = config.inner.as_ref()
+            .ok_or_else(|| LoaderError::missing(&config.name, "inner"))?
+            .to_block_config(&config.name);
+        let bypassed = match find_param(config, "bypass") {
+            None => false,
+            Some(p) => p.value.as_bool()
+                .ok_or_else(|| LoaderError::mismatch(&config.name, "bypass", "bool"))?,
+        };
+
+        match inner.block_type.as_str() {
+            "Apply" => match get_param_string(&inner, "function")?.as_str() {
+                "dc_offset_removal" => {
+                    let stage = Apply::<_, _, _>::new(dc_offset_removal());
+                    Ok(fg.add_block(Bypass::new(stage, bypassed)).into())
+                }
+                f => Err(LoaderError::invalid(
+                    &config.name,
+                    "function",
+                    format!("cannot bypass Apply closure {} (input and output type must match)", f),
+                )),
+            },
+            "FreqXlate" => {
+                let stage = FreqXlateFactory::block(&inner)?;
+                Ok(fg.add_block(Bypass::new(stage, bypassed)).into())
+            }
+            t => Err(LoaderError::invalid(
+                &config.name,
+                "inner",
+                format!("cannot bypass blocks of type {}", t),
+            )),
         }
     }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("bypass", "bool", Some("false")).with_runtime_port("bypass"),
+        ]
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -248,11 +647,17 @@ struct WebsocketPmtSinkFactory;
 #[cfg(not(target_arch = "wasm32"))]
 impl BlockFactory for WebsocketPmtSinkFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
-        let port = get_param_u32(&config.parameters, "port")?;
+        let port = get_param_u32(config, "port")?;
         
         let block = WebsocketPmtSink::new(port);
         Ok(fg.add_block(block).into())
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("port", "u16"),
+        ]
+    }
 }
 
 /// Factory for NullSource
@@ -268,9 +673,129 @@ impl BlockFactory for NullSourceFactory {
             "u32" => Ok(fg.add_block(NullSource::<u32>::new()).into()),
             "f32" => Ok(fg.add_block(NullSource::<f32>::new()).into()),
             "Complex32" => Ok(fg.add_block(NullSource::<Complex32>::new()).into()),
-            _ => bail!("Unsupported dtype for NullSource: {}", dtype),
+            _ => Err(unsupported_dtype(config, dtype)),
+        }
+    }
+}
+
+/// Factory for PatternSource
+struct PatternSourceFactory;
+
+/// Helper to read the pattern of a PatternSource, using `parse` to convert TOML values
+fn get_pattern<T: PatternType>(
+    config: &BlockConfig,
+    parse: impl Fn(&toml::Value) -> Option<T>,
+    type_name: &str,
+) -> Result<Pattern<T>> {
+    let value = |name: &str| -> Result<T> {
+        parse(&require_param(config, name)?.value)
+            .ok_or_else(|| LoaderError::mismatch(&config.name, name, type_name))
+    };
+    
+    let pattern = match find_param(config, "pattern") {
+        None => "zero".to_string(),
+        Some(_) => get_param_string(config, "pattern")?,
+    };
+    
+    match pattern.as_str() {
+        "zero" => Ok(Pattern::Zero),
+        "counter" => Ok(Pattern::Counter),
+        "const" => Ok(Pattern::Const(value("value")?)),
+        "ramp" => match find_param(config, "step") {
+            None => Ok(Pattern::Ramp(T::from_index(1))),
+            Some(_) => Ok(Pattern::Ramp(value("step")?)),
+        },
+        _ => Err(LoaderError::invalid(
+            &config.name,
+            "pattern",
+            format!("unknown pattern '{}' (expected zero, counter, const, or ramp)", pattern),
+        )),
+    }
+}
+
+impl BlockFactory for PatternSourceFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let dtype = config.dtype.as_deref().unwrap_or("u8");
+        
+        let float = |v: &toml::Value| v.as_float().or_else(|| v.as_integer().map(|i| i as f64));
+        
+        match dtype {
+            "u8" => {
+                let pattern = get_pattern(config, |v| as_integer(v).and_then(|i| u8::try_from(i).ok()), "u8")?;
+                Ok(fg.add_block(PatternSource::<u8>::new(pattern)).into())
+            }
+            "u32" => {
+                let pattern = get_pattern(config, |v| as_integer(v).and_then(|i| u32::try_from(i).ok()), "u32")?;
+                Ok(fg.add_block(PatternSource::<u32>::new(pattern)).into())
+            }
+            "f32" => {
+                let pattern = get_pattern(config, |v| float(v).map(|f| f as f32), "float")?;
+                Ok(fg.add_block(PatternSource::<f32>::new(pattern)).into())
+            }
+            "Complex32" => {
+                // either a real value or [re, im]
+                let complex = |v: &toml::Value| match v.as_array() {
+                    Some(a) if a.len() == 2 => Some(Complex32::new(float(&a[0])? as f32, float(&a[1])? as f32)),
+                    Some(_) => None,
+                    None => float(v).map(|f| Complex32::new(f as f32, 0.0)),
+                };
+                let pattern = get_pattern(config, complex, "float or [re, im]")?;
+                Ok(fg.add_block(PatternSource::<Complex32>::new(pattern)).into())
+            }
+            _ => Err(unsupported_dtype(config, dtype)),
         }
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("pattern", "string", Some("zero")),
+            ParamSpec::optional("value", "number", None),
+            ParamSpec::optional("step", "number", Some("1")),
+        ]
+    }
+}
+
+/// Helper to read the PRBS polynomial of a PrbsSource or BerSink
+fn get_polynomial(config: &BlockConfig) -> Result<Polynomial> {
+    let name = match find_param(config, "polynomial") {
+        Some(_) => get_param_string(config, "polynomial")?,
+        None => "prbs9".to_string(),
+    };
+    Polynomial::from_name(&name).ok_or_else(|| {
+        LoaderError::invalid(
+            &config.name,
+            "polynomial",
+            format!("unknown polynomial '{}' (expected prbs7, prbs9, prbs11, prbs15, prbs23, or prbs31)", name),
+        )
+    })
+}
+
+/// Factory for PrbsSource
+struct PrbsSourceFactory;
+
+impl BlockFactory for PrbsSourceFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let polynomial = get_polynomial(config)?;
+        Ok(fg.add_block(PrbsSource::<DefaultCpuWriter<u8>>::new(polynomial)).into())
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![ParamSpec::optional("polynomial", "string", Some("prbs9"))]
+    }
+}
+
+/// Factory for BerSink
+struct BerSinkFactory;
+
+impl BlockFactory for BerSinkFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let polynomial = get_polynomial(config)?;
+        Ok(fg.add_block(BerSink::<DefaultCpuReader<u8>>::new(polynomial)).into())
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![ParamSpec::optional("polynomial", "string", Some("prbs9"))]
+    }
 }
 
 /// Factory for NullSink
@@ -286,11 +811,74 @@ impl BlockFactory for NullSinkFactory {
             "u32" => Ok(fg.add_block(NullSink::<u32>::new()).into()),
             "f32" => Ok(fg.add_block(NullSink::<f32>::new()).into()),
             "Complex32" => Ok(fg.add_block(NullSink::<Complex32>::new()).into()),
-            _ => bail!("Unsupported dtype for NullSink: {}", dtype),
+            _ => Err(unsupported_dtype(config, dtype)),
         }
     }
 }
 
+#[cfg(not(target_arch = "wasm32"))]
+/// Helper to create a seify builder from the common SDR parameters
+///
+/// For a dry run, the parameters are checked but the builder uses the dummy driver instead of
+/// opening the device, which needs the `seify_dummy` feature. Otherwise, the device has to
+/// provide all `channels` for `direction`.
+fn seify_builder(
+    config: &BlockConfig,
+    dry: bool,
+    channels: &[usize],
+    direction: futuresdr::seify::Direction,
+) -> Result<Builder<futuresdr::seify::GenericDevice>> {
+    let frequency = get_param_f64(config, "frequency")?;
+    let sample_rate = get_param_f64(config, "sample_rate")?;
+    let gain = get_param_f64(config, "gain")?;
+    
+    let antenna = config.parameters.iter()
+        .find(|p| p.name == "antenna")
+        .and_then(|p| p.value.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    
+    let args = config.parameters.iter()
+        .find(|p| p.name == "args")
+        .and_then(|p| p.value.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    
+    if dry {
+        let builder = Builder::new("driver=dummy").map_err(|e| {
+            futuresdr::runtime::Error::RuntimeError(format!(
+                "{}: dry runs of SDR blocks need the seify_dummy feature ({})",
+                config.name, e
+            ))
+        })?;
+        return Ok(builder.channels(channels.to_vec()));
+    }
+    
+    let dev = futuresdr::seify::Device::from_args(args).map_err(futuresdr::runtime::Error::from)?;
+    let available = dev
+        .num_channels(direction)
+        .map_err(futuresdr::runtime::Error::from)?;
+    if let Some(c) = channels.iter().find(|c| **c >= available) {
+        return Err(LoaderError::invalid(
+            &config.name,
+            "channels",
+            format!("channel {} does not exist, the device has {} channels", c, available),
+        ));
+    }
+    
+    let mut builder = Builder::from_device(dev)
+        .channels(channels.to_vec())
+        .frequency(frequency)
+        .sample_rate(sample_rate)
+        .gain(gain);
+    
+    if let Some(ant) = antenna {
+        builder = builder.antenna(Some(ant));
+    }
+    
+    Ok(builder)
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 /// Factory for seify::Source (SDR hardware source)
 struct SeifySourceFactory;
@@ -298,34 +886,53 @@ struct SeifySourceFactory;
 #[cfg(not(target_arch = "wasm32"))]
 impl BlockFactory for SeifySourceFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
-        let frequency = get_param_f64(&config.parameters, "frequency")?;
-        let sample_rate = get_param_f64(&config.parameters, "sample_rate")?;
-        let gain = get_param_f64(&config.parameters, "gain")?;
-        
-        let antenna = config.parameters.iter()
-            .find(|p| p.name == "antenna")
-            .and_then(|p| p.value.as_str())
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string());
-        
-        let args = config.parameters.iter()
-            .find(|p| p.name == "args")
-            .and_then(|p| p.value.as_str())
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string());
-        
-        let mut builder = Builder::new(args)?
-            .frequency(frequency)
-            .sample_rate(sample_rate)
-            .gain(gain);
-        
-        if let Some(ant) = antenna {
-            builder = builder.antenna(Some(ant));
-        }
-        
-        let source = builder.build_source()?;
+        let channels = seify_channels(config)?;
+        let source = seify_builder(config, false, &channels, futuresdr::seify::Direction::Rx)?
+            .build_source()?;
+        Ok(fg.add_block(source).into())
+    }
+    
+    fn create_dry(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let channels = seify_channels(config)?;
+        let source = seify_builder(config, true, &channels, futuresdr::seify::Direction::Rx)?
+            .build_source()?;
         Ok(fg.add_block(source).into())
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("frequency", "f64").with_runtime_port("freq"),
+            ParamSpec::required("sample_rate", "f64").with_runtime_port("sample_rate"),
+            ParamSpec::required("gain", "f64").with_runtime_port("gain"),
+            ParamSpec::optional("antenna", "string", None),
+            ParamSpec::optional("args", "string", None),
+            ParamSpec::optional("channels", "array", Some("[0]")),
+        ]
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Channels of a seify::Source, one stream output (`outputs[i]`) per entry
+fn seify_channels(config: &BlockConfig) -> Result<Vec<usize>> {
+    let Some(param) = find_param(config, "channels") else {
+        return Ok(vec![0]);
+    };
+    let channels = param
+        .value
+        .as_array()
+        .and_then(|a| {
+            a.iter()
+                .map(|v| as_integer(v).and_then(|i| usize::try_from(i).ok()))
+                .collect::<Option<Vec<usize>>>()
+        })
+        .ok_or_else(|| LoaderError::mismatch(&config.name, "channels", "array of usize"))?;
+    if channels.is_empty() {
+        return Err(LoaderError::invalid(&config.name, "channels", "needs at least one channel"));
+    }
+    if channels.iter().enumerate().any(|(i, c)| channels[..i].contains(c)) {
+        return Err(LoaderError::invalid(&config.name, "channels", "contains duplicates"));
+    }
+    Ok(channels)
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -335,45 +942,59 @@ struct SeifySinkFactory;
 #[cfg(not(target_arch = "wasm32"))]
 impl BlockFactory for SeifySinkFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
-        let frequency = get_param_f64(&config.parameters, "frequency")?;
-        let sample_rate = get_param_f64(&config.parameters, "sample_rate")?;
-        let gain = get_param_f64(&config.parameters, "gain")?;
-        
-        let antenna = config.parameters.iter()
-            .find(|p| p.name == "antenna")
-            .and_then(|p| p.value.as_str())
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string());
-        
-        let args = config.parameters.iter()
-            .find(|p| p.name == "args")
-            .and_then(|p| p.value.as_str())
-            .filter(|s| !s.is_empty())
-            .map(|s| s.to_string());
-        
-        let mut builder = Builder::new(args)?
-            .frequency(frequency)
-            .sample_rate(sample_rate)
-            .gain(gain);
-        
-        if let Some(ant) = antenna {
-            builder = builder.antenna(Some(ant));
-        }
-        
-        let sink = builder.build_sink()?;
+        let sink = seify_builder(config, false, &[0], futuresdr::seify::Direction::Tx)?
+            .build_sink()?;
         Ok(fg.add_block(sink).into())
     }
+    
+    fn create_dry(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let sink = seify_builder(config, true, &[0], futuresdr::seify::Direction::Tx)?
+            .build_sink()?;
+        Ok(fg.add_block(sink).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("frequency", "f64").with_runtime_port("freq"),
+            ParamSpec::required("sample_rate", "f64").with_runtime_port("sample_rate"),
+            ParamSpec::required("gain", "f64").with_runtime_port("gain"),
+            ParamSpec::optional("antenna", "string", None),
+            ParamSpec::optional("args", "string", None),
+        ]
+    }
 }
 
 /// FlowgraphController factory
 struct FlowgraphControllerFactory;
 
 impl BlockFactory for FlowgraphControllerFactory {
-    fn create(&self, fg: &mut Flowgraph, _config: &BlockConfig) -> Result<BlockId> {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
         use crate::loader::flowgraph_controller::FlowgraphController;
-        let block = FlowgraphController::new();
+        let loopback = config.parameters.iter()
+            .find(|p| p.name == "loopback")
+            .and_then(|p| p.value.as_bool())
+            .unwrap_or(false);
+        let mut block = if loopback {
+            FlowgraphController::new_loopback()
+        } else {
+            FlowgraphController::new()
+        };
+        if find_param(config, "queue_capacity").is_some() {
+            let capacity = get_param_u32(config, "queue_capacity")? as usize;
+            if capacity == 0 {
+                return Err(LoaderError::invalid(&config.name, "queue_capacity", "must be positive"));
+            }
+            block = block.with_queue(capacity);
+        }
         Ok(fg.add_block(block).into())
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("loopback", "bool", Some("false")),
+            ParamSpec::optional("queue_capacity", "usize", None),
+        ]
+    }
 }
 
 // ========================================
@@ -381,20 +1002,45 @@ impl BlockFactory for FlowgraphControllerFactory {
 // ========================================
 
 /// Factory for Delay
+///
+/// A positive `delay` prepends that many zeros to the stream. A negative `delay` advances the
+/// stream instead, i.e., the first `-delay` input samples are consumed and dropped, which is
+/// useful to align one branch relative to another.
 struct DelayFactory;
 
+impl DelayFactory {
+    fn delay(config: &BlockConfig) -> Result<isize> {
+        let delay = get_param_isize(config, "delay")?;
+        // Delay::new negates negative delays
+        if delay == isize::MIN {
+            return Err(LoaderError::invalid(
+                &config.name,
+                "delay",
+                format!("delay must be at least {}", isize::MIN + 1),
+            ));
+        }
+        Ok(delay)
+    }
+}
+
 impl BlockFactory for DelayFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
-        let delay = get_param_u32(&config.parameters, "delay")? as isize;
+        let delay = Self::delay(config)?;
         let dtype = config.dtype.as_deref().unwrap_or("Complex32");
         
         match dtype {
             "Complex32" => Ok(fg.add_block(Delay::<Complex32>::new(delay)).into()),
             "f32" => Ok(fg.add_block(Delay::<f32>::new(delay)).into()),
             "u8" => Ok(fg.add_block(Delay::<u8>::new(delay)).into()),
-            _ => bail!("Unsupported dtype for Delay: {}", dtype),
+            _ => Err(unsupported_dtype(config, dtype)),
         }
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("delay", "isize"),
+        ]
+    }
 }
 
 /// Factory for Fft
@@ -404,7 +1050,7 @@ impl BlockFactory for FftFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
         use futuresdr::blocks::FftDirection;
         
-        let size = get_param_u32(&config.parameters, "size")? as usize;
+        let size = get_param_u32(config, "size")? as usize;
         
         let direction = config.parameters.iter()
             .find(|p| p.name == "direction")
@@ -430,6 +1076,15 @@ impl BlockFactory for FftFactory {
         let fft: Fft = Fft::with_options(size, fft_dir, normalize, scaling);
         Ok(fg.add_block(fft).into())
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("size", "usize"),
+            ParamSpec::optional("direction", "string", Some("Forward")),
+            ParamSpec::optional("normalize", "bool", Some("false")),
+            ParamSpec::optional("scaling", "f64", None),
+        ]
+    }
 }
 
 /// Factory for Throttle
@@ -437,28 +1092,204 @@ struct ThrottleFactory;
 
 impl BlockFactory for ThrottleFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
-        let rate = get_param_f64(&config.parameters, "rate")?;
+        let rate = get_param_f64(config, "rate")?;
         let dtype = config.dtype.as_deref().unwrap_or("Complex32");
         
         match dtype {
             "Complex32" => Ok(fg.add_block(Throttle::<Complex32>::new(rate)).into()),
             "f32" => Ok(fg.add_block(Throttle::<f32>::new(rate)).into()),
             "u8" => Ok(fg.add_block(Throttle::<u8>::new(rate)).into()),
-            _ => bail!("Unsupported dtype for Throttle: {}", dtype),
+            _ => Err(unsupported_dtype(config, dtype)),
+        }
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("rate", "f64"),
+        ]
+    }
+}
+
+/// Factory for StreamTap
+struct StreamTapFactory;
+
+impl BlockFactory for StreamTapFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let decimate = match find_param(config, "decimate") {
+            None => 1,
+            Some(_) => get_param_u32(config, "decimate")? as usize,
+        };
+        if decimate == 0 {
+            return Err(LoaderError::invalid(&config.name, "decimate", "must be positive"));
+        }
+        let dtype = config.dtype.as_deref().unwrap_or("Complex32");
+        
+        match dtype {
+            "Complex32" => Ok(fg.add_block(StreamTap::<Complex32>::new(decimate)).into()),
+            "f32" => Ok(fg.add_block(StreamTap::<f32>::new(decimate)).into()),
+            "u8" => Ok(fg.add_block(StreamTap::<u8>::new(decimate)).into()),
+            _ => Err(unsupported_dtype(config, dtype)),
         }
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("decimate", "u32", Some("1")),
+        ]
+    }
 }
 
 /// Factory for Combine
+///
+/// Either a named `closure` with fixed types, or an element-wise `op` (`add`, `sub`, `mul`,
+/// `max`, `min`) on the type given by `input1_type`, `input2_type`, and `output_type`
+/// (default `f32`), which have to be the same. On `i16` and `i32`, the ops saturate.
 struct CombineFactory;
 
+/// Signature of the named Combine closures
+const COMBINE_CLOSURES: &[(&str, [&str; 3])] = &[
+    ("multiply_conj", ["Complex32", "Complex32", "Complex32"]),
+    ("mult_conjugate", ["Complex32", "Complex32", "Complex32"]),
+    ("divide", ["Complex32", "Complex32", "Complex32"]),
+    ("norm_divide", ["Complex32", "f32", "f32"]),
+];
+
+/// Arithmetic of the Combine ops, saturating for integer samples instead of overflowing
+trait CombineArith: Copy {
+    fn add(a: Self, b: Self) -> Self;
+    fn sub(a: Self, b: Self) -> Self;
+    fn mul(a: Self, b: Self) -> Self;
+}
+
+macro_rules! combine_arith {
+    (saturating $($t:ty),*) => {
+        $(impl CombineArith for $t {
+            fn add(a: Self, b: Self) -> Self {
+                a.saturating_add(b)
+            }
+            fn sub(a: Self, b: Self) -> Self {
+                a.saturating_sub(b)
+            }
+            fn mul(a: Self, b: Self) -> Self {
+                a.saturating_mul(b)
+            }
+        })*
+    };
+    ($($t:ty),*) => {
+        $(impl CombineArith for $t {
+            fn add(a: Self, b: Self) -> Self {
+                a + b
+            }
+            fn sub(a: Self, b: Self) -> Self {
+                a - b
+            }
+            fn mul(a: Self, b: Self) -> Self {
+                a * b
+            }
+        })*
+    };
+}
+
+combine_arith!(f32, f64, Complex32);
+combine_arith!(saturating i16, i32);
+
+/// Element-wise arithmetic operation of a Combine block
+fn combine_arith_op<T: CombineArith>(op: &str) -> Option<fn(&T, &T) -> T> {
+    match op {
+        "add" => Some(|a, b| T::add(*a, *b)),
+        "sub" => Some(|a, b| T::sub(*a, *b)),
+        "mul" => Some(|a, b| T::mul(*a, *b)),
+        _ => None,
+    }
+}
+
+/// Element-wise operation of a Combine block on an ordered type
+fn combine_op<T: CombineArith + PartialOrd>(op: &str) -> Option<fn(&T, &T) -> T> {
+    match op {
+        "max" => Some(|a, b| if a >= b { *a } else { *b }),
+        "min" => Some(|a, b| if a <= b { *a } else { *b }),
+        _ => combine_arith_op(op),
+    }
+}
+
+impl CombineFactory {
+    fn add_op<T: CpuSample>(
+        fg: &mut Flowgraph,
+        config: &BlockConfig,
+        op: &str,
+        dtype: &str,
+        f: Option<fn(&T, &T) -> T>,
+    ) -> Result<BlockId> {
+        let f = f.ok_or_else(|| {
+            LoaderError::invalid(
+                &config.name,
+                "op",
+                format!("unsupported op for {}: {} (expected add, sub, mul, max, or min)", dtype, op),
+            )
+        })?;
+        let combine: Combine<_, T, T, T> = Combine::new(f);
+        Ok(fg.add_block(combine).into())
+    }
+
+    fn create_op(fg: &mut Flowgraph, config: &BlockConfig, op: &str) -> Result<BlockId> {
+        let dtype = config.input1_type.as_deref().unwrap_or("f32");
+        for (field, t) in [("input2_type", &config.input2_type), ("output_type", &config.output_type)] {
+            if let Some(t) = t.as_deref().filter(|t| *t != dtype) {
+                return Err(LoaderError::invalid(
+                    &config.name,
+                    field,
+                    format!("op {} needs the same input and output types, got {} and {}", op, dtype, t),
+                ));
+            }
+        }
+
+        match dtype {
+            "f32" => Self::add_op(fg, config, op, dtype, combine_op::<f32>(op)),
+            "f64" => Self::add_op(fg, config, op, dtype, combine_op::<f64>(op)),
+            "i16" => Self::add_op(fg, config, op, dtype, combine_op::<i16>(op)),
+            "i32" => Self::add_op(fg, config, op, dtype, combine_op::<i32>(op)),
+            "Complex32" => Self::add_op(fg, config, op, dtype, combine_arith_op::<Complex32>(op)),
+            _ => Err(LoaderError::invalid(
+                &config.name,
+                "input1_type",
+                format!("unsupported type for Combine: {}", dtype),
+            )),
+        }
+    }
+}
+
 impl BlockFactory for CombineFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        if find_param(config, "op").is_some() {
+            if find_param(config, "closure").or_else(|| find_param(config, "function")).is_some() {
+                return Err(LoaderError::invalid(&config.name, "op", "cannot be combined with closure"));
+            }
+            let op = get_param_string(config, "op")?;
+            return Self::create_op(fg, config, &op);
+        }
+
         // Try both 'closure' and 'function' parameter names
         let closure_name = config.parameters.iter()
             .find(|p| p.name == "closure" || p.name == "function")
-            .and_then(|p| p.value.as_str())
-            .context("Combine block requires 'closure' or 'function' parameter")?;
+            .ok_or_else(|| LoaderError::missing(&config.name, "closure"))?
+            .value
+            .as_str()
+            .ok_or_else(|| LoaderError::mismatch(&config.name, "closure", "string"))?;
+
+        // the types are optional for named closures, but have to match if given
+        if let Some((_, types)) = COMBINE_CLOSURES.iter().find(|(name, _)| *name == closure_name) {
+            let given = [&config.input1_type, &config.input2_type, &config.output_type];
+            let fields = ["input1_type", "input2_type", "output_type"];
+            for ((field, given), expected) in fields.iter().zip(given).zip(types) {
+                if let Some(t) = given.as_deref().filter(|t| t != expected) {
+                    return Err(LoaderError::invalid(
+                        &config.name,
+                        field,
+                        format!("closure {} expects {}, got {}", closure_name, expected, t),
+                    ));
+                }
+            }
+        }
         
         match closure_name {
             "multiply_conj" | "mult_conjugate" => {
@@ -482,9 +1313,612 @@ impl BlockFactory for CombineFactory {
                 );
                 Ok(fg.add_block(combine).into())
             }
-            _ => bail!("Unknown closure type for Combine block: {}", closure_name),
+            _ => Err(LoaderError::invalid(
+                &config.name,
+                "closure",
+                format!("unknown closure type for Combine block: {}", closure_name),
+            )),
         }
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("closure", "closure", None).with_aliases(&["function"]),
+            ParamSpec::optional("op", "string", None),
+        ]
+    }
+}
+
+/// Factory for Squelch
+struct SquelchFactory;
+
+impl BlockFactory for SquelchFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let threshold_db = get_param_f32(config, "threshold_db")?;
+        let window = get_param_u32(config, "window")? as usize;
+        if window == 0 {
+            return Err(LoaderError::invalid(&config.name, "window", "must be greater than zero"));
+        }
+        
+        let mode = match find_param(config, "mode") {
+            None => SquelchMode::Mute,
+            Some(_) => get_param_string(config, "mode")?
+                .parse()
+                .map_err(|e: String| LoaderError::invalid(&config.name, "mode", e))?,
+        };
+        let attack = match find_param(config, "attack") {
+            None => 0,
+            Some(_) => get_param_u32(config, "attack")? as usize,
+        };
+        let release = match find_param(config, "release") {
+            None => 0,
+            Some(_) => get_param_u32(config, "release")? as usize,
+        };
+        
+        let squelch: Squelch = Squelch::new(threshold_db, window, mode, attack, release);
+        Ok(fg.add_block(squelch).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("threshold_db", "f64"),
+            ParamSpec::required("window", "usize"),
+            ParamSpec::optional("mode", "string", Some("mute")),
+            ParamSpec::optional("attack", "usize", Some("0")),
+            ParamSpec::optional("release", "usize", Some("0")),
+        ]
+    }
+}
+
+/// Factory for PowerMeter
+struct PowerMeterFactory;
+
+impl BlockFactory for PowerMeterFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let interval_ms = match find_param(config, "interval_ms") {
+            None => 1000,
+            Some(_) => get_param_u32(config, "interval_ms")?,
+        };
+        let decay = match find_param(config, "decay") {
+            None => 0.99,
+            Some(_) => get_param_f32(config, "decay")?,
+        };
+        if !(0.0..1.0).contains(&decay) {
+            return Err(LoaderError::invalid(&config.name, "decay", "must be in [0, 1)"));
+        }
+        
+        let meter: PowerMeter = PowerMeter::new(std::time::Duration::from_millis(interval_ms as u64), decay);
+        Ok(fg.add_block(meter).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("interval_ms", "u32", Some("1000")),
+            ParamSpec::optional("decay", "f32", Some("0.99")),
+        ]
+    }
+}
+
+/// Factory for RssiAnnotate
+struct RssiAnnotateFactory;
+
+impl BlockFactory for RssiAnnotateFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let window = match find_param(config, "window") {
+            None => 64,
+            Some(_) => get_param_u32(config, "window")? as usize,
+        };
+        if window == 0 {
+            return Err(LoaderError::invalid(&config.name, "window", "must be positive"));
+        }
+        let history = match find_param(config, "history") {
+            None => 65536,
+            Some(_) => get_param_u32(config, "history")? as usize,
+        };
+        if history < window {
+            return Err(LoaderError::invalid(
+                &config.name,
+                "history",
+                format!("must be at least the window ({} samples)", window),
+            ));
+        }
+        
+        let annotate: RssiAnnotate = RssiAnnotate::new(window, history);
+        Ok(fg.add_block(annotate).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("window", "usize", Some("64")),
+            ParamSpec::optional("history", "usize", Some("65536")),
+        ]
+    }
+}
+
+/// Factory for Cca
+struct CcaFactory;
+
+impl BlockFactory for CcaFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let threshold_db = match find_param(config, "threshold_db") {
+            None => -62.0,
+            Some(_) => get_param_f32(config, "threshold_db")?,
+        };
+        let defer_us = match find_param(config, "defer_us") {
+            None => 34.0,
+            Some(_) => get_param_f64(config, "defer_us")?,
+        };
+        if !(defer_us >= 0.0 && defer_us.is_finite()) {
+            return Err(LoaderError::invalid(&config.name, "defer_us", "must be finite and not negative"));
+        }
+        let backoff_us = match find_param(config, "backoff_us") {
+            None => 135.0,
+            Some(_) => get_param_f64(config, "backoff_us")?,
+        };
+        if !(backoff_us >= 0.0 && backoff_us.is_finite()) {
+            return Err(LoaderError::invalid(&config.name, "backoff_us", "must be finite and not negative"));
+        }
+        let sample_rate = get_param_f64(config, "sample_rate")?;
+        if !(sample_rate > 0.0 && sample_rate.is_finite()) {
+            return Err(LoaderError::invalid(&config.name, "sample_rate", "must be positive and finite"));
+        }
+        
+        let cca: Cca = Cca::new(threshold_db, defer_us, backoff_us, sample_rate);
+        Ok(fg.add_block(cca).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("threshold_db", "f32", Some("-62")),
+            ParamSpec::optional("defer_us", "f64", Some("34")),
+            ParamSpec::optional("backoff_us", "f64", Some("135")),
+            ParamSpec::required("sample_rate", "f64"),
+        ]
+    }
+}
+
+/// Factory for FreqXlate
+struct FreqXlateFactory;
+
+impl FreqXlateFactory {
+    fn block(config: &BlockConfig) -> Result<FreqXlate> {
+        let freq_hz = get_param_f64(config, "freq_hz")?;
+        if !freq_hz.is_finite() {
+            return Err(LoaderError::invalid(&config.name, "freq_hz", "must be finite"));
+        }
+        let sample_rate = get_param_f64(config, "sample_rate")?;
+        if !(sample_rate > 0.0 && sample_rate.is_finite()) {
+            return Err(LoaderError::invalid(&config.name, "sample_rate", "must be positive and finite"));
+        }
+        
+        Ok(FreqXlate::new(freq_hz, sample_rate))
+    }
+}
+
+impl BlockFactory for FreqXlateFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        Ok(fg.add_block(Self::block(config)?).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("freq_hz", "f64").with_runtime_port("freq"),
+            ParamSpec::required("sample_rate", "f64"),
+        ]
+    }
+}
+
+/// Factory for SroCorrect
+struct SroCorrectFactory;
+
+impl BlockFactory for SroCorrectFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let ppm = match find_param(config, "ppm") {
+            Some(_) => get_param_f64(config, "ppm")?,
+            None => 0.0,
+        };
+        if !<SroCorrect>::valid_ppm(ppm) {
+            return Err(LoaderError::invalid(&config.name, "ppm", "must be finite and above -1e6"));
+        }
+        let sample_rate = get_param_f64(config, "sample_rate")?;
+        if !(sample_rate > 0.0 && sample_rate.is_finite()) {
+            return Err(LoaderError::invalid(&config.name, "sample_rate", "must be positive and finite"));
+        }
+        
+        let sro: SroCorrect = SroCorrect::new(ppm, sample_rate);
+        Ok(fg.add_block(sro).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("ppm", "f64", Some("0.0")).with_runtime_port("ppm"),
+            ParamSpec::required("sample_rate", "f64"),
+        ]
+    }
+}
+
+/// Factory for ComplexToFloat
+struct ComplexToFloatFactory;
+
+impl BlockFactory for ComplexToFloatFactory {
+    fn create(&self, fg: &mut Flowgraph, _config: &BlockConfig) -> Result<BlockId> {
+        let block: ComplexToFloat = ComplexToFloat::new();
+        Ok(fg.add_block(block).into())
+    }
+}
+
+/// Factory for FloatToComplex
+struct FloatToComplexFactory;
+
+impl BlockFactory for FloatToComplexFactory {
+    fn create(&self, fg: &mut Flowgraph, _config: &BlockConfig) -> Result<BlockId> {
+        let block: FloatToComplex = FloatToComplex::new();
+        Ok(fg.add_block(block).into())
+    }
+}
+
+/// `enabled` parameter of the IQ inversion blocks
+fn iq_invert_enabled(config: &BlockConfig) -> Result<bool> {
+    match find_param(config, "enabled") {
+        None => Ok(true),
+        Some(p) => p.value.as_bool()
+            .ok_or_else(|| LoaderError::mismatch(&config.name, "enabled", "bool")),
+    }
+}
+
+fn iq_invert_schema() -> Vec<ParamSpec> {
+    vec![
+        ParamSpec::optional("enabled", "bool", Some("true")).with_runtime_port("enabled"),
+    ]
+}
+
+/// Factory for ConjugateIq
+struct ConjugateIqFactory;
+
+impl BlockFactory for ConjugateIqFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let block: ConjugateIq = ConjugateIq::new(iq_invert_enabled(config)?);
+        Ok(fg.add_block(block).into())
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        iq_invert_schema()
+    }
+}
+
+/// Factory for SwapIq
+struct SwapIqFactory;
+
+impl BlockFactory for SwapIqFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let block: SwapIq = SwapIq::new(iq_invert_enabled(config)?);
+        Ok(fg.add_block(block).into())
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        iq_invert_schema()
+    }
+}
+
+/// Factory for Decimate (FutureSDR's decimating `Fir`)
+///
+/// Without `taps`, the anti-aliasing filter is a Kaiser low-pass with the passband up to 80% of
+/// the output Nyquist frequency and at least 60 dB attenuation above it, i.e., for `factor = 4`
+/// at 20 MSps, the passband ends at 2 MHz and the stopband starts at 2.5 MHz.
+struct DecimateFactory;
+
+impl DecimateFactory {
+    fn taps(factor: usize) -> Vec<f32> {
+        if factor == 1 {
+            return vec![1.0];
+        }
+        let nyquist = 0.5 / factor as f64;
+        firdes::kaiser::lowpass(0.8 * nyquist, 0.2 * nyquist, 0.001)
+    }
+}
+
+impl BlockFactory for DecimateFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let factor = get_param_u32(config, "factor")? as usize;
+        if factor == 0 {
+            return Err(LoaderError::invalid(&config.name, "factor", "must be positive"));
+        }
+        let dtype = config.dtype.as_deref().unwrap_or("Complex32");
+        if dtype != "Complex32" {
+            return Err(unsupported_dtype(config, dtype));
+        }
+
+        let taps = match find_param(config, "taps") {
+            None => DecimateFactory::taps(factor),
+            Some(p) => {
+                let taps = get_param_f32_array(config, p)?;
+                if taps.is_empty() {
+                    return Err(LoaderError::invalid(&config.name, "taps", "must not be empty"));
+                }
+                taps
+            }
+        };
+        let block = FirBuilder::decimating_with_taps::<Complex32, Complex32, _>(factor, taps);
+        Ok(fg.add_block(block).into())
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("factor", "usize"),
+            ParamSpec::optional("taps", "array", None),
+        ]
+    }
+}
+
+/// Factory for Upsample
+struct UpsampleFactory;
+
+impl BlockFactory for UpsampleFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let factor = get_param_u32(config, "factor")? as usize;
+        if factor == 0 {
+            return Err(LoaderError::invalid(&config.name, "factor", "must be positive"));
+        }
+        let dtype = config.dtype.as_deref().unwrap_or("Complex32");
+        if dtype != "Complex32" {
+            return Err(unsupported_dtype(config, dtype));
+        }
+
+        let block: Upsample = Upsample::new(factor);
+        Ok(fg.add_block(block).into())
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![ParamSpec::required("factor", "usize")]
+    }
+}
+
+/// Factory for ArbResampler (FutureSDR's `PfbArbResampler`)
+///
+/// The prototype is a Kaiser low-pass at the rate of the filter bank, with the passband up to
+/// 80% of the lower of the input and output Nyquist frequencies.
+struct ArbResamplerFactory;
+
+impl ArbResamplerFactory {
+    fn taps(ratio: f64, num_filters: usize) -> Vec<f32> {
+        let nyquist = 0.5 * ratio.min(1.0) / num_filters as f64;
+        firdes::kaiser::lowpass::<f32>(0.8 * nyquist, 0.2 * nyquist, 0.001)
+            .into_iter()
+            // the polyphase decomposition takes the gain of `num_filters`
+            .map(|t| t * num_filters as f32)
+            .collect()
+    }
+}
+
+impl BlockFactory for ArbResamplerFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let ratio = get_param_f64(config, "ratio")?;
+        if !(ratio > 0.0 && ratio.is_finite()) {
+            return Err(LoaderError::invalid(&config.name, "ratio", "must be positive"));
+        }
+        let dtype = config.dtype.as_deref().unwrap_or("Complex32");
+        if dtype != "Complex32" {
+            return Err(unsupported_dtype(config, dtype));
+        }
+
+        let num_filters = match find_param(config, "num_filters") {
+            None => 32,
+            Some(_) => get_param_u32(config, "num_filters")? as usize,
+        };
+        if num_filters == 0 {
+            return Err(LoaderError::invalid(&config.name, "num_filters", "must be positive"));
+        }
+
+        let taps = ArbResamplerFactory::taps(ratio, num_filters);
+        let block: PfbArbResampler = PfbArbResampler::new(ratio as f32, &taps, num_filters);
+        Ok(fg.add_block(block).into())
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("ratio", "f64"),
+            ParamSpec::optional("num_filters", "usize", Some("32")),
+        ]
+    }
+}
+
+/// Factory for Iir
+struct IirFactory;
+
+impl BlockFactory for IirFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let b = get_param_f32_array(config, require_param(config, "b")?)?;
+        let a = get_param_f32_array(config, require_param(config, "a")?)?;
+        if b.is_empty() {
+            return Err(LoaderError::invalid(&config.name, "b", "must not be empty"));
+        }
+        if a.is_empty() {
+            return Err(LoaderError::invalid(&config.name, "a", "must not be empty"));
+        }
+        if a[0] == 0.0 {
+            return Err(LoaderError::invalid(&config.name, "a", "a[0] must not be zero"));
+        }
+
+        let dtype = config.dtype.as_deref().unwrap_or("Complex32");
+        match dtype {
+            "f32" => Ok(fg.add_block(Iir::<f32>::new(b, a)).into()),
+            "Complex32" => Ok(fg.add_block(Iir::<Complex32>::new(b, a)).into()),
+            _ => Err(unsupported_dtype(config, dtype)),
+        }
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("b", "array"),
+            ParamSpec::required("a", "array"),
+        ]
+    }
+}
+
+/// Factory for Fir
+///
+/// Takes either explicit `taps` or a `design` table, e.g.,
+/// `{ type = "lowpass", cutoff = 0.1, num_taps = 63 }` with the -3 dB cutoff in cycles/sample.
+/// With `symmetric = true`, the number of taps has to be odd.
+struct FirFactory;
+
+impl FirFactory {
+    fn taps(config: &BlockConfig) -> Result<Vec<f32>> {
+        match (find_param(config, "taps"), find_param(config, "design")) {
+            (Some(p), None) => get_param_f32_array(config, p),
+            (None, Some(p)) => {
+                let design = p.value.as_table()
+                    .ok_or_else(|| LoaderError::mismatch(&config.name, "design", "table"))?;
+                let invalid = |reason: &str| LoaderError::invalid(&config.name, "design", reason);
+                match design.get("type").and_then(|t| t.as_str()) {
+                    Some("lowpass") => {}
+                    Some(t) => return Err(invalid(&format!("unknown filter type {t}"))),
+                    None => return Err(invalid("missing type")),
+                }
+                let cutoff = design.get("cutoff")
+                    .and_then(|c| c.as_float())
+                    .filter(|c| *c > 0.0 && *c < 0.5)
+                    .ok_or_else(|| invalid("cutoff must be a float in (0, 0.5) cycles/sample"))?;
+                let num_taps = design.get("num_taps")
+                    .and_then(as_integer)
+                    .and_then(|n| usize::try_from(n).ok())
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| invalid("num_taps must be a positive integer"))?;
+                Ok(Self::lowpass(cutoff, num_taps))
+            }
+            (Some(_), Some(_)) => Err(LoaderError::invalid(
+                &config.name,
+                "design",
+                "taps and design are mutually exclusive",
+            )),
+            (None, None) => Err(LoaderError::missing(&config.name, "taps")),
+        }
+    }
+
+    /// Windowed-sinc (Hamming) low-pass taps with unit DC gain and the -3 dB point at `cutoff`
+    ///
+    /// The windowed sinc is down by 6 dB at its design frequency, which is therefore moved up
+    /// until the response at `cutoff` is `1/sqrt(2)`.
+    fn lowpass(cutoff: f64, num_taps: usize) -> Vec<f32> {
+        let window = windows::hamming(num_taps, false);
+        let gain = |design: f64| {
+            let taps: Vec<f64> = firdes::lowpass(design, &window);
+            let w = 2.0 * std::f64::consts::PI * cutoff;
+            let (re, im) = taps.iter().enumerate().fold((0.0, 0.0), |(re, im), (n, t)| {
+                (re + t * (w * n as f64).cos(), im - t * (w * n as f64).sin())
+            });
+            re.hypot(im)
+        };
+
+        let (mut lo, mut hi) = (cutoff, 0.5);
+        for _ in 0..40 {
+            let mid = (lo + hi) / 2.0;
+            if gain(mid) < std::f64::consts::FRAC_1_SQRT_2 {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        firdes::lowpass(lo, &window)
+    }
+}
+
+impl BlockFactory for FirFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let taps = Self::taps(config)?;
+        if taps.is_empty() {
+            return Err(LoaderError::invalid(&config.name, "taps", "must not be empty"));
+        }
+        let symmetric = find_param(config, "symmetric")
+            .map(|p| p.value.as_bool()
+                .ok_or_else(|| LoaderError::mismatch(&config.name, "symmetric", "bool")))
+            .transpose()?
+            .unwrap_or(false);
+        if symmetric && taps.len() % 2 == 0 {
+            return Err(LoaderError::invalid(
+                &config.name,
+                "taps",
+                format!("symmetric filter needs an odd number of taps, got {}", taps.len()),
+            ));
+        }
+
+        let dtype = config.dtype.as_deref().unwrap_or("Complex32");
+        match dtype {
+            "f32" => Ok(fg.add_block(FirBuilder::fir::<f32, f32, _>(taps)).into()),
+            "Complex32" => Ok(fg.add_block(FirBuilder::fir::<Complex32, Complex32, _>(taps)).into()),
+            _ => Err(unsupported_dtype(config, dtype)),
+        }
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("taps", "array", None),
+            ParamSpec::optional("design", "table", None),
+            ParamSpec::optional("symmetric", "bool", Some("false")),
+        ]
+    }
+}
+
+/// Factory for Dedup
+///
+/// `pattern` is `"futuresdr"`, `"load"`, `"default"` (both), or a regex whose first matching
+/// capture group is the sequence number.
+struct DedupFactory;
+
+impl BlockFactory for DedupFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let window = match find_param(config, "window") {
+            Some(_) => get_param_u32(config, "window")? as usize,
+            None => 64,
+        };
+        if window == 0 {
+            return Err(LoaderError::invalid(&config.name, "window", "must be positive"));
+        }
+        let pattern = match find_param(config, "pattern") {
+            Some(_) => get_param_string(config, "pattern")?,
+            None => "default".to_string(),
+        };
+        let regex = match pattern.as_str() {
+            "futuresdr" => Dedup::FUTURESDR,
+            "load" => Dedup::LOAD,
+            "default" => Dedup::DEFAULT,
+            p => p,
+        };
+
+        let block = Dedup::new(window, regex)
+            .map_err(|e| LoaderError::invalid(&config.name, "pattern", e.to_string()))?;
+        Ok(fg.add_block(block).into())
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("window", "usize", Some("64")),
+            ParamSpec::optional("pattern", "string", Some("default")),
+        ]
+    }
+}
+
+/// Factory for Defragment
+struct DefragmentFactory;
+
+impl BlockFactory for DefragmentFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let timeout_ms = match find_param(config, "timeout_ms") {
+            Some(_) => get_param_u32(config, "timeout_ms")?,
+            None => 1000,
+        };
+        if timeout_ms == 0 {
+            return Err(LoaderError::invalid(&config.name, "timeout_ms", "must be positive"));
+        }
+        let block = Defragment::new(std::time::Duration::from_millis(timeout_ms as u64));
+        Ok(fg.add_block(block).into())
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("timeout_ms", "u32", Some("1000")),
+        ]
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -494,10 +1928,7 @@ struct FileSourceFactory;
 #[cfg(not(target_arch = "wasm32"))]
 impl BlockFactory for FileSourceFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
-        let path = config.parameters.iter()
-            .find(|p| p.name == "path")
-            .and_then(|p| p.value.as_str())
-            .context("FileSource requires 'path' parameter")?;
+        let path = get_param_string(config, "path")?;
         
         let repeat = config.parameters.iter()
             .find(|p| p.name == "repeat")
@@ -507,12 +1938,19 @@ impl BlockFactory for FileSourceFactory {
         let dtype = config.dtype.as_deref().unwrap_or("Complex32");
         
         match dtype {
-            "Complex32" => Ok(fg.add_block(FileSource::<Complex32>::new(path, repeat)).into()),
-            "f32" => Ok(fg.add_block(FileSource::<f32>::new(path, repeat)).into()),
-            "u8" => Ok(fg.add_block(FileSource::<u8>::new(path, repeat)).into()),
-            _ => bail!("Unsupported dtype for FileSource: {}", dtype),
+            "Complex32" => Ok(fg.add_block(FileSource::<Complex32>::new(&path, repeat)).into()),
+            "f32" => Ok(fg.add_block(FileSource::<f32>::new(&path, repeat)).into()),
+            "u8" => Ok(fg.add_block(FileSource::<u8>::new(&path, repeat)).into()),
+            _ => Err(unsupported_dtype(config, dtype)),
         }
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("path", "string"),
+            ParamSpec::optional("repeat", "bool", Some("false")),
+        ]
+    }
 }
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -522,14 +1960,416 @@ struct BlobToUdpFactory;
 #[cfg(not(target_arch = "wasm32"))]
 impl BlockFactory for BlobToUdpFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
-        // Try both 'address' and 'addr' parameter names
-        let address = config.parameters.iter()
-            .find(|p| p.name == "address" || p.name == "addr")
-            .and_then(|p| p.value.as_str())
-            .context("BlobToUdp requires 'address' or 'addr' parameter")?;
-        
+        let address = get_param_address(config)?;
         Ok(fg.add_block(BlobToUdp::new(address)).into())
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("address", "string").with_aliases(&["addr"]),
+        ]
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Socket address of a UDP block, given as `address` or `addr`
+fn get_param_address(config: &BlockConfig) -> Result<&str> {
+    config.parameters.iter()
+        .find(|p| p.name == "address" || p.name == "addr")
+        .ok_or_else(|| LoaderError::missing(&config.name, "address"))?
+        .value
+        .as_str()
+        .ok_or_else(|| LoaderError::mismatch(&config.name, "address", "string"))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Factory for BlobToUdpFramed
+struct BlobToUdpFramedFactory;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BlockFactory for BlobToUdpFramedFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let address = get_param_address(config)?;
+        let tag = match find_param(config, "tag") {
+            None => 0,
+            Some(_) => get_param_int::<u16>(config, "tag", "u16")?,
+        };
+        let block = BlobToUdpFramed::new(address, tag)
+            .map_err(|e| LoaderError::invalid(&config.name, "address", e.to_string()))?;
+        Ok(fg.add_block(block).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("address", "string").with_aliases(&["addr"]),
+            ParamSpec::optional("tag", "u16", Some("0")),
+        ]
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Factory for BlobToUdpWithMeta
+struct BlobToUdpWithMetaFactory;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BlockFactory for BlobToUdpWithMetaFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let address = get_param_address(config)?;
+        let block = BlobToUdpWithMeta::new(address)
+            .map_err(|e| LoaderError::invalid(&config.name, "address", e.to_string()))?;
+        Ok(fg.add_block(block).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("address", "string").with_aliases(&["addr"]),
+        ]
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Factory for UdpToBlob
+struct UdpToBlobFactory;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BlockFactory for UdpToBlobFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let address = get_param_address(config)?;
+        let tag = match find_param(config, "tag") {
+            None => None,
+            Some(_) => Some(get_param_int::<u16>(config, "tag", "u16")?),
+        };
+        Ok(fg.add_block(UdpToBlob::new(address, tag)).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("address", "string").with_aliases(&["addr"]),
+            ParamSpec::optional("tag", "u16", None),
+        ]
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Factory for MqttSink
+struct MqttSinkFactory;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BlockFactory for MqttSinkFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let broker = get_param_string(config, "broker")?;
+        let topic = get_param_string(config, "topic")?;
+        if topic.is_empty() || topic.contains(['+', '#']) {
+            return Err(LoaderError::invalid(&config.name, "topic", "must be a non-empty topic without wildcards"));
+        }
+        let qos = match find_param(config, "qos") {
+            None => 0,
+            Some(_) => get_param_int::<u8>(config, "qos", "u8")?,
+        };
+        if qos > 1 {
+            return Err(LoaderError::invalid(&config.name, "qos", "only QoS 0 and 1 are supported"));
+        }
+        Ok(fg.add_block(MqttSink::new(broker, topic, qos)).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("broker", "string"),
+            ParamSpec::required("topic", "string"),
+            ParamSpec::optional("qos", "u8", Some("0")),
+        ]
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Factory for CsvSink
+struct CsvSinkFactory;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BlockFactory for CsvSinkFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let path = get_param_string(config, "path")?;
+        
+        let flexible = config.parameters.iter()
+            .find(|p| p.name == "flexible")
+            .and_then(|p| p.value.as_bool())
+            .unwrap_or(false);
+        
+        Ok(fg.add_block(CsvSink::new(path, flexible)).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("path", "string"),
+            ParamSpec::optional("flexible", "bool", Some("false")),
+        ]
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Factory for SymbolCsvSink
+struct SymbolCsvSinkFactory;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BlockFactory for SymbolCsvSinkFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let path = get_param_string(config, "path")?;
+        let max_rows = match find_param(config, "max_rows") {
+            None => None,
+            Some(_) => Some(get_param_int::<u64>(config, "max_rows", "u64")?),
+        };
+        if max_rows == Some(0) {
+            return Err(LoaderError::invalid(&config.name, "max_rows", "must be positive"));
+        }
+        let rotate = find_param(config, "rotate")
+            .and_then(|p| p.value.as_bool())
+            .unwrap_or(false);
+        if rotate && max_rows.is_none() {
+            return Err(LoaderError::invalid(&config.name, "rotate", "requires max_rows"));
+        }
+        let index = find_param(config, "index")
+            .and_then(|p| p.value.as_bool())
+            .unwrap_or(false);
+        
+        let sink: SymbolCsvSink = SymbolCsvSink::new(path, max_rows, rotate, index);
+        Ok(fg.add_block(sink).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("path", "string"),
+            ParamSpec::optional("max_rows", "u64", None),
+            ParamSpec::optional("rotate", "bool", Some("false")),
+            ParamSpec::optional("index", "bool", Some("false")),
+        ]
+    }
+}
+
+/// Factory for PmtMerge
+struct PmtMergeFactory;
+
+impl BlockFactory for PmtMergeFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let n_inputs = get_param_u32(config, "n_inputs")? as usize;
+        if !(1..=PmtMerge::MAX_INPUTS).contains(&n_inputs) {
+            return Err(LoaderError::invalid(
+                &config.name,
+                "n_inputs",
+                format!("must be between 1 and {}", PmtMerge::MAX_INPUTS),
+            ));
+        }
+
+        Ok(fg.add_block(PmtMerge::new(n_inputs)).into())
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("n_inputs", "usize"),
+        ]
+    }
+}
+
+/// Factory for LatencyHistogram
+struct LatencyHistogramFactory;
+
+impl BlockFactory for LatencyHistogramFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let edges_ns = require_param(config, "edges_ns")?
+            .value
+            .as_array()
+            .and_then(|a| {
+                a.iter()
+                    .map(|v| as_integer(v).and_then(|i| u64::try_from(i).ok()))
+                    .collect::<Option<Vec<u64>>>()
+            })
+            .ok_or_else(|| LoaderError::mismatch(&config.name, "edges_ns", "array of u64"))?;
+        if edges_ns.len() < 2 || edges_ns.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(LoaderError::invalid(
+                &config.name,
+                "edges_ns",
+                "needs at least two strictly increasing edges",
+            ));
+        }
+
+        Ok(fg.add_block(LatencyHistogram::new(edges_ns)).into())
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("edges_ns", "array"),
+        ]
+    }
+}
+
+/// Factory for FrameStats
+struct FrameStatsFactory;
+
+impl BlockFactory for FrameStatsFactory {
+    fn create(&self, fg: &mut Flowgraph, _config: &BlockConfig) -> Result<BlockId> {
+        Ok(fg.add_block(FrameStats::new()).into())
+    }
+}
+
+/// Factory for FrameCounter
+struct FrameCounterFactory;
+
+impl BlockFactory for FrameCounterFactory {
+    fn create(&self, fg: &mut Flowgraph, _config: &BlockConfig) -> Result<BlockId> {
+        Ok(fg.add_block(FrameCounter::new()).into())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Factory for MsgRateLimit
+struct MsgRateLimitFactory;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BlockFactory for MsgRateLimitFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let rate = get_param_f64(config, "rate")?;
+        if !(rate > 0.0 && rate.is_finite()) {
+            return Err(LoaderError::invalid(&config.name, "rate", "must be positive and finite"));
+        }
+        let policy = match find_param(config, "policy") {
+            None => RateLimitPolicy::Drop,
+            Some(_) => get_param_string(config, "policy")?
+                .parse()
+                .map_err(|e: String| LoaderError::invalid(&config.name, "policy", e))?,
+        };
+        let capacity = match find_param(config, "capacity") {
+            None => 64,
+            Some(_) => get_param_u32(config, "capacity")? as usize,
+        };
+        
+        let limit = MsgRateLimit::new(rate, policy, capacity);
+        Ok(fg.add_block(limit).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("rate", "f64"),
+            ParamSpec::optional("policy", "string", Some("drop")),
+            ParamSpec::optional("capacity", "usize", Some("64")),
+        ]
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Factory for HexFileSource
+struct HexFileSourceFactory;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BlockFactory for HexFileSourceFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let path = get_param_string(config, "path")?;
+        let interval_ms = match find_param(config, "interval_ms") {
+            None => 1000,
+            Some(_) => get_param_u32(config, "interval_ms")?,
+        };
+        let repeat = match find_param(config, "repeat") {
+            None => false,
+            Some(p) => p
+                .value
+                .as_bool()
+                .ok_or_else(|| LoaderError::mismatch(&config.name, "repeat", "bool"))?,
+        };
+        
+        let source = HexFileSource::new(
+            path,
+            std::time::Duration::from_millis(interval_ms as u64),
+            repeat,
+        );
+        Ok(fg.add_block(source).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("path", "string"),
+            ParamSpec::optional("interval_ms", "u32", Some("1000")),
+            ParamSpec::optional("repeat", "bool", Some("false")),
+        ]
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Factory for Heartbeat
+struct HeartbeatFactory;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BlockFactory for HeartbeatFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let interval_ms = match find_param(config, "interval_ms") {
+            None => 1000,
+            Some(_) => get_param_u32(config, "interval_ms")?,
+        };
+        if interval_ms == 0 {
+            return Err(LoaderError::invalid(&config.name, "interval_ms", "must be positive"));
+        }
+        
+        let heartbeat = Heartbeat::new(std::time::Duration::from_millis(interval_ms as u64));
+        Ok(fg.add_block(heartbeat).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![ParamSpec::optional("interval_ms", "u32", Some("1000"))]
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Factory for Watchdog
+struct WatchdogFactory;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BlockFactory for WatchdogFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let timeout_secs = get_param_f64(config, "timeout_secs")?;
+        if !(timeout_secs > 0.0 && timeout_secs.is_finite()) {
+            return Err(LoaderError::invalid(&config.name, "timeout_secs", "must be positive"));
+        }
+
+        let watchdog = Watchdog::new(std::time::Duration::from_secs_f64(timeout_secs));
+        Ok(fg.add_block(watchdog).into())
+    }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![ParamSpec::required("timeout_secs", "f64")]
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+/// Factory for PreTriggerRecorder
+struct PreTriggerRecorderFactory;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BlockFactory for PreTriggerRecorderFactory {
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let sample_rate = get_param_f64(config, "sample_rate")?;
+        if !(sample_rate > 0.0 && sample_rate.is_finite()) {
+            return Err(LoaderError::invalid(&config.name, "sample_rate", "must be positive and finite"));
+        }
+        let samples = |name: &str| -> Result<usize> {
+            let secs = get_param_f64(config, name)?;
+            if !(secs >= 0.0 && secs.is_finite()) {
+                return Err(LoaderError::invalid(&config.name, name, "must be finite and not negative"));
+            }
+            Ok((secs * sample_rate).round() as usize)
+        };
+        let pre = samples("pre_secs")?;
+        let post = samples("post_secs")?;
+        let path = get_param_string(config, "path")?;
+        
+        let recorder: PreTriggerRecorder = PreTriggerRecorder::new(path, pre, post);
+        Ok(fg.add_block(recorder).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("pre_secs", "f64"),
+            ParamSpec::required("post_secs", "f64"),
+            ParamSpec::required("sample_rate", "f64"),
+            ParamSpec::required("path", "string"),
+        ]
+    }
 }
 
 // ========================================
@@ -537,42 +2377,79 @@ impl BlockFactory for BlobToUdpFactory {
 // ========================================
 
 /// Helper to parse MAC address from string
-fn parse_mac_addr(s: &str) -> Result<[u8; 6]> {
-    let parts: Vec<&str> = s.split(':').collect();
+///
+/// Accepts six hexadecimal octets separated by `:` or `-`, e.g., `42:42:42:42:42:42`.
+fn parse_mac_addr(s: &str) -> std::result::Result<[u8; 6], String> {
+    let sep = if s.contains('-') { '-' } else { ':' };
+    let parts: Vec<&str> = s.split(sep).collect();
     if parts.len() != 6 {
-        bail!("Invalid MAC address format: {}", s);
+        return Err(format!(
+            "invalid MAC address '{}': expected 6 octets separated by ':' or '-'",
+            s
+        ));
     }
     let mut result = [0u8; 6];
     for (i, part) in parts.iter().enumerate() {
-        result[i] = u8::from_str_radix(part, 16)?;
+        if part.is_empty() || !part.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!(
+                "invalid MAC address '{}': octet {} ('{}') is not a hexadecimal number",
+                s,
+                i + 1,
+                part
+            ));
+        }
+        if part.len() > 2 {
+            return Err(format!(
+                "invalid MAC address '{}': octet {} ('{}') is out of range (00-ff)",
+                s,
+                i + 1,
+                part
+            ));
+        }
+        result[i] = u8::from_str_radix(part, 16).unwrap();
     }
     Ok(result)
 }
 
+/// Helper to read an optional MAC address parameter, falling back to a default
+fn get_param_mac(config: &BlockConfig, name: &str, default: [u8; 6]) -> Result<[u8; 6]> {
+    match find_param(config, name) {
+        None => Ok(default),
+        Some(p) => {
+            let s = p.value
+                .as_str()
+                .ok_or_else(|| LoaderError::mismatch(&config.name, name, "string"))?;
+            parse_mac_addr(s).map_err(|e| LoaderError::invalid(&config.name, name, e))
+        }
+    }
+}
+
 /// Factory for wifi::Mac
 struct WifiMacFactory;
 
 impl BlockFactory for WifiMacFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
-        let src_addr = config.parameters.iter()
-            .find(|p| p.name == "src_addr")
-            .and_then(|p| p.value.as_str())
-            .map(parse_mac_addr)
-            .unwrap_or_else(|| Ok([0x42; 6]))?;
-        
-        let dst_addr = config.parameters.iter()
-            .find(|p| p.name == "dst_addr")
-            .and_then(|p| p.value.as_str())
-            .map(parse_mac_addr)
-            .unwrap_or_else(|| Ok([0x23; 6]))?;
-        
-        let bssid = config.parameters.iter()
-            .find(|p| p.name == "bssid")
-            .and_then(|p| p.value.as_str())
-            .map(parse_mac_addr)
-            .unwrap_or_else(|| Ok([0xff; 6]))?;
+        let src_addr = get_param_mac(config, "src_addr", [0x42; 6])?;
+        let dst_addr = get_param_mac(config, "dst_addr", [0x23; 6])?;
+        let bssid = get_param_mac(config, "bssid", [0xff; 6])?;
+        let fragment = find_param(config, "fragment")
+            .and_then(|p| p.value.as_bool())
+            .unwrap_or(false);
         
-        Ok(fg.add_block(wifi::Mac::new(src_addr, dst_addr, bssid)).into())
+        let mut mac = wifi::Mac::new(src_addr, dst_addr, bssid);
+        if fragment {
+            mac = mac.with_fragmentation();
+        }
+        Ok(fg.add_block(mac).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("src_addr", "string", Some("42:42:42:42:42:42")),
+            ParamSpec::optional("dst_addr", "string", Some("23:23:23:23:23:23")),
+            ParamSpec::optional("bssid", "string", Some("ff:ff:ff:ff:ff:ff")),
+            ParamSpec::optional("fragment", "bool", Some("false")),
+        ]
     }
 }
 
@@ -581,36 +2458,67 @@ struct WifiEncoderFactory;
 
 impl BlockFactory for WifiEncoderFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
-        let mcs_str = config.parameters.iter()
-            .find(|p| p.name == "mcs")
-            .and_then(|p| p.value.as_str())
-            .unwrap_or("Qpsk_1_2");
-        
-        let mcs = match mcs_str {
-            "Bpsk_1_2" => wifi::Mcs::Bpsk_1_2,
-            "Bpsk_3_4" => wifi::Mcs::Bpsk_3_4,
-            "Qpsk_1_2" => wifi::Mcs::Qpsk_1_2,
-            "Qpsk_3_4" => wifi::Mcs::Qpsk_3_4,
-            "Qam16_1_2" => wifi::Mcs::Qam16_1_2,
-            "Qam16_3_4" => wifi::Mcs::Qam16_3_4,
-            "Qam64_2_3" => wifi::Mcs::Qam64_2_3,
-            "Qam64_3_4" => wifi::Mcs::Qam64_3_4,
-            _ => wifi::Mcs::Qpsk_1_2,
+        let mcs = match find_param(config, "mcs") {
+            None => wifi::Mcs::Qpsk_1_2,
+            Some(_) => get_param_string(config, "mcs")?
+                .parse()
+                .map_err(|e| LoaderError::invalid(&config.name, "mcs", e))?,
         };
         
         let encoder: wifi::Encoder = wifi::Encoder::new(mcs);
         Ok(fg.add_block(encoder).into())
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("mcs", "string", Some("Qpsk_1_2")).with_runtime_port("mcs"),
+        ]
+    }
 }
 
 /// Factory for wifi::Mapper
 struct WifiMapperFactory;
 
 impl BlockFactory for WifiMapperFactory {
-    fn create(&self, fg: &mut Flowgraph, _config: &BlockConfig) -> Result<BlockId> {
-        let mapper: wifi::Mapper = wifi::Mapper::new();
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let Some(param) = find_param(config, "constellation") else {
+            if find_param(config, "bits_per_symbol").is_some() {
+                return Err(LoaderError::missing(&config.name, "constellation"));
+            }
+            let mapper: wifi::Mapper = wifi::Mapper::new();
+            return Ok(fg.add_block(mapper).into());
+        };
+
+        let points = get_param_complex_array(config, param)?;
+        let bits_per_symbol = match find_param(config, "bits_per_symbol") {
+            Some(_) => get_param_u32(config, "bits_per_symbol")? as usize,
+            None => points.len().trailing_zeros() as usize,
+        };
+        if ![1, 2, 4, 6].contains(&bits_per_symbol) {
+            return Err(LoaderError::invalid(
+                &config.name,
+                "bits_per_symbol",
+                format!("{} is not the bits per symbol of an 802.11 modulation (1, 2, 4, or 6)", bits_per_symbol),
+            ));
+        }
+        if points.len() != 1 << bits_per_symbol {
+            return Err(LoaderError::invalid(
+                &config.name,
+                "constellation",
+                format!("expected 2^{} = {} points, got {}", bits_per_symbol, 1 << bits_per_symbol, points.len()),
+            ));
+        }
+
+        let mapper: wifi::Mapper = wifi::Mapper::with_constellation(points, bits_per_symbol);
         Ok(fg.add_block(mapper).into())
     }
+
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("constellation", "array", None),
+            ParamSpec::optional("bits_per_symbol", "usize", None),
+        ]
+    }
 }
 
 /// Factory for wifi::Prefix
@@ -618,12 +2526,19 @@ struct WifiPrefixFactory;
 
 impl BlockFactory for WifiPrefixFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
-        let pad_front = get_param_u32(&config.parameters, "pad_front")? as usize;
-        let pad_tail = get_param_u32(&config.parameters, "pad_tail")? as usize;
+        let pad_front = get_param_u32(config, "pad_front")? as usize;
+        let pad_tail = get_param_u32(config, "pad_tail")? as usize;
         
         let prefix: wifi::Prefix = wifi::Prefix::new(pad_front, pad_tail);
         Ok(fg.add_block(prefix).into())
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("pad_front", "usize"),
+            ParamSpec::required("pad_tail", "usize"),
+        ]
+    }
 }
 
 /// Factory for wifi::MovingAverage
@@ -631,7 +2546,7 @@ struct WifiMovingAverageFactory;
 
 impl BlockFactory for WifiMovingAverageFactory {
     fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
-        let length = get_param_u32(&config.parameters, "length")? as usize;
+        let length = get_param_u32(config, "length")? as usize;
         let dtype = config.dtype.as_deref().unwrap_or("f32");
         
         match dtype {
@@ -639,12 +2554,19 @@ impl BlockFactory for WifiMovingAverageFactory {
                 let avg: wifi::MovingAverage<Complex32> = wifi::MovingAverage::new(length);
                 Ok(fg.add_block(avg).into())
             }
-            "f32" | _ => {
+            "f32" => {
                 let avg: wifi::MovingAverage<f32> = wifi::MovingAverage::new(length);
                 Ok(fg.add_block(avg).into())
             }
+            _ => Err(unsupported_dtype(config, dtype)),
         }
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::required("length", "usize"),
+        ]
+    }
 }
 
 /// Factory for wifi::SyncShort
@@ -671,9 +2593,26 @@ impl BlockFactory for WifiSyncLongFactory {
 struct WifiFrameEqualizerFactory;
 
 impl BlockFactory for WifiFrameEqualizerFactory {
-    fn create(&self, fg: &mut Flowgraph, _config: &BlockConfig) -> Result<BlockId> {
-        let eq: wifi::FrameEqualizer = wifi::FrameEqualizer::new();
-        Ok(fg.add_block(eq).into())
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let llr = find_param(config, "llr")
+            .and_then(|p| p.value.as_bool())
+            .unwrap_or(false);
+        let pilot_tracking = find_param(config, "pilot_tracking")
+            .and_then(|p| p.value.as_bool())
+            .unwrap_or(true);
+        let eq: wifi::FrameEqualizer = if llr {
+            wifi::FrameEqualizer::with_llr()
+        } else {
+            wifi::FrameEqualizer::new()
+        };
+        Ok(fg.add_block(eq.with_pilot_tracking(pilot_tracking)).into())
+    }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("llr", "bool", Some("false")),
+            ParamSpec::optional("pilot_tracking", "bool", Some("true")),
+        ]
     }
 }
 
@@ -681,8 +2620,400 @@ impl BlockFactory for WifiFrameEqualizerFactory {
 struct WifiDecoderFactory;
 
 impl BlockFactory for WifiDecoderFactory {
-    fn create(&self, fg: &mut Flowgraph, _config: &BlockConfig) -> Result<BlockId> {
-        let decoder: wifi::Decoder = wifi::Decoder::new();
+    fn create(&self, fg: &mut Flowgraph, config: &BlockConfig) -> Result<BlockId> {
+        let decoder: wifi::Decoder = match find_param(config, "traceback") {
+            None => wifi::Decoder::new(),
+            Some(_) => {
+                let depth = get_param_u32(config, "traceback")? as usize;
+                if !(1..=24).contains(&depth) {
+                    return Err(LoaderError::invalid(&config.name, "traceback", "must be in 1..=24"));
+                }
+                wifi::Decoder::with_traceback(depth)
+            }
+        };
         Ok(fg.add_block(decoder).into())
     }
+    
+    fn parameter_schema(&self) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::optional("traceback", "usize", None),
+        ]
+    }
+}
+
+/// Factory for wifi::McsDemux
+struct WifiMcsDemuxFactory;
+
+impl BlockFactory for WifiMcsDemuxFactory {
+    fn create(&self, fg: &mut Flowgraph, _config: &BlockConfig) -> Result<BlockId> {
+        Ok(fg.add_block(wifi::McsDemux::new()).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::blocks::VectorSink;
+    use futuresdr::blocks::VectorSource;
+
+    #[test]
+    fn clock_recovery_schema() {
+        let registry = BlockRegistry::new();
+        let schema = registry.schema_for("zigbee::ClockRecoveryMm").unwrap();
+        let names: Vec<&str> = schema.iter().map(|p| p.name).collect();
+        assert_eq!(
+            names,
+            ["omega", "gain_omega", "mu", "gain_mu", "omega_relative_limit"]
+        );
+        assert!(schema.iter().all(|p| p.required && p.param_type == "f64"));
+
+        assert!(registry.schema_for("NoSuchBlock").is_none());
+        let text = registry.describe_block("zigbee::ClockRecoveryMm").unwrap();
+        assert!(text.contains("gain_mu: f64 (required)"), "{text}");
+    }
+
+    #[test]
+    fn combine_ops() {
+        let add = combine_op::<f32>("add").unwrap();
+        assert_eq!(add(&1.5, &2.25), 3.75);
+        assert_eq!(combine_op::<f32>("sub").unwrap()(&1.5, &2.25), -0.75);
+        assert_eq!(combine_op::<i16>("mul").unwrap()(&-3, &4), -12);
+        assert_eq!(combine_op::<f64>("max").unwrap()(&-3.0, &4.0), 4.0);
+        assert_eq!(combine_op::<f64>("min").unwrap()(&-3.0, &4.0), -3.0);
+        assert!(combine_op::<f32>("div").is_none());
+
+        let mul = combine_arith_op::<Complex32>("mul").unwrap();
+        assert_eq!(mul(&Complex32::new(0.0, 1.0), &Complex32::new(0.0, 1.0)), Complex32::new(-1.0, 0.0));
+        assert!(combine_arith_op::<Complex32>("max").is_none());
+    }
+
+    #[test]
+    fn combine_ops_saturate() {
+        assert_eq!(combine_op::<i16>("add").unwrap()(&(i16::MAX - 1), &2), i16::MAX);
+        assert_eq!(combine_op::<i16>("sub").unwrap()(&(i16::MIN + 1), &2), i16::MIN);
+        assert_eq!(combine_op::<i16>("mul").unwrap()(&i16::MAX, &-2), i16::MIN);
+        assert_eq!(combine_op::<i32>("mul").unwrap()(&i32::MAX, &i32::MAX), i32::MAX);
+        assert_eq!(combine_op::<i16>("add").unwrap()(&(i16::MAX - 1), &1), i16::MAX);
+    }
+
+    #[test]
+    fn block_schemas_json() {
+        let registry = BlockRegistry::new();
+        let types = registry.registered_types();
+        assert!(types.windows(2).all(|w| w[0] < w[1]));
+
+        let json = serde_json::to_value(registry.block_schemas()).unwrap();
+        let blocks = json.as_array().unwrap();
+        assert_eq!(blocks.len(), types.len());
+        let block = |t: &str| blocks.iter().find(|b| b["type"] == t).unwrap();
+
+        let source = block("seify::Source");
+        assert_eq!(source["native_only"], true);
+        let frequency = &source["parameters"][0];
+        assert_eq!(frequency["name"], "frequency");
+        assert_eq!(frequency["param_type"], "f64");
+        assert_eq!(frequency["required"], true);
+        assert_eq!(frequency["runtime_port"], "freq");
+
+        let dedup = block("Dedup");
+        assert_eq!(dedup["native_only"], false);
+        assert_eq!(dedup["parameters"][0]["name"], "window");
+        assert_eq!(dedup["parameters"][0]["default"], "64");
+        assert_eq!(block("wifi::McsDemux")["parameters"], serde_json::json!([]));
+
+        let text = registry.describe_block("FileSource").unwrap();
+        assert!(text.starts_with("FileSource (native only)\n"), "{text}");
+    }
+
+    #[test]
+    fn parse_mac_colon() {
+        assert_eq!(
+            parse_mac_addr("42:42:42:42:42:4f").unwrap(),
+            [0x42, 0x42, 0x42, 0x42, 0x42, 0x4f]
+        );
+    }
+
+    #[test]
+    fn parse_mac_dash() {
+        assert_eq!(
+            parse_mac_addr("ff-FF-00-01-2-a").unwrap(),
+            [0xff, 0xff, 0x00, 0x01, 0x02, 0x0a]
+        );
+    }
+
+    #[test]
+    fn parse_mac_malformed() {
+        let err = parse_mac_addr("zz:42:42:42:42:42").unwrap_err();
+        assert!(err.contains("octet 1 ('zz')"), "{err}");
+
+        let err = parse_mac_addr("42:42:42:42:42:100").unwrap_err();
+        assert!(err.contains("octet 6 ('100') is out of range"), "{err}");
+
+        let err = parse_mac_addr("42:42:+4:42:42:42").unwrap_err();
+        assert!(err.contains("octet 3"), "{err}");
+
+        assert!(parse_mac_addr("42:42:42:42:42").is_err());
+        assert!(parse_mac_addr("42:42-42:42-42:42").is_err());
+        assert!(parse_mac_addr("42:42:42::42:42").is_err());
+    }
+
+    fn delay_config(value: &str) -> BlockConfig {
+        toml::from_str(&format!(
+            "name = \"delay\"\ntype = \"Delay\"\n\
+             [[parameters]]\nname = \"delay\"\ntype = \"isize\"\nvalue = {value}\n"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn delay_param() {
+        assert_eq!(DelayFactory::delay(&delay_config("16")).unwrap(), 16);
+        assert_eq!(DelayFactory::delay(&delay_config("0")).unwrap(), 0);
+        assert_eq!(DelayFactory::delay(&delay_config("-48")).unwrap(), -48);
+
+        let err = DelayFactory::delay(&delay_config("1.5")).unwrap_err();
+        assert!(err.to_string().contains("isize"), "{err}");
+        let err = DelayFactory::delay(&delay_config(&i64::MIN.to_string())).unwrap_err();
+        assert!(err.to_string().contains("at least"), "{err}");
+
+        let mut fg = Flowgraph::new();
+        assert!(DelayFactory.create(&mut fg, &delay_config("-48")).is_ok());
+    }
+
+    /// Run `input` through the block the factory creates for `config`
+    fn run_block<T: CpuSample>(
+        factory: &dyn BlockFactory,
+        config: &BlockConfig,
+        input: Vec<T>,
+    ) -> Vec<T> {
+        let mut fg = Flowgraph::new();
+        let n = input.len();
+        let src = fg.add_block(VectorSource::<T>::new(input));
+        let block = factory.create(&mut fg, config).unwrap();
+        let snk = fg.add_block(VectorSink::<T>::new(n));
+        fg.connect_dyn(&src, "output", block, "input").unwrap();
+        fg.connect_dyn(block, "output", &snk, "input").unwrap();
+        let _ = Runtime::new().run(fg).unwrap();
+        let snk = snk.get().unwrap();
+        snk.items().clone()
+    }
+
+    fn fir_config(params: &str) -> BlockConfig {
+        toml::from_str(&format!("name = \"fir\"\ntype = \"Fir\"\n{params}")).unwrap()
+    }
+
+    #[test]
+    fn fir_taps() {
+        let design = "[[parameters]]\nname = \"design\"\ntype = \"table\"\n\
+                      value = { type = \"lowpass\", cutoff = 0.1, num_taps = 63 }\n";
+        assert_eq!(FirFactory::taps(&fir_config(design)).unwrap().len(), 63);
+
+        let taps = "[[parameters]]\nname = \"taps\"\ntype = \"array\"\nvalue = [1, 0.5]\n";
+        assert_eq!(FirFactory::taps(&fir_config(taps)).unwrap(), vec![1.0, 0.5]);
+
+        let mut fg = Flowgraph::new();
+        assert!(FirFactory.create(&mut fg, &fir_config(design)).is_ok());
+        assert!(FirFactory.create(&mut fg, &fir_config(taps)).is_ok());
+        let symmetric = "[[parameters]]\nname = \"symmetric\"\ntype = \"bool\"\nvalue = true\n";
+        let err = FirFactory
+            .create(&mut fg, &fir_config(&format!("{taps}{symmetric}")))
+            .unwrap_err();
+        assert!(err.to_string().contains("odd number of taps"), "{err}");
+
+        let err = FirFactory::taps(&fir_config(&design.replace("0.1", "0.7"))).unwrap_err();
+        assert!(err.to_string().contains("cutoff"), "{err}");
+    }
+
+    #[test]
+    fn fir_impulse_response() {
+        let taps = [0.5, -1.0, 2.0, 0.25];
+        let config = |dtype: &str| {
+            fir_config(&format!(
+                "dtype = \"{dtype}\"\n[[parameters]]\nname = \"taps\"\ntype = \"array\"\n\
+                 value = {taps:?}\n"
+            ))
+        };
+        let mut impulse = vec![0.0f32; 2 * taps.len() - 1];
+        impulse[taps.len() - 1] = 1.0;
+
+        // the filter only produces output once it has a full window of input
+        let output = run_block(&FirFactory, &config("f32"), impulse.clone());
+        assert_eq!(output, taps);
+
+        let impulse = impulse.iter().map(|x| Complex32::new(*x, -*x)).collect();
+        let output = run_block(&FirFactory, &config("Complex32"), impulse);
+        let expected: Vec<_> = taps.iter().map(|t| Complex32::new(*t, -*t)).collect();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn fir_lowpass_3db() {
+        const N: usize = 4000;
+        let config = fir_config(
+            "[[parameters]]\nname = \"design\"\ntype = \"table\"\n\
+             value = { type = \"lowpass\", cutoff = 0.1, num_taps = 101 }\n",
+        );
+        let gain = |freq: f64| {
+            let tone = (0..N)
+                .map(|n| Complex32::from_polar(1.0, (2.0 * std::f64::consts::PI * freq * n as f64) as f32))
+                .collect();
+            let output = run_block(&FirFactory, &config, tone);
+            let power = output.iter().map(|s| s.norm_sqr()).sum::<f32>() / output.len() as f32;
+            power.sqrt() as f64
+        };
+
+        assert!((gain(0.0) - 1.0).abs() < 0.01);
+        assert!((gain(0.05) - 1.0).abs() < 0.01);
+        let cutoff = gain(0.1);
+        assert!((cutoff - std::f64::consts::FRAC_1_SQRT_2).abs() < 0.01, "{cutoff}");
+        assert!(gain(0.15) < 0.01);
+    }
+
+    #[test]
+    fn decimate_taps() {
+        let config = |params: &str| -> BlockConfig {
+            toml::from_str(&format!("name = \"decim\"\ntype = \"Decimate\"\n{params}")).unwrap()
+        };
+        let factor = |f: u32| format!("[[parameters]]\nname = \"factor\"\ntype = \"usize\"\nvalue = {f}\n");
+
+        assert_eq!(DecimateFactory::taps(1), vec![1.0]);
+        // unit DC gain
+        let dc: f32 = DecimateFactory::taps(4).iter().sum();
+        assert!((dc - 1.0).abs() < 0.01, "{dc}");
+
+        let mut fg = Flowgraph::new();
+        assert!(DecimateFactory.create(&mut fg, &config(&factor(4))).is_ok());
+        let taps = "[[parameters]]\nname = \"taps\"\ntype = \"array\"\nvalue = [0.5, 0.5]\n";
+        assert!(DecimateFactory.create(&mut fg, &config(&format!("{}{taps}", factor(2)))).is_ok());
+
+        let err = DecimateFactory.create(&mut fg, &config(&factor(0))).unwrap_err();
+        assert!(err.to_string().contains("must be positive"), "{err}");
+        let empty = taps.replace("[0.5, 0.5]", "[]");
+        let err = DecimateFactory
+            .create(&mut fg, &config(&format!("{}{empty}", factor(2))))
+            .unwrap_err();
+        assert!(err.to_string().contains("must not be empty"), "{err}");
+    }
+
+    #[test]
+    fn decimate_suppresses_aliases() {
+        const N: usize = 40000;
+        let config: BlockConfig = toml::from_str(
+            "name = \"decim\"\ntype = \"Decimate\"\n\
+             [[parameters]]\nname = \"factor\"\ntype = \"usize\"\nvalue = 4\n",
+        )
+        .unwrap();
+        // mean power of the decimated tone, after the filter transient
+        let power = |freq: f32| {
+            let tone = (0..N)
+                .map(|n| Complex32::from_polar(1.0, 2.0 * std::f32::consts::PI * freq * n as f32))
+                .collect();
+            let output = run_block(&DecimateFactory, &config, tone);
+            let transient = DecimateFactory::taps(4).len();
+            assert!(output.len() >= (N - transient) / 4, "{} samples", output.len());
+            let output = &output[transient..];
+            output.iter().map(|s| s.norm_sqr()).sum::<f32>() / output.len() as f32
+        };
+
+        // new Nyquist frequency is 0.125 cycles/sample
+        let passband = power(0.08);
+        assert!((passband - 1.0).abs() < 0.01, "passband power {passband}");
+        // would alias to -0.11 cycles/sample
+        let alias = power(0.14);
+        assert!(alias < 1e-5, "alias power {alias}");
+    }
+
+    #[test]
+    fn arb_resampler_tone() {
+        use std::f32::consts::PI;
+        const N: usize = 8000;
+        let ratio = 1.0417;
+        let freq = 0.05;
+        let config: BlockConfig = toml::from_str(&format!(
+            "name = \"resamp\"\ntype = \"ArbResampler\"\n\
+             [[parameters]]\nname = \"ratio\"\ntype = \"f64\"\nvalue = {ratio}\n"
+        ))
+        .unwrap();
+        let input = (0..N)
+            .map(|k| Complex32::from_polar(1.0, 2.0 * PI * freq * k as f32))
+            .collect();
+
+        let output = run_block(&ArbResamplerFactory, &config, input);
+        // the first samples of the input only fill the filters
+        let len = ArbResamplerFactory::taps(ratio, 32).len().div_ceil(32);
+        let expected = (N - len) as f64 * ratio;
+        assert!(
+            (output.len() as f64 - expected).abs() < 2.0,
+            "{} samples, expected {expected}",
+            output.len()
+        );
+
+        // skip the transient of the filters
+        let settled = &output[2 * len..];
+        let rotation: Complex32 = settled.windows(2).map(|w| w[1] * w[0].conj()).sum();
+        let out_freq = rotation.arg() / (2.0 * PI);
+        assert!((out_freq - freq / ratio as f32).abs() < 1e-4, "{out_freq}");
+        for s in settled {
+            assert!((s.norm() - 1.0).abs() < 0.01, "{s}");
+        }
+    }
+
+    #[test]
+    fn mapper_constellation() {
+        let config = |params: &str| -> BlockConfig {
+            toml::from_str(&format!("name = \"mapper\"\ntype = \"wifi::Mapper\"\n{params}")).unwrap()
+        };
+        let points = "[[parameters]]\nname = \"constellation\"\ntype = \"array\"\n\
+                      value = [[1, 0], [0, 1], [-1, 0], [0, -1]]\n";
+        let bits = |n: u32| format!("[[parameters]]\nname = \"bits_per_symbol\"\ntype = \"usize\"\nvalue = {n}\n");
+
+        let mut fg = Flowgraph::new();
+        assert!(WifiMapperFactory.create(&mut fg, &config("")).is_ok());
+        assert!(WifiMapperFactory.create(&mut fg, &config(points)).is_ok());
+        assert!(WifiMapperFactory.create(&mut fg, &config(&format!("{points}{}", bits(2)))).is_ok());
+
+        let err = WifiMapperFactory
+            .create(&mut fg, &config(&format!("{points}{}", bits(4))))
+            .unwrap_err();
+        assert!(err.to_string().contains("expected 2^4 = 16 points, got 4"), "{err}");
+        let err = WifiMapperFactory.create(&mut fg, &config(&bits(2))).unwrap_err();
+        assert!(matches!(err, LoaderError::MissingParameter { .. }), "{err}");
+    }
+    #[test]
+    fn int_literals() {
+        assert_eq!(parse_int_literal("12"), Ok(12));
+        assert_eq!(parse_int_literal("-12"), Ok(-12));
+        assert_eq!(parse_int_literal("0x0C"), Ok(12));
+        assert_eq!(parse_int_literal("0XfF"), Ok(255));
+        assert_eq!(parse_int_literal("0b1100"), Ok(12));
+        assert_eq!(parse_int_literal("0b1010_0111"), Ok(0xa7));
+        for malformed in ["", "0x", "0xZZ", "0b102", "0x-1", "12a", "twelve"] {
+            let err = parse_int_literal(malformed).unwrap_err();
+            assert!(err.contains("malformed integer literal"), "{err}");
+        }
+
+        let config = |value: &str| -> BlockConfig {
+            toml::from_str(&format!(
+                "name = \"decoder\"\ntype = \"zigbee::Decoder\"\n\
+                 [[parameters]]\nname = \"threshold\"\ntype = \"u32\"\nvalue = {value}\n"
+            ))
+            .unwrap()
+        };
+        for value in ["12", "\"12\"", "\"0x0C\"", "\"0b1100\""] {
+            assert_eq!(get_param_u32(&config(value), "threshold").unwrap(), 12, "{value}");
+        }
+        let err = get_param_u32(&config("\"0xZZ\""), "threshold").unwrap_err();
+        assert!(matches!(err, LoaderError::InvalidParameter { .. }), "{err}");
+        let err = get_param_u32(&config("\"-0x1\""), "threshold").unwrap_err();
+        assert!(matches!(err, LoaderError::InvalidParameter { .. }), "{err}");
+        let err = get_param_u32(&config("\"0x1_0000_0000\""), "threshold").unwrap_err();
+        assert!(matches!(err, LoaderError::TypeMismatch { .. }), "{err}");
+        let err = get_param_u32(&config("\"twelve\""), "threshold").unwrap_err();
+        assert!(matches!(err, LoaderError::TypeMismatch { .. }), "{err}");
+        let err = get_param_u32(&config("1.5"), "threshold").unwrap_err();
+        assert!(matches!(err, LoaderError::TypeMismatch { .. }), "{err}");
+
+        let mut fg = Flowgraph::new();
+        assert!(DecoderFactory.create(&mut fg, &config("\"0x0C\"")).is_ok());
+    }
 }
@@ -0,0 +1,128 @@
+//! Calls with Timeout and Retry
+//!
+//! The GUI calls handlers of the backend's flowgraph over HTTP. While the backend switches
+//! flowgraphs, a call may not be answered at all, so the GUI gives up on it after a timeout and
+//! tries once more before reporting the failure.
+
+use futuresdr::futures::future::Either;
+use futuresdr::futures::future::select;
+use std::fmt;
+use std::future::Future;
+use std::pin::pin;
+
+/// Status shown while a call is retried
+pub const RETRY_STATUS: &str = "timed out, retrying";
+
+/// Why a call did not succeed
+#[derive(Debug, Clone, PartialEq)]
+pub enum CallError<E> {
+    /// Neither the call nor its retry completed in time
+    Timeout,
+    /// The call completed with an error
+    Failed(E),
+}
+
+impl<E: fmt::Display> fmt::Display for CallError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CallError::Timeout => write!(f, "timed out"),
+            CallError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Run `call`, retrying it once if it does not complete before `timeout()` resolves
+///
+/// `on_retry` is run before the retry, e.g., to tell the user. Errors of a call that completed
+/// are returned without retrying, since the backend did answer.
+pub async fn retry_on_timeout<T, E, C, CF, S, SF>(
+    mut call: C,
+    mut timeout: S,
+    on_retry: impl FnOnce(),
+) -> Result<T, CallError<E>>
+where
+    C: FnMut() -> CF,
+    CF: Future<Output = Result<T, E>>,
+    S: FnMut() -> SF,
+    SF: Future<Output = ()>,
+{
+    let mut on_retry = Some(on_retry);
+    loop {
+        match select(pin!(call()), pin!(timeout())).await {
+            Either::Left((res, _)) => return res.map_err(CallError::Failed),
+            Either::Right(_) => match on_retry.take() {
+                Some(f) => f(),
+                None => return Err(CallError::Timeout),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::async_io::Timer;
+    use futuresdr::async_io::block_on;
+    use futuresdr::futures::future::pending;
+    use std::cell::Cell;
+    use std::time::Duration;
+
+    fn timeout() -> impl Future<Output = ()> {
+        async {
+            Timer::after(Duration::from_millis(10)).await;
+        }
+    }
+
+    #[test]
+    fn never_resolves() {
+        let calls = Cell::new(0);
+        let retries = Cell::new(0);
+        let res: Result<(), CallError<String>> = block_on(retry_on_timeout(
+            || {
+                calls.set(calls.get() + 1);
+                pending()
+            },
+            timeout,
+            || retries.set(retries.get() + 1),
+        ));
+        assert_eq!(res, Err(CallError::Timeout));
+        assert_eq!((calls.get(), retries.get()), (2, 1));
+        assert_eq!(res.unwrap_err().to_string(), "timed out");
+    }
+
+    #[test]
+    fn retry_succeeds() {
+        let calls = Cell::new(0);
+        let res: Result<u32, CallError<String>> = block_on(retry_on_timeout(
+            || {
+                calls.set(calls.get() + 1);
+                let first = calls.get() == 1;
+                async move {
+                    if first {
+                        pending::<()>().await;
+                    }
+                    Ok(7)
+                }
+            },
+            timeout,
+            || {},
+        ));
+        assert_eq!(res, Ok(7));
+        assert_eq!(calls.get(), 2);
+    }
+
+    #[test]
+    fn error_is_not_retried() {
+        let calls = Cell::new(0);
+        let res: Result<(), _> = block_on(retry_on_timeout(
+            || {
+                calls.set(calls.get() + 1);
+                async { Err("refused".to_string()) }
+            },
+            timeout,
+            || panic!("retried"),
+        ));
+        assert_eq!(res, Err(CallError::Failed("refused".to_string())));
+        assert_eq!(calls.get(), 1);
+    }
+}
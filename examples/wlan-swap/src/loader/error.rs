@@ -0,0 +1,146 @@
+//! Loader Errors
+//!
+//! Structured errors returned by the TOML loader and the block registry, so callers
+//! can tell a typo in a block type apart from a missing parameter or a bad connection.
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Errors produced while reading a flowgraph configuration and building it
+#[derive(Debug, Error)]
+pub enum LoaderError {
+    /// No factory is registered for the requested block type
+    #[error("No factory registered for block type: {0}")]
+    UnknownBlockType(String),
+    /// A required block parameter is not present
+    #[error("Block '{block}': missing parameter '{name}'")]
+    MissingParameter { block: String, name: String },
+    /// A parameter override targets a parameter the block does not have
+    #[error("Block '{block}' does not have parameter '{name}'")]
+    UnknownParameter { block: String, name: String },
+    /// A parameter is present but has the wrong TOML type
+    #[error("Block '{block}': parameter '{name}' must be of type {expected}")]
+    TypeMismatch {
+        block: String,
+        name: String,
+        expected: String,
+    },
+    /// A parameter has the right type but an unusable value
+    #[error("Block '{block}': invalid parameter '{name}': {reason}")]
+    InvalidParameter {
+        block: String,
+        name: String,
+        reason: String,
+    },
+    /// A block extends a template that is not declared
+    #[error("Block '{block}': unknown template '{template}'")]
+    UnknownTemplate { block: String, template: String },
+    /// A block has neither a type nor a template
+    #[error("Block '{0}' has neither a type nor a template")]
+    MissingBlockType(String),
+    /// Two blocks share the same name
+    #[error("Block '{0}' is declared more than once")]
+    DuplicateBlock(String),
+    /// Two `[[ports]]` entries share the same name
+    #[error("Port '{0}' is declared more than once")]
+    DuplicatePort(String),
+    /// A connection references a block name that is not declared
+    #[error("Block '{0}' not found")]
+    UnknownBlock(String),
+    /// A connection references a port the block does not have
+    #[error("Block '{block}' does not have port '{port}'")]
+    UnknownPort { block: String, port: String },
+    /// A message connection targets an input the block does not declare
+    #[error(
+        "Block '{block}' does not have message input '{port}' (available: {})",
+        .valid.join(", ")
+    )]
+    UnknownMessageInput {
+        block: String,
+        port: String,
+        valid: Vec<String>,
+    },
+    /// A message connection starts at an output the block does not declare
+    #[error(
+        "Block '{block}' does not have message output '{port}' (available: {})",
+        .valid.join(", ")
+    )]
+    UnknownMessageOutput {
+        block: String,
+        port: String,
+        valid: Vec<String>,
+    },
+    /// The TOML document could not be parsed
+    #[error("Failed to parse TOML configuration: {0}")]
+    ParseError(#[from] toml::de::Error),
+    /// The TOML file could not be read
+    #[error("Failed to read TOML file {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// A connection option has an unusable value
+    #[error("Connection '{from}' -> '{to}': {reason}")]
+    InvalidConnection {
+        from: String,
+        to: String,
+        reason: String,
+    },
+    /// The connection was rejected by the flowgraph for another reason (e.g., an input that is
+    /// already connected). `index` is the position in `[[connections]]` or
+    /// `[[message_connections]]`.
+    #[error(
+        "Connection {index}: failed to connect '{from}'.{from_port} -> '{to}'.{to_port}: {source}"
+    )]
+    ConnectionFailed {
+        index: usize,
+        from: String,
+        from_port: String,
+        to: String,
+        to_port: String,
+        source: Box<futuresdr::runtime::Error>,
+    },
+    /// Two stream connections feed the same input
+    #[error("Connection {index}: input '{block}'.{port} is already used by connection {first}")]
+    DuplicateInput {
+        index: usize,
+        first: usize,
+        block: String,
+        port: String,
+    },
+    /// Several wiring errors, collected by a non-strict loader
+    #[error(
+        "{} connection errors:\n{}",
+        .0.len(),
+        .0.iter().map(|e| format!("  {e}")).collect::<Vec<_>>().join("\n")
+    )]
+    Connections(Vec<LoaderError>),
+    /// The block could not be constructed (e.g., SDR hardware not available)
+    #[error(transparent)]
+    Runtime(#[from] futuresdr::runtime::Error),
+}
+
+impl LoaderError {
+    pub(crate) fn missing(block: &str, name: &str) -> Self {
+        LoaderError::MissingParameter {
+            block: block.to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    pub(crate) fn mismatch(block: &str, name: &str, expected: &str) -> Self {
+        LoaderError::TypeMismatch {
+            block: block.to_string(),
+            name: name.to_string(),
+            expected: expected.to_string(),
+        }
+    }
+
+    pub(crate) fn invalid(block: &str, name: &str, reason: impl Into<String>) -> Self {
+        LoaderError::InvalidParameter {
+            block: block.to_string(),
+            name: name.to_string(),
+            reason: reason.into(),
+        }
+    }
+}
@@ -4,39 +4,288 @@
 //! Also acts as a proxy for MAC tx/rx messages.
 
 use futuresdr::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::sync::{Mutex, OnceLock};
 use std::sync::mpsc;
+use std::time::Duration;
+use web_time::Instant;
+
+/// Interval in which queued messages are offered again to receivers that were busy
+const RETRY_INTERVAL: Duration = Duration::from_millis(10);
 
 /// Global reload channel for flowgraph switching
 static RELOAD_CHANNEL: OnceLock<Mutex<mpsc::Sender<String>>> = OnceLock::new();
 
+/// Sentinel sent through the reload channel to stop the current flowgraph without loading a new one
+pub const STOP_SENTINEL: &str = "__stop__";
+
+/// Instance label used for control messages that do not name one
+pub const DEFAULT_LABEL: &str = "default";
+
 /// Global gain channel for gain control (used by PER flowgraph)
 static GAIN_CHANNEL: OnceLock<Mutex<mpsc::Sender<f64>>> = OnceLock::new();
 
+/// Runtime status reported on the "health" port
+struct RuntimeStatus {
+    flowgraphs: usize,
+    last_reload: Option<String>,
+}
+
+/// Start of the runtime, for the uptime in the health report
+static START: OnceLock<Instant> = OnceLock::new();
+
+/// Global runtime status, updated by the process that switches flowgraphs
+static STATUS: Mutex<RuntimeStatus> = Mutex::new(RuntimeStatus {
+    flowgraphs: 0,
+    last_reload: None,
+});
+
 /// Set the reload channel (called once at startup)
 pub fn set_reload_channel(tx: mpsc::Sender<String>) {
+    START.get_or_init(Instant::now);
     RELOAD_CHANNEL.set(Mutex::new(tx)).ok();
 }
 
+/// Update the runtime status with the number of active flowgraphs and the last loaded file
+pub fn set_runtime_status(flowgraphs: usize, last_reload: Option<&str>) {
+    START.get_or_init(Instant::now);
+    let mut status = STATUS.lock().unwrap_or_else(|e| e.into_inner());
+    status.flowgraphs = flowgraphs;
+    if let Some(path) = last_reload {
+        status.last_reload = Some(path.to_string());
+    }
+}
+
+/// Health report: `uptime_secs` (Pmt::F64), `flowgraphs` (Pmt::Usize), and `last_reload`
+/// (Pmt::String, or Pmt::Null if no flowgraph was loaded yet)
+pub fn health() -> Pmt {
+    let uptime = START.get_or_init(Instant::now).elapsed().as_secs_f64();
+    let status = STATUS.lock().unwrap_or_else(|e| e.into_inner());
+    Pmt::MapStrPmt(HashMap::from([
+        ("uptime_secs".to_string(), Pmt::F64(uptime)),
+        ("flowgraphs".to_string(), Pmt::Usize(status.flowgraphs)),
+        (
+            "last_reload".to_string(),
+            status.last_reload.clone().map(Pmt::String).unwrap_or(Pmt::Null),
+        ),
+    ]))
+}
+
+/// JSON command received on the "control" port, e.g., `{"cmd": "switch", "file": "..."}`
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(tag = "cmd", rename_all = "lowercase")]
+enum Command {
+    /// Load `file`, replacing the instance named by `label` or the current flowgraph
+    Switch {
+        file: String,
+        #[serde(default)]
+        label: Option<String>,
+    },
+    /// Stop the instance named by `label` or the current flowgraph
+    Stop {
+        #[serde(default)]
+        label: Option<String>,
+    },
+    /// Report the runtime status
+    Status,
+}
+
+impl Command {
+    /// Parse a control string, `None` if it is not a JSON object (i.e., a legacy command)
+    fn parse(s: &str) -> Option<std::result::Result<Self, String>> {
+        let value = serde_json::from_str::<serde_json::Value>(s).ok()?;
+        value
+            .is_object()
+            .then(|| Self::deserialize(value).map_err(|e| e.to_string()))
+    }
+}
+
+/// JSON result of a command: `{"ok": true, ...}` or `{"ok": false, "error": ...}`
+fn json_result(result: std::result::Result<serde_json::Value, String>) -> Pmt {
+    let value = match result {
+        Ok(serde_json::Value::Object(mut fields)) => {
+            fields.insert("ok".to_string(), true.into());
+            serde_json::Value::Object(fields)
+        }
+        Ok(_) => serde_json::json!({ "ok": true }),
+        Err(e) => serde_json::json!({ "ok": false, "error": e }),
+    };
+    Pmt::String(value.to_string())
+}
+
+/// Runtime status of [`health`] as JSON fields
+fn status_json() -> serde_json::Value {
+    let uptime = START.get_or_init(Instant::now).elapsed().as_secs_f64();
+    let status = STATUS.lock().unwrap_or_else(|e| e.into_inner());
+    serde_json::json!({
+        "uptime_secs": uptime,
+        "flowgraphs": status.flowgraphs,
+        "last_reload": status.last_reload,
+    })
+}
+
 /// Set the gain channel (called once at startup)
 pub fn set_gain_channel(tx: mpsc::Sender<f64>) {
     GAIN_CHANNEL.set(Mutex::new(tx)).ok();
 }
 
 /// Block that receives PMT commands to switch flowgraphs and proxies MAC messages
-/// - Port "control": Receives Pmt::String messages with flowgraph paths
-/// - Port "tx": Forwards messages to MAC block (for transmission)
-/// - Port "rx": Receives messages from MAC block (for reception)
+/// - Port "control": Receives Pmt::String messages with flowgraph paths or
+///   `"load:label:/path"` / `"stop:label"` commands for a labelled instance. A JSON object
+///   is a command instead, answered with a JSON result as Pmt::String (`{"ok": true, ...}` or
+///   `{"ok": false, "error": ...}`):
+///   - `{"cmd": "switch", "file": "/path"}` loads a flowgraph, optionally with a `"label"`
+///   - `{"cmd": "stop"}` stops the current flowgraph, optionally with a `"label"`
+///   - `{"cmd": "status"}` returns the fields of the health report (see [`health`])
+/// - Port "stop": Stops the current flowgraph (or the instance named by a Pmt::String label)
+///   without loading a new one. `Pmt::Finished`, sent by a connected block that terminates
+///   (e.g., a `Watchdog` when the flowgraph is stopped), is ignored
+/// - Port "tx": Forwards messages to MAC block (for transmission) unchanged, so `Pmt::Blob`
+///   payloads need not be valid UTF-8 (see [`parse_tx_payload`](super::parse_tx_payload))
+/// - Port "rx": Receives messages from MAC block (for reception). When the MAC finishes, the
+///   `finished` signal (see [`RxMessage::Finished`](super::RxMessage::Finished)) is sent to the
+///   GUI, while the controller keeps running, so that the GUI can still switch flowgraphs
+/// - Port "describe": Returns the block parameter description
+///   (see [`BlockInfo`](super::block_info::BlockInfo)) when called with Pmt::Null; the loader
+///   sets it by sending the encoded description
+/// - Port "health": Returns the runtime status (see [`health`])
+/// - Port "metrics": Returns the number of messages handled so far as `Pmt::MapStrPmt` with
+///   `tx_forwarded`, `rx_forwarded`, and `forward_errors` (`Pmt::U64`). Echoed loopback
+///   messages count as RX. Queued messages count once they were delivered to all receivers;
+///   messages dropped from a full queue count as errors.
 /// - Port "tx_out": Forwards TX messages to MAC
 /// - Port "rx_out": Forwards RX messages to WebSocket sink
+/// - Port "stats": Reports the number of dropped messages (see below)
+///
+/// In loopback mode, TX messages are not forwarded to the MAC but echoed back on "rx_out",
+/// which allows testing the GUI without a radio or decoder.
+///
+/// By default, the handlers await the forwarded message, so a slow receiver holds up the
+/// controller and everything that sends to it. With [`with_queue`](Self::with_queue), messages
+/// are queued per output instead, and if a queue is full, its oldest message is dropped. Queued
+/// messages are offered to the receivers without waiting; a receiver that is busy gets the
+/// message on the next attempt, after 10 ms or once the controller handled new messages, so
+/// that with several receivers each gets each message once. The number of dropped messages so far is posted as
+/// `Pmt::MapStrPmt` with a `dropped` field (`Pmt::U64`) on "stats" whenever it changes.
 #[derive(Block)]
-#[message_inputs(control, tx, rx)]
-#[message_outputs(tx_out, rx_out)]
-pub struct FlowgraphController {}
+#[message_inputs(control, stop, tx, rx, describe, health, metrics)]
+#[message_outputs(tx_out, rx_out, stats)]
+pub struct FlowgraphController {
+    loopback: bool,
+    description: Pmt,
+    /// Capacity of the forwarding queues, `None` to await each message
+    capacity: Option<usize>,
+    tx_queue: VecDeque<Pmt>,
+    rx_queue: VecDeque<Pmt>,
+    /// Number of receivers that already have the first queued message of each output
+    tx_delivered: usize,
+    rx_delivered: usize,
+    dropped: u64,
+    reported: u64,
+    tx_forwarded: u64,
+    rx_forwarded: u64,
+    forward_errors: u64,
+}
 
 impl FlowgraphController {
     pub fn new() -> Self {
-        FlowgraphController {}
+        FlowgraphController {
+            loopback: false,
+            description: Pmt::Null,
+            capacity: None,
+            tx_queue: VecDeque::new(),
+            rx_queue: VecDeque::new(),
+            tx_delivered: 0,
+            rx_delivered: 0,
+            dropped: 0,
+            reported: 0,
+            tx_forwarded: 0,
+            rx_forwarded: 0,
+            forward_errors: 0,
+        }
+    }
+
+    /// Create a controller that echoes TX messages back to "rx_out"
+    pub fn new_loopback() -> Self {
+        FlowgraphController {
+            loopback: true,
+            ..Self::new()
+        }
+    }
+
+    /// Queue up to `capacity` messages per output instead of awaiting slow receivers
+    pub fn with_queue(mut self, capacity: usize) -> Self {
+        assert!(capacity >= 1);
+        self.capacity = Some(capacity);
+        self.tx_queue = VecDeque::with_capacity(capacity);
+        self.rx_queue = VecDeque::with_capacity(capacity);
+        self
+    }
+
+    /// Number of queued messages
+    pub fn queued(&self) -> usize {
+        self.tx_queue.len() + self.rx_queue.len()
+    }
+
+    /// Number of messages dropped because a forwarding queue was full
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Forwarding queue of "tx_out" or "rx_out"
+    fn queue(&mut self, port: &str) -> &mut VecDeque<Pmt> {
+        if port == "tx_out" {
+            &mut self.tx_queue
+        } else {
+            &mut self.rx_queue
+        }
+    }
+
+    /// Receivers of "tx_out" or "rx_out" that already have the first queued message
+    fn delivered(&mut self, port: &str) -> &mut usize {
+        if port == "tx_out" {
+            &mut self.tx_delivered
+        } else {
+            &mut self.rx_delivered
+        }
+    }
+
+    /// Counter of the messages forwarded on "tx_out" or "rx_out"
+    fn forwarded(&mut self, port: &str) -> &mut u64 {
+        if port == "tx_out" {
+            &mut self.tx_forwarded
+        } else {
+            &mut self.rx_forwarded
+        }
+    }
+
+    /// Post a message on "tx_out" or "rx_out", or queue it if queueing is enabled
+    async fn forward(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        port: &str,
+        p: Pmt,
+    ) -> Result<()> {
+        let Some(capacity) = self.capacity else {
+            if let Err(e) = mio.post(port, p).await {
+                self.forward_errors += 1;
+                return Err(e.into());
+            }
+            *self.forwarded(port) += 1;
+            return Ok(());
+        };
+        if self.queue(port).len() == capacity {
+            self.queue(port).pop_front();
+            *self.delivered(port) = 0;
+            self.dropped += 1;
+            self.forward_errors += 1;
+        }
+        self.queue(port).push_back(p);
+        io.call_again = true;
+        Ok(())
     }
 
     async fn control(
@@ -47,31 +296,13 @@ impl FlowgraphController {
         p: Pmt,
     ) -> Result<Pmt> {
         match p {
-            Pmt::String(path) => {
-                info!("FlowgraphController: Received reload request for {}", path);
-                
-                // Send reload signal through global channel
-                if let Some(tx_mutex) = RELOAD_CHANNEL.get() {
-                    if let Ok(tx) = tx_mutex.lock() {
-                        match tx.send(path.clone()) {
-                            Ok(_) => {
-                                info!("FlowgraphController: Reload signal sent successfully");
-                                Ok(Pmt::Ok)
-                            }
-                            Err(e) => {
-                                error!("FlowgraphController: Failed to send reload signal: {}", e);
-                                Ok(Pmt::String(format!("Error: {}", e)))
-                            }
-                        }
-                    } else {
-                        error!("FlowgraphController: Failed to lock reload channel");
-                        Ok(Pmt::String("Error: Channel lock failed".to_string()))
-                    }
-                } else {
-                    warn!("FlowgraphController: No reload channel configured");
-                    Ok(Pmt::String("Error: No reload channel".to_string()))
+            Pmt::String(s) => match Command::parse(&s) {
+                Some(command) => Ok(json_result(command.and_then(Self::run_command))),
+                None => {
+                    info!("FlowgraphController: Received reload request for {}", s);
+                    Ok(Self::send_reload(s))
                 }
-            }
+            },
             _ => {
                 warn!("FlowgraphController: Expected Pmt::String, got {:?}", p);
                 Ok(Pmt::String("Error: Expected Pmt::String".to_string()))
@@ -79,16 +310,129 @@ impl FlowgraphController {
         }
     }
 
-    async fn tx(
+    async fn stop(
         &mut self,
         _io: &mut WorkIo,
-        mio: &mut MessageOutputs,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::String(label) => {
+                info!("FlowgraphController: Received stop request for {}", label);
+                Ok(Self::send_reload(format!("stop:{}", label)))
+            }
+            Pmt::Finished => Ok(Pmt::Ok),
+            _ => {
+                info!("FlowgraphController: Received stop request");
+                Ok(Self::send_reload(STOP_SENTINEL.to_string()))
+            }
+        }
+    }
+
+    /// Execute a JSON command, returning the fields of its result
+    fn run_command(command: Command) -> std::result::Result<serde_json::Value, String> {
+        info!("FlowgraphController: Received command {:?}", command);
+        let msg = match command {
+            Command::Switch { file, label: None } => file,
+            Command::Switch {
+                file,
+                label: Some(label),
+            } => format!("load:{}:{}", label, file),
+            Command::Stop { label: None } => STOP_SENTINEL.to_string(),
+            Command::Stop { label: Some(label) } => format!("stop:{}", label),
+            Command::Status => return Ok(status_json()),
+        };
+        match Self::send_reload(msg) {
+            Pmt::Ok => Ok(serde_json::Value::Null),
+            Pmt::String(e) => Err(e),
+            p => Err(format!("{:?}", p)),
+        }
+    }
+
+    /// Send a message through the global reload channel
+    fn send_reload(msg: String) -> Pmt {
+        if let Some(tx_mutex) = RELOAD_CHANNEL.get() {
+            if let Ok(tx) = tx_mutex.lock() {
+                match tx.send(msg) {
+                    Ok(_) => {
+                        info!("FlowgraphController: Reload signal sent successfully");
+                        Pmt::Ok
+                    }
+                    Err(e) => {
+                        error!("FlowgraphController: Failed to send reload signal: {}", e);
+                        Pmt::String(format!("Error: {}", e))
+                    }
+                }
+            } else {
+                error!("FlowgraphController: Failed to lock reload channel");
+                Pmt::String("Error: Channel lock failed".to_string())
+            }
+        } else {
+            warn!("FlowgraphController: No reload channel configured");
+            Pmt::String("Error: No reload channel".to_string())
+        }
+    }
+
+    async fn describe(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        match p {
+            Pmt::Null => Ok(self.description.clone()),
+            Pmt::VecPmt(_) => {
+                self.description = p;
+                Ok(Pmt::Ok)
+            }
+            _ => {
+                warn!("FlowgraphController: Expected Pmt::Null or Pmt::VecPmt, got {:?}", p);
+                Ok(Pmt::InvalidValue)
+            }
+        }
+    }
+
+    async fn health(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(health())
+    }
+
+    async fn metrics(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
         _meta: &mut BlockMeta,
+        _p: Pmt,
+    ) -> Result<Pmt> {
+        Ok(Pmt::MapStrPmt(HashMap::from([
+            ("tx_forwarded".to_string(), Pmt::U64(self.tx_forwarded)),
+            ("rx_forwarded".to_string(), Pmt::U64(self.rx_forwarded)),
+            ("forward_errors".to_string(), Pmt::U64(self.forward_errors)),
+        ])))
+    }
+
+    async fn tx(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        meta: &mut BlockMeta,
         p: Pmt,
     ) -> Result<Pmt> {
+        if self.loopback {
+            info!("FlowgraphController: Echoing TX message (loopback): {:?}", p);
+            return self.rx(io, mio, meta, p).await;
+        }
+
         // Forward TX message to MAC block
         info!("FlowgraphController: Received TX message: {:?}", p);
-        match mio.post("tx_out", p.clone()).await {
+        match self.forward(io, mio, "tx_out", p).await {
             Ok(_) => {
                 info!("FlowgraphController: TX message forwarded to MAC successfully");
                 Ok(Pmt::Ok)
@@ -102,7 +446,7 @@ impl FlowgraphController {
 
     async fn rx(
         &mut self,
-        _io: &mut WorkIo,
+        io: &mut WorkIo,
         mio: &mut MessageOutputs,
         _meta: &mut BlockMeta,
         p: Pmt,
@@ -122,12 +466,18 @@ impl FlowgraphController {
             
             // Also forward to rx_out for UI display
             let display_msg = Pmt::String(format!("gain:{}", gain));
-            let _ = mio.post("rx_out", display_msg).await;
+            if let Err(e) = self.forward(io, mio, "rx_out", display_msg).await {
+                error!("FlowgraphController: Failed to forward gain message: {:?}", e);
+            }
             return Ok(Pmt::Ok);
         }
         
         // Convert Blob to String for GUI display
         let display_msg = match p {
+            Pmt::Finished => {
+                info!("FlowgraphController: RX source finished");
+                Pmt::String("finished".to_string())
+            }
             Pmt::Blob(bytes) => {
                 match String::from_utf8(bytes.clone()) {
                     Ok(s) => {
@@ -163,7 +513,7 @@ impl FlowgraphController {
 
         // Forward the converted message to rx_out (WebSocketPmtSink)
         info!("FlowgraphController: Forwarding RX message to WebSocket");
-        match mio.post("rx_out", display_msg).await {
+        match self.forward(io, mio, "rx_out", display_msg).await {
             Ok(_) => {
                 info!("FlowgraphController: RX message forwarded to WebSocket successfully");
                 Ok(Pmt::Ok)
@@ -176,4 +526,373 @@ impl FlowgraphController {
     }
 }
 
-impl Kernel for FlowgraphController {}
+/// Wait for [`RETRY_INTERVAL`]
+async fn retry_interval() {
+    #[cfg(target_arch = "wasm32")]
+    gloo_timers::future::sleep(RETRY_INTERVAL).await;
+    #[cfg(not(target_arch = "wasm32"))]
+    futuresdr::async_io::Timer::after(RETRY_INTERVAL).await;
+}
+
+impl Kernel for FlowgraphController {
+    async fn work(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _b: &mut BlockMeta,
+    ) -> Result<()> {
+        let mut busy = false;
+        for port in ["tx_out", "rx_out"] {
+            while let Some(p) = self.queue(port).pop_front() {
+                let mut delivered = std::mem::take(self.delivered(port));
+                match mio.try_post(port, &p, &mut delivered) {
+                    Ok(true) => *self.forwarded(port) += 1,
+                    Ok(false) => {
+                        // a receiver is busy, offer the message to the remaining ones later
+                        self.queue(port).push_front(p);
+                        *self.delivered(port) = delivered;
+                        busy = true;
+                        break;
+                    }
+                    Err(e) => {
+                        error!(
+                            "FlowgraphController: Failed to forward queued message: {:?}",
+                            e
+                        );
+                        self.forward_errors += 1;
+                    }
+                }
+            }
+        }
+        if busy {
+            io.block_on(retry_interval());
+        }
+
+        if self.dropped != self.reported {
+            self.reported = self.dropped;
+            let stats = HashMap::from([("dropped".to_string(), Pmt::U64(self.dropped))]);
+            mio.post("stats", Pmt::MapStrPmt(stats)).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::BlockMessage;
+    use futuresdr::runtime::WrappedKernel;
+    use futuresdr::runtime::mocker::Mocker;
+
+    /// Receiving end of the reload channel, held by one test at a time
+    fn reload_rx() -> std::sync::MutexGuard<'static, mpsc::Receiver<String>> {
+        static RX: OnceLock<Mutex<mpsc::Receiver<String>>> = OnceLock::new();
+        let rx = RX
+            .get_or_init(|| {
+                let (tx, rx) = mpsc::channel::<String>();
+                set_reload_channel(tx);
+                Mutex::new(rx)
+            })
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        while rx.try_recv().is_ok() {}
+        rx
+    }
+
+    /// Send a control string and parse the JSON result
+    fn command(mocker: &mut Mocker<FlowgraphController>, cmd: &str) -> serde_json::Value {
+        match mocker.post("control", Pmt::String(cmd.to_string())).unwrap() {
+            Pmt::String(s) => serde_json::from_str(&s).unwrap(),
+            p => panic!("expected a JSON result, got {p:?}"),
+        }
+    }
+
+    #[test]
+    fn stop_sends_sentinel() {
+        let rx = reload_rx();
+
+        let mut mocker = Mocker::new(FlowgraphController::new());
+        let ret = mocker.post("stop", Pmt::Null).unwrap();
+
+        assert!(matches!(ret, Pmt::Ok));
+        assert_eq!(rx.try_recv().unwrap(), STOP_SENTINEL);
+
+        mocker.post("stop", Pmt::String("zigbee".to_string())).unwrap();
+        assert_eq!(rx.try_recv().unwrap(), "stop:zigbee");
+
+        // an upstream block terminating is not a stop request
+        assert!(matches!(mocker.post("stop", Pmt::Finished).unwrap(), Pmt::Ok));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn json_switch() {
+        let rx = reload_rx();
+        let mut mocker = Mocker::new(FlowgraphController::new());
+
+        let ret = command(&mut mocker, r#"{"cmd": "switch", "file": "flowgraphs/wifi_rx.toml"}"#);
+        assert_eq!(ret, serde_json::json!({ "ok": true }));
+        assert_eq!(rx.try_recv().unwrap(), "flowgraphs/wifi_rx.toml");
+
+        let ret = command(
+            &mut mocker,
+            r#"{"cmd": "switch", "file": "flowgraphs/zigbee_trx.toml", "label": "zigbee"}"#,
+        );
+        assert_eq!(ret["ok"], true);
+        assert_eq!(rx.try_recv().unwrap(), "load:zigbee:flowgraphs/zigbee_trx.toml");
+
+        // a switch without a file is rejected and nothing is reloaded
+        let ret = command(&mut mocker, r#"{"cmd": "switch"}"#);
+        assert_eq!(ret["ok"], false);
+        assert!(ret["error"].as_str().unwrap().contains("file"), "{ret}");
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn json_stop() {
+        let rx = reload_rx();
+        let mut mocker = Mocker::new(FlowgraphController::new());
+
+        assert_eq!(command(&mut mocker, r#"{"cmd": "stop"}"#)["ok"], true);
+        assert_eq!(rx.try_recv().unwrap(), STOP_SENTINEL);
+        assert_eq!(command(&mut mocker, r#"{"cmd": "stop", "label": "wifi"}"#)["ok"], true);
+        assert_eq!(rx.try_recv().unwrap(), "stop:wifi");
+
+        let ret = command(&mut mocker, r#"{"cmd": "restart"}"#);
+        assert_eq!(ret["ok"], false);
+        assert!(ret["error"].as_str().unwrap().contains("restart"), "{ret}");
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn json_status() {
+        let mut mocker = Mocker::new(FlowgraphController::new());
+        let ret = command(&mut mocker, r#"{"cmd": "status"}"#);
+        assert_eq!(ret["ok"], true);
+        assert!(ret["uptime_secs"].as_f64().unwrap() >= 0.0);
+        assert!(ret["flowgraphs"].is_u64());
+        assert!(ret["last_reload"].is_string() || ret["last_reload"].is_null());
+    }
+
+    #[test]
+    fn legacy_path() {
+        let rx = reload_rx();
+        let mut mocker = Mocker::new(FlowgraphController::new());
+
+        let ret = mocker
+            .post("control", Pmt::String("flowgraphs/wifi_rx.toml".to_string()))
+            .unwrap();
+        assert_eq!(ret, Pmt::Ok);
+        assert_eq!(rx.try_recv().unwrap(), "flowgraphs/wifi_rx.toml");
+
+        // JSON that is not an object is not a command either
+        let ret = mocker.post("control", Pmt::String("\"x.toml\"".to_string())).unwrap();
+        assert_eq!(ret, Pmt::Ok);
+        assert_eq!(rx.try_recv().unwrap(), "\"x.toml\"");
+        mocker
+            .post("control", Pmt::String("load:zigbee:flowgraphs/zigbee_trx.toml".to_string()))
+            .unwrap();
+        assert_eq!(rx.try_recv().unwrap(), "load:zigbee:flowgraphs/zigbee_trx.toml");
+    }
+
+    #[test]
+    fn stores_description() {
+        let mut mocker = Mocker::new(FlowgraphController::new());
+        assert!(matches!(mocker.post("describe", Pmt::Null).unwrap(), Pmt::Null));
+
+        let desc = Pmt::VecPmt(vec![Pmt::String("block".to_string())]);
+        mocker.post("describe", desc.clone()).unwrap();
+        assert_eq!(mocker.post("describe", Pmt::Null).unwrap(), desc);
+    }
+
+    #[test]
+    fn reports_health() {
+        set_runtime_status(2, Some("flowgraphs/wifi_rx.toml"));
+        set_runtime_status(1, None);
+
+        let mut mocker = Mocker::new(FlowgraphController::new());
+        let Pmt::MapStrPmt(map) = mocker.post("health", Pmt::Null).unwrap() else {
+            panic!("expected Pmt::MapStrPmt");
+        };
+        let mut keys: Vec<&str> = map.keys().map(|k| k.as_str()).collect();
+        keys.sort();
+        assert_eq!(keys, ["flowgraphs", "last_reload", "uptime_secs"]);
+        assert!(matches!(map["uptime_secs"], Pmt::F64(t) if t >= 0.0));
+        assert_eq!(map["flowgraphs"], Pmt::Usize(1));
+        assert_eq!(map["last_reload"], Pmt::String("flowgraphs/wifi_rx.toml".to_string()));
+    }
+
+    #[test]
+    fn signals_finished() {
+        let mut mocker = Mocker::new(FlowgraphController::new());
+        assert_eq!(mocker.post("rx", Pmt::Finished).unwrap(), Pmt::Ok);
+        mocker.run();
+
+        let messages = mocker.messages();
+        assert!(matches!(&messages[1][..], [Pmt::String(s)] if s == "finished"));
+    }
+
+    #[test]
+    fn forwards_binary_tx() {
+        let payload = Pmt::Blob(vec![0xff, 0xfe, 0x00, 0x80]);
+        let mut mocker = Mocker::new(FlowgraphController::new());
+        mocker.post("tx", payload.clone()).unwrap();
+        mocker.run();
+
+        let messages = mocker.messages();
+        assert_eq!(messages[0], vec![payload]);
+        assert!(messages[1].is_empty());
+    }
+
+    #[test]
+    fn loopback_echoes_tx() {
+        let mut mocker = Mocker::new(FlowgraphController::new_loopback());
+        mocker.post("tx", Pmt::Blob(b"hello".to_vec())).unwrap();
+        mocker.run();
+
+        let messages = mocker.messages();
+        assert!(messages[0].is_empty());
+        assert!(matches!(&messages[1][..], [Pmt::String(s)] if s == "hello"));
+    }
+
+    #[test]
+    fn counts_forwarded_messages() {
+        let mut mocker = Mocker::new(FlowgraphController::new());
+        for i in 0..3u8 {
+            assert_eq!(mocker.post("tx", Pmt::Blob(vec![i])).unwrap(), Pmt::Ok);
+        }
+        assert_eq!(mocker.post("rx", Pmt::String("frame".to_string())).unwrap(), Pmt::Ok);
+        assert_eq!(mocker.post("rx", Pmt::Finished).unwrap(), Pmt::Ok);
+
+        let Pmt::MapStrPmt(metrics) = mocker.post("metrics", Pmt::Null).unwrap() else {
+            panic!("expected Pmt::MapStrPmt");
+        };
+        assert_eq!(metrics["tx_forwarded"], Pmt::U64(3));
+        assert_eq!(metrics["rx_forwarded"], Pmt::U64(2));
+        assert_eq!(metrics["forward_errors"], Pmt::U64(0));
+
+        // loopback echoes count as RX
+        let mut mocker = Mocker::new(FlowgraphController::new_loopback());
+        mocker.post("tx", Pmt::Blob(b"hello".to_vec())).unwrap();
+        let Pmt::MapStrPmt(metrics) = mocker.post("metrics", Pmt::Null).unwrap() else {
+            panic!("expected Pmt::MapStrPmt");
+        };
+        assert_eq!(metrics["tx_forwarded"], Pmt::U64(0));
+        assert_eq!(metrics["rx_forwarded"], Pmt::U64(1));
+    }
+
+    #[test]
+    fn queue_drops_oldest() {
+        // nothing is forwarded while the handlers run, like with a stalled receiver
+        let mut mocker = Mocker::new(FlowgraphController::new().with_queue(3));
+        for i in 0..10 {
+            let frame = Pmt::String(format!("frame {i}"));
+            assert_eq!(mocker.post("rx", frame).unwrap(), Pmt::Ok);
+        }
+        assert_eq!(mocker.dropped(), 7);
+        mocker.run();
+
+        let [tx_out, rx_out, stats] = mocker.messages().try_into().unwrap();
+        assert!(tx_out.is_empty());
+        let expected: Vec<Pmt> = (7..10).map(|i| Pmt::String(format!("frame {i}"))).collect();
+        assert_eq!(rx_out, expected);
+        let [Pmt::MapStrPmt(stats)] = &stats[..] else {
+            panic!("expected a single stats message, got {stats:?}");
+        };
+        assert_eq!(stats["dropped"], Pmt::U64(7));
+    }
+
+    #[test]
+    fn counts_delivered_messages() {
+        let metrics = |mocker: &mut Mocker<FlowgraphController>| {
+            let Pmt::MapStrPmt(m) = mocker.post("metrics", Pmt::Null).unwrap() else {
+                panic!("expected Pmt::MapStrPmt");
+            };
+            ["tx_forwarded", "rx_forwarded", "forward_errors"].map(|k| m[k].clone())
+        };
+
+        let mut mocker = Mocker::new(FlowgraphController::new().with_queue(2));
+        for i in 0..5u8 {
+            mocker.post("tx", Pmt::Blob(vec![i])).unwrap();
+        }
+        mocker.post("rx", Pmt::String("frame".to_string())).unwrap();
+        // nothing was delivered yet, but three TX messages were dropped
+        assert_eq!(
+            metrics(&mut mocker),
+            [Pmt::U64(0), Pmt::U64(0), Pmt::U64(3)]
+        );
+
+        mocker.run();
+        assert_eq!(mocker.messages()[0].len(), 2);
+        assert_eq!(
+            metrics(&mut mocker),
+            [Pmt::U64(2), Pmt::U64(1), Pmt::U64(3)]
+        );
+    }
+
+    #[test]
+    fn slow_receiver_does_not_block() {
+        let mut mocker = Mocker::new(FlowgraphController::new().with_queue(2));
+        // a receiver that takes one message and then stalls
+        let (tx, _rx) = futuresdr::futures::channel::mpsc::channel(0);
+        mocker
+            .mio
+            .connect(&"tx_out".into(), tx, &"input".into())
+            .unwrap();
+        for i in 0..5u8 {
+            assert_eq!(mocker.post("tx", Pmt::Blob(vec![i])).unwrap(), Pmt::Ok);
+        }
+        assert_eq!(mocker.dropped(), 3);
+
+        let mut io = WorkIo {
+            call_again: false,
+            finished: false,
+            block_on: None,
+        };
+        let start = Instant::now();
+        let WrappedKernel { meta, mio, kernel, .. } = &mut mocker.block;
+        futuresdr::async_io::block_on(kernel.work(&mut io, mio, meta)).unwrap();
+        assert!(start.elapsed() < Duration::from_secs(1));
+        // the second message waits for the receiver, while new messages are still accepted
+        assert!(io.block_on.is_some());
+        assert_eq!(mocker.queued(), 1);
+        assert_eq!(mocker.post("tx", Pmt::Blob(vec![5])).unwrap(), Pmt::Ok);
+        assert_eq!(mocker.post("tx", Pmt::Blob(vec![6])).unwrap(), Pmt::Ok);
+        assert_eq!(mocker.queued(), 2);
+        assert_eq!(mocker.dropped(), 4);
+    }
+
+    #[test]
+    fn fan_out_without_duplicates() {
+        let mut mocker = Mocker::new(FlowgraphController::new().with_queue(4));
+        // a second receiver that takes one message and then stalls
+        let (tx, mut rx) = futuresdr::futures::channel::mpsc::channel(0);
+        mocker
+            .mio
+            .connect(&"tx_out".into(), tx, &"input".into())
+            .unwrap();
+        for i in 0..3u8 {
+            mocker.post("tx", Pmt::Blob(vec![i])).unwrap();
+        }
+
+        // the first receiver gets each message once, however often it is offered
+        for _ in 0..3 {
+            mocker.run();
+        }
+        let blobs = |n: u8| (0..n).map(|i| Pmt::Blob(vec![i])).collect::<Vec<_>>();
+        assert_eq!(mocker.messages()[0], blobs(2));
+        assert_eq!(mocker.queued(), 2);
+
+        // once the second receiver catches up, it gets the remaining messages, in order
+        let mut received = Vec::new();
+        while received.len() < 3 {
+            while let Ok(BlockMessage::Call { data, .. }) = rx.try_recv() {
+                received.push(data);
+            }
+            mocker.run();
+        }
+        assert_eq!(received, blobs(3));
+        assert_eq!(mocker.messages()[0], blobs(3));
+        assert_eq!(mocker.queued(), 0);
+    }
+}
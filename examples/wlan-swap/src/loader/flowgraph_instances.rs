@@ -0,0 +1,485 @@
+//! Flowgraph Instances
+//!
+//! Keeps several loader-built flowgraphs running side by side, each under an instance label,
+//! so that e.g. WiFi RX and ZigBee RX can run at the same time on two SDRs and be swapped
+//! individually.
+
+use anyhow::{anyhow, Result};
+use futuresdr::async_io::block_on;
+use futuresdr::runtime::scheduler::Scheduler;
+use futuresdr::runtime::{BlockId, Error, Flowgraph, FlowgraphHandle, Pmt, Runtime};
+use futuresdr::tracing::{debug, warn};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::task::{Context, Poll, Wake, Waker};
+
+use super::block_info::blocks_to_pmt;
+use super::flowgraph_controller::{DEFAULT_LABEL, STOP_SENTINEL};
+use super::toml_loader::load_flowgraph_with_loader;
+
+/// Command received through the reload channel
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ControlCommand {
+    /// Load `path` as instance `label`, replacing a running instance with the same label
+    Load { label: String, path: String },
+    /// Stop instance `label`
+    Stop { label: String },
+    /// Instance `label` finished on its own (e.g., a file source reached the end of the file)
+    Finished { label: String },
+}
+
+impl ControlCommand {
+    /// Parse a control message.
+    ///
+    /// Accepts `"load:label:/path"`, `"stop:label"`, and `"finished:label"`. For backwards compatibility, a bare
+    /// path loads and [`STOP_SENTINEL`] stops the [`DEFAULT_LABEL`] instance.
+    pub fn parse(msg: &str) -> std::result::Result<Self, String> {
+        if msg == STOP_SENTINEL {
+            return Ok(ControlCommand::Stop {
+                label: DEFAULT_LABEL.to_string(),
+            });
+        }
+
+        let check_label = |label: &str| {
+            if label.is_empty() {
+                Err(format!("empty instance label in control message '{}'", msg))
+            } else {
+                Ok(label.to_string())
+            }
+        };
+
+        if let Some(rest) = msg.strip_prefix("load:") {
+            match rest.split_once(':') {
+                Some((label, path)) if !path.is_empty() => Ok(ControlCommand::Load {
+                    label: check_label(label)?,
+                    path: path.to_string(),
+                }),
+                _ => Err(format!("expected 'load:label:/path', got '{}'", msg)),
+            }
+        } else if let Some(label) = msg.strip_prefix("stop:") {
+            Ok(ControlCommand::Stop {
+                label: check_label(label)?,
+            })
+        } else if let Some(label) = msg.strip_prefix("finished:") {
+            Ok(ControlCommand::Finished {
+                label: check_label(label)?,
+            })
+        } else {
+            Ok(ControlCommand::Load {
+                label: DEFAULT_LABEL.to_string(),
+                path: msg.to_string(),
+            })
+        }
+    }
+}
+
+impl ControlCommand {
+    /// Label of the instance the command refers to
+    pub fn label(&self) -> &str {
+        match self {
+            ControlCommand::Load { label, .. }
+            | ControlCommand::Stop { label }
+            | ControlCommand::Finished { label } => label,
+        }
+    }
+}
+
+/// Keep only the last command for each instance label.
+///
+/// Rapid GUI clicks queue several switches of the same instance; only the most recent one
+/// matters, so the intermediate flowgraphs are never started. Commands are returned in the
+/// order in which their label was last addressed.
+///
+/// A [`ControlCommand::Finished`] does not replace earlier commands, since the instance it
+/// refers to might only have been started by them; [`FlowgraphInstances::apply`] ignores it if
+/// the instance is alive by then.
+pub fn coalesce(cmds: impl IntoIterator<Item = ControlCommand>) -> Vec<ControlCommand> {
+    let mut out: Vec<ControlCommand> = Vec::new();
+    for cmd in cmds {
+        if matches!(cmd, ControlCommand::Finished { .. }) {
+            if out.contains(&cmd) {
+                continue;
+            }
+        } else {
+            out.retain(|c| c.label() != cmd.label());
+        }
+        out.push(cmd);
+    }
+    out
+}
+
+/// Wait for the next control message and coalesce it with the ones queued behind it
+///
+/// Messages that arrive while a switch is in progress queue up in the channel and are
+/// coalesced on the next call (see [`coalesce`]). Malformed messages are logged and skipped.
+/// Returns `None` once the channel is disconnected.
+pub fn receive_commands(rx: &mpsc::Receiver<String>) -> Option<Vec<ControlCommand>> {
+    let first = rx.recv().ok()?;
+    let msgs: Vec<String> = std::iter::once(first).chain(rx.try_iter()).collect();
+    let n_msgs = msgs.len();
+
+    let cmds = coalesce(msgs.iter().filter_map(|msg| match ControlCommand::parse(msg) {
+        Ok(cmd) => Some(cmd),
+        Err(e) => {
+            warn!("ignoring control message: {}", e);
+            None
+        }
+    }));
+    if cmds.len() < n_msgs {
+        debug!("coalesced {} control messages into {}", n_msgs, cmds.len());
+    }
+    Some(cmds)
+}
+
+/// Task of a running flowgraph (dropping it detaches the flowgraph)
+type FlowgraphTask<'a> = Pin<Box<dyn Future<Output = std::result::Result<Flowgraph, Error>> + 'a>>;
+
+/// Waker of a flowgraph task, which reports its completion on the reload channel
+///
+/// The task only wakes the waker once the flowgraph terminated, so the control loop learns
+/// about it immediately, without checking the instances periodically.
+struct FinishedWaker {
+    tx: mpsc::Sender<String>,
+    msg: String,
+    stopped: Arc<AtomicBool>,
+}
+
+impl FinishedWaker {
+    fn notify(&self) {
+        if !self.stopped.load(Ordering::SeqCst) {
+            let _ = self.tx.send(self.msg.clone());
+        }
+    }
+}
+
+impl Wake for FinishedWaker {
+    fn wake(self: Arc<Self>) {
+        self.notify();
+    }
+}
+
+/// A running flowgraph
+struct Instance<'a> {
+    path: String,
+    handle: FlowgraphHandle,
+    task: FlowgraphTask<'a>,
+    /// Result of the task, once it completed while watched
+    result: Option<std::result::Result<Flowgraph, Error>>,
+    /// Woken by the task when the flowgraph terminates
+    waker: Waker,
+    /// Set when the instance is stopped, so that it is not reported as finished
+    stopped: Arc<AtomicBool>,
+    /// `FrameCounter` blocks of a loaded flowgraph
+    counters: Vec<BlockId>,
+}
+
+impl Instance<'_> {
+    /// Whether the flowgraph terminated, (re)registering the waker otherwise
+    fn poll_finished(&mut self) -> bool {
+        if self.result.is_none()
+            && let Poll::Ready(res) = self.task.as_mut().poll(&mut Context::from_waker(&self.waker))
+        {
+            self.result = Some(res);
+        }
+        self.result.is_some()
+    }
+}
+
+/// Running flowgraphs, keyed by instance label
+pub struct FlowgraphInstances<'a, S> {
+    rt: &'a Runtime<'a, S>,
+    instances: HashMap<String, Instance<'a>>,
+    finished_tx: Option<mpsc::Sender<String>>,
+}
+
+impl<'a, S: Scheduler + Sync> FlowgraphInstances<'a, S> {
+    pub fn new(rt: &'a Runtime<'a, S>) -> Self {
+        FlowgraphInstances {
+            rt,
+            instances: HashMap::new(),
+            finished_tx: None,
+        }
+    }
+
+    /// Report instances that finish on their own as `"finished:label"` on `tx`.
+    ///
+    /// Passing the reload channel lets the control loop receive them as
+    /// [`ControlCommand::Finished`]. Only instances started afterwards are watched.
+    pub fn notify_finished(&mut self, tx: mpsc::Sender<String>) {
+        self.finished_tx = Some(tx);
+    }
+
+    /// Load a flowgraph from `path` and start it as instance `label`.
+    ///
+    /// A running instance with the same label is stopped first. The `[[on_start]]` messages of
+    /// the config are posted once it runs. If the flowgraph has a `flowgraph_controller` block,
+    /// it is told that the flowgraph was (re)initialized.
+    pub fn load(&mut self, label: &str, path: &str) -> Result<()> {
+        self.stop(label);
+
+        let (fg, loader) = load_flowgraph_with_loader(path)?;
+        self.start(label, path, fg)?;
+
+        let instance = self.instances.get_mut(label).unwrap();
+        instance.counters = loader
+            .config()
+            .blocks
+            .iter()
+            .filter(|b| b.block_type == "FrameCounter")
+            .filter_map(|b| loader.get_block(&b.name))
+            .collect();
+        let handle = &mut instance.handle;
+        if let Err(e) = block_on(loader.send_on_start(handle)) {
+            warn!("instance '{}': on_start message failed: {}", label, e);
+        }
+
+        if let Some(controller_id) = loader.get_block("flowgraph_controller") {
+            let handle = &mut self.instances.get_mut(label).unwrap().handle;
+            let _ = block_on(handle.call(controller_id, "tx", Pmt::String("initialized".to_string())));
+            // Also send reload message to RX port for any UI state resets
+            let _ = block_on(handle.call(controller_id, "rx", Pmt::String("reload".to_string())));
+            // Parameter description for the GUI's block inspector
+            let _ = block_on(handle.call(controller_id, "describe", blocks_to_pmt(&loader.describe())));
+        }
+        Ok(())
+    }
+
+    /// Start an already built flowgraph as instance `label`, stopping any instance with that label
+    pub fn start(&mut self, label: &str, path: &str, fg: Flowgraph) -> Result<()> {
+        self.stop(label);
+
+        let (task, handle) = self
+            .rt
+            .start_sync(fg)
+            .map_err(|e| anyhow!("failed to start flowgraph '{}': {}", label, e))?;
+        let stopped = Arc::new(AtomicBool::new(false));
+        let mut instance = Instance {
+            path: path.to_string(),
+            handle,
+            task: Box::pin(task),
+            result: None,
+            waker: Waker::noop().clone(),
+            stopped: stopped.clone(),
+            counters: Vec::new(),
+        };
+        if let Some(tx) = &self.finished_tx {
+            let waker = Arc::new(FinishedWaker {
+                tx: tx.clone(),
+                msg: format!("finished:{}", label),
+                stopped,
+            });
+            instance.waker = Waker::from(waker.clone());
+            // the task holds on to the waker until the flowgraph terminates
+            if instance.poll_finished() {
+                waker.notify();
+            }
+        }
+        self.instances.insert(label.to_string(), instance);
+        Ok(())
+    }
+
+    /// Terminate instance `label` and wait for its task to finish.
+    ///
+    /// Returns `false` if no instance with this label is running.
+    pub fn stop(&mut self, label: &str) -> bool {
+        let Some(mut instance) = self.instances.remove(label) else {
+            return false;
+        };
+        instance.stopped.store(true, Ordering::SeqCst);
+
+        block_on(async {
+            // the flowgraph might have finished on its own, in which case its inbox is closed
+            if let Err(e) = instance.handle.terminate().await {
+                debug!("instance '{}' already terminated: {}", label, e);
+            }
+            let res = match instance.result.take() {
+                Some(res) => res,
+                None => instance.task.await,
+            };
+            if let Err(e) = res {
+                warn!("instance '{}' ({}) terminated with error: {}", label, instance.path, e);
+            }
+        });
+        true
+    }
+
+    /// Frames counted by the `FrameCounter` blocks of instance `label`
+    ///
+    /// Returns `None` if no instance with this label is running or it has no `FrameCounter`,
+    /// e.g., to report what a flowgraph handled before it is stopped for a switch.
+    pub fn frame_count(&mut self, label: &str) -> Option<u64> {
+        let instance = self.instances.get_mut(label)?;
+        if instance.counters.is_empty() {
+            return None;
+        }
+        let mut frames = 0;
+        for id in &instance.counters {
+            match block_on(instance.handle.callback(*id, "summary", Pmt::Null)) {
+                Ok(Pmt::MapStrPmt(summary)) => {
+                    if let Some(Pmt::U64(n)) = summary.get("frames") {
+                        frames += n;
+                    }
+                }
+                res => debug!("instance '{}': frame counter {:?} not available: {:?}", label, id, res),
+            }
+        }
+        Some(frames)
+    }
+
+    /// Stop all running instances
+    pub fn stop_all(&mut self) {
+        for label in self.labels() {
+            self.stop(&label);
+        }
+    }
+
+    /// Execute a control command
+    pub fn apply(&mut self, cmd: &ControlCommand) -> Result<()> {
+        match cmd {
+            ControlCommand::Load { label, path } => self.load(label, path),
+            ControlCommand::Stop { label } => {
+                if self.stop(label) {
+                    Ok(())
+                } else {
+                    Err(anyhow!("no flowgraph running as '{}'", label))
+                }
+            }
+            ControlCommand::Finished { label } => {
+                // stale if the instance was stopped or replaced in the meantime
+                if self.instances.get_mut(label).is_some_and(|i| i.poll_finished()) {
+                    self.stop(label);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Sorted labels of the running instances
+    pub fn labels(&self) -> Vec<String> {
+        let mut labels: Vec<String> = self.instances.keys().cloned().collect();
+        labels.sort();
+        labels
+    }
+
+    /// Whether an instance with this label is running
+    pub fn contains(&self, label: &str) -> bool {
+        self.instances.contains_key(label)
+    }
+
+    /// Path the instance was loaded from
+    pub fn path(&self, label: &str) -> Option<&str> {
+        self.instances.get(label).map(|i| i.path.as_str())
+    }
+
+    /// Handle of a running instance
+    pub fn handle(&self, label: &str) -> Option<FlowgraphHandle> {
+        self.instances.get(label).map(|i| i.handle.clone())
+    }
+
+    /// Number of running instances
+    pub fn len(&self) -> usize {
+        self.instances.len()
+    }
+
+    /// Whether no instance is running
+    pub fn is_empty(&self) -> bool {
+        self.instances.is_empty()
+    }
+}
+
+impl<S> Drop for FlowgraphInstances<'_, S> {
+    fn drop(&mut self) {
+        for (_, mut instance) in self.instances.drain() {
+            let _ = block_on(instance.handle.terminate());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_commands() {
+        assert_eq!(
+            ControlCommand::parse("load:wifi:flowgraphs/wifi_rx.toml"),
+            Ok(ControlCommand::Load {
+                label: "wifi".to_string(),
+                path: "flowgraphs/wifi_rx.toml".to_string(),
+            })
+        );
+        assert_eq!(
+            ControlCommand::parse("stop:wifi"),
+            Ok(ControlCommand::Stop {
+                label: "wifi".to_string()
+            })
+        );
+        assert_eq!(
+            ControlCommand::parse("flowgraphs/wifi_rx.toml"),
+            Ok(ControlCommand::Load {
+                label: DEFAULT_LABEL.to_string(),
+                path: "flowgraphs/wifi_rx.toml".to_string(),
+            })
+        );
+        assert_eq!(
+            ControlCommand::parse(STOP_SENTINEL),
+            Ok(ControlCommand::Stop {
+                label: DEFAULT_LABEL.to_string()
+            })
+        );
+        assert!(ControlCommand::parse("load:wifi").is_err());
+        assert!(ControlCommand::parse("load::flowgraphs/wifi_rx.toml").is_err());
+        assert_eq!(
+            ControlCommand::parse("finished:wifi"),
+            Ok(ControlCommand::Finished {
+                label: "wifi".to_string()
+            })
+        );
+        assert!(ControlCommand::parse("stop:").is_err());
+        assert!(ControlCommand::parse("finished:").is_err());
+    }
+
+    #[test]
+    fn coalesce_per_label() {
+        let cmds = ["load:wifi:a.toml", "load:zigbee:b.toml", "load:wifi:c.toml", "stop:zigbee"]
+            .into_iter()
+            .map(|m| ControlCommand::parse(m).unwrap());
+        assert_eq!(
+            coalesce(cmds),
+            vec![
+                ControlCommand::Load {
+                    label: "wifi".to_string(),
+                    path: "c.toml".to_string(),
+                },
+                ControlCommand::Stop {
+                    label: "zigbee".to_string()
+                },
+            ]
+        );
+    }
+    #[test]
+    fn coalesce_keeps_finished() {
+        let cmds = ["load:wifi:a.toml", "finished:wifi", "finished:wifi", "stop:zigbee", "finished:zigbee", "load:zigbee:b.toml"]
+            .into_iter()
+            .map(|m| ControlCommand::parse(m).unwrap());
+        assert_eq!(
+            coalesce(cmds),
+            vec![
+                ControlCommand::Load {
+                    label: "wifi".to_string(),
+                    path: "a.toml".to_string(),
+                },
+                ControlCommand::Finished {
+                    label: "wifi".to_string()
+                },
+                ControlCommand::Load {
+                    label: "zigbee".to_string(),
+                    path: "b.toml".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -5,13 +5,36 @@
 
 use anyhow::Result;
 use std::fs;
+use std::net::IpAddr;
+use std::net::SocketAddr;
 use std::path::Path;
+use super::error::LoaderError;
+use super::toml_loader::FlowgraphLoader;
+use super::toml_loader::FlowgraphMeta;
 
 pub const CONTROL_FILE: &str = ".flowgraph_control";
 
+/// Default port of the runtime's control server (the REST API the GUI talks to)
+pub const DEFAULT_CONTROL_PORT: u16 = 1337;
+
+/// Bind the runtime's control server to `addr:port`
+///
+/// Has to be called before the [`Runtime`](futuresdr::runtime::Runtime) is created. Returns the
+/// bind address, e.g., for logging.
+pub fn set_control_bind(addr: IpAddr, port: u16) -> SocketAddr {
+    let bind = SocketAddr::new(addr, port);
+    futuresdr::runtime::config::set("ctrlport_bind", bind.to_string());
+    bind
+}
+
 /// List all available flowgraph TOML files in the flowgraphs directory
 pub fn list_flowgraphs() -> Result<Vec<String>> {
-    let flowgraph_dir = Path::new("flowgraphs");
+    list_flowgraphs_in("flowgraphs")
+}
+
+/// List all flowgraph TOML files in a directory
+pub fn list_flowgraphs_in<P: AsRef<Path>>(dir: P) -> Result<Vec<String>> {
+    let flowgraph_dir = dir.as_ref();
     let mut flowgraphs = Vec::new();
     
     if flowgraph_dir.exists() {
@@ -30,6 +53,21 @@ pub fn list_flowgraphs() -> Result<Vec<String>> {
     Ok(flowgraphs)
 }
 
+/// Validate and dry-run every flowgraph in a directory
+///
+/// Returns the path of each flowgraph together with the outcome of
+/// [`FlowgraphLoader::validate`] and [`FlowgraphLoader::dry_run`]. No hardware is opened.
+pub fn check_flowgraphs<P: AsRef<Path>>(
+    dir: P,
+) -> Result<Vec<(String, std::result::Result<(), LoaderError>)>> {
+    let mut results = Vec::new();
+    for path in list_flowgraphs_in(dir)? {
+        let res = FlowgraphLoader::from_file(&path).and_then(|mut loader| loader.dry_run());
+        results.push((path, res));
+    }
+    Ok(results)
+}
+
 /// Read the current flowgraph from the control file
 pub fn read_control_file() -> Result<String> {
     Ok(fs::read_to_string(CONTROL_FILE)?.trim().to_string())
@@ -55,9 +93,14 @@ pub fn get_flowgraph_name(path: &str) -> String {
         .to_string()
 }
 
-/// Get the category/type of a flowgraph from its name
-pub fn get_flowgraph_category(name: &str) -> &str {
-    if name.contains("wifi") {
+/// Get the category/type of a flowgraph
+///
+/// Uses the `category` of the flowgraph's `[meta]` section if it declares one, and guesses
+/// from its name otherwise.
+pub fn get_flowgraph_category<'a>(name: &'a str, meta: Option<&'a FlowgraphMeta>) -> &'a str {
+    if let Some(category) = meta.and_then(|m| m.category.as_deref()) {
+        category
+    } else if name.contains("wifi") {
         "WiFi"
     } else if name.contains("zigbee") {
         "ZigBee"
@@ -67,3 +110,88 @@ pub fn get_flowgraph_category(name: &str) -> &str {
         "Other"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_good_and_broken() {
+        let dir = std::env::temp_dir().join(format!("check_flowgraphs_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("good.toml"),
+            "[[blocks]]\nname = \"flowgraph_controller\"\ntype = \"FlowgraphController\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("broken.toml"),
+            "[[blocks]]\nname = \"decoder\"\ntype = \"zigbee::Decoder\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), "not a flowgraph").unwrap();
+
+        let results = check_flowgraphs(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(results.len(), 2);
+        // sorted by path
+        assert!(results[0].0.ends_with("broken.toml"));
+        assert!(matches!(
+            results[0].1,
+            Err(LoaderError::MissingParameter { ref name, .. }) if name == "threshold"
+        ));
+        assert!(results[1].0.ends_with("good.toml"));
+        assert!(results[1].1.is_ok());
+    }
+
+    #[test]
+    fn category_prefers_meta() {
+        let meta = FlowgraphMeta {
+            category: Some("Receivers".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(get_flowgraph_category("wifi_rx", Some(&meta)), "Receivers");
+        // fall back to the name without a declared category
+        assert_eq!(get_flowgraph_category("wifi_rx", Some(&FlowgraphMeta::default())), "WiFi");
+        assert_eq!(get_flowgraph_category("zigbee_tx", None), "ZigBee");
+        assert_eq!(get_flowgraph_category("nullstream", None), "Other");
+    }
+
+    #[test]
+    fn dry_run_does_not_open_hardware() {
+        let toml = r#"
+[[blocks]]
+name = "src"
+type = "seify::Source"
+[[blocks.parameters]]
+name = "frequency"
+type = "f64"
+value = 2.45e9
+[[blocks.parameters]]
+name = "sample_rate"
+type = "f64"
+value = 4e6
+[[blocks.parameters]]
+name = "gain"
+type = "f64"
+value = 30.0
+[[blocks.parameters]]
+name = "args"
+type = "string"
+value = "driver=no_such_driver"
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "Complex32"
+
+[[connections]]
+from = "src"
+from_port = "outputs[0]"
+to = "snk"
+        "#;
+        let mut loader: FlowgraphLoader = toml.parse().unwrap();
+        loader.dry_run().unwrap();
+    }
+}
@@ -2,20 +2,46 @@
 //! 
 //! Provides TOML-based flowgraph loading with block registry and management utilities
 
+pub mod error;
+pub mod block_info;
 pub mod toml_loader;
 pub mod block_registry;
 pub mod flowgraph_manager;
 pub mod flowgraph_controller;
+pub mod rx_message;
+pub mod tx_payload;
+pub mod call_retry;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod flowgraph_instances;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod shutdown;
 
-pub use toml_loader::{FlowgraphLoader, load_flowgraph, load_flowgraph_with_loader};
-pub use block_registry::BlockRegistry;
+pub use error::LoaderError;
+pub use block_info::{BlockInfo, ParamInfo};
+pub use toml_loader::{
+    BlockDiff, BlockProfile, ConfigDiff, FlowgraphConfig, FlowgraphLoader, FlowgraphMeta, PortMap,
+    PortRef, load_flowgraph, load_flowgraph_scriptable,
+    load_flowgraph_with_loader, load_flowgraph_with_overrides,
+};
+pub use block_registry::{BlockRegistry, BlockSchema, ParamSpec};
 pub use flowgraph_manager::{
     list_flowgraphs, 
+    list_flowgraphs_in,
+    check_flowgraphs,
     read_control_file, 
     write_control_file, 
     control_file_exists,
     get_flowgraph_name,
     get_flowgraph_category,
-    CONTROL_FILE
+    set_control_bind,
+    CONTROL_FILE,
+    DEFAULT_CONTROL_PORT,
 };
-pub use flowgraph_controller::FlowgraphController;
+pub use flowgraph_controller::{FlowgraphController, DEFAULT_LABEL};
+pub use rx_message::RxMessage;
+pub use tx_payload::parse_tx_payload;
+pub use call_retry::{retry_on_timeout, CallError, RETRY_STATUS};
+#[cfg(not(target_arch = "wasm32"))]
+pub use flowgraph_instances::{coalesce, receive_commands, ControlCommand, FlowgraphInstances};
+#[cfg(not(target_arch = "wasm32"))]
+pub use shutdown::{run_until, run_with_ctrlc};
@@ -0,0 +1,147 @@
+//! RX WebSocket Messages
+//!
+//! Frames the backend sends to the GUI on the RX WebSocket, either plain text (received
+//! frames and the legacy `initialized`/`reload`/`finished` signals) or a JSON envelope
+//! `{"type": ..., "payload": ...}` for structured control. Heartbeats carry their time stamp
+//! as `{"type": "heartbeat", "ts": ...}`.
+
+use serde::Deserialize;
+
+/// Message received on the RX WebSocket
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RxMessage {
+    /// The flowgraph finished initialization, the page should reload
+    Initialized,
+    /// The backend reloaded the flowgraph
+    Reload,
+    /// The flowgraph's data path finished (e.g., the end of a file was reached), so there is
+    /// nothing left to send to
+    Finished,
+    /// The backend is alive, with its time in milliseconds since the Unix epoch
+    Heartbeat(u64),
+    /// Status text to log
+    Status(String),
+    /// Received frame or other text for the console
+    Text(String),
+}
+
+/// JSON envelope of a structured message
+#[derive(Deserialize)]
+struct Envelope {
+    #[serde(rename = "type")]
+    msg_type: String,
+    #[serde(default)]
+    payload: serde_json::Value,
+    #[serde(default)]
+    ts: Option<u64>,
+}
+
+impl RxMessage {
+    /// Parse a text frame, `None` if it is empty
+    ///
+    /// Frames that are not a valid envelope, including envelopes of unknown type, are
+    /// returned as [`RxMessage::Text`] unchanged.
+    pub fn parse(s: &str) -> Option<Self> {
+        if s.is_empty() {
+            return None;
+        }
+        match s {
+            "initialized" => return Some(RxMessage::Initialized),
+            "reload" => return Some(RxMessage::Reload),
+            "finished" => return Some(RxMessage::Finished),
+            _ => {}
+        }
+
+        let Ok(envelope) = serde_json::from_str::<Envelope>(s) else {
+            return Some(RxMessage::Text(s.to_string()));
+        };
+        let payload = match envelope.payload {
+            serde_json::Value::String(p) => p,
+            serde_json::Value::Null => String::new(),
+            p => p.to_string(),
+        };
+        Some(match envelope.msg_type.as_str() {
+            "initialized" => RxMessage::Initialized,
+            "reload" => RxMessage::Reload,
+            "finished" => RxMessage::Finished,
+            "heartbeat" => match envelope.ts {
+                Some(ts) => RxMessage::Heartbeat(ts),
+                None => RxMessage::Text(s.to_string()),
+            },
+            "status" => RxMessage::Status(payload),
+            "message" => RxMessage::Text(payload),
+            _ => RxMessage::Text(s.to_string()),
+        })
+    }
+
+    /// Parse a binary frame (e.g., a `Pmt::Blob`), replacing invalid UTF-8
+    pub fn parse_bytes(b: &[u8]) -> Option<Self> {
+        Self::parse(&String::from_utf8_lossy(b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text() {
+        assert_eq!(RxMessage::parse(""), None);
+        assert_eq!(RxMessage::parse("initialized"), Some(RxMessage::Initialized));
+        assert_eq!(RxMessage::parse("reload"), Some(RxMessage::Reload));
+        assert_eq!(RxMessage::parse("finished"), Some(RxMessage::Finished));
+        assert_eq!(
+            RxMessage::parse("hello"),
+            Some(RxMessage::Text("hello".to_string()))
+        );
+        assert_eq!(
+            RxMessage::parse_bytes(b"frame \xff"),
+            Some(RxMessage::Text("frame \u{fffd}".to_string()))
+        );
+    }
+
+    #[test]
+    fn envelopes() {
+        assert_eq!(
+            RxMessage::parse(r#"{"type": "initialized"}"#),
+            Some(RxMessage::Initialized)
+        );
+        assert_eq!(
+            RxMessage::parse(r#"{"type": "reload", "payload": null}"#),
+            Some(RxMessage::Reload)
+        );
+        assert_eq!(
+            RxMessage::parse(r#"{"type": "finished"}"#),
+            Some(RxMessage::Finished)
+        );
+        assert_eq!(
+            RxMessage::parse(r#"{"type": "status", "payload": "2 flowgraphs"}"#),
+            Some(RxMessage::Status("2 flowgraphs".to_string()))
+        );
+        assert_eq!(
+            RxMessage::parse(r#"{"type": "status", "payload": {"gain": 30}}"#),
+            Some(RxMessage::Status(r#"{"gain":30}"#.to_string()))
+        );
+        assert_eq!(
+            RxMessage::parse(r#"{"type": "message", "payload": "hi"}"#),
+            Some(RxMessage::Text("hi".to_string()))
+        );
+        assert_eq!(
+            RxMessage::parse(r#"{"type": "heartbeat", "ts": 1700000000123}"#),
+            Some(RxMessage::Heartbeat(1700000000123))
+        );
+    }
+
+    #[test]
+    fn malformed_envelopes() {
+        for s in [
+            r#"{"type": "nope"}"#,
+            r#"{"payload": "hi"}"#,
+            "{\"type\": ",
+            r#"{"type": "heartbeat"}"#,
+            r#"{"type": "heartbeat", "ts": "now"}"#,
+        ] {
+            assert_eq!(RxMessage::parse(s), Some(RxMessage::Text(s.to_string())));
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Graceful Shutdown
+//!
+//! Run a flowgraph until Ctrl-C and terminate it cleanly, so that sinks stop their streams
+//! instead of being killed mid-transmit.
+
+use anyhow::Result;
+use futuresdr::async_io::Timer;
+use futuresdr::async_io::block_on;
+use futuresdr::futures::future::Either;
+use futuresdr::futures::future::select;
+use futuresdr::runtime::Flowgraph;
+use futuresdr::runtime::Runtime;
+use futuresdr::runtime::scheduler::SmolScheduler;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Interval in which the interrupt flag is checked
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Run a flowgraph until it finishes or SIGINT (Ctrl-C) is received
+///
+/// On SIGINT, the flowgraph is terminated and awaited before returning. A second Ctrl-C kills
+/// the process, in case the shutdown hangs. On non-Unix platforms, no handler is installed and
+/// Ctrl-C keeps its default behavior.
+pub fn run_with_ctrlc(rt: &Runtime<'_, SmolScheduler>, fg: Flowgraph) -> Result<Flowgraph> {
+    install_handler();
+    run_until(rt, fg, interrupted())
+}
+
+/// Run a flowgraph until it finishes or `shutdown` completes
+///
+/// In the latter case, the flowgraph is terminated and awaited before returning.
+pub fn run_until(
+    rt: &Runtime<'_, SmolScheduler>,
+    fg: Flowgraph,
+    shutdown: impl Future<Output = ()>,
+) -> Result<Flowgraph> {
+    let (task, mut handle) = rt.start_sync(fg)?;
+    block_on(async move {
+        match select(task, pin!(shutdown)).await {
+            Either::Left((fg, _)) => Ok(fg?),
+            Either::Right(((), task)) => {
+                // fails if the flowgraph finished in the meantime, which is fine
+                handle.terminate_and_wait().await.ok();
+                Ok(task.await?)
+            }
+        }
+    })
+}
+
+/// Completes once SIGINT was received
+async fn interrupted() {
+    while !INTERRUPTED.load(Ordering::SeqCst) {
+        Timer::after(POLL_INTERVAL).await;
+    }
+}
+
+#[cfg(unix)]
+fn install_handler() {
+    extern "C" fn on_sigint(_: libc::c_int) {
+        INTERRUPTED.store(true, Ordering::SeqCst);
+        // restore the default, so that a second Ctrl-C kills the process
+        unsafe {
+            libc::signal(libc::SIGINT, libc::SIG_DFL);
+        }
+    }
+
+    INTERRUPTED.store(false, Ordering::SeqCst);
+    unsafe {
+        libc::signal(libc::SIGINT, on_sigint as *const () as libc::sighandler_t);
+    }
+}
+
+#[cfg(not(unix))]
+fn install_handler() {
+    INTERRUPTED.store(false, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run_until;
+    use futuresdr::async_io::Timer;
+    use futuresdr::blocks::NullSink;
+    use futuresdr::blocks::NullSource;
+    use futuresdr::prelude::*;
+    use std::time::Duration;
+
+    #[test]
+    fn terminates_on_shutdown() -> Result<()> {
+        let mut fg = Flowgraph::new();
+        let src = NullSource::<u8>::new();
+        let snk = NullSink::<u8>::new();
+        connect!(fg, src > snk);
+
+        let rt = Runtime::new();
+        let shutdown = async {
+            Timer::after(Duration::from_millis(200)).await;
+        };
+        assert!(run_until(&rt, fg, shutdown).is_ok());
+        assert!(snk.get().unwrap().n_received() > 0);
+        Ok(())
+    }
+}
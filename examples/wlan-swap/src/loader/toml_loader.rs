@@ -3,15 +3,32 @@
 //! This module provides functionality to load and instantiate FutureSDR flowgraphs
 //! from TOML configuration files.
 
-use anyhow::{Result, Context};
 use futuresdr::prelude::*;
+use futuresdr::runtime::BlockPortCtx;
+use futuresdr::runtime::WorkStats;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
-use super::block_registry::BlockRegistry;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+use super::block_info::{BlockInfo, ParamInfo};
+use super::block_registry::{parse_int_literal, BlockRegistry};
+use super::error::LoaderError;
+
+type Result<T> = std::result::Result<T, LoaderError>;
+
+/// Connection as `from.port -> to.port`, with its condition and buffer size hint
+type ConnectionKey = (String, (Option<String>, Option<usize>));
+
+/// Sources that produce samples as fast as they can, see [`FlowgraphLoader::set_auto_throttle`]
+const UNTHROTTLED_SOURCES: [&str; 2] = ["FileSource", "NullSource"];
+
+/// Item types supported by the `Throttle` factory
+const THROTTLE_DTYPES: [&str; 3] = ["Complex32", "f32", "u8"];
 
 /// TOML Flowgraph Configuration
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct FlowgraphConfig {
     /// List of blocks in the flowgraph
     pub blocks: Vec<BlockConfig>,
@@ -21,12 +38,335 @@ pub struct FlowgraphConfig {
     /// Message connections between blocks
     #[serde(default)]
     pub message_connections: Vec<MessageConnectionConfig>,
+    /// Linear chains of stream connections with inferred ports (`[[chains]]`)
+    #[serde(default)]
+    pub chains: Vec<ChainConfig>,
     /// Runtime configuration
     #[serde(default)]
     pub runtime: Option<RuntimeConfig>,
     /// CLI argument definitions
     #[serde(default)]
     pub cli: Option<CliConfig>,
+    /// Reusable block definitions (`[templates.NAME]`), see [`BlockConfig::template`]
+    #[serde(default)]
+    pub templates: HashMap<String, BlockTemplate>,
+    /// Messages posted once the flowgraph is started (`[[on_start]]`)
+    #[serde(default)]
+    pub on_start: Vec<OnStartConfig>,
+    /// Description of the flowgraph for the GUI (`[meta]`)
+    #[serde(default)]
+    pub meta: Option<FlowgraphMeta>,
+    /// Named message inputs for scripting (`[[ports]]`), see [`FlowgraphLoader::port_map`]
+    #[serde(default)]
+    pub ports: Vec<PortConfig>,
+}
+
+/// Description of a flowgraph (`[meta]`), shown by the GUI instead of the file path
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct FlowgraphMeta {
+    /// Short name, e.g., `WiFi Receiver`
+    #[serde(default)]
+    pub title: Option<String>,
+    /// What the flowgraph does, e.g., shown as a tooltip
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Group in the flowgraph list (see [`get_flowgraph_category`](super::get_flowgraph_category))
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+impl FlowgraphMeta {
+    /// Read only the `[meta]` section of a flowgraph
+    ///
+    /// Returns `None` if the TOML is malformed or has no `[meta]` section. Unlike loading the
+    /// full config, this does not check the blocks, so it is cheap enough for listings.
+    pub fn from_toml(toml_str: &str) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct MetaOnly {
+            meta: Option<FlowgraphMeta>,
+        }
+        toml::from_str::<MetaOnly>(toml_str).ok()?.meta
+    }
+}
+
+impl FlowgraphConfig {
+    /// Check that parameter values match their declared `type`
+    ///
+    /// Integer types (e.g., `u32`, `usize`, `i64`) need an integer or a string with an integer
+    /// literal (e.g., `"0x0C"` or `"0b1100"`), `f32` and `f64` a float or an
+    /// integer, `string` and `closure` a string, `bool` a boolean, `array` an array, and `table`
+    /// a table. Other types (e.g., `mac_addr`) are left to the factories.
+    pub fn validate_param_types(&self) -> Result<()> {
+        for block in &self.blocks {
+            let inner = block.inner.iter().flat_map(|i| i.parameters.iter());
+            for param in block.parameters.iter().chain(inner) {
+                if !param_type_matches(&param.param_type, &param.value) {
+                    return Err(LoaderError::mismatch(&block.name, &param.name, &param.param_type));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Insert a `Throttle` at `rate` samples/s after each source in [`UNTHROTTLED_SOURCES`]
+    ///
+    /// The throttle takes over all stream connections and chains of the source. Sources that
+    /// are optional, unconnected, or already followed by a `Throttle` are left alone. Returns
+    /// the names of the inserted blocks.
+    fn insert_throttles(&mut self, rate: f64) -> Vec<String> {
+        let types: HashMap<String, String> = self
+            .blocks
+            .iter()
+            .map(|b| (b.name.clone(), b.block_type.clone()))
+            .collect();
+        let sources: Vec<(String, String)> = self
+            .blocks
+            .iter()
+            .filter(|b| !b.optional && UNTHROTTLED_SOURCES.contains(&b.block_type.as_str()))
+            .map(|b| {
+                let default = if b.block_type == "NullSource" { "u8" } else { "Complex32" };
+                (b.name.clone(), b.dtype.clone().unwrap_or_else(|| default.to_string()))
+            })
+            .collect();
+
+        let mut inserted = Vec::new();
+        for (source, dtype) in sources {
+            let downstream: Vec<&str> = self
+                .connections
+                .iter()
+                .filter(|c| c.from == source)
+                .map(|c| c.to.as_str())
+                .chain(
+                    self.chains
+                        .iter()
+                        .filter(|c| c.chain.len() > 1 && c.chain[0] == source)
+                        .map(|c| c.chain[1].as_str()),
+                )
+                .collect();
+            if downstream.is_empty()
+                || downstream.iter().any(|b| types.get(*b).is_some_and(|t| t == "Throttle"))
+            {
+                continue;
+            }
+            if !THROTTLE_DTYPES.contains(&dtype.as_str()) {
+                warn!("block '{}': cannot throttle {} samples", source, dtype);
+                continue;
+            }
+
+            let mut name = format!("{source}_throttle");
+            while types.contains_key(&name) || inserted.contains(&name) {
+                name.push('_');
+            }
+            for conn in self.connections.iter_mut().filter(|c| c.from == source) {
+                conn.from = name.clone();
+            }
+            for chain in self.chains.iter_mut().filter(|c| c.chain.len() > 1) {
+                if chain.chain[0] == source {
+                    chain.chain[0] = name.clone();
+                }
+            }
+            self.connections.push(ConnectionConfig {
+                from: source.clone(),
+                from_port: None,
+                to: name.clone(),
+                to_port: None,
+                conditional: None,
+                min_buffer: None,
+            });
+            self.blocks.push(BlockConfig {
+                name: name.clone(),
+                block_type: "Throttle".to_string(),
+                template: None,
+                dtype: Some(dtype),
+                output_type: None,
+                input1_type: None,
+                input2_type: None,
+                parameters: vec![ParameterConfig {
+                    name: "rate".to_string(),
+                    param_type: "f64".to_string(),
+                    value: toml::Value::Float(rate),
+                }],
+                optional: false,
+                inner: None,
+                scheduler: None,
+            });
+            inserted.push(name);
+        }
+        inserted
+    }
+
+    /// Replace template references by concrete block definitions
+    ///
+    /// Fields set on the block take precedence over the template. Parameters are merged by
+    /// name: the block's parameters override template parameters of the same name, and new
+    /// ones are appended.
+    pub fn resolve_templates(&mut self) -> Result<()> {
+        for block in self.blocks.iter_mut() {
+            let Some(name) = block.template.take() else {
+                if block.block_type.is_empty() {
+                    return Err(LoaderError::MissingBlockType(block.name.clone()));
+                }
+                continue;
+            };
+            let template = self.templates.get(&name).ok_or_else(|| LoaderError::UnknownTemplate {
+                block: block.name.clone(),
+                template: name.clone(),
+            })?;
+
+            if block.block_type.is_empty() {
+                block.block_type = template.block_type.clone();
+            }
+            block.dtype = block.dtype.take().or_else(|| template.dtype.clone());
+            block.output_type = block.output_type.take().or_else(|| template.output_type.clone());
+            block.input1_type = block.input1_type.take().or_else(|| template.input1_type.clone());
+            block.input2_type = block.input2_type.take().or_else(|| template.input2_type.clone());
+            block.inner = block.inner.take().or_else(|| template.inner.clone());
+
+            let mut parameters = template.parameters.clone();
+            for param in block.parameters.drain(..) {
+                match parameters.iter_mut().find(|p| p.name == param.name) {
+                    Some(p) => *p = param,
+                    None => parameters.push(param),
+                }
+            }
+            block.parameters = parameters;
+        }
+        Ok(())
+    }
+
+    /// Compare this config with `other`, e.g., the one about to replace it
+    ///
+    /// Blocks are matched by name and connections by their endpoints, so renaming a block shows
+    /// up as a removed and an added block. Templates are compared after resolution.
+    pub fn diff(&self, other: &FlowgraphConfig) -> ConfigDiff {
+        let mut diff = ConfigDiff::default();
+
+        for block in &other.blocks {
+            match self.blocks.iter().find(|b| b.name == block.name) {
+                None => diff.added_blocks.push(block.name.clone()),
+                Some(old) => {
+                    let block_diff = old.diff(block);
+                    if !block_diff.fields.is_empty() || !block_diff.parameters.is_empty() {
+                        diff.modified_blocks.push(block_diff);
+                    }
+                }
+            }
+        }
+        diff.removed_blocks = self
+            .blocks
+            .iter()
+            .filter(|b| !other.blocks.iter().any(|o| o.name == b.name))
+            .map(|b| b.name.clone())
+            .collect();
+
+        let old = self.connection_keys();
+        let new = other.connection_keys();
+        for (key, settings) in &new {
+            match old.iter().find(|(k, _)| k == key) {
+                None => diff.added_connections.push(key.clone()),
+                Some((_, s)) if s != settings => diff.modified_connections.push(key.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed_connections = old
+            .into_iter()
+            .filter(|(k, _)| !new.iter().any(|(n, _)| n == k))
+            .map(|(k, _)| k)
+            .collect();
+
+        diff
+    }
+
+    /// Stream and message connections as `from.port -> to.port`, with their other settings
+    fn connection_keys(&self) -> Vec<ConnectionKey> {
+        let stream = self.connections.iter().map(|c| {
+            (
+                format!(
+                    "{}.{} -> {}.{}",
+                    c.from,
+                    c.from_port.as_deref().unwrap_or("output"),
+                    c.to,
+                    c.to_port.as_deref().unwrap_or("input"),
+                ),
+                (c.conditional.clone(), c.min_buffer),
+            )
+        });
+        let message = self.message_connections.iter().map(|c| {
+            (
+                format!(
+                    "{}.{} -> {}.{} (message)",
+                    c.from,
+                    c.from_port,
+                    c.to,
+                    c.to_port.as_deref().unwrap_or(&c.from_port),
+                ),
+                (c.conditional.clone(), None),
+            )
+        });
+        let chains = self.chains.iter().flat_map(|c| {
+            c.chain
+                .windows(2)
+                .map(|w| (format!("{} -> {} (chain)", w[0], w[1]), (c.conditional.clone(), None)))
+        });
+        stream.chain(message).chain(chains).collect()
+    }
+}
+
+/// Differences between two flowgraph configs, see [`FlowgraphConfig::diff`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    /// Blocks only in the new config
+    pub added_blocks: Vec<String>,
+    /// Blocks only in the old config
+    pub removed_blocks: Vec<String>,
+    pub modified_blocks: Vec<BlockDiff>,
+    /// Connections (`from.port -> to.port`) only in the new config
+    pub added_connections: Vec<String>,
+    /// Connections only in the old config
+    pub removed_connections: Vec<String>,
+    /// Connections in both configs with a different `conditional` or `min_buffer`
+    pub modified_connections: Vec<String>,
+}
+
+impl ConfigDiff {
+    /// Whether both configs describe the same flowgraph
+    pub fn is_empty(&self) -> bool {
+        self == &ConfigDiff::default()
+    }
+}
+
+impl std::fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return write!(f, "no changes");
+        }
+        let mut lines = Vec::new();
+        lines.extend(self.added_blocks.iter().map(|b| format!("+ block {b}")));
+        lines.extend(self.removed_blocks.iter().map(|b| format!("- block {b}")));
+        for block in &self.modified_blocks {
+            let changes: Vec<&str> = block
+                .fields
+                .iter()
+                .chain(block.parameters.iter())
+                .map(|s| s.as_str())
+                .collect();
+            lines.push(format!("~ block {} ({})", block.name, changes.join(", ")));
+        }
+        lines.extend(self.added_connections.iter().map(|c| format!("+ {c}")));
+        lines.extend(self.removed_connections.iter().map(|c| format!("- {c}")));
+        lines.extend(self.modified_connections.iter().map(|c| format!("~ {c}")));
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+/// Changes of a block present in both configs, see [`ConfigDiff`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockDiff {
+    pub name: String,
+    /// Changed settings other than parameters (e.g., `type`, `dtype`, `inner`)
+    pub fields: Vec<String>,
+    /// Parameters that were added, removed, or changed their type or value
+    pub parameters: Vec<String>,
 }
 
 /// Block configuration
@@ -34,9 +374,12 @@ pub struct FlowgraphConfig {
 pub struct BlockConfig {
     /// Unique name for this block instance
     pub name: String,
-    /// Block type (e.g., "Apply", "Fft", "wifi::Decoder")
-    #[serde(rename = "type")]
+    /// Block type (e.g., "Apply", "Fft", "wifi::Decoder"), may be omitted with `template`
+    #[serde(rename = "type", default)]
     pub block_type: String,
+    /// Template this block extends (a key of `[templates]`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
     /// Data type for typed blocks (e.g., "Complex32", "f32")
     #[serde(default)]
     pub dtype: Option<String>,
@@ -54,10 +397,128 @@ pub struct BlockConfig {
     /// Whether this block is optional (for conditional instantiation)
     #[serde(default)]
     pub optional: bool,
+    /// Processing stage wrapped by a Bypass block
+    #[serde(default)]
+    pub inner: Option<InnerBlockConfig>,
+    /// Scheduling hints (`[blocks.scheduler]`), see [`FlowgraphLoader::pinned_blocks`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheduler: Option<SchedulerConfig>,
 }
 
-/// Block parameter configuration
+impl BlockConfig {
+    /// Compare with the block of the same name in another config
+    fn diff(&self, other: &BlockConfig) -> BlockDiff {
+        let mut fields = Vec::new();
+        let mut changed = |name: &str, differs: bool| {
+            if differs {
+                fields.push(name.to_string());
+            }
+        };
+        changed("type", self.block_type != other.block_type);
+        changed("dtype", self.dtype != other.dtype);
+        changed("output_type", self.output_type != other.output_type);
+        changed("input1_type", self.input1_type != other.input1_type);
+        changed("input2_type", self.input2_type != other.input2_type);
+        changed("optional", self.optional != other.optional);
+        changed("inner", self.inner != other.inner);
+        changed("scheduler", self.scheduler != other.scheduler);
+
+        let mut parameters: Vec<String> = other
+            .parameters
+            .iter()
+            .filter(|p| self.parameters.iter().find(|o| o.name == p.name) != Some(*p))
+            .map(|p| p.name.clone())
+            .collect();
+        parameters.extend(
+            self.parameters
+                .iter()
+                .filter(|p| !other.parameters.iter().any(|o| o.name == p.name))
+                .map(|p| p.name.clone()),
+        );
+
+        BlockDiff {
+            name: self.name.clone(),
+            fields,
+            parameters,
+        }
+    }
+}
+
+/// Reusable block definition (`[templates.NAME]`)
+///
+/// Holds the same fields as [`BlockConfig`] except for the block name.
 #[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BlockTemplate {
+    /// Block type
+    #[serde(rename = "type")]
+    pub block_type: String,
+    /// Data type for typed blocks
+    #[serde(default)]
+    pub dtype: Option<String>,
+    /// Output type for Apply/Combine blocks
+    #[serde(default)]
+    pub output_type: Option<String>,
+    /// Additional input types for Combine blocks
+    #[serde(default)]
+    pub input1_type: Option<String>,
+    #[serde(default)]
+    pub input2_type: Option<String>,
+    /// Default parameters
+    #[serde(default)]
+    pub parameters: Vec<ParameterConfig>,
+    /// Processing stage wrapped by a Bypass block
+    #[serde(default)]
+    pub inner: Option<InnerBlockConfig>,
+}
+
+/// Configuration of the stage wrapped by a Bypass block (`[blocks.inner]`)
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct InnerBlockConfig {
+    /// Block type of the stage (e.g., "Apply")
+    #[serde(rename = "type")]
+    pub block_type: String,
+    /// Stage parameters
+    #[serde(default)]
+    pub parameters: Vec<ParameterConfig>,
+}
+
+impl InnerBlockConfig {
+    /// Configuration of the stage on its own, named after the Bypass block for error messages
+    pub fn to_block_config(&self, name: &str) -> BlockConfig {
+        BlockConfig {
+            name: name.to_string(),
+            block_type: self.block_type.clone(),
+            template: None,
+            dtype: None,
+            output_type: None,
+            input1_type: None,
+            input2_type: None,
+            parameters: self.parameters.clone(),
+            optional: false,
+            inner: None,
+            scheduler: None,
+        }
+    }
+}
+
+/// Scheduling hints of a block (`[blocks.scheduler]`)
+///
+/// Only the flow scheduler can run a block on a given core, and none of the FutureSDR
+/// schedulers has priorities, so settings that cannot be applied are ignored with a warning.
+/// A valid `cpu_affinity` is also reported, since it only takes effect if the caller hands
+/// [`FlowgraphLoader::pinned_blocks`] to the flow scheduler.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct SchedulerConfig {
+    /// Cores the block should run on, the block is pinned to the first one
+    #[serde(default)]
+    pub cpu_affinity: Vec<usize>,
+    /// Priority of the block (`low`, `normal`, or `high`)
+    #[serde(default)]
+    pub priority: Option<String>,
+}
+
+/// Block parameter configuration
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 pub struct ParameterConfig {
     /// Parameter name
     pub name: String,
@@ -68,6 +529,76 @@ pub struct ParameterConfig {
     pub value: toml::Value,
 }
 
+/// Message posted to a block once the flowgraph is started
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct OnStartConfig {
+    /// Destination block name
+    pub block: String,
+    /// Destination message port
+    pub port: String,
+    /// PMT type of the message (`string`, `f64`, `f32`, `u32`, `u64`, `usize`, `bool`, `null`)
+    #[serde(rename = "type")]
+    pub pmt_type: String,
+    /// Message value (ignored for `null`)
+    #[serde(default)]
+    pub value: Option<toml::Value>,
+}
+
+impl OnStartConfig {
+    /// Convert the configured value into a PMT
+    fn to_pmt(&self) -> Result<Pmt> {
+        let invalid = || {
+            LoaderError::invalid(
+                &self.block,
+                &format!("on_start.{}", self.port),
+                format!("expected a {} value", self.pmt_type),
+            )
+        };
+        let int = || match self.value.as_ref() {
+            Some(toml::Value::String(s)) => parse_int_literal(s).ok(),
+            v => v.and_then(|v| v.as_integer()),
+        }
+        .ok_or_else(invalid);
+        let float = || {
+            self.value
+                .as_ref()
+                .and_then(|v| v.as_float().or_else(|| v.as_integer().map(|i| i as f64)))
+                .ok_or_else(invalid)
+        };
+
+        Ok(match self.pmt_type.as_str() {
+            "null" => Pmt::Null,
+            "string" => Pmt::String(
+                self.value.as_ref().and_then(|v| v.as_str()).ok_or_else(invalid)?.to_string(),
+            ),
+            "bool" => Pmt::Bool(self.value.as_ref().and_then(|v| v.as_bool()).ok_or_else(invalid)?),
+            "f64" => Pmt::F64(float()?),
+            "f32" => Pmt::F32(float()? as f32),
+            "u32" => Pmt::U32(u32::try_from(int()?).map_err(|_| invalid())?),
+            "u64" => Pmt::U64(u64::try_from(int()?).map_err(|_| invalid())?),
+            "usize" => Pmt::Usize(usize::try_from(int()?).map_err(|_| invalid())?),
+            _ => {
+                return Err(LoaderError::invalid(
+                    &self.block,
+                    &format!("on_start.{}", self.port),
+                    format!("unsupported type '{}'", self.pmt_type),
+                ))
+            }
+        })
+    }
+}
+
+/// Named message input of a block (`[[ports]]`)
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PortConfig {
+    /// Name used to look up the port
+    pub name: String,
+    /// Block name
+    pub block: String,
+    /// Message input port of the block
+    pub port: String,
+}
+
 /// Stream connection configuration
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ConnectionConfig {
@@ -84,6 +615,25 @@ pub struct ConnectionConfig {
     /// Conditional expression for this connection
     #[serde(default)]
     pub conditional: Option<String>,
+    /// Minimum size of the stream buffer on this edge, in items (optional, defaults to the
+    /// runtime's buffer size)
+    #[serde(default)]
+    pub min_buffer: Option<usize>,
+}
+
+/// Chain of stream connections (`[[chains]]`), like `connect!(fg, a > b > c)`
+///
+/// Consecutive blocks are connected from the stream output of the first to the stream input of
+/// the second. A block with a single stream output (input) uses it; a block with several uses
+/// `output` (`input`) if it has such a port, otherwise the chain is ambiguous and the ports have
+/// to be given in `[[connections]]`.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ChainConfig {
+    /// Block names in stream order
+    pub chain: Vec<String>,
+    /// Conditional expression for all connections of the chain
+    #[serde(default)]
+    pub conditional: Option<String>,
 }
 
 /// Message connection configuration
@@ -161,11 +711,86 @@ pub struct CliArgConfig {
     pub description: Option<String>,
 }
 
+/// Message input of a loaded block, see [`FlowgraphLoader::port_handle`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PortRef {
+    pub block: BlockId,
+    pub port: String,
+}
+
+impl PortRef {
+    /// Post a message to the port of a started flowgraph
+    pub async fn call(&self, handle: &mut FlowgraphHandle, pmt: Pmt) -> Result<()> {
+        handle.call(self.block, self.port.as_str(), pmt).await?;
+        Ok(())
+    }
+}
+
+/// Message inputs declared in the `[[ports]]` section, by name
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PortMap {
+    ports: HashMap<String, PortRef>,
+}
+
+impl PortMap {
+    /// Port declared with `name`
+    pub fn get(&self, name: &str) -> Option<&PortRef> {
+        self.ports.get(name)
+    }
+
+    /// Names of the declared ports, sorted
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.ports.keys().map(|n| n.as_str()).collect();
+        names.sort();
+        names
+    }
+
+    /// Post a message to the port declared with `name` of a started flowgraph
+    pub async fn call(&self, handle: &mut FlowgraphHandle, name: &str, pmt: Pmt) -> Result<()> {
+        let port = self
+            .get(name)
+            .ok_or_else(|| LoaderError::UnknownPort {
+                block: "[[ports]]".to_string(),
+                port: name.to_string(),
+            })?;
+        port.call(handle, pmt).await
+    }
+}
+
+/// Wall time a loaded block spent in `work()`, see [`FlowgraphLoader::profile_report`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockProfile {
+    pub name: String,
+    pub block_type: String,
+    /// Number of `work()` calls
+    pub calls: u64,
+    pub wall_time: Duration,
+    /// Wall time per `work()` call
+    pub average_wall_time: Duration,
+}
+
 /// Flowgraph loader
 pub struct FlowgraphLoader {
     config: FlowgraphConfig,
+    on_start: Vec<(String, String, Pmt)>,
     block_map: HashMap<String, BlockId>,
+    /// Message inputs of the built blocks
+    message_inputs: HashMap<String, Vec<String>>,
+    /// Message outputs of the built blocks
+    message_outputs: HashMap<String, Vec<String>>,
+    /// Stream inputs and outputs of the built blocks
+    stream_ports: HashMap<String, (Vec<String>, Vec<String>)>,
     conditions: HashMap<String, bool>,
+    strict: bool,
+    profile: bool,
+    /// Work wall time counters of the built blocks, if profiled
+    work_stats: Vec<(String, Arc<WorkStats>)>,
+    /// Cores requested by `[blocks.scheduler]`
+    pinned_blocks: HashMap<BlockId, usize>,
+    /// Scheduler settings ignored in the last build
+    scheduler_warnings: Vec<String>,
+    /// Rate of the throttles inserted after sources without a rate limit
+    auto_throttle: Option<f64>,
     registry: BlockRegistry,
 }
 
@@ -173,21 +798,66 @@ impl FlowgraphLoader {
     /// Load flowgraph configuration from TOML file
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = std::fs::read_to_string(path.as_ref())
-            .with_context(|| format!("Failed to read TOML file: {:?}", path.as_ref()))?;
+            .map_err(|source| LoaderError::Io {
+                path: path.as_ref().to_path_buf(),
+                source,
+            })?;
         Self::from_str(&content)
     }
 
-    /// Load flowgraph configuration from TOML string
-    pub fn from_str(toml_str: &str) -> Result<Self> {
-        let config: FlowgraphConfig = toml::from_str(toml_str)
-            .context("Failed to parse TOML configuration")?;
-        
-        Ok(Self {
-            config,
-            block_map: HashMap::new(),
-            conditions: HashMap::new(),
-            registry: BlockRegistry::new(),
-        })
+    /// Stop building at the first wiring error (the default)
+    ///
+    /// A non-strict loader tries all connections and returns all wiring errors at once, as
+    /// [`LoaderError::Connections`] if there is more than one.
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Measure the wall time each block built afterwards spends in `work()` (off by default)
+    ///
+    /// Read the result with [`profile_report`](Self::profile_report), e.g., to find the block
+    /// that keeps a receiver from running in real time.
+    pub fn set_profile(&mut self, profile: bool) {
+        self.profile = profile;
+    }
+
+    /// Work wall time of the profiled blocks, the highest total first
+    ///
+    /// The counters are shared with the running blocks, so the report can also be taken while
+    /// the flowgraph runs. Empty if profiling was not enabled with
+    /// [`set_profile`](Self::set_profile) before the build.
+    pub fn profile_report(&self) -> Vec<BlockProfile> {
+        let mut report: Vec<BlockProfile> = self
+            .work_stats
+            .iter()
+            .map(|(name, stats)| BlockProfile {
+                name: name.clone(),
+                block_type: self
+                    .config
+                    .blocks
+                    .iter()
+                    .find(|b| &b.name == name)
+                    .map(|b| b.block_type.clone())
+                    .unwrap_or_default(),
+                calls: stats.calls(),
+                wall_time: stats.wall_time(),
+                average_wall_time: stats.average_wall_time(),
+            })
+            .collect();
+        report.sort_by_key(|p| std::cmp::Reverse(p.wall_time));
+        report
+    }
+
+    /// Insert a `Throttle` at `rate` samples/s after each `FileSource` and `NullSource` when
+    /// building (off by default)
+    ///
+    /// Without hardware, these sources produce samples as fast as the CPU allows, so that
+    /// offline graphs (e.g., decoding a recording) peg a core instead of running at a realistic
+    /// pace. Sources already followed by a `Throttle` are left alone. The throttles are added to
+    /// the configuration, named after their source (e.g., `src_throttle`).
+    pub fn set_auto_throttle(&mut self, rate: Option<f64>) {
+        assert!(rate.is_none_or(|r| r > 0.0), "throttle rate must be positive");
+        self.auto_throttle = rate;
     }
 
     /// Set a condition value (for conditional blocks/connections)
@@ -210,92 +880,733 @@ impl FlowgraphLoader {
         }
     }
 
-    /// Build the flowgraph (placeholder - needs actual block creation logic)
-    pub fn build(&mut self, fg: &mut Flowgraph) -> Result<()> {
-        // Step 1: Create blocks
-        for block_cfg in &self.config.blocks {
-            if block_cfg.optional && !self.eval_condition(&Some(block_cfg.name.clone())) {
+    /// Reject message connections from a block to itself
+    ///
+    /// This is loader policy rather than a port rule: `Flowgraph::connect_message` asserts that
+    /// source and destination differ (and fails to lock the block twice in release builds), so
+    /// self-connections are reported up front with the port names. Fan-out, fan-in, and repeated
+    /// connections are left to the runtime.
+    fn validate_message_connections(&self) -> Result<()> {
+        for msg_conn in &self.config.message_connections {
+            if !self.eval_condition(&msg_conn.conditional) {
                 continue;
             }
 
-            let block_id = self.create_block(fg, block_cfg)?;
-            self.block_map.insert(block_cfg.name.clone(), block_id);
+            if msg_conn.from == msg_conn.to {
+                let from_port = msg_conn.from_port.as_str();
+                let to_port = msg_conn.to_port.as_deref().unwrap_or(from_port);
+                return Err(LoaderError::InvalidConnection {
+                    from: format!("{}.{from_port}", msg_conn.from),
+                    to: format!("{}.{to_port}", msg_conn.to),
+                    reason: "a block cannot send messages to itself".to_string(),
+                });
+            }
         }
+        Ok(())
+    }
 
-        // Step 2: Create stream connections
-        for conn in &self.config.connections {
-            if !self.eval_condition(&conn.conditional) {
+    /// Check that every message connection starts at an output and targets an input the blocks
+    /// declare
+    fn validate_message_ports(&self) -> Result<()> {
+        for msg_conn in &self.config.message_connections {
+            if !self.eval_condition(&msg_conn.conditional) {
                 continue;
             }
 
-            let from_id = self.block_map.get(&conn.from)
-                .with_context(|| format!("Source block '{}' not found", conn.from))?;
-            let to_id = self.block_map.get(&conn.to)
-                .with_context(|| format!("Destination block '{}' not found", conn.to))?;
+            let outputs = self.message_outputs.get(&msg_conn.from)
+                .ok_or_else(|| LoaderError::UnknownBlock(msg_conn.from.clone()))?;
+            if !outputs.contains(&msg_conn.from_port) {
+                return Err(LoaderError::UnknownMessageOutput {
+                    block: msg_conn.from.clone(),
+                    port: msg_conn.from_port.clone(),
+                    valid: outputs.clone(),
+                });
+            }
+            let to_port = msg_conn.to_port.as_deref().unwrap_or(msg_conn.from_port.as_str());
+            self.port_handle(&msg_conn.to, to_port)?;
+        }
+        Ok(())
+    }
 
-            let from_port = conn.from_port.as_deref().unwrap_or("output");
-            let to_port = conn.to_port.as_deref().unwrap_or("input");
+    /// Check the configuration without creating any blocks
+    ///
+    /// Verifies that parameter values match their declared types (see
+    /// [`FlowgraphConfig::validate_param_types`]), block names are unique, block types are
+    /// registered, required parameters are present, and connections, chains, and `[[on_start]]`
+    /// messages reference declared blocks, and that no block sends messages to itself.
+    pub fn validate(&self) -> Result<()> {
+        self.config.validate_param_types()?;
 
-            fg.connect_dyn(*from_id, from_port, *to_id, to_port)?;
-        }
+        let mut names = std::collections::HashSet::new();
+        for block_cfg in &self.config.blocks {
+            if !names.insert(block_cfg.name.as_str()) {
+                return Err(LoaderError::DuplicateBlock(block_cfg.name.clone()));
+            }
 
-        // Step 3: Create message connections
-        for msg_conn in &self.config.message_connections {
-            if !self.eval_condition(&msg_conn.conditional) {
-                continue;
+            let schema = self.registry.schema_for(&block_cfg.block_type)
+                .ok_or_else(|| LoaderError::UnknownBlockType(block_cfg.block_type.clone()))?;
+            for spec in schema.iter().filter(|p| p.required) {
+                if !block_cfg.parameters.iter().any(|p| spec.matches(&p.name)) {
+                    return Err(LoaderError::missing(&block_cfg.name, spec.name));
+                }
             }
+        }
 
-            let from_id = self.block_map.get(&msg_conn.from)
-                .with_context(|| format!("Source block '{}' not found", msg_conn.from))?;
-            let to_id = self.block_map.get(&msg_conn.to)
-                .with_context(|| format!("Destination block '{}' not found", msg_conn.to))?;
+        for conn in &self.config.connections {
+            check_min_buffer(conn)?;
+        }
 
-            println!("DEBUG: Connecting message: {} ({:?}) port '{}' -> {} ({:?})", 
-                msg_conn.from, from_id, msg_conn.from_port, msg_conn.to, to_id);
+        for chain in &self.config.chains {
+            if chain.chain.len() < 2 {
+                return Err(LoaderError::InvalidConnection {
+                    from: chain.chain.first().cloned().unwrap_or_default(),
+                    to: String::new(),
+                    reason: "a chain needs at least two blocks".to_string(),
+                });
+            }
+        }
 
-            fg.connect_message(*from_id, msg_conn.from_port.as_str(), *to_id, 
-                msg_conn.to_port.as_deref().unwrap_or(msg_conn.from_port.as_str()))?;
+        let links = self.config.chains.iter().flat_map(|c| c.chain.windows(2));
+        let connections = self
+            .config
+            .connections
+            .iter()
+            .map(|c| (&c.from, &c.to))
+            .chain(links.map(|w| (&w[0], &w[1])));
+        let message_connections = self.config.message_connections.iter().map(|c| (&c.from, &c.to));
+        for (from, to) in connections.chain(message_connections) {
+            for name in [from, to] {
+                if !names.contains(name.as_str()) {
+                    return Err(LoaderError::UnknownBlock(name.clone()));
+                }
+            }
+        }
+        for msg in &self.config.on_start {
+            if !names.contains(msg.block.as_str()) {
+                return Err(LoaderError::UnknownBlock(msg.block.clone()));
+            }
+        }
+        let mut port_names = std::collections::HashSet::new();
+        for port in &self.config.ports {
+            if !names.contains(port.block.as_str()) {
+                return Err(LoaderError::UnknownBlock(port.block.clone()));
+            }
+            if !port_names.insert(port.name.as_str()) {
+                return Err(LoaderError::DuplicatePort(port.name.clone()));
+            }
         }
 
+        self.validate_message_connections()
+    }
+
+    /// Override a block parameter before building
+    ///
+    /// Replaces the value given in the TOML or, for a parameter the TOML omits, adds it with the
+    /// type from the block's schema. Fails if the block is not declared or neither sets nor
+    /// accepts the parameter.
+    pub fn set_parameter(&mut self, block: &str, name: &str, value: toml::Value) -> Result<()> {
+        let block_cfg = self.config.blocks.iter_mut()
+            .find(|b| b.name == block)
+            .ok_or_else(|| LoaderError::UnknownBlock(block.to_string()))?;
+        let spec = self.registry.schema_for(&block_cfg.block_type)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|spec| spec.matches(name));
+
+        let existing = block_cfg.parameters.iter_mut().find(|p| match &spec {
+            Some(spec) => spec.matches(&p.name),
+            None => p.name == name,
+        });
+        match (existing, spec) {
+            (Some(p), _) => p.value = value,
+            (None, Some(spec)) => block_cfg.parameters.push(ParameterConfig {
+                name: name.to_string(),
+                param_type: spec.param_type.to_string(),
+                value,
+            }),
+            (None, None) => {
+                return Err(LoaderError::UnknownParameter {
+                    block: block.to_string(),
+                    name: name.to_string(),
+                });
+            }
+        }
         Ok(())
     }
 
-    /// Create a block from configuration
-    fn create_block(&self, fg: &mut Flowgraph, block_cfg: &BlockConfig) -> Result<BlockId> {
-        self.registry.create_block(fg, block_cfg)
+    /// Apply [`set_parameter`](Self::set_parameter) for each `(block, parameter)` override
+    pub fn apply_overrides(&mut self, overrides: HashMap<(String, String), toml::Value>) -> Result<()> {
+        for ((block, name), value) in overrides {
+            self.set_parameter(&block, &name, value)?;
+        }
+        Ok(())
     }
 
-    /// Get block ID by name
-    pub fn get_block(&self, name: &str) -> Option<BlockId> {
-        self.block_map.get(name).copied()
+    /// Validate the configuration and build it into a throwaway flowgraph
+    ///
+    /// Hardware blocks are created with stand-ins (see [`BlockFactory::create_dry`]), so this
+    /// checks parameters and connections without opening any device. The flowgraph is not run.
+    ///
+    /// [`BlockFactory::create_dry`]: super::block_registry::BlockFactory::create_dry
+    pub fn dry_run(&mut self) -> Result<()> {
+        self.validate()?;
+        let mut fg = Flowgraph::new();
+        let res = self.build_with(&mut fg, true);
+        self.block_map.clear();
+        self.message_inputs.clear();
+        self.message_outputs.clear();
+        self.stream_ports.clear();
+        res
     }
 
-    /// Get the configuration
-    pub fn config(&self) -> &FlowgraphConfig {
-        &self.config
+    /// Build the flowgraph
+    pub fn build(&mut self, fg: &mut Flowgraph) -> Result<()> {
+        self.build_with(fg, false)
     }
-}
 
-/// Convenience function to load a flowgraph from a TOML file
-/// 
-/// This is a high-level helper that creates a loader, builds the flowgraph,
-/// and returns it ready to run.
-pub fn load_flowgraph<P: AsRef<Path>>(path: P) -> Result<Flowgraph> {
-    let mut loader = FlowgraphLoader::from_file(path)?;
-    let mut fg = Flowgraph::new();
-    loader.build(&mut fg)?;
-    Ok(fg)
-}
+    fn build_with(&mut self, fg: &mut Flowgraph, dry: bool) -> Result<()> {
+        self.scheduler_warnings.clear();
+        self.pinned_blocks.clear();
+        self.work_stats.clear();
 
-/// Load a flowgraph and return both the flowgraph and the loader
-/// 
-/// This allows access to block IDs and runtime configuration after loading.
-pub fn load_flowgraph_with_loader<P: AsRef<Path>>(path: P) -> Result<(Flowgraph, FlowgraphLoader)> {
-    let mut loader = FlowgraphLoader::from_file(path)?;
-    let mut fg = Flowgraph::new();
-    loader.build(&mut fg)?;
-    Ok((fg, loader))
-}
+        if let Some(rate) = self.auto_throttle {
+            for name in self.config.insert_throttles(rate) {
+                info!("Inserted {} at {} samples/s", name, rate);
+            }
+        }
+
+        // Step 1: Create blocks
+        for block_cfg in &self.config.blocks {
+            if block_cfg.optional && !self.eval_condition(&Some(block_cfg.name.clone())) {
+                continue;
+            }
+
+            let block_id = if dry {
+                self.registry.create_block_dry(fg, block_cfg)?
+            } else {
+                self.create_block(fg, block_cfg)?
+            };
+            let block = fg.get_block(block_id)?;
+            let mut block = block
+                .try_lock()
+                .ok_or_else(|| Error::RuntimeError(format!("unable to lock block {:?}", block_id)))?;
+            if self.profile
+                && !dry
+                && let Some(stats) = block.profile()
+            {
+                self.work_stats.push((block_cfg.name.clone(), stats));
+            }
+            let inputs = block.message_inputs().iter().map(|s| s.to_string()).collect();
+            self.message_inputs.insert(block_cfg.name.clone(), inputs);
+            let outputs = block.message_outputs().iter().map(|s| s.to_string()).collect();
+            self.message_outputs.insert(block_cfg.name.clone(), outputs);
+            let ports = (block.stream_inputs(), block.stream_outputs());
+            self.stream_ports.insert(block_cfg.name.clone(), ports);
+            self.block_map.insert(block_cfg.name.clone(), block_id);
+
+            if let Some(scheduler) = &block_cfg.scheduler {
+                let core = apply_scheduler_config(
+                    &block_cfg.name,
+                    scheduler,
+                    &mut self.scheduler_warnings,
+                )?;
+                if let Some(core) = core.filter(|_| !dry) {
+                    self.pinned_blocks.insert(block_id, core);
+                }
+            }
+        }
+
+        let mut errors = Vec::new();
+        let strict = self.strict;
+        let mut check = |res: Result<()>| match res {
+            Err(e) if !strict => {
+                errors.push(e);
+                Ok(())
+            }
+            res => res,
+        };
+
+        // Check message connections before wiring anything, since a failed `connect_message` may
+        // leave the source already connected
+        let message_ports = self
+            .validate_message_connections()
+            .and_then(|_| self.validate_message_ports());
+        let message_ports_ok = message_ports.is_ok();
+        check(message_ports)?;
+
+        // Step 2: Create stream connections
+        let mut inputs = HashMap::new();
+        for (index, conn) in self.config.connections.iter().enumerate() {
+            if self.eval_condition(&conn.conditional) {
+                check(self.connect_stream(fg, index, conn, &mut inputs))?;
+            }
+        }
+        let links = self
+            .config
+            .chains
+            .iter()
+            .filter(|c| self.eval_condition(&c.conditional))
+            .flat_map(|c| c.chain.windows(2));
+        for (k, link) in links.enumerate() {
+            let index = self.config.connections.len() + k;
+            let conn = self.chain_link(&link[0], &link[1]);
+            check(conn.and_then(|conn| self.connect_stream(fg, index, &conn, &mut inputs)))?;
+        }
+
+        // Step 3: Create message connections
+        if message_ports_ok {
+            for (index, msg_conn) in self.config.message_connections.iter().enumerate() {
+                if self.eval_condition(&msg_conn.conditional) {
+                    check(self.connect_message(fg, index, msg_conn))?;
+                }
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            _ => Err(LoaderError::Connections(errors)),
+        }
+    }
+
+    /// Create a stream connection, `inputs` maps the inputs connected so far to their connection
+    fn connect_stream(
+        &self,
+        fg: &mut Flowgraph,
+        index: usize,
+        conn: &ConnectionConfig,
+        inputs: &mut HashMap<(String, String), usize>,
+    ) -> Result<()> {
+        let from_id = self.block_map.get(&conn.from)
+            .ok_or_else(|| LoaderError::UnknownBlock(conn.from.clone()))?;
+        let to_id = self.block_map.get(&conn.to)
+            .ok_or_else(|| LoaderError::UnknownBlock(conn.to.clone()))?;
+
+        let from_port = conn.from_port.as_deref().unwrap_or("output");
+        let to_port = conn.to_port.as_deref().unwrap_or("input");
+        if let Some(first) = inputs.insert((conn.to.clone(), to_port.to_string()), index) {
+            return Err(LoaderError::DuplicateInput {
+                index,
+                first,
+                block: conn.to.clone(),
+                port: to_port.to_string(),
+            });
+        }
+        let err = |e| connect_error(e, index, &conn.from, from_port, &conn.to, to_port);
+
+        if let Some(items) = check_min_buffer(conn)? {
+            set_min_buffer(fg, *to_id, to_port, items).map_err(err)?;
+        }
+
+        fg.connect_dyn(*from_id, from_port, *to_id, to_port).map_err(err)
+    }
+
+    /// Stream connection between consecutive blocks of a chain, see [`ChainConfig`]
+    fn chain_link(&self, from: &str, to: &str) -> Result<ConnectionConfig> {
+        let ports = |block: &str| {
+            self.stream_ports
+                .get(block)
+                .ok_or_else(|| LoaderError::UnknownBlock(block.to_string()))
+        };
+        let infer = |block: &str, ports: &[String], default: &str, kind: &str| {
+            let port = match ports {
+                [port] => Some(port),
+                ports => ports.iter().find(|p| *p == default),
+            };
+            port.cloned().ok_or_else(|| LoaderError::InvalidConnection {
+                from: from.to_string(),
+                to: to.to_string(),
+                reason: if ports.is_empty() {
+                    format!("'{block}' has no stream {kind}s")
+                } else {
+                    format!(
+                        "cannot infer the stream {kind} of '{block}' from {ports:?}, \
+                         connect it in [[connections]]"
+                    )
+                },
+            })
+        };
+
+        Ok(ConnectionConfig {
+            from: from.to_string(),
+            from_port: Some(infer(from, &ports(from)?.1, "output", "output")?),
+            to: to.to_string(),
+            to_port: Some(infer(to, &ports(to)?.0, "input", "input")?),
+            conditional: None,
+            min_buffer: None,
+        })
+    }
+
+    fn connect_message(
+        &self,
+        fg: &mut Flowgraph,
+        index: usize,
+        msg_conn: &MessageConnectionConfig,
+    ) -> Result<()> {
+        let from_id = self.block_map.get(&msg_conn.from)
+            .ok_or_else(|| LoaderError::UnknownBlock(msg_conn.from.clone()))?;
+        let to_id = self.block_map.get(&msg_conn.to)
+            .ok_or_else(|| LoaderError::UnknownBlock(msg_conn.to.clone()))?;
+
+        let from_port = msg_conn.from_port.as_str();
+        let to_port = msg_conn.to_port.as_deref().unwrap_or(from_port);
+        debug!(
+            "Connecting message: {} ({:?}) port '{}' -> {} ({:?}) port '{}'",
+            msg_conn.from, from_id, from_port, msg_conn.to, to_id, to_port
+        );
+        fg.connect_message(*from_id, from_port, *to_id, to_port)
+            .map_err(|e| connect_error(e, index, &msg_conn.from, from_port, &msg_conn.to, to_port))
+    }
+
+    /// Create a block from configuration
+    fn create_block(&self, fg: &mut Flowgraph, block_cfg: &BlockConfig) -> Result<BlockId> {
+        self.registry.create_block(fg, block_cfg)
+    }
+
+    /// Get block ID by name
+    pub fn get_block(&self, name: &str) -> Option<BlockId> {
+        self.block_map.get(name).copied()
+    }
+
+    /// Resolve a message input of a built block, e.g., to call it once the flowgraph runs
+    pub fn port_handle(&self, block_name: &str, port: &str) -> Result<PortRef> {
+        let block = self.get_block(block_name)
+            .ok_or_else(|| LoaderError::UnknownBlock(block_name.to_string()))?;
+        let inputs = &self.message_inputs[block_name];
+        if !inputs.iter().any(|i| i == port) {
+            return Err(LoaderError::UnknownMessageInput {
+                block: block_name.to_string(),
+                port: port.to_string(),
+                valid: inputs.clone(),
+            });
+        }
+        Ok(PortRef {
+            block,
+            port: port.to_string(),
+        })
+    }
+
+    /// Resolve the message inputs declared in the `[[ports]]` section of the built flowgraph
+    ///
+    /// Ports of optional blocks that were not instantiated are left out.
+    pub fn port_map(&self) -> Result<PortMap> {
+        let mut ports = HashMap::new();
+        for port in &self.config.ports {
+            if self.get_block(&port.block).is_none()
+                && self.config.blocks.iter().any(|b| b.name == port.block && b.optional)
+            {
+                continue;
+            }
+            if ports.contains_key(&port.name) {
+                return Err(LoaderError::DuplicatePort(port.name.clone()));
+            }
+            ports.insert(port.name.clone(), self.port_handle(&port.block, &port.port)?);
+        }
+        Ok(PortMap { ports })
+    }
+
+    /// Messages of the `[[on_start]]` section as `(block, port, message)`
+    pub fn on_start_messages(&self) -> Vec<(String, String, Pmt)> {
+        self.on_start.clone()
+    }
+
+    /// Post the `[[on_start]]` messages to a started flowgraph, in configuration order
+    ///
+    /// Messages to optional blocks that were not instantiated are skipped.
+    pub async fn send_on_start(&self, handle: &mut FlowgraphHandle) -> Result<()> {
+        for (block, port, pmt) in &self.on_start {
+            let Some(id) = self.get_block(block) else {
+                if self.config.blocks.iter().any(|b| &b.name == block && b.optional) {
+                    continue;
+                }
+                return Err(LoaderError::UnknownBlock(block.clone()));
+            };
+            handle.call(id, port.as_str(), pmt.clone()).await?;
+        }
+        Ok(())
+    }
+
+    /// Describe the built blocks, their parameters, and configured values
+    ///
+    /// Parameters come in schema order, with their default if they were omitted, followed by
+    /// configured parameters the schema does not know.
+    pub fn describe(&self) -> Vec<BlockInfo> {
+        let value_string = |v: &toml::Value| match v {
+            toml::Value::String(s) => s.clone(),
+            v => v.to_string(),
+        };
+
+        self.config.blocks.iter().filter_map(|block_cfg| {
+            let id = self.block_map.get(&block_cfg.name)?;
+            let schema = self.registry.schema_for(&block_cfg.block_type).unwrap_or_default();
+
+            let mut params: Vec<ParamInfo> = schema.iter().map(|spec| ParamInfo {
+                name: spec.name.to_string(),
+                param_type: spec.param_type.to_string(),
+                value: block_cfg.parameters.iter()
+                    .find(|p| spec.matches(&p.name))
+                    .map(|p| value_string(&p.value))
+                    .or_else(|| spec.default.map(|d| d.to_string())),
+                runtime_port: spec.runtime_port.map(|p| p.to_string()),
+            }).collect();
+            for p in &block_cfg.parameters {
+                if !schema.iter().any(|spec| spec.matches(&p.name)) {
+                    params.push(ParamInfo {
+                        name: p.name.clone(),
+                        param_type: p.param_type.clone(),
+                        value: Some(value_string(&p.value)),
+                        runtime_port: None,
+                    });
+                }
+            }
+
+            Some(BlockInfo {
+                name: block_cfg.name.clone(),
+                block_type: block_cfg.block_type.clone(),
+                id: id.0,
+                params,
+            })
+        }).collect()
+    }
+
+    /// Cores the built blocks asked for with `[blocks.scheduler] cpu_affinity`
+    ///
+    /// The runtime is created independently of the loader, so the pinning only takes effect if
+    /// the map is handed to `FlowScheduler::with_pinned_blocks` (FutureSDR's `flow_scheduler`
+    /// feature). The default scheduler runs every block on any of its workers.
+    pub fn pinned_blocks(&self) -> HashMap<BlockId, usize> {
+        self.pinned_blocks.clone()
+    }
+
+    /// Scheduler settings of the last build that could not be applied
+    pub fn scheduler_warnings(&self) -> &[String] {
+        &self.scheduler_warnings
+    }
+
+    /// Description of the flowgraph from its `[meta]` section
+    pub fn meta(&self) -> Option<&FlowgraphMeta> {
+        self.config.meta.as_ref()
+    }
+
+    /// Get the configuration
+    pub fn config(&self) -> &FlowgraphConfig {
+        &self.config
+    }
+}
+
+impl FromStr for FlowgraphLoader {
+    type Err = LoaderError;
+
+    /// Load flowgraph configuration from TOML string
+    fn from_str(toml_str: &str) -> Result<Self> {
+        let mut config: FlowgraphConfig = toml::from_str(toml_str)?;
+        config.resolve_templates()?;
+        let on_start = config
+            .on_start
+            .iter()
+            .map(|m| Ok((m.block.clone(), m.port.clone(), m.to_pmt()?)))
+            .collect::<Result<_>>()?;
+        
+        Ok(Self {
+            config,
+            on_start,
+            block_map: HashMap::new(),
+            message_inputs: HashMap::new(),
+            message_outputs: HashMap::new(),
+            stream_ports: HashMap::new(),
+            conditions: HashMap::new(),
+            strict: true,
+            profile: false,
+            work_stats: Vec::new(),
+            pinned_blocks: HashMap::new(),
+            scheduler_warnings: Vec::new(),
+            auto_throttle: None,
+            registry: BlockRegistry::new(),
+        })
+    }
+}
+
+/// Check the scheduler settings of a block and return the core to pin it to
+///
+/// Settings that cannot be applied on this platform are reported in `warnings` and logged.
+fn apply_scheduler_config(
+    block: &str,
+    config: &SchedulerConfig,
+    warnings: &mut Vec<String>,
+) -> Result<Option<usize>> {
+    let mut warn = |msg: String| {
+        warn!("block '{}': {}", block, msg);
+        warnings.push(format!("{block}: {msg}"));
+    };
+
+    match config.priority.as_deref() {
+        None | Some("normal") => {}
+        Some(p @ ("low" | "high")) => {
+            warn(format!("priority '{p}' is not supported by the scheduler, ignored"));
+        }
+        Some(p) => {
+            return Err(LoaderError::invalid(
+                block,
+                "scheduler.priority",
+                format!("unknown priority '{p}', expected low, normal, or high"),
+            ));
+        }
+    }
+
+    let Some(&core) = config.cpu_affinity.first() else {
+        return Ok(None);
+    };
+    if cfg!(target_arch = "wasm32") {
+        warn("cpu_affinity is not supported on this platform, ignored".to_string());
+        return Ok(None);
+    }
+    let cores = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    if core >= cores {
+        warn(format!("core {core} does not exist ({cores} cores), cpu_affinity ignored"));
+        return Ok(None);
+    }
+    if config.cpu_affinity.len() > 1 {
+        warn(format!("a block runs on a single core, pinned to core {core}"));
+    }
+    // the loader does not create the runtime, so it cannot tell whether the pinning is used
+    warn(format!(
+        "pinned to core {core} only if the runtime uses a FlowScheduler with pinned_blocks()"
+    ));
+    Ok(Some(core))
+}
+
+/// Return the buffer size hint of a connection, rejecting a size of zero
+fn check_min_buffer(conn: &ConnectionConfig) -> Result<Option<usize>> {
+    match conn.min_buffer {
+        Some(0) => Err(LoaderError::InvalidConnection {
+            from: conn.from.clone(),
+            to: conn.to.clone(),
+            reason: "min_buffer must be positive".to_string(),
+        }),
+        items => Ok(items),
+    }
+}
+
+/// Configure the minimum buffer size on a stream input before it is connected
+///
+/// The buffer is allocated on connect, using the larger of the writer and reader minimum, so
+/// setting it on the reader is sufficient. Only default CPU buffers of the dtypes supported by
+/// the registry can be configured.
+fn set_min_buffer(fg: &mut Flowgraph, block_id: BlockId, port: &str, items: usize) -> std::result::Result<(), Error> {
+    let block = fg.get_block(block_id)?;
+    let mut block = block
+        .try_lock()
+        .ok_or_else(|| Error::RuntimeError(format!("unable to lock block {:?}", block_id)))?;
+    let reader = block
+        .stream_input(port)
+        .ok_or_else(|| Error::InvalidStreamPort(BlockPortCtx::Id(block_id), PortId::new(port)))?
+        .as_any_mut();
+
+    macro_rules! try_set {
+        ($($t:ty),*) => {
+            $(
+                if let Some(r) = reader.downcast_mut::<DefaultCpuReader<$t>>() {
+                    r.set_min_buffer_size_in_items(items);
+                    return Ok(());
+                }
+            )*
+        };
+    }
+    try_set!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64, Complex32, Complex64);
+
+    Err(Error::ValidationError(format!(
+        "min_buffer is not supported for the buffer type of port '{}'",
+        port
+    )))
+}
+
+/// Attribute a failed connection to the block whose port is missing
+fn connect_error(
+    e: Error,
+    index: usize,
+    from: &str,
+    from_port: &str,
+    to: &str,
+    to_port: &str,
+) -> LoaderError {
+    match e {
+        Error::InvalidStreamPort(_, port) | Error::InvalidMessagePort(_, port) => {
+            let block = if port.name() == to_port { to } else { from };
+            LoaderError::UnknownPort {
+                block: block.to_string(),
+                port: port.name().to_string(),
+            }
+        }
+        source => LoaderError::ConnectionFailed {
+            index,
+            from: from.to_string(),
+            from_port: from_port.to_string(),
+            to: to.to_string(),
+            to_port: to_port.to_string(),
+            source: Box::new(source),
+        },
+    }
+}
+
+/// Whether a parameter value is of the TOML kind its declared type needs
+fn param_type_matches(param_type: &str, value: &toml::Value) -> bool {
+    match param_type {
+        "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64" | "isize" => {
+            value.is_integer() || value.as_str().is_some_and(|s| parse_int_literal(s).is_ok())
+        }
+        "f32" | "f64" => value.is_float() || value.is_integer(),
+        "string" | "closure" => value.is_str(),
+        "bool" => value.is_bool(),
+        "array" => value.is_array(),
+        "table" => value.is_table(),
+        _ => true,
+    }
+}
+
+/// Convenience function to load a flowgraph from a TOML file
+/// 
+/// This is a high-level helper that creates a loader, builds the flowgraph,
+/// and returns it ready to run.
+pub fn load_flowgraph<P: AsRef<Path>>(path: P) -> Result<Flowgraph> {
+    let mut loader = FlowgraphLoader::from_file(path)?;
+    let mut fg = Flowgraph::new();
+    loader.build(&mut fg)?;
+    Ok(fg)
+}
+
+/// Load a flowgraph from TOML file with some block parameters overridden
+///
+/// Each override maps a `(block, parameter)` pair to the value used instead of the one in the
+/// file, e.g., to sweep `Encoder.mcs` without editing the configuration.
+pub fn load_flowgraph_with_overrides<P: AsRef<Path>>(
+    path: P,
+    overrides: HashMap<(String, String), toml::Value>,
+) -> Result<Flowgraph> {
+    let mut loader = FlowgraphLoader::from_file(path)?;
+    loader.apply_overrides(overrides)?;
+    let mut fg = Flowgraph::new();
+    loader.build(&mut fg)?;
+    Ok(fg)
+}
+
+/// Load a flowgraph and return both the flowgraph and the loader
+/// 
+/// This allows access to block IDs and runtime configuration after loading.
+pub fn load_flowgraph_with_loader<P: AsRef<Path>>(path: P) -> Result<(Flowgraph, FlowgraphLoader)> {
+    let mut loader = FlowgraphLoader::from_file(path)?;
+    let mut fg = Flowgraph::new();
+    loader.build(&mut fg)?;
+    Ok((fg, loader))
+}
+
+/// Load a flowgraph and resolve the message inputs of its `[[ports]]` section
+///
+/// For scripting: once the flowgraph is started, the ports can be called by name with the
+/// flowgraph's handle, e.g., `ports.call(&mut handle, "tx", pmt)`.
+pub fn load_flowgraph_scriptable<P: AsRef<Path>>(path: P) -> Result<(Flowgraph, PortMap)> {
+    let (fg, loader) = load_flowgraph_with_loader(path)?;
+    Ok((fg, loader.port_map()?))
+}
 
 #[cfg(test)]
 mod tests {
@@ -338,4 +1649,1110 @@ to = "snk"
         assert!(!loader.eval_condition(&Some("feature_b".to_string())));
         assert!(loader.eval_condition(&Some("!feature_b".to_string())));
     }
+
+    const MIN_BUFFER: &str = r#"
+[[blocks]]
+name = "src"
+type = "NullSource"
+dtype = "f32"
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "f32"
+
+[[connections]]
+from = "src"
+to = "snk"
+min_buffer = 1000000
+    "#;
+
+    #[test]
+    fn test_min_buffer() {
+        let mut loader = FlowgraphLoader::from_str(MIN_BUFFER).unwrap();
+        assert_eq!(loader.config.connections[0].min_buffer, Some(1_000_000));
+
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap();
+
+        let snk = fg.get_block(loader.get_block("snk").unwrap()).unwrap();
+        let mut snk = snk.try_lock().unwrap();
+        let reader = snk
+            .stream_input("input")
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut::<DefaultCpuReader<f32>>()
+            .unwrap();
+        assert!(reader.max_items() >= 1_000_000);
+    }
+
+    #[test]
+    fn test_error_min_buffer_zero() {
+        let toml = MIN_BUFFER.replace("1000000", "0");
+        let loader = FlowgraphLoader::from_str(&toml).unwrap();
+        assert!(matches!(loader.validate(), Err(LoaderError::InvalidConnection { .. })));
+        assert!(matches!(build_err(&toml), LoaderError::InvalidConnection { .. }));
+    }
+
+    #[test]
+    fn test_describe() {
+        let toml = r#"
+[[blocks]]
+name = "xlate"
+type = "FreqXlate"
+[[blocks.parameters]]
+name = "freq_hz"
+type = "f64"
+value = 1000.0
+[[blocks.parameters]]
+name = "sample_rate"
+type = "f64"
+value = 8000.0
+
+[[blocks]]
+name = "meter"
+type = "PowerMeter"
+
+[[connections]]
+from = "xlate"
+to = "meter"
+        "#;
+        let mut loader = FlowgraphLoader::from_str(toml).unwrap();
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap();
+
+        let blocks = loader.describe();
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].id, loader.get_block("xlate").unwrap().0);
+        let freq = &blocks[0].params[0];
+        assert_eq!(freq.value.as_deref(), Some("1000.0"));
+        assert_eq!(freq.runtime_port.as_deref(), Some("freq"));
+        assert!(!blocks[0].params[1].is_runtime());
+        // omitted optional parameters report their default
+        assert_eq!(blocks[1].params[0].value.as_deref(), Some("1000"));
+    }
+
+    #[test]
+    fn test_overrides() {
+        let toml = r#"
+[[blocks]]
+name = "xlate"
+type = "FreqXlate"
+[[blocks.parameters]]
+name = "freq_hz"
+type = "f64"
+value = 1000.0
+[[blocks.parameters]]
+name = "sample_rate"
+type = "f64"
+value = 8000.0
+
+[[blocks]]
+name = "meter"
+type = "PowerMeter"
+
+[[connections]]
+from = "xlate"
+to = "meter"
+        "#;
+        let mut loader = FlowgraphLoader::from_str(toml).unwrap();
+        loader.apply_overrides(HashMap::from([
+            (("xlate".to_string(), "freq_hz".to_string()), toml::Value::Float(-250.0)),
+            (("meter".to_string(), "interval_ms".to_string()), toml::Value::Integer(500)),
+        ])).unwrap();
+        loader.dry_run().unwrap();
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap();
+
+        let blocks = loader.describe();
+        assert_eq!(blocks[0].params[0].value.as_deref(), Some("-250.0"));
+        assert_eq!(blocks[1].params[0].value.as_deref(), Some("500"));
+
+        let err = loader.set_parameter("nope", "freq_hz", toml::Value::Float(0.0)).unwrap_err();
+        assert!(matches!(err, LoaderError::UnknownBlock(_)), "{err}");
+        let err = loader.set_parameter("xlate", "freq", toml::Value::Float(0.0)).unwrap_err();
+        assert!(matches!(err, LoaderError::UnknownParameter { .. }), "{err}");
+    }
+
+    #[test]
+    fn test_bypass() {
+        let toml = r#"
+[[blocks]]
+name = "src"
+type = "NullSource"
+dtype = "Complex32"
+
+[[blocks]]
+name = "dc"
+type = "Bypass"
+[[blocks.parameters]]
+name = "bypass"
+type = "bool"
+value = true
+[blocks.inner]
+type = "Apply"
+[[blocks.inner.parameters]]
+name = "function"
+type = "string"
+value = "dc_offset_removal"
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "Complex32"
+
+[[connections]]
+from = "src"
+to = "dc"
+
+[[connections]]
+from = "dc"
+to = "snk"
+        "#;
+        let mut loader = FlowgraphLoader::from_str(toml).unwrap();
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap();
+        assert_eq!(loader.describe()[1].params[0].runtime_port.as_deref(), Some("bypass"));
+
+        let toml = toml.replace("dc_offset_removal", "phase_detector_iir");
+        assert!(matches!(build_err(&toml), LoaderError::InvalidParameter { .. }));
+    }
+
+    fn build_err(toml: &str) -> LoaderError {
+        let mut loader = FlowgraphLoader::from_str(toml).unwrap();
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap_err()
+    }
+
+    #[test]
+    fn test_error_connections() {
+        let toml = r#"
+[[blocks]]
+name = "src1"
+type = "NullSource"
+dtype = "f32"
+
+[[blocks]]
+name = "src2"
+type = "NullSource"
+dtype = "f32"
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "f32"
+
+[[connections]]
+from = "src1"
+to = "snk"
+
+[[connections]]
+from = "src2"
+to = "snk"
+
+[[connections]]
+from = "src2"
+to = "snk"
+to_port = "nope"
+        "#;
+        // strict: stop at the first error
+        let err = build_err(toml);
+        assert!(matches!(err, LoaderError::DuplicateInput { index: 1, first: 0, .. }), "{err}");
+        assert!(err.to_string().contains("input 'snk'.input is already used"), "{err}");
+
+        let mut loader = FlowgraphLoader::from_str(toml).unwrap();
+        loader.set_strict(false);
+        let mut fg = Flowgraph::new();
+        let err = loader.build(&mut fg).unwrap_err();
+        let LoaderError::Connections(errors) = &err else {
+            panic!("unexpected error {err}");
+        };
+        assert_eq!(errors.len(), 2, "{err}");
+        assert!(matches!(errors[0], LoaderError::DuplicateInput { index: 1, .. }));
+        assert!(matches!(
+            errors[1],
+            LoaderError::UnknownPort { ref block, ref port } if block == "snk" && port == "nope"
+        ));
+        assert!(err.to_string().starts_with("2 connection errors:"), "{err}");
+    }
+
+    #[test]
+    fn test_template() {
+        let toml = r#"
+[templates.xlate]
+type = "FreqXlate"
+[[templates.xlate.parameters]]
+name = "freq_hz"
+type = "f64"
+value = 1000.0
+[[templates.xlate.parameters]]
+name = "sample_rate"
+type = "f64"
+value = 8000.0
+
+[[blocks]]
+name = "xlate"
+template = "xlate"
+[[blocks.parameters]]
+name = "freq_hz"
+type = "f64"
+value = -500.0
+        "#;
+        let mut loader = FlowgraphLoader::from_str(toml).unwrap();
+        let block = &loader.config.blocks[0];
+        assert_eq!(block.block_type, "FreqXlate");
+        assert!(block.template.is_none());
+        let params: Vec<(&str, f64)> = block
+            .parameters
+            .iter()
+            .map(|p| (p.name.as_str(), p.value.as_float().unwrap()))
+            .collect();
+        assert_eq!(params, vec![("freq_hz", -500.0), ("sample_rate", 8000.0)]);
+
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap();
+        assert_eq!(loader.describe()[0].params[0].value.as_deref(), Some("-500.0"));
+    }
+
+    #[test]
+    fn test_error_unknown_template() {
+        let toml = r#"
+[[blocks]]
+name = "xlate"
+template = "missing"
+        "#;
+        assert!(matches!(
+            FlowgraphLoader::from_str(toml),
+            Err(LoaderError::UnknownTemplate { .. })
+        ));
+        let toml = "[[blocks]]\nname = \"xlate\"\n";
+        assert!(matches!(
+            FlowgraphLoader::from_str(toml),
+            Err(LoaderError::MissingBlockType(_))
+        ));
+    }
+
+    #[test]
+    fn test_on_start() {
+        let toml = r#"
+[[blocks]]
+name = "xlate"
+type = "FreqXlate"
+[[blocks.parameters]]
+name = "freq_hz"
+type = "f64"
+value = 1000.0
+[[blocks.parameters]]
+name = "sample_rate"
+type = "f64"
+value = 8000.0
+
+[[on_start]]
+block = "xlate"
+port = "freq"
+type = "f64"
+value = -250.0
+        "#;
+        let mut loader = FlowgraphLoader::from_str(toml).unwrap();
+        loader.validate().unwrap();
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap();
+
+        let messages = loader.on_start_messages();
+        assert_eq!(messages.len(), 1);
+        let (block, port, pmt) = &messages[0];
+        assert_eq!(loader.get_block(block), loader.get_block("xlate"));
+        assert!(loader.get_block(block).is_some());
+        assert_eq!(port, "freq");
+        assert_eq!(pmt, &Pmt::F64(-250.0));
+
+        // integers are accepted for floats
+        let int_toml = toml.replace("value = -250.0", "value = -250");
+        let mut loader = FlowgraphLoader::from_str(&int_toml).unwrap();
+        loader.build(&mut Flowgraph::new()).unwrap();
+        assert_eq!(loader.on_start_messages()[0].2, Pmt::F64(-250.0));
+
+        let toml = toml.replace("value = -250.0", "value = \"fast\"");
+        assert!(matches!(
+            FlowgraphLoader::from_str(&toml),
+            Err(LoaderError::InvalidParameter { .. })
+        ));
+    }
+
+    #[test]
+    fn test_port_handle() {
+        let mut loader = FlowgraphLoader::from_str(PARAM_TYPES).unwrap();
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap();
+
+        let port = loader.port_handle("xlate", "freq").unwrap();
+        assert_eq!(Some(port.block), loader.get_block("xlate"));
+        assert_eq!(port.port, "freq");
+
+        assert!(matches!(
+            loader.port_handle("xlate", "nope"),
+            Err(LoaderError::UnknownMessageInput { ref block, ref port, ref valid })
+                if block == "xlate" && port == "nope" && valid.iter().any(|v| v == "freq")
+        ));
+        assert!(matches!(
+            loader.port_handle("nope", "freq"),
+            Err(LoaderError::UnknownBlock(ref b)) if b == "nope"
+        ));
+    }
+
+    #[test]
+    fn test_port_map() {
+        let toml = format!(
+            "{PARAM_TYPES}\n\
+             [[ports]]\nname = \"tune\"\nblock = \"xlate\"\nport = \"freq\"\n"
+        );
+        let path = std::env::temp_dir().join(format!("port_map_{}.toml", std::process::id()));
+        std::fs::write(&path, &toml).unwrap();
+        let res = load_flowgraph_scriptable(&path);
+        std::fs::remove_file(&path).unwrap();
+        let (_fg, ports) = res.unwrap();
+
+        let mut loader = FlowgraphLoader::from_str(&toml).unwrap();
+        loader.validate().unwrap();
+        loader.build(&mut Flowgraph::new()).unwrap();
+        let tune = ports.get("tune").unwrap();
+        assert_eq!(*tune, loader.port_handle("xlate", "freq").unwrap());
+        assert_eq!(Some(tune.block), loader.get_block("xlate"));
+        assert_eq!(ports.names(), ["tune"]);
+        assert!(ports.get("freq").is_none());
+
+        let wrong_port = toml.replace("port = \"freq\"", "port = \"nope\"");
+        let mut loader = FlowgraphLoader::from_str(&wrong_port).unwrap();
+        loader.build(&mut Flowgraph::new()).unwrap();
+        assert!(matches!(
+            loader.port_map(),
+            Err(LoaderError::UnknownMessageInput { ref port, .. }) if port == "nope"
+        ));
+
+        let twice = format!("{toml}[[ports]]\nname = \"tune\"\nblock = \"xlate\"\nport = \"freq\"\n");
+        let loader = FlowgraphLoader::from_str(&twice).unwrap();
+        assert!(matches!(loader.validate(), Err(LoaderError::DuplicatePort(ref n)) if n == "tune"));
+    }
+
+    #[test]
+    fn test_meta() {
+        let toml = r#"
+[meta]
+title = "WiFi Receiver"
+description = "Decode 802.11a/g/p frames from an SDR"
+category = "Receivers"
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "f32"
+        "#;
+        let loader = FlowgraphLoader::from_str(toml).unwrap();
+        let meta = loader.meta().unwrap();
+        assert_eq!(meta.title.as_deref(), Some("WiFi Receiver"));
+        assert_eq!(meta.category.as_deref(), Some("Receivers"));
+        assert_eq!(FlowgraphMeta::from_toml(toml).as_ref(), Some(meta));
+
+        let toml = "[meta]\ntitle = \"Only a title\"\n\n[[blocks]]\nname = \"snk\"\ntype = \"NullSink\"";
+        let meta = FlowgraphLoader::from_str(toml).unwrap().meta().unwrap().clone();
+        assert_eq!(meta.description, None);
+        assert_eq!(meta.category, None);
+
+        let loader = FlowgraphLoader::from_str("[[blocks]]\nname = \"snk\"\ntype = \"NullSink\"").unwrap();
+        assert!(loader.meta().is_none());
+        assert!(FlowgraphMeta::from_toml("[meta\n").is_none());
+    }
+
+    const CHAIN: &str = r#"
+[[blocks]]
+name = "src"
+type = "NullSource"
+dtype = "Complex32"
+
+[[blocks]]
+name = "throttle"
+type = "Throttle"
+dtype = "Complex32"
+[[blocks.parameters]]
+name = "rate"
+type = "f64"
+value = 1e6
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "Complex32"
+
+[[chains]]
+chain = ["src", "throttle", "snk"]
+    "#;
+
+    #[test]
+    fn test_auto_throttle() {
+        let toml = r#"
+[[blocks]]
+name = "src"
+type = "FileSource"
+[[blocks.parameters]]
+name = "path"
+type = "string"
+value = "recording.cf32"
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "Complex32"
+
+[[connections]]
+from = "src"
+to = "snk"
+        "#;
+        let mut loader = FlowgraphLoader::from_str(toml).unwrap();
+        loader.set_auto_throttle(Some(1e6));
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap();
+        assert!(loader.get_block("src_throttle").is_some());
+
+        let config = loader.config();
+        let throttle = config.blocks.iter().find(|b| b.name == "src_throttle").unwrap();
+        assert_eq!(throttle.block_type, "Throttle");
+        assert_eq!(throttle.dtype.as_deref(), Some("Complex32"));
+        assert_eq!(throttle.parameters[0].value, toml::Value::Float(1e6));
+        let links: Vec<(&str, &str)> = config
+            .connections
+            .iter()
+            .map(|c| (c.from.as_str(), c.to.as_str()))
+            .collect();
+        assert_eq!(links, [("src_throttle", "snk"), ("src", "src_throttle")]);
+
+        // a source that is already throttled is left alone
+        let toml = r#"
+[[blocks]]
+name = "src"
+type = "FileSource"
+[[blocks.parameters]]
+name = "path"
+type = "string"
+value = "recording.cf32"
+
+[[blocks]]
+name = "throttle"
+type = "Throttle"
+[[blocks.parameters]]
+name = "rate"
+type = "f64"
+value = 20e6
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "Complex32"
+
+[[chains]]
+chain = ["src", "throttle", "snk"]
+        "#;
+        let mut loader = FlowgraphLoader::from_str(toml).unwrap();
+        loader.set_auto_throttle(Some(1e6));
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap();
+        assert!(loader.get_block("src_throttle").is_none());
+        assert_eq!(loader.config().blocks.len(), 3);
+        assert!(loader.config().connections.is_empty());
+    }
+
+    #[test]
+    fn test_chain() {
+        let mut loader = FlowgraphLoader::from_str(CHAIN).unwrap();
+        loader.validate().unwrap();
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap();
+
+        // the chain already connected the sink's input
+        let toml = format!("{CHAIN}\n[[connections]]\nfrom = \"src\"\nto = \"snk\"\n");
+        assert!(matches!(
+            build_err(&toml),
+            LoaderError::DuplicateInput { index: 2, first: 0, ref block, ref port }
+                if block == "snk" && port == "input"
+        ));
+
+        let toml = CHAIN.replace(r#""throttle", "snk""#, r#""throttle", "missing""#);
+        let loader = FlowgraphLoader::from_str(&toml).unwrap();
+        assert!(matches!(loader.validate(), Err(LoaderError::UnknownBlock(ref b)) if b == "missing"));
+
+        let toml = CHAIN.replace(r#"chain = ["src", "throttle", "snk"]"#, r#"chain = ["src"]"#);
+        let loader = FlowgraphLoader::from_str(&toml).unwrap();
+        assert!(matches!(loader.validate(), Err(LoaderError::InvalidConnection { .. })));
+    }
+
+    #[test]
+    fn test_chain_ambiguous_port() {
+        // Combine has two stream inputs, in0 and in1
+        let toml = CHAIN.replace(
+            "type = \"Throttle\"\ndtype = \"Complex32\"",
+            "type = \"Combine\"\ninput1_type = \"Complex32\"",
+        ).replace("name = \"rate\"\ntype = \"f64\"\nvalue = 1e6", "name = \"op\"\ntype = \"string\"\nvalue = \"add\"");
+        let LoaderError::InvalidConnection { from, to, reason } = build_err(&toml) else {
+            panic!("expected an invalid connection");
+        };
+        assert_eq!((from.as_str(), to.as_str()), ("src", "throttle"));
+        assert!(reason.contains("in0") && reason.contains("in1"), "{reason}");
+    }
+
+    #[test]
+    fn test_error_unknown_block_type() {
+        let err = build_err("[[blocks]]\nname = \"foo\"\ntype = \"NoSuchBlock\"");
+        assert!(matches!(err, LoaderError::UnknownBlockType(t) if t == "NoSuchBlock"));
+    }
+
+    #[test]
+    fn test_error_missing_parameter() {
+        let err = build_err("[[blocks]]\nname = \"decoder\"\ntype = \"zigbee::Decoder\"");
+        assert!(matches!(
+            err,
+            LoaderError::MissingParameter { ref block, ref name } if block == "decoder" && name == "threshold"
+        ));
+    }
+
+    #[test]
+    fn test_error_type_mismatch() {
+        let toml = r#"
+[[blocks]]
+name = "decoder"
+type = "zigbee::Decoder"
+[[blocks.parameters]]
+name = "threshold"
+type = "usize"
+value = "twelve"
+        "#;
+        let err = build_err(toml);
+        assert!(matches!(
+            err,
+            LoaderError::TypeMismatch { ref block, ref name, .. } if block == "decoder" && name == "threshold"
+        ));
+    }
+
+    const PARAM_TYPES: &str = r#"
+[[blocks]]
+name = "xlate"
+type = "FreqXlate"
+[[blocks.parameters]]
+name = "freq_hz"
+type = "f64"
+value = 1000
+[[blocks.parameters]]
+name = "sample_rate"
+type = "f64"
+value = 4e6
+    "#;
+
+    #[test]
+    fn test_param_types() {
+        let loader = FlowgraphLoader::from_str(PARAM_TYPES).unwrap();
+        loader.config().validate_param_types().unwrap();
+        loader.validate().unwrap();
+    }
+
+    #[test]
+    fn test_error_param_type() {
+        let toml = PARAM_TYPES.replace("value = 4e6", "value = \"4 MHz\"");
+        let loader = FlowgraphLoader::from_str(&toml).unwrap();
+        let err = loader.validate().unwrap_err();
+        assert!(matches!(
+            err,
+            LoaderError::TypeMismatch { ref block, ref name, ref expected }
+                if block == "xlate" && name == "sample_rate" && expected == "f64"
+        ));
+    }
+
+    #[test]
+    fn test_error_unknown_port() {
+        let toml = r#"
+[[blocks]]
+name = "src"
+type = "NullSource"
+dtype = "u8"
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "u8"
+
+[[connections]]
+from = "src"
+to = "snk"
+to_port = "nope"
+        "#;
+        let err = build_err(toml);
+        assert!(matches!(
+            err,
+            LoaderError::UnknownPort { ref block, ref port } if block == "snk" && port == "nope"
+        ));
+    }
+
+    #[test]
+    fn test_error_unknown_block() {
+        let toml = r#"
+[[blocks]]
+name = "src"
+type = "NullSource"
+
+[[connections]]
+from = "src"
+to = "missing"
+        "#;
+        let err = build_err(toml);
+        assert!(matches!(err, LoaderError::UnknownBlock(b) if b == "missing"));
+    }
+
+    #[test]
+    fn test_error_parse() {
+        let err = FlowgraphLoader::from_str("[[blocks]\nname =").err().unwrap();
+        assert!(matches!(err, LoaderError::ParseError(_)));
+
+        // still usable with anyhow
+        let err: anyhow::Error = err.into();
+        assert!(err.to_string().contains("Failed to parse TOML"));
+    }
+
+    #[test]
+    fn test_error_malformed_mac() {
+        let toml = r#"
+[[blocks]]
+name = "wifi_mac"
+type = "wifi::Mac"
+[[blocks.parameters]]
+name = "src_addr"
+type = "string"
+value = "42:42:zz:42:42:42"
+        "#;
+        let err = build_err(toml);
+        assert!(matches!(
+            err,
+            LoaderError::InvalidParameter { ref block, ref name, ref reason }
+                if block == "wifi_mac" && name == "src_addr" && reason.contains("octet 3 ('zz')")
+        ));
+    }
+
+    const MESSAGE_BLOCKS: &str = r#"
+[[blocks]]
+name = "mac1"
+type = "zigbee::Mac"
+
+[[blocks]]
+name = "mac2"
+type = "zigbee::Mac"
+
+[[blocks]]
+name = "ctrl1"
+type = "FlowgraphController"
+
+[[blocks]]
+name = "ctrl2"
+type = "FlowgraphController"
+"#;
+
+    fn message_connection(from: &str, to: &str, to_port: &str) -> String {
+        format!(
+            "\n[[message_connections]]\nfrom = \"{from}\"\nfrom_port = \"rxed\"\nto = \"{to}\"\nto_port = \"{to_port}\"\n"
+        )
+    }
+
+    #[test]
+    fn test_message_fan_out_fan_in() {
+        let toml = [
+            MESSAGE_BLOCKS.to_string(),
+            message_connection("mac1", "ctrl1", "rx"),
+            message_connection("mac1", "ctrl2", "rx"),
+            message_connection("mac2", "ctrl1", "rx"),
+            // repeated connections are left to the runtime
+            message_connection("mac1", "ctrl1", "rx"),
+        ]
+        .concat();
+        let mut loader = FlowgraphLoader::from_str(&toml).unwrap();
+        loader.validate().unwrap();
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap();
+    }
+
+    fn combine(types: &str, params: &str) -> String {
+        format!(
+            r#"
+[[blocks]]
+name = "comb"
+type = "Combine"
+{types}
+{params}
+            "#
+        )
+    }
+
+    fn combine_input_is<T: CpuSample>(toml: &str) -> bool {
+        let mut loader = FlowgraphLoader::from_str(toml).unwrap();
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap();
+        let block = fg.get_block(loader.get_block("comb").unwrap()).unwrap();
+        let mut block = block.try_lock().unwrap();
+        block
+            .stream_input("in1")
+            .unwrap()
+            .as_any_mut()
+            .downcast_mut::<DefaultCpuReader<T>>()
+            .is_some()
+    }
+
+    #[test]
+    fn test_combine_op() {
+        let add = "[[blocks.parameters]]\nname = \"op\"\ntype = \"string\"\nvalue = \"add\"";
+        assert!(combine_input_is::<f32>(&combine("", add)));
+        let f32_types = "input1_type = \"f32\"\ninput2_type = \"f32\"\noutput_type = \"f32\"";
+        assert!(combine_input_is::<f32>(&combine(f32_types, add)));
+        assert!(combine_input_is::<i16>(&combine("input1_type = \"i16\"", add)));
+
+        let mixed = "input1_type = \"f32\"\noutput_type = \"f64\"";
+        assert!(matches!(
+            build_err(&combine(mixed, add)),
+            LoaderError::InvalidParameter { ref name, .. } if name == "output_type"
+        ));
+        // no ordering on complex numbers
+        let max = add.replace("add", "max");
+        assert!(combine_input_is::<f32>(&combine("", &max)));
+        assert!(matches!(
+            build_err(&combine("input1_type = \"Complex32\"", &max)),
+            LoaderError::InvalidParameter { ref name, .. } if name == "op"
+        ));
+        assert!(matches!(
+            build_err(&combine("input1_type = \"u8\"", add)),
+            LoaderError::InvalidParameter { ref name, .. } if name == "input1_type"
+        ));
+    }
+
+    #[test]
+    fn test_combine_closure() {
+        let conj = "[[blocks.parameters]]\nname = \"function\"\ntype = \"closure\"\nvalue = \"multiply_conj\"";
+        assert!(combine_input_is::<Complex32>(&combine("", conj)));
+        assert!(combine_input_is::<Complex32>(&combine("output_type = \"Complex32\"", conj)));
+        assert!(matches!(
+            build_err(&combine("input1_type = \"f32\"", conj)),
+            LoaderError::InvalidParameter { ref name, .. } if name == "input1_type"
+        ));
+        assert!(matches!(build_err(&combine("", "")), LoaderError::MissingParameter { .. }));
+    }
+
+    #[test]
+    fn test_error_message_self_connection() {
+        let toml = [MESSAGE_BLOCKS.to_string(), message_connection("mac1", "mac1", "tx")].concat();
+        let err = FlowgraphLoader::from_str(&toml).unwrap().validate().unwrap_err();
+        assert!(matches!(
+            err,
+            LoaderError::InvalidConnection { ref from, ref to, .. }
+                if from == "mac1.rxed" && to == "mac1.tx"
+        ), "{err}");
+        // reported before `connect_message` would assert on it
+        assert!(matches!(build_err(&toml), LoaderError::InvalidConnection { .. }));
+    }
+
+    #[test]
+    fn test_error_unknown_message_input() {
+        let toml = r#"
+[[blocks]]
+name = "flowgraph_controller"
+type = "FlowgraphController"
+
+[[blocks]]
+name = "mac"
+type = "zigbee::Mac"
+
+[[message_connections]]
+from = "mac"
+from_port = "rxed"
+to = "flowgraph_controller"
+to_port = "ctrl"
+        "#;
+        let err = build_err(toml);
+        let msg = err.to_string();
+        assert!(matches!(
+            err,
+            LoaderError::UnknownMessageInput { ref block, ref port, ref valid }
+                if block == "flowgraph_controller"
+                    && port == "ctrl"
+                    && ["control", "tx", "rx"].iter().all(|p| valid.iter().any(|v| v == p))
+        ));
+        assert!(msg.contains("control, stop, tx, rx"), "{msg}");
+
+        let toml = toml.replace("from_port = \"rxed\"", "from_port = \"rx\"");
+        let err = build_err(&toml);
+        let msg = err.to_string();
+        assert!(matches!(
+            err,
+            LoaderError::UnknownMessageOutput { ref block, ref port, ref valid }
+                if block == "mac" && port == "rx" && valid.iter().any(|v| v == "rxed")
+        ));
+        assert!(msg.contains("message output 'rx'"), "{msg}");
+    }
+    #[test]
+    fn test_profile_report() {
+        let toml = r#"
+[[blocks]]
+name = "src"
+type = "NullSource"
+dtype = "u8"
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "u8"
+
+[[connections]]
+from = "src"
+to = "snk"
+        "#;
+        let mut loader = FlowgraphLoader::from_str(toml).unwrap();
+        loader.set_profile(true);
+        loader.build(&mut Flowgraph::new()).unwrap();
+        // a rebuild replaces the counters of the previous build
+        let mut fg = Flowgraph::new();
+        loader.build(&mut fg).unwrap();
+        assert_eq!(loader.profile_report().len(), 2);
+
+        let rt = Runtime::new();
+        let shutdown = futuresdr::async_io::Timer::after(Duration::from_millis(200));
+        super::super::run_until(&rt, fg, async {
+            shutdown.await;
+        })
+        .unwrap();
+
+        let report = loader.profile_report();
+        let mut names: Vec<&str> = report.iter().map(|p| p.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["snk", "src"]);
+        assert!(report.windows(2).all(|w| w[0].wall_time >= w[1].wall_time));
+        for p in &report {
+            assert!(p.calls > 0, "{p:?}");
+            assert!(p.wall_time > Duration::ZERO, "{p:?}");
+            assert!(p.average_wall_time <= p.wall_time, "{p:?}");
+        }
+        assert!(report.iter().any(|p| p.block_type == "NullSource"));
+    }
+
+    #[test]
+    fn seify_channels() {
+        let toml = |channels: &str, port: &str| {
+            format!(
+                r#"
+[[blocks]]
+name = "src"
+type = "seify::Source"
+[[blocks.parameters]]
+name = "frequency"
+type = "f64"
+value = 2.45e9
+[[blocks.parameters]]
+name = "sample_rate"
+type = "f64"
+value = 4e6
+[[blocks.parameters]]
+name = "gain"
+type = "f64"
+value = 0.0
+[[blocks.parameters]]
+name = "args"
+type = "string"
+value = "driver=dummy"
+[[blocks.parameters]]
+name = "channels"
+type = "array"
+value = {channels}
+
+[[blocks]]
+name = "snk0"
+type = "NullSink"
+dtype = "Complex32"
+
+[[blocks]]
+name = "snk1"
+type = "NullSink"
+dtype = "Complex32"
+
+[[connections]]
+from = "src"
+from_port = "outputs[0]"
+to = "snk0"
+
+[[connections]]
+from = "src"
+from_port = "{port}"
+to = "snk1"
+"#
+            )
+        };
+
+        FlowgraphLoader::from_str(&toml("[0, 1]", "outputs[1]"))
+            .unwrap()
+            .dry_run()
+            .unwrap();
+        assert!(FlowgraphLoader::from_str(&toml("[0, 1]", "outputs[2]"))
+            .unwrap()
+            .dry_run()
+            .is_err());
+        let err = FlowgraphLoader::from_str(&toml("[0, 0]", "outputs[1]"))
+            .unwrap()
+            .dry_run()
+            .unwrap_err();
+        assert!(matches!(err, LoaderError::InvalidParameter { .. }), "{err}");
+
+        // the dummy device has a single channel
+        let mut loader = FlowgraphLoader::from_str(&toml("[0, 1]", "outputs[1]")).unwrap();
+        let err = loader.build(&mut Flowgraph::new()).unwrap_err();
+        assert!(
+            matches!(&err, LoaderError::InvalidParameter { name, .. } if name == "channels"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_config_diff() {
+        let old = r#"
+[[blocks]]
+name = "src"
+type = "NullSource"
+dtype = "u8"
+
+[[blocks]]
+name = "head"
+type = "Head"
+dtype = "u8"
+[[blocks.parameters]]
+name = "n_items"
+type = "u64"
+value = 100
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "u8"
+
+[[connections]]
+from = "src"
+to = "head"
+
+[[connections]]
+from = "head"
+to = "snk"
+        "#;
+        let new = r#"
+[[blocks]]
+name = "src"
+type = "NullSource"
+dtype = "u8"
+
+[[blocks]]
+name = "head"
+type = "Head"
+dtype = "u8"
+[[blocks.parameters]]
+name = "n_items"
+type = "u64"
+value = 200
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "u8"
+
+[[blocks]]
+name = "snk2"
+type = "NullSink"
+dtype = "u8"
+
+[[connections]]
+from = "src"
+to = "head"
+
+[[connections]]
+from = "head"
+to = "snk2"
+        "#;
+        let old = FlowgraphLoader::from_str(old).unwrap();
+        let new = FlowgraphLoader::from_str(new).unwrap();
+
+        assert!(old.config().diff(old.config()).is_empty());
+
+        let diff = old.config().diff(new.config());
+        assert_eq!(diff.added_blocks, ["snk2"]);
+        assert!(diff.removed_blocks.is_empty());
+        assert_eq!(
+            diff.modified_blocks,
+            [BlockDiff {
+                name: "head".to_string(),
+                fields: vec![],
+                parameters: vec!["n_items".to_string()],
+            }]
+        );
+        assert_eq!(diff.added_connections, ["head.output -> snk2.input"]);
+        assert_eq!(diff.removed_connections, ["head.output -> snk.input"]);
+        assert!(diff.modified_connections.is_empty());
+
+        let reverse = new.config().diff(old.config());
+        assert_eq!(reverse.removed_blocks, ["snk2"]);
+        assert!(reverse.added_blocks.is_empty());
+    }
+
+    #[test]
+    fn test_scheduler_config() {
+        let toml = |scheduler: &str| {
+            format!(
+                r#"
+[[blocks]]
+name = "src"
+type = "NullSource"
+dtype = "u8"
+[blocks.scheduler]
+{scheduler}
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "u8"
+
+[[connections]]
+from = "src"
+to = "snk"
+"#
+            )
+        };
+
+        let mut loader = FlowgraphLoader::from_str(&toml("cpu_affinity = [0]")).unwrap();
+        let scheduler = loader.config().blocks[0].scheduler.clone().unwrap();
+        assert_eq!(scheduler.cpu_affinity, [0]);
+        assert_eq!(scheduler.priority, None);
+        assert!(loader.config().blocks[1].scheduler.is_none());
+        loader.build(&mut Flowgraph::new()).unwrap();
+        let warnings = loader.scheduler_warnings();
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        assert!(warnings[0].contains("FlowScheduler"), "{warnings:?}");
+        let src = loader.get_block("src").unwrap();
+        assert_eq!(loader.pinned_blocks(), HashMap::from([(src, 0)]));
+
+        // a rebuild starts over
+        let mut fg = Flowgraph::new();
+        loader.dry_run().unwrap();
+        assert!(loader.pinned_blocks().is_empty());
+        loader.build(&mut fg).unwrap();
+        assert_eq!(loader.scheduler_warnings().len(), 1);
+        assert_eq!(loader.pinned_blocks().len(), 1);
+
+        // settings that cannot be applied are ignored
+        let unsupported = toml("cpu_affinity = [100000]\npriority = \"high\"");
+        let mut loader = FlowgraphLoader::from_str(&unsupported).unwrap();
+        loader.dry_run().unwrap();
+        loader.build(&mut Flowgraph::new()).unwrap();
+        assert!(loader.pinned_blocks().is_empty());
+        let warnings = loader.scheduler_warnings();
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert!(warnings.iter().all(|w| w.starts_with("src: ")), "{warnings:?}");
+
+        let mut loader = FlowgraphLoader::from_str(&toml("priority = \"urgent\"")).unwrap();
+        let err = loader.build(&mut Flowgraph::new()).unwrap_err();
+        assert!(matches!(err, LoaderError::InvalidParameter { .. }), "{err}");
+    }
 }
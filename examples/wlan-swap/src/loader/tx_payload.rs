@@ -0,0 +1,51 @@
+//! TX Console Payloads
+//!
+//! Text typed into the GUI's TX console is sent as the UTF-8 bytes of the text, unless it
+//! starts with `hex:`, in which case the rest is decoded as hexadecimal digits into raw bytes,
+//! e.g., `hex:41 88 ff 00`. This allows sending frames that are not valid UTF-8.
+
+/// Prefix of a payload given as hexadecimal digits
+pub const HEX_PREFIX: &str = "hex:";
+
+/// Bytes to send for a console input
+///
+/// Whitespace between the hex digits is ignored. Returns an error for non-hex characters, an
+/// odd number of digits, or an empty hex payload.
+pub fn parse_tx_payload(s: &str) -> Result<Vec<u8>, String> {
+    let Some(hex) = s.strip_prefix(HEX_PREFIX) else {
+        return Ok(s.as_bytes().to_vec());
+    };
+    let digits: String = hex.chars().filter(|c| !c.is_whitespace()).collect();
+    if digits.is_empty() {
+        return Err("empty hex payload".to_string());
+    }
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(format!("'{}' is not a hex digit", c));
+    }
+    if !digits.len().is_multiple_of(2) {
+        return Err("odd number of hex digits".to_string());
+    }
+    Ok((0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).unwrap())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_and_hex() {
+        assert_eq!(parse_tx_payload("hello").unwrap(), b"hello");
+        assert_eq!(parse_tx_payload("hex:41 88 ff00").unwrap(), [0x41, 0x88, 0xff, 0x00]);
+        assert_eq!(parse_tx_payload("HEX:41").unwrap(), b"HEX:41");
+    }
+
+    #[test]
+    fn malformed_hex() {
+        assert!(parse_tx_payload("hex:").is_err());
+        assert!(parse_tx_payload("hex:4g").unwrap_err().contains("'g'"));
+        assert!(parse_tx_payload("hex:418").unwrap_err().contains("odd"));
+    }
+}
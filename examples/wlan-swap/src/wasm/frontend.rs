@@ -1,4 +1,5 @@
 use any_spawner::Executor;
+use futuresdr::runtime::FlowgraphDescription;
 use futuresdr::runtime::FlowgraphId;
 use futuresdr::runtime::Pmt;
 use leptos::html::Span;
@@ -12,12 +13,62 @@ use prophecy::FlowgraphMermaid;
 use prophecy::ListSelector;
 use prophecy::RadioSelector;
 use prophecy::RuntimeHandle;
+use std::collections::VecDeque;
 use std::rc::Rc;
 
+use crate::loader::block_info::blocks_from_pmt;
+use crate::loader::retry_on_timeout;
+use crate::loader::CallError;
+use crate::loader::FlowgraphMeta;
+use crate::loader::ParamInfo;
+use crate::loader::parse_tx_payload;
+use crate::loader::RxMessage;
+use crate::loader::RETRY_STATUS;
+use crate::wifi::Modulation;
+
+/// Time without a heartbeat on the RX WebSocket after which the backend is shown as dead
+const HEARTBEAT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Number of messages kept in the MAC console displays
+const CONSOLE_MESSAGES: usize = 50;
+
+/// Time to wait for the backend to answer a call before retrying it once
+const CALL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Call a handler of the flowgraph, retrying once if the backend does not answer in time
+///
+/// `on_retry` is run before the retry, to show that the call is still pending.
+async fn call_with_timeout(
+    fg_handle: &FlowgraphHandle,
+    block_id: usize,
+    handler: &'static str,
+    pmt: Pmt,
+    on_retry: impl FnOnce(),
+) -> Result<(), CallError<prophecy::Error>> {
+    retry_on_timeout(
+        || {
+            let mut fg_handle = fg_handle.clone();
+            let pmt = pmt.clone();
+            async move { fg_handle.call(block_id, handler, pmt).await }
+        },
+        || gloo_timers::future::sleep(CALL_TIMEOUT),
+        on_retry,
+    )
+    .await
+}
+
+/// Append a message to a console display, dropping the oldest ones beyond [`CONSOLE_MESSAGES`]
+fn push_message(msgs: &mut VecDeque<String>, msg: String) {
+    msgs.push_back(msg);
+    while msgs.len() > CONSOLE_MESSAGES {
+        msgs.pop_front();
+    }
+}
+
 #[component]
 pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
-    let (rx_messages, set_rx_messages) = signal(Vec::<String>::new());
-    let (tx_messages, set_tx_messages) = signal(Vec::<String>::new());
+    let (rx_messages, set_rx_messages) = signal(VecDeque::<String>::new());
+    let (tx_messages, set_tx_messages) = signal(VecDeque::<String>::new());
     let (tx_input, set_tx_input) = signal(String::new());
     let (status_msg, set_status_msg) = signal(String::new());
     let (auto_send_active, set_auto_send_active) = signal(false);
@@ -29,6 +80,15 @@ pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
     let (benchmark_packets_at_gain, set_benchmark_packets_at_gain) = signal(0u32);
     let (benchmark_total, set_benchmark_total) = signal(0u64);
     let (benchmark_repeat, set_benchmark_repeat) = signal(0u64);
+    // Set once the backend signals that the flowgraph finished (e.g., end of a file)
+    let (finished, set_finished) = signal(false);
+    // Page time (ms) of the last heartbeat, and the page time, updated every second, to age it
+    let (last_heartbeat, set_last_heartbeat) = signal(None::<f64>);
+    let (now, set_now) = signal(leptos::web_sys::js_sys::Date::now());
+    set_interval(
+        move || set_now.set(leptos::web_sys::js_sys::Date::now()),
+        std::time::Duration::from_secs(1),
+    );
     // Clone fg_handle for all usages upfront
     let fg_handle_for_auto = fg_handle.clone();
     let fg_handle_for_benchmark = fg_handle.clone();
@@ -80,20 +140,20 @@ pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
                         set_benchmark_packets_at_gain.set(0);
                         
                         // Set new gain on SDR sink (block 5, gain handler)
-                        let mut fg_for_gain = fg_handle_clone.clone();
+                        let fg_for_gain = fg_handle_clone.clone();
                         let gain_pmt = Pmt::F64(new_gain as f64);
                         spawn_local(async move {
-                            let _ = fg_for_gain.call(5, "gain", gain_pmt).await;
+                            let on_retry = move || set_status_msg(RETRY_STATUS.to_string());
+                            if let Err(e) = call_with_timeout(&fg_for_gain, 5, "gain", gain_pmt, on_retry).await {
+                                set_status_msg(format!("✗ Failed to set gain: {}", e));
+                            }
                         });
                         leptos::logging::log!("Benchmark: switched to gain {} dB", new_gain);
                     }
                     
                     // Update TX display
                     set_tx_messages.update(|msgs| {
-                        msgs.push(format!("[Benchmark g={}] {}", gain, msg_for_display));
-                        if msgs.len() > 20 {
-                            msgs.remove(0);
-                        }
+                        push_message(msgs, format!("[Benchmark g={}] {}", gain, msg_for_display));
                     });
                 },
                 std::time::Duration::from_millis(100), // 100 packets/sec
@@ -135,10 +195,7 @@ pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
                     
                     // Update TX display
                     set_tx_messages.update(|msgs| {
-                        msgs.push(format!("[Auto] {}", msg_for_display));
-                        if msgs.len() > 20 {
-                            msgs.remove(0);
-                        }
+                        push_message(msgs, format!("[Auto] {}", msg_for_display));
                     });
                 },
                 std::time::Duration::from_secs(1),
@@ -171,31 +228,55 @@ pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
             }
         };
         
+        // receive binary frames (e.g., Pmt::Blob) as ArrayBuffer, so they can be decoded in place
+        ws.set_binary_type(leptos::web_sys::BinaryType::Arraybuffer);
+
         let set_rx_messages_clone = set_rx_messages.clone();
         let onmessage_callback = Closure::wrap(Box::new(move |e: leptos::web_sys::MessageEvent| {
-            if let Ok(txt) = e.data().dyn_into::<leptos::wasm_bindgen::JsValue>() {
-                if let Some(msg_str) = txt.as_string() {
-                    if !msg_str.is_empty() {
-                        leptos::logging::log!("RX WebSocket: {}", msg_str);
-                        if msg_str == "initialized" {
-                            leptos::logging::log!("Flowgraph initialized! Auto-refreshing page...");
-                            // Reload page when flowgraph finishes initialization
-                            if let Some(window) = leptos::web_sys::window() {
-                                let _ = window.location().reload();
-                            }
-                        } else if msg_str == "reload" {
-                            leptos::logging::log!("Received reload signal from backend (no page reload)");
-                            // Here you can trigger a signal update or refetch logic instead of reloading the page
-                        } else {
-                            set_rx_messages_clone.update(|msgs| {
-                                msgs.push(msg_str);
-                                if msgs.len() > 20 {
-                                    msgs.remove(0);
-                                }
-                            });
-                        }
+            let data = e.data();
+            let msg = if let Some(msg_str) = data.as_string() {
+                RxMessage::parse(&msg_str)
+            } else if let Ok(buf) = data.dyn_into::<leptos::web_sys::js_sys::ArrayBuffer>() {
+                RxMessage::parse_bytes(&leptos::web_sys::js_sys::Uint8Array::new(&buf).to_vec())
+            } else {
+                leptos::logging::warn!("RX WebSocket: unsupported frame");
+                None
+            };
+            let Some(msg) = msg else {
+                return;
+            };
+
+            leptos::logging::log!("RX WebSocket: {:?}", msg);
+            match msg {
+                RxMessage::Initialized => {
+                    leptos::logging::log!("Flowgraph initialized! Auto-refreshing page...");
+                    // Reload page when flowgraph finishes initialization
+                    if let Some(window) = leptos::web_sys::window() {
+                        let _ = window.location().reload();
                     }
                 }
+                RxMessage::Reload => {
+                    leptos::logging::log!("Received reload signal from backend (no page reload)");
+                    // Here you can trigger a signal update or refetch logic instead of reloading the page
+                    set_finished.set(false);
+                }
+                RxMessage::Finished => {
+                    leptos::logging::log!("Flowgraph finished, stopping auto-send and benchmark");
+                    set_finished.set(true);
+                    set_auto_send_active.set(false);
+                    set_benchmark_active.set(false);
+                }
+                RxMessage::Heartbeat(_) => {
+                    let t = leptos::web_sys::js_sys::Date::now();
+                    set_last_heartbeat.set(Some(t));
+                    set_now.set(t);
+                }
+                RxMessage::Status(status) => {
+                    leptos::logging::log!("Backend status: {}", status);
+                }
+                RxMessage::Text(text) => {
+                    set_rx_messages_clone.update(|msgs| push_message(msgs, text));
+                }
             }
         }) as Box<dyn FnMut(_)>);
         ws.set_onmessage(Some(onmessage_callback.as_ref().unchecked_ref()));
@@ -205,43 +286,46 @@ pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
     let send_message = move |_ev| {
         let text = tx_input.get();
         if !text.is_empty() {
-            let pmt = Pmt::Blob(text.as_bytes().to_vec());
-            let mut fg_handle = fg_handle_for_send.clone();
+            let bytes = match parse_tx_payload(&text) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    set_status_msg(format!("✗ Invalid payload: {}", e));
+                    return;
+                }
+            };
+            let pmt = Pmt::Blob(bytes);
+            let fg_handle = fg_handle_for_send.clone();
             let text_clone = text.clone();
             
             spawn_local(async move {
                 // Send to FlowgraphController (block 0) which forwards to MAC
                 leptos::logging::log!("Sending message via FlowgraphController (block 0)");
-                match fg_handle.call(0, "tx", pmt).await {
+                let on_retry = move || set_status_msg(RETRY_STATUS.to_string());
+                match call_with_timeout(&fg_handle, 0, "tx", pmt, on_retry).await {
                     Ok(_) => {
                         leptos::logging::log!("Message sent successfully");
+                        set_status_msg(format!("Sent: {}", text));
                     }
                     Err(e) => {
                         leptos::logging::error!("Failed to send message: {:?}", e);
+                        set_status_msg(format!("✗ Failed to send: {}", e));
                     }
                 }
+
+                // Clear status after 3 seconds using set_timeout
+                set_timeout(
+                    move || {
+                        set_status_msg(String::new());
+                    },
+                    std::time::Duration::from_secs(3),
+                );
             });
             
             // Add to TX messages display
-            set_tx_messages.update(|msgs| {
-                msgs.push(format!("[Manual] {}", text_clone));
-                // Keep only last 20 messages
-                if msgs.len() > 20 {
-                    msgs.remove(0);
-                }
-            });
+            set_tx_messages.update(|msgs| push_message(msgs, format!("[Manual] {}", text_clone)));
             
             set_tx_input(String::new());
-            set_status_msg(format!("Sent: {}", text));
-            
-            // Clear status after 3 seconds using set_timeout
-            let set_status_msg_clone = set_status_msg.clone();
-            set_timeout(
-                move || {
-                    set_status_msg_clone(String::new());
-                },
-                std::time::Duration::from_secs(3),
-            );
+            set_status_msg(format!("Sending: {}", text_clone));
         }
     };
 
@@ -263,9 +347,12 @@ pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
                 set_benchmark_total.set(0);
                 
                 // Set initial gain to 88
-                let mut fg = fg_handle_for_toggle.clone();
+                let fg = fg_handle_for_toggle.clone();
                 spawn_local(async move {
-                    let _ = fg.call(0, "gain", Pmt::F64(88.0)).await;
+                    let on_retry = move || set_status_msg(RETRY_STATUS.to_string());
+                    if let Err(e) = call_with_timeout(&fg, 0, "gain", Pmt::F64(88.0), on_retry).await {
+                        set_status_msg(format!("✗ Failed to set gain: {}", e));
+                    }
                 });
             }
         }
@@ -276,14 +363,31 @@ pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
             <div class="flex justify-between items-center mb-4">
                 <h2 class="text-lg text-white">"MAC Console"</h2>
                 <div class="flex items-center gap-4">
+                    {move || match last_heartbeat.get() {
+                        None => view! {
+                            <span class="text-gray-400 text-sm">"● No heartbeat yet"</span>
+                        }.into_any(),
+                        Some(t) => {
+                            let age = ((now.get() - t) / 1000.0).max(0.0);
+                            let class = if age > HEARTBEAT_TIMEOUT.as_secs_f64() {
+                                "text-red-500 text-sm"
+                            } else {
+                                "text-green-400 text-sm"
+                            };
+                            view! {
+                                <span class=class>{format!("● Last seen {:.0}s ago", age)}</span>
+                            }.into_any()
+                        }
+                    }}
                     <button
                         class=move || {
                             if auto_send_active.get() {
                                 "bg-green-600 hover:bg-green-700 text-white px-4 py-2 rounded flex items-center gap-2"
                             } else {
-                                "bg-gray-600 hover:bg-gray-700 text-white px-4 py-2 rounded flex items-center gap-2"
+                                "bg-gray-600 hover:bg-gray-700 disabled:opacity-50 text-white px-4 py-2 rounded flex items-center gap-2"
                             }
                         }
+                        disabled=move || finished.get()
                         on:click=toggle_auto_send
                     >
                         {move || {
@@ -304,9 +408,10 @@ pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
                             if benchmark_active.get() {
                                 "bg-orange-600 hover:bg-orange-700 text-white px-4 py-2 rounded flex items-center gap-2"
                             } else {
-                                "bg-purple-600 hover:bg-purple-700 text-white px-4 py-2 rounded flex items-center gap-2"
+                                "bg-purple-600 hover:bg-purple-700 disabled:opacity-50 text-white px-4 py-2 rounded flex items-center gap-2"
                             }
                         }
+                        disabled=move || finished.get()
                         on:click=toggle_benchmark
                     >
                         {move || {
@@ -325,6 +430,12 @@ pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
                 </div>
             </div>
             
+            <Show when=move || finished.get()>
+                <div class="bg-yellow-900 border border-yellow-600 text-yellow-200 rounded p-2 mb-4">
+                    "Flowgraph finished: nothing left to send to. Load another flowgraph to continue."
+                </div>
+            </Show>
+            
             // TX Messages Display - DISABLED TO PREVENT MEMORY LEAKS
             /*
             <div class="flex-1 mb-4 flex flex-col">
@@ -340,7 +451,7 @@ pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
                         } else {
                             view! {
                                 <div>
-                                    {messages.iter().rev().take(20).enumerate().map(|(i, msg)| {
+                                    {messages.iter().rev().enumerate().map(|(i, msg)| {
                                         view! {
                                             <div class="text-blue-400 mb-1">
                                                 <span class="text-gray-400">{format!("[{}] ", messages.len() - i - 1)}</span>
@@ -370,7 +481,7 @@ pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
                         } else {
                             view! {
                                 <div>
-                                    {messages.iter().rev().take(20).enumerate().map(|(i, msg)| {
+                                    {messages.iter().rev().enumerate().map(|(i, msg)| {
                                         view! {
                                             <div class="text-green-400 mb-1">
                                                 <span class="text-gray-400">{format!("[{}] ", messages.len() - i - 1)}</span>
@@ -394,14 +505,15 @@ pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
                         prop:value=tx_input
                         class="flex-1 bg-gray-800 text-white border border-gray-600 rounded p-2 font-mono text-sm"
                         rows="3"
-                        placeholder="Type your message here..."
+                        placeholder="Type your message here, or hex:41 88 ... for raw bytes"
                         on:input=move |ev| {
                             let val = event_target_value(&ev);
                             set_tx_input(val);
                         }
                     />
                     <button
-                        class="bg-blue-600 hover:bg-blue-700 text-white px-4 py-2 rounded"
+                        class="bg-blue-600 hover:bg-blue-700 disabled:opacity-50 text-white px-4 py-2 rounded"
+                        disabled=move || finished.get()
                         on:click=send_message
                     >
                         "Send"
@@ -422,6 +534,92 @@ pub fn MacConsole(fg_handle: FlowgraphHandle) -> impl IntoView {
     }
 }
 
+/// Lists the blocks of the running flowgraph with their parameters.
+///
+/// Parameters with a runtime message port are editable and sent to the block on change; the
+/// others are construction-only and shown read-only.
+#[component]
+pub fn BlockInspector(fg_handle: FlowgraphHandle) -> impl IntoView {
+    let blocks = {
+        let fg_handle = fg_handle.clone();
+        LocalResource::new(move || {
+            let mut fg_handle = fg_handle.clone();
+            async move {
+                // FlowgraphController (block 0) keeps the description sent by the loader
+                match fg_handle.callback(0, "describe", Pmt::Null).await {
+                    Ok(p) => blocks_from_pmt(&p).unwrap_or_default(),
+                    Err(e) => {
+                        leptos::logging::error!("Fetching block parameters failed: {:?}", e);
+                        Vec::new()
+                    }
+                }
+            }
+        })
+    };
+    let (status, set_status) = signal(String::new());
+
+    let param_row = move |block_id: usize, param: ParamInfo| {
+        let value = param.value.clone().unwrap_or_default();
+        let editor = match param.runtime_port.clone() {
+            Some(port) => {
+                let fg_handle = fg_handle.clone();
+                view! {
+                    <input
+                        type="text"
+                        class="bg-gray-800 text-white border border-gray-600 rounded px-2"
+                        value=value
+                        on:change=move |ev| {
+                            let input = event_target_value(&ev);
+                            let Some(pmt) = param.parse_value(&input) else {
+                                set_status(format!("✗ '{}' is not a valid {}", input, param.param_type));
+                                return;
+                            };
+                            let mut fg_handle = fg_handle.clone();
+                            let port = port.clone();
+                            let name = param.name.clone();
+                            spawn_local(async move {
+                                match fg_handle.call(block_id, port.as_str(), pmt).await {
+                                    Ok(_) => set_status(format!("✓ {} = {}", name, input)),
+                                    Err(e) => set_status(format!("✗ Error: {}", e)),
+                                }
+                            });
+                        }
+                    />
+                }.into_any()
+            }
+            None => view! { <span class="text-gray-400">{value}</span> }.into_any(),
+        };
+        view! {
+            <tr>
+                <td class="pr-4">{param.name.clone()}</td>
+                <td class="pr-4 text-gray-400">{param.param_type.clone()}</td>
+                <td>{editor}</td>
+            </tr>
+        }
+    };
+
+    view! {
+        <div class="border-2 border-slate-500 rounded-md m-4 p-4 text-white">
+            <h3 class="mb-2">"Block Parameters"</h3>
+            <div class="text-gray-400 text-sm mb-2">{move || status.get()}</div>
+            {move || {
+                let param_row = param_row.clone();
+                blocks.get().unwrap_or_default().into_iter().filter(|b| !b.params.is_empty()).map(|b| {
+                    let param_row = param_row.clone();
+                    view! {
+                        <div class="mb-4">
+                            <div class="font-bold">{format!("{} ({}, block {})", b.name, b.block_type, b.id)}</div>
+                            <table class="text-sm">
+                                {b.params.into_iter().map(|p| param_row(b.id, p)).collect::<Vec<_>>()}
+                            </table>
+                        </div>
+                    }
+                }).collect::<Vec<_>>()
+            }}
+        </div>
+    }
+}
+
 #[component]
 pub fn Wlan(
     fg_handle: FlowgraphHandle,
@@ -551,9 +749,18 @@ pub fn Wlan(
                             gain_label.get().unwrap().set_inner_text(&format!("gain: {} dB", input.value()));
                             let gain : f64 = input.value().parse().unwrap();
                             let p = Pmt::F64(gain);
-                            let mut fg_handle = fg_handle.clone();
+                            let fg_handle = fg_handle.clone();
                             spawn_local(async move {
-                                let _ = fg_handle.call(0, "gain", p).await;
+                                let label = move |text: String| {
+                                    if let Some(l) = gain_label.get_untracked() {
+                                        l.set_inner_text(&text);
+                                    }
+                                };
+                                let on_retry = move || label(format!("gain: {} dB ({})", gain, RETRY_STATUS));
+                                match call_with_timeout(&fg_handle, 0, "gain", p, on_retry).await {
+                                    Ok(_) => label(format!("gain: {} dB", gain)),
+                                    Err(e) => label(format!("gain: {} dB (✗ {})", gain, e)),
+                                }
                             });
                 }} />
                 <span class="text-white p-2 m-2" node_ref=gain_label>"gain: 60 dB"</span>
@@ -562,7 +769,8 @@ pub fn Wlan(
 
         <div class="flex flex-row gap-4 m-4" style="height: 800px; max-height: 90vh">
             <div class="flex-1 border-2 border-slate-500 rounded-md">
-                <ConstellationSinkDensity width=width />
+                // reference points of the default TX MCS (QPSK 1/2)
+                <ConstellationSinkDensity width=width reference=Modulation::Qpsk.constellation() show_stats=true />
             </div>
             <div class="flex-1 border-2 border-slate-500 rounded-md p-4 overflow-y-auto">
                 <MacConsole fg_handle=fg_handle.clone() />
@@ -577,46 +785,130 @@ pub fn Wlan(
                 }
             }}
         </div>
+
+        <BlockInspector fg_handle=fg_handle.clone() />
     }
 }
 
+/// Time after a switch request during which further switch clicks are ignored
+const SWITCH_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Id of the FlowgraphController block, which is not necessarily the first one
+fn controller_id(desc: &FlowgraphDescription) -> Option<usize> {
+    desc.blocks
+        .iter()
+        .find(|b| b.type_name == "FlowgraphController")
+        .map(|b| b.id.0)
+}
+
+/// Flowgraphs offered by the [`FlowgraphSelector`], with their TOML for the `[meta]` section
+const FLOWGRAPHS: &[(&str, &str)] = &[
+    ("flowgraphs/control_only.toml", include_str!("../../flowgraphs/control_only.toml")),
+    ("flowgraphs/control_loopback.toml", include_str!("../../flowgraphs/control_loopback.toml")),
+    ("flowgraphs/nullstream.toml", include_str!("../../flowgraphs/nullstream.toml")),
+    ("flowgraphs/wifi_loopback.toml", include_str!("../../flowgraphs/wifi_loopback.toml")),
+    ("flowgraphs/wifi_rx.toml", include_str!("../../flowgraphs/wifi_rx.toml")),
+    ("flowgraphs/wifi_tx.toml", include_str!("../../flowgraphs/wifi_tx.toml")),
+    ("flowgraphs/wifi_tx_bis.toml", include_str!("../../flowgraphs/wifi_tx_bis.toml")),
+    ("flowgraphs/zigbee_rx.toml", include_str!("../../flowgraphs/zigbee_rx.toml")),
+    ("flowgraphs/zigbee_rx_v2.toml", include_str!("../../flowgraphs/zigbee_rx_v2.toml")),
+    ("flowgraphs/zigbee_rx_v3.toml", include_str!("../../flowgraphs/zigbee_rx_v3.toml")),
+    ("flowgraphs/zigbee_trx.toml", include_str!("../../flowgraphs/zigbee_trx.toml")),
+    ("flowgraphs/zigbee_tx.toml", include_str!("../../flowgraphs/zigbee_tx.toml")),
+    ("flowgraphs/zigbee_tx_v2.toml", include_str!("../../flowgraphs/zigbee_tx_v2.toml")),
+];
+
 #[component]
 pub fn FlowgraphSelector(
     rt_handle: RuntimeHandle,
     #[prop(optional)] on_switch: Option<Rc<dyn Fn()>>,
 ) -> impl IntoView {
-    let (flowgraphs, set_flowgraphs) = signal(Vec::<String>::new());
+    let (flowgraphs, set_flowgraphs) = signal(Vec::<(String, Option<FlowgraphMeta>)>::new());
     let (selected, set_selected) = signal(String::new());
     let (status, set_status) = signal(String::new());
+    // Instance label, so several flowgraphs (e.g. on two SDRs) can run side by side
+    let (label, set_label) = signal(crate::loader::DEFAULT_LABEL.to_string());
+    // Set while a switch or stop request is pending; the buttons ignore clicks until it clears
+    let (switching, set_switching) = signal(false);
     
-    // Load available flowgraphs - hardcoded list since WASM can't access filesystem
-    // To add new flowgraphs, add them to this list
+    // Load available flowgraphs - embedded at build time since WASM can't access filesystem
+    // To add new flowgraphs, add them to FLOWGRAPHS
     Effect::new(move |_| {
-        let fgs = vec![
-            "flowgraphs/control_only.toml",
-            "flowgraphs/nullstream.toml",
-            "flowgraphs/wifi_loopback.toml",
-            "flowgraphs/wifi_rx.toml",
-            "flowgraphs/wifi_tx.toml",
-            "flowgraphs/wifi_tx_bis.toml",
-            "flowgraphs/zigbee_rx.toml",
-            "flowgraphs/zigbee_rx_v2.toml",
-            "flowgraphs/zigbee_rx_v3.toml",
-            "flowgraphs/zigbee_trx.toml",
-            "flowgraphs/zigbee_tx.toml",
-            "flowgraphs/zigbee_tx_v2.toml",
-        ].into_iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let fgs = FLOWGRAPHS
+            .iter()
+            .map(|(path, toml)| (path.to_string(), FlowgraphMeta::from_toml(toml)))
+            .collect::<Vec<_>>();
         
         if !fgs.is_empty() {
-            set_selected(fgs[0].clone());
+            set_selected(fgs[0].0.clone());
         }
         set_flowgraphs(fgs);
     });
     
+    let rt_handle_for_stop = rt_handle.clone();
+    let on_switch_for_stop = on_switch.clone();
+    let stop_flowgraph = move |_| {
+        if switching.get() {
+            return;
+        }
+        set_switching(true);
+        let fg_label = label.get();
+        set_status(format!("Stopping flowgraph '{}'...", fg_label));
+
+        let rt = rt_handle_for_stop.clone();
+        let callback = on_switch_for_stop.clone();
+        spawn_local(async move {
+            let fg_handle_opt = if let Ok(fg_ids) = rt.get_flowgraphs().await {
+                if let Some(latest_id) = fg_ids.last() {
+                    rt.get_flowgraph(*latest_id).await.ok()
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+
+            match fg_handle_opt {
+                Some(mut fg_handle) => {
+                    // FlowgraphController signals the backend to stop the labelled graph
+                    let controller = match fg_handle.description().await {
+                        Ok(desc) => controller_id(&desc),
+                        Err(_) => None,
+                    };
+                    let Some(controller) = controller else {
+                        set_status("✗ Error: no FlowgraphController in the flowgraph".to_string());
+                        set_timeout(move || set_switching(false), SWITCH_DEBOUNCE);
+                        return;
+                    };
+                    match fg_handle.call(controller, "stop", Pmt::String(fg_label.clone())).await {
+                        Ok(_) => {
+                            set_status(format!("✓ Flowgraph '{}' stopped", fg_label));
+                            if let Some(ref cb) = callback {
+                                cb();
+                            }
+                        }
+                        Err(e) => {
+                            set_status(format!("✗ Error: {}", e));
+                        }
+                    }
+                }
+                None => {
+                    set_status("✗ Error getting latest flowgraph".to_string());
+                }
+            }
+            set_timeout(move || set_switching(false), SWITCH_DEBOUNCE);
+        });
+    };
+
     let switch_flowgraph = move |_| {
+        if switching.get() {
+            return;
+        }
         let fg_path = selected.get();
-        if !fg_path.is_empty() {
-            set_status(format!("Switching to {}...", fg_path));
+        let fg_label = label.get();
+        if !fg_path.is_empty() && !fg_label.is_empty() {
+            set_switching(true);
+            set_status(format!("Switching '{}' to {}...", fg_label, fg_path));
             
             let rt = rt_handle.clone();
             let fg_clone = fg_path.clone();
@@ -637,11 +929,11 @@ pub fn FlowgraphSelector(
                     Some(mut fg_handle) => {
                         // Send PMT message to FlowgraphController (always at block 0)
                         use futuresdr::runtime::Pmt;
-                        let pmt = Pmt::String(fg_clone.clone());
+                        let pmt = Pmt::String(format!("load:{}:{}", fg_label, fg_clone));
                         
                         match fg_handle.call(0, "control", pmt).await {
                             Ok(_) => {
-                                set_status(format!("✓ Switching to {}", fg_clone));
+                                set_status(format!("✓ Switching '{}' to {}", fg_label, fg_clone));
                                 // Notify parent component that switch happened
                                 if let Some(ref cb) = callback {
                                     cb();
@@ -657,6 +949,7 @@ pub fn FlowgraphSelector(
                         set_status(format!("✗ Error getting latest flowgraph"));
                     }
                 }
+                set_timeout(move || set_switching(false), SWITCH_DEBOUNCE);
             });
         }
     };
@@ -672,21 +965,43 @@ pub fn FlowgraphSelector(
                     }
                 >
                     {move || {
-                        flowgraphs.get().iter().map(|fg| {
-                            let is_selected = fg == &selected.get();
+                        flowgraphs.get().into_iter().map(|(fg, meta)| {
+                            let is_selected = fg == selected.get();
+                            let name = crate::loader::get_flowgraph_name(&fg);
+                            let category = crate::loader::get_flowgraph_category(&name, meta.as_ref()).to_string();
+                            let meta = meta.unwrap_or_default();
+                            let title = meta.title.unwrap_or_else(|| fg.clone());
+                            let tooltip = meta.description.unwrap_or_else(|| fg.clone());
                             view! {
-                                <option value=fg.clone() selected=is_selected>
-                                    {fg.clone()}
+                                <option value=fg title=tooltip selected=is_selected>
+                                    {format!("{} – {}", category, title)}
                                 </option>
                             }
                         }).collect::<Vec<_>>()
                     }}
                 </select>
+                <input
+                    type="text"
+                    class="w-32 bg-gray-800 text-white border border-gray-600 rounded p-2"
+                    title="Instance label"
+                    prop:value=move || label.get()
+                    on:input=move |ev| {
+                        set_label(event_target_value(&ev).replace(':', ""));
+                    }
+                />
                 <button
-                    class="bg-green-600 hover:bg-green-700 text-white px-4 py-2 rounded"
+                    class="bg-green-600 hover:bg-green-700 text-white px-4 py-2 rounded disabled:opacity-50"
+                    disabled=move || switching.get()
                     on:click=switch_flowgraph
                 >
-                    "Switch"
+                    {move || if switching.get() { "Switching…" } else { "Switch" }}
+                </button>
+                <button
+                    class="bg-red-600 hover:bg-red-700 text-white px-4 py-2 rounded disabled:opacity-50"
+                    disabled=move || switching.get()
+                    on:click=stop_flowgraph
+                >
+                    "Stop"
                 </button>
             </div>
             <div class="text-gray-400 text-sm mt-2">
@@ -696,12 +1011,78 @@ pub fn FlowgraphSelector(
     }
 }
 
+/// Interval in which the status bar polls the runtime health
+const HEALTH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Format the `health` reply of the flowgraph controller for the status bar
+fn format_health(p: &Pmt) -> String {
+    let Pmt::MapStrPmt(map) = p else {
+        return "Status unavailable".to_string();
+    };
+    let uptime = match map.get("uptime_secs") {
+        Some(Pmt::F64(s)) => {
+            let s = *s as u64;
+            format!("{}:{:02}:{:02}", s / 3600, s / 60 % 60, s % 60)
+        }
+        _ => "?".to_string(),
+    };
+    let flowgraphs = match map.get("flowgraphs") {
+        Some(Pmt::Usize(n)) => n.to_string(),
+        _ => "?".to_string(),
+    };
+    let last_reload = match map.get("last_reload") {
+        Some(Pmt::String(s)) => s.clone(),
+        _ => "none".to_string(),
+    };
+    format!(
+        "Uptime {} | Flowgraphs {} | Last reload: {}",
+        uptime, flowgraphs, last_reload
+    )
+}
+
+/// Call the `health` handler of the controller of the most recently started flowgraph
+async fn runtime_health(rt_handle: &RuntimeHandle) -> Result<Pmt, String> {
+    let fg_ids = rt_handle.get_flowgraphs().await.map_err(|e| e.to_string())?;
+    let id = fg_ids.last().ok_or("no flowgraph running")?;
+    let mut fg_handle = rt_handle.get_flowgraph(*id).await.map_err(|e| e.to_string())?;
+    let desc = fg_handle.description().await.map_err(|e| e.to_string())?;
+    let controller = controller_id(&desc).ok_or("no FlowgraphController in the flowgraph")?;
+    fg_handle
+        .callback(controller, "health", Pmt::Null)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Status bar showing the runtime health
+#[component]
+pub fn StatusBar(rt_handle: RuntimeHandle) -> impl IntoView {
+    let (health, set_health) = signal("Connecting".to_string());
+
+    let poll = move || {
+        let rt_handle = rt_handle.clone();
+        spawn_local(async move {
+            let text = match runtime_health(&rt_handle).await {
+                Ok(p) => format_health(&p),
+                Err(e) => format!("Status unavailable: {}", e),
+            };
+            set_health(text);
+        });
+    };
+    poll();
+    set_interval(poll, HEALTH_INTERVAL);
+
+    view! {
+        <div class="text-gray-400 text-sm m-4">{move || health.get()}</div>
+    }
+}
+
 #[component]
 pub fn Gui() -> impl IntoView {
     // Get the hostname from the current page's location for remote access
     let host = leptos::web_sys::window()
         .and_then(|w| w.location().hostname().ok())
         .unwrap_or_else(|| "127.0.0.1".to_string());
+    // has to match the runtime's control port, i.e., `radio_frontend --control-port`
     let rt_url = format!("http://{}:1337", host);
     
     let rt_handle = RuntimeHandle::from_url(&rt_url);
@@ -750,6 +1131,7 @@ pub fn Gui() -> impl IntoView {
 
     view! {
         <h1 class="text-xl text-white m-4">FutureSDR Radio Frontend</h1>
+        <StatusBar rt_handle=rt_handle.clone() />
         <div class="m-4 flex gap-2">
             <FlowgraphSelector rt_handle=rt_handle.clone() on_switch=on_switch />
             <button
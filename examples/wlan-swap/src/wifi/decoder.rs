@@ -1,4 +1,5 @@
 use futuresdr::prelude::*;
+use std::collections::HashMap;
 
 use super::FrameParam;
 use super::MAX_ENCODED_BITS;
@@ -7,8 +8,14 @@ use super::MAX_SYM;
 use super::Mcs;
 use super::ViterbiDecoder;
 
+/// WiFi (IEEE 802.11a/g/p) data decoder.
+///
+/// Posts the MPDU of each frame with a valid FCS on `rx_frames`, and as RFtap packet on `rftap`.
+/// Right before that, `frame_status` reports every decoded frame, including the ones with an
+/// invalid FCS, as `Pmt::MapStrPmt` with `crc_ok` (`Pmt::Bool`), `mcs` (`Pmt::String`, e.g.,
+/// `Qpsk_1_2`), and `psdu_size` (`Pmt::Usize`).
 #[derive(Block)]
-#[message_outputs(rx_frames, rftap)]
+#[message_outputs(rx_frames, rftap, frame_status)]
 pub struct Decoder<I = DefaultCpuReader<u8>>
 where
     I: CpuBufferReader<Item = u8>,
@@ -31,11 +38,20 @@ where
     I: CpuBufferReader<Item = u8>,
 {
     pub fn new() -> Self {
+        Self::with_viterbi(ViterbiDecoder::new())
+    }
+
+    /// Create a decoder with a fixed Viterbi traceback depth (see [`ViterbiDecoder::with_traceback`])
+    pub fn with_traceback(depth: usize) -> Self {
+        Self::with_viterbi(ViterbiDecoder::with_traceback(depth))
+    }
+
+    fn with_viterbi(decoder: ViterbiDecoder) -> Self {
         Self {
             input: I::default(),
             frame_complete: true,
             frame_param: FrameParam::new(Mcs::Bpsk_1_2, 0),
-            decoder: ViterbiDecoder::new(),
+            decoder,
             copied: 0,
             rx_symbols: [0; 48 * MAX_SYM],
             rx_bits: [0; MAX_ENCODED_BITS],
@@ -186,11 +202,28 @@ where
             if self.copied == self.frame_param.n_symbols() {
                 self.frame_complete = true;
 
-                if self.decode() {
-                     println!(
+                let crc_ok = self.decode();
+                mio.post(
+                    "frame_status",
+                    Pmt::MapStrPmt(HashMap::from([
+                        ("crc_ok".to_string(), Pmt::Bool(crc_ok)),
+                        (
+                            "mcs".to_string(),
+                            Pmt::String(format!("{:?}", self.frame_param.mcs())),
+                        ),
+                        (
+                            "psdu_size".to_string(),
+                            Pmt::Usize(self.frame_param.psdu_size()),
+                        ),
+                    ])),
+                )
+                .await?;
+
+                if crc_ok {
+                    debug!(
                         "decoded: {:?}",
                         &self.out_bytes[0..self.frame_param.psdu_size() + 2]
-                     );
+                    );
                     let mut blob = vec![0; self.frame_param.psdu_size() - 4];
                     blob.copy_from_slice(&self.out_bytes[2..self.frame_param.psdu_size() - 2]);
 
@@ -131,8 +131,13 @@ impl Enc {
     }
 }
 
+/// Encode frames into the data symbols of 802.11a/g/p OFDM frames.
+///
+/// Frames on `tx` are encoded with the MCS they come with, or with the default MCS. A
+/// `Pmt::String` with an MCS name (e.g., `"Qam16_1_2"`, see [`Mcs::parse`]) on `mcs` changes
+/// the default for the frames that arrive afterwards.
 #[derive(Block)]
-#[message_inputs(tx)]
+#[message_inputs(tx, mcs)]
 pub struct Encoder<O = DefaultCpuWriter<u8>>
 where
     O: CpuBufferWriter<Item = u8>,
@@ -169,6 +174,33 @@ where
         }
     }
 
+    /// Current default MCS
+    pub fn default_mcs(&self) -> Mcs {
+        self.default_mcs
+    }
+
+    async fn mcs(
+        &mut self,
+        _io: &mut WorkIo,
+        _mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        let Pmt::String(name) = p else {
+            return Ok(Pmt::InvalidValue);
+        };
+        match name.parse() {
+            Ok(mcs) => {
+                self.default_mcs = mcs;
+                Ok(Pmt::Ok)
+            }
+            Err(e) => {
+                warn!("WLAN Encoder: {}", e);
+                Ok(Pmt::InvalidValue)
+            }
+        }
+    }
+
     async fn tx(
         &mut self,
         io: &mut WorkIo,
@@ -276,3 +308,59 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Writer;
+
+    fn frame_mcs(tags: &[ItemTag]) -> Vec<Mcs> {
+        tags.iter()
+            .filter_map(|t| match &t.tag {
+                Tag::NamedAny(n, a) if n == "wifi_start" => {
+                    a.downcast_ref::<FrameParam>().map(|f| f.mcs())
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn change_mcs() {
+        let mut block = Encoder::<Writer<u8>>::new(Mcs::Qpsk_1_2);
+        block.output().reserve(10_000);
+        let mut mocker = Mocker::new(block);
+
+        mocker.post("tx", Pmt::Blob(vec![1; 100])).unwrap();
+        mocker.run();
+        assert_eq!(
+            mocker.post("mcs", Pmt::String("Qam16_1_2".into())).unwrap(),
+            Pmt::Ok
+        );
+        assert_eq!(mocker.default_mcs(), Mcs::Qam16_1_2);
+        mocker.post("tx", Pmt::Blob(vec![1; 100])).unwrap();
+        // an explicit MCS still takes precedence
+        let frame = (vec![1u8; 100], Some(Mcs::Bpsk_1_2));
+        mocker.post("tx", Pmt::Any(Box::new(frame))).unwrap();
+        mocker.run();
+
+        let (symbols, tags) = mocker.output.get();
+        assert_eq!(
+            frame_mcs(&tags),
+            [Mcs::Qpsk_1_2, Mcs::Qam16_1_2, Mcs::Bpsk_1_2]
+        );
+        let n = |mcs| FrameParam::new(mcs, 100).n_symbols() * 48;
+        assert_eq!(
+            symbols.len(),
+            n(Mcs::Qpsk_1_2) + n(Mcs::Qam16_1_2) + n(Mcs::Bpsk_1_2)
+        );
+
+        assert_eq!(
+            mocker.post("mcs", Pmt::String("Qam256".into())).unwrap(),
+            Pmt::InvalidValue
+        );
+        assert_eq!(mocker.post("mcs", Pmt::U32(3)).unwrap(), Pmt::InvalidValue);
+        assert_eq!(mocker.default_mcs(), Mcs::Qam16_1_2);
+    }
+}
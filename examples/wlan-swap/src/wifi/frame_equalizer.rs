@@ -12,6 +12,55 @@ const INTERLEAVER_PATTERN: [usize; 48] = [
     28, 31, 34, 37, 40, 43, 46, 2, 5, 8, 11, 14, 17, 20, 23, 26, 29, 32, 35, 38, 41, 44, 47,
 ];
 
+/// Size of a record in the `labeled_symbols` layout
+pub const LABELED_SYMBOL_SIZE: usize = 9;
+
+/// Serialize symbols with a `u8` label each, e.g., the data subcarrier
+///
+/// Each record is the real and imaginary part as little-endian `f32`, followed by the label.
+pub fn encode_labeled_symbols(syms: impl IntoIterator<Item = (Complex32, u8)>) -> Vec<u8> {
+    let syms = syms.into_iter();
+    let mut b = Vec::with_capacity(syms.size_hint().0 * LABELED_SYMBOL_SIZE);
+    for (s, label) in syms {
+        b.extend_from_slice(&s.re.to_le_bytes());
+        b.extend_from_slice(&s.im.to_le_bytes());
+        b.push(label);
+    }
+    b
+}
+
+/// Parse records produced by [`encode_labeled_symbols`], ignoring a trailing partial record
+pub fn decode_labeled_symbols(b: &[u8]) -> Vec<(Complex32, u8)> {
+    b.chunks_exact(LABELED_SYMBOL_SIZE)
+        .map(|r| {
+            let re = f32::from_le_bytes(r[0..4].try_into().unwrap());
+            let im = f32::from_le_bytes(r[4..8].try_into().unwrap());
+            (Complex32::new(re, im), r[8])
+        })
+        .collect()
+}
+
+/// Number of subcarriers in the channel estimate: -26..=26 without DC
+pub const CHANNEL_ESTIMATE_LEN: usize = 52;
+
+/// Serialize a channel estimate as real and imaginary part (little-endian `f32`) per subcarrier
+pub fn encode_channel_estimate(h: &[Complex32]) -> Vec<u8> {
+    h.iter()
+        .flat_map(|h| h.re.to_le_bytes().into_iter().chain(h.im.to_le_bytes()))
+        .collect()
+}
+
+/// Parse a channel estimate produced by [`encode_channel_estimate`]
+pub fn decode_channel_estimate(b: &[u8]) -> Vec<Complex32> {
+    b.chunks_exact(8)
+        .map(|r| {
+            let re = f32::from_le_bytes(r[0..4].try_into().unwrap());
+            let im = f32::from_le_bytes(r[4..8].try_into().unwrap());
+            Complex32::new(re, im)
+        })
+        .collect()
+}
+
 struct Equalizer {
     h: [Complex32; 64],
     snr: f32,
@@ -64,6 +113,11 @@ impl Equalizer {
     fn snr(&self) -> f32 {
         self.snr
     }
+
+    /// Estimate of the occupied subcarriers, from -26 to 26
+    fn estimate(&self) -> Vec<Complex32> {
+        (6..=58).filter(|i| *i != 32).map(|i| self.h[i]).collect()
+    }
 }
 
 #[derive(Debug)]
@@ -75,8 +129,29 @@ enum State {
     Skip,
 }
 
+/// OFDM frame equalizer.
+///
+/// Posts the equalized symbols of each frame as `Pmt::VecCF32` on `symbols` and, for views
+/// that color symbols by subcarrier, as a `Pmt::Blob` of [`encode_labeled_symbols`] on
+/// `labeled_symbols`, labeled with their data subcarrier (0..48). The latter are only encoded
+/// if the port is connected.
+///
+/// Created with [`with_llr`](Self::with_llr), it also posts the soft bits of each frame as
+/// `Pmt::VecF32` on `llr`: the LLRs of [`Modulation::llr`] for every data subcarrier in the
+/// order of `symbols`, i.e., before deinterleaving and depuncturing. The noise variance is
+/// taken from the SNR estimate of the long training field, limited to 40 dB, so the LLRs of
+/// different frames are comparable.
+///
+/// The channel estimate of the long training field is posted on `channel_estimate` once per
+/// frame, as a `Pmt::Blob` in the layout of [`encode_channel_estimate`], with the
+/// [`CHANNEL_ESTIMATE_LEN`] occupied subcarriers from -26 to 26 (skipping DC).
+///
+/// The phase of the SIGNAL field and data symbols is corrected with their pilots, unless
+/// disabled with [`with_pilot_tracking`](Self::with_pilot_tracking). Without tracking, only
+/// the channel estimate of the long training field is applied, so residual phase errors (e.g.,
+/// from a frequency offset) remain visible in the symbols.
 #[derive(Block)]
-#[message_outputs(symbols)]
+#[message_outputs(symbols, labeled_symbols, llr, channel_estimate)]
 pub struct FrameEqualizer<I = DefaultCpuReader<Complex32>, O = DefaultCpuWriter<u8>>
 where
     I: CpuBufferReader<Item = Complex32>,
@@ -94,6 +169,9 @@ where
     bits_out: [u8; 48],
     decoder: ViterbiDecoder,
     syms: Vec<Complex32>,
+    /// Soft bits of the current frame, if enabled
+    llrs: Option<Vec<f32>>,
+    pilot_tracking: bool,
 }
 
 impl<I, O> FrameEqualizer<I, O>
@@ -113,9 +191,25 @@ where
             bits_out: [0; 48],
             decoder: ViterbiDecoder::new(),
             syms: Vec::new(),
+            llrs: None,
+            pilot_tracking: true,
         }
     }
 
+    /// Create an equalizer that also posts the LLRs of the data bits on `llr`
+    pub fn with_llr() -> Self {
+        Self {
+            llrs: Some(Vec::new()),
+            ..Self::new()
+        }
+    }
+
+    /// Enable or disable the per-symbol phase correction with the pilots (enabled by default)
+    pub fn with_pilot_tracking(mut self, enabled: bool) -> Self {
+        self.pilot_tracking = enabled;
+        self
+    }
+
     fn decode_signal_field(
         decoder: &mut ViterbiDecoder,
         bits: &[u8; 48],
@@ -215,6 +309,9 @@ where
                 if !matches!(self.state, State::Skip) {
                     info!("frame equalizer: canceling frame");
                 }
+                if let Some(llrs) = self.llrs.as_mut() {
+                    llrs.clear();
+                }
                 self.state = State::Sync1;
             } else {
                 input = &input[0..*index];
@@ -242,7 +339,7 @@ where
                         self.sym_in[i] *= Complex32::from_polar(1.0, -beta);
                     }
                 }
-                State::Signal => {
+                State::Signal if self.pilot_tracking => {
                     let p = POLARITY[0];
                     let beta = ((self.sym_in[11] * p)
                         + (self.sym_in[39] * p)
@@ -253,7 +350,7 @@ where
                         self.sym_in[i] *= Complex32::from_polar(1.0, -beta);
                     }
                 }
-                State::Copy(left, n, _) => {
+                State::Copy(left, n, _) if self.pilot_tracking => {
                     let p = POLARITY[(n - left + 1) % 127];
                     let beta = ((self.sym_in[11] * p)
                         + (self.sym_in[39] * p)
@@ -285,6 +382,11 @@ where
                 }
                 State::Sync2 => {
                     self.equalizer.sync2(&self.sym_in);
+                    mio.post(
+                        "channel_estimate",
+                        Pmt::Blob(encode_channel_estimate(&self.equalizer.estimate())),
+                    )
+                    .await?;
                     self.state = State::Signal;
                     i += 1;
                 }
@@ -331,6 +433,12 @@ where
                         );
 
                         self.syms.extend_from_slice(&self.sym_out);
+                        if let Some(llrs) = self.llrs.as_mut() {
+                            let noise_var = 10f32.powf(-self.equalizer.snr() / 10.0).max(1e-4);
+                            for s in &self.sym_out {
+                                modulation.llr(s, noise_var, llrs);
+                            }
+                        }
 
                         i += 1;
                         o += 1;
@@ -338,9 +446,21 @@ where
                         n_sym -= 1;
                         if n_sym == 0 {
                             if !self.syms.is_empty() {
+                                if mio.is_connected("labeled_symbols")? {
+                                    let labeled = self
+                                        .syms
+                                        .iter()
+                                        .enumerate()
+                                        .map(|(i, s)| (*s, (i % 48) as u8));
+                                    let b = encode_labeled_symbols(labeled);
+                                    mio.post("labeled_symbols", Pmt::Blob(b)).await?;
+                                }
                                 mio.post("symbols", Pmt::VecCF32(std::mem::take(&mut self.syms)))
                                     .await?;
                             }
+                            if let Some(llrs) = self.llrs.as_mut().filter(|l| !l.is_empty()) {
+                                mio.post("llr", Pmt::VecF32(std::mem::take(llrs))).await?;
+                            }
                             self.state = State::Skip;
                         } else {
                             self.state = State::Copy(n_sym, *all_sym, *modulation);
@@ -365,3 +485,151 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::Mapper;
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    /// Run the equalizer on a frame, given as subcarriers in the order of `LONG`
+    fn equalize(
+        mut eq: FrameEqualizer<Reader<Complex32>, Writer<u8>>,
+        carriers: &[Complex32],
+        n_sym: usize,
+    ) -> Mocker<FrameEqualizer<Reader<Complex32>, Writer<u8>>> {
+        // undo the FFT shift of the equalizer
+        let input: Vec<Complex32> = carriers
+            .chunks(64)
+            .flat_map(|sym| (0..64).map(move |k| sym[(k + 32) % 64]))
+            .collect();
+        eq.input().set_with_tags(
+            input,
+            vec![ItemTag {
+                index: 0,
+                tag: Tag::NamedF32("wifi_start".to_string(), 0.0),
+            }],
+        );
+        eq.output().reserve(n_sym * 48);
+        let mut mocker = Mocker::new(eq);
+        mocker.run();
+        mocker
+    }
+
+    /// Equalize a BPSK frame with a phase error that grows by `step` per OFDM symbol
+    fn equalize_frame(
+        step: f32,
+        pilot_tracking: bool,
+    ) -> Mocker<FrameEqualizer<Reader<Complex32>, Writer<u8>>> {
+        let frame = FrameParam::new(Mcs::Bpsk_1_2, 10);
+        let n_sym = frame.n_symbols();
+        let bits: Vec<u8> = (0..n_sym * 48).map(|i| (i % 3 == 0) as u8).collect();
+
+        let mut mapper = Mapper::<Reader<_>, Writer<_>>::new();
+        mapper.input().set_with_tags(
+            bits,
+            vec![ItemTag {
+                index: 0,
+                tag: Tag::NamedAny("wifi_start".to_string(), Box::new(frame)),
+            }],
+        );
+        mapper.output().reserve((n_sym + 1) * 64);
+        let mut mocker = Mocker::new(mapper);
+        mocker.run();
+        let (mapped, _) = mocker.output().get();
+
+        // two long training symbols, then SIGNAL and data with the phase ramp
+        let mut carriers = [LONG.to_vec(), LONG.to_vec()].concat();
+        for (k, sym) in mapped.chunks(64).enumerate() {
+            let rot = Complex32::from_polar(1.0, step * (k + 1) as f32);
+            carriers.extend(sym.iter().map(|c| c * rot));
+        }
+
+        let eq = FrameEqualizer::<Reader<_>, Writer<_>>::new().with_pilot_tracking(pilot_tracking);
+        equalize(eq, &carriers, n_sym)
+    }
+
+    /// Symbols of a BPSK frame with a phase error that grows by `step` per OFDM symbol
+    fn equalize_with_phase_ramp(step: f32, pilot_tracking: bool) -> Vec<Complex32> {
+        let mocker = equalize_frame(step, pilot_tracking);
+        match &mocker.messages()[0][..] {
+            [Pmt::VecCF32(syms)] => syms.clone(),
+            m => panic!("unexpected {m:?}"),
+        }
+    }
+
+    #[test]
+    fn pilot_tracking() {
+        let step = 0.05;
+        let tracked = equalize_with_phase_ramp(step, true);
+        let raw = equalize_with_phase_ramp(step, false);
+        assert_eq!(tracked.len(), raw.len());
+        assert!(tracked.len() >= 4 * 48);
+
+        // with tracking, the BPSK symbols are back on the real axis
+        assert!(tracked.iter().all(|s| s.im.abs() < 1e-3));
+        // without, data symbol k keeps the phase of OFDM symbol k + 2 (after SIGNAL)
+        for (k, (r, t)) in raw.chunks(48).zip(tracked.chunks(48)).enumerate() {
+            let rot = Complex32::from_polar(1.0, step * (k + 2) as f32);
+            for (r, t) in r.iter().zip(t) {
+                assert!((r - t * rot).norm() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn labeled_symbols() {
+        let mocker = equalize_frame(0.0, true);
+        let messages = mocker.messages();
+        let ([Pmt::VecCF32(syms)], [Pmt::Blob(b)]) = (&messages[0][..], &messages[1][..]) else {
+            panic!("unexpected {:?}", &messages[..2]);
+        };
+
+        let labeled = decode_labeled_symbols(b);
+        assert_eq!(labeled.len(), syms.len());
+        // the subcarrier index restarts with every OFDM symbol
+        for (i, ((s, label), sym)) in labeled.iter().zip(syms).enumerate() {
+            assert_eq!(s, sym);
+            assert_eq!(*label as usize, i % 48);
+        }
+    }
+
+    #[test]
+    fn labeled_symbol_layout() {
+        let syms = (0..50u8).map(|k| (Complex32::new(k as f32, -0.5), k % 48));
+        let b = encode_labeled_symbols(syms);
+        assert_eq!(b.len(), 50 * LABELED_SYMBOL_SIZE);
+        // record 1: re = 1.0, im = -0.5, label 1
+        assert_eq!(&b[9..18], &[0, 0, 128, 63, 0, 0, 0, 191, 1]);
+
+        let decoded = decode_labeled_symbols(&b[..b.len() - 3]);
+        assert_eq!(decoded.len(), 49);
+        assert_eq!(decoded[47], (Complex32::new(47.0, -0.5), 47));
+        assert_eq!(decoded[48], (Complex32::new(48.0, -0.5), 0));
+    }
+
+    #[test]
+    fn flat_channel_estimate() {
+        // two long training symbols through a flat channel with a phase offset
+        let rot = Complex32::from_polar(1.0, 0.7);
+        let carriers: Vec<Complex32> = [LONG, LONG].iter().flatten().map(|c| c * rot).collect();
+        let mocker = equalize(FrameEqualizer::new(), &carriers, 0);
+
+        let messages = mocker.messages();
+        let [Pmt::Blob(b)] = &messages[3][..] else {
+            panic!("unexpected {:?}", messages[3]);
+        };
+        let h = decode_channel_estimate(b);
+        assert_eq!(h.len(), CHANNEL_ESTIMATE_LEN);
+        // the common phase is removed with the pilots
+        for h in h {
+            assert!((h - Complex32::new(1.0, 0.0)).norm() < 1e-4, "{h}");
+        }
+        assert_eq!(
+            decode_channel_estimate(&encode_channel_estimate(&[Complex32::new(0.5, -2.0)])),
+            [Complex32::new(0.5, -2.0)]
+        );
+    }
+}
@@ -4,12 +4,24 @@ use super::MAX_PAYLOAD_SIZE;
 use super::MAX_PSDU_SIZE;
 use super::Mcs;
 
+/// Maximum number of fragments of one MSDU (4-bit fragment number)
+const MAX_FRAGMENTS: usize = 16;
+
+/// Wrap payloads into 802.11 data frames.
+///
+/// Payloads on `tx` (`Pmt::Blob`, or `Pmt::Any` with `(Vec<u8>, Mcs)`) longer than
+/// [`MAX_PAYLOAD_SIZE`] are rejected with a `Pmt::String` describing the error, which is
+/// returned to the caller of the handler. Created with
+/// [`with_fragmentation`](Self::with_fragmentation), the Mac instead splits them into up to
+/// 16 fragments of at most [`MAX_PAYLOAD_SIZE`] bytes that share a sequence number, with
+/// increasing fragment numbers and the More Fragments flag set on all but the last.
 #[derive(Block)]
 #[message_inputs(tx)]
 #[message_outputs(tx)]
 pub struct Mac {
     current_frame: [u8; MAX_PSDU_SIZE],
     sequence_number: u16,
+    fragment: bool,
 }
 
 impl Mac {
@@ -28,9 +40,16 @@ impl Mac {
         Mac {
             current_frame,
             sequence_number: 0,
+            fragment: false,
         }
     }
 
+    /// Fragment oversized payloads instead of rejecting them
+    pub fn with_fragmentation(mut self) -> Self {
+        self.fragment = true;
+        self
+    }
+
     async fn tx(
         &mut self,
         io: &mut WorkIo,
@@ -39,38 +58,10 @@ impl Mac {
         p: Pmt,
     ) -> Result<Pmt> {
         match p {
-            Pmt::Blob(data) => {
-                if data.len() > MAX_PAYLOAD_SIZE {
-                    warn!(
-                        "WLAN Mac: TX frame too large ({}, max {}). Dropping.",
-                        data.len(),
-                        MAX_PAYLOAD_SIZE
-                    );
-                } else {
-                    let len = self.generate_mac_data_frame(&data);
-                    debug!("mac frame {:?}", &self.current_frame[0..len]);
-                    let mut vec = vec![0; len];
-                    vec.copy_from_slice(&self.current_frame[0..len]);
-                    mio.post("tx", Pmt::Any(Box::new((vec, None as Option<Mcs>))))
-                        .await?;
-                }
-            }
+            Pmt::Blob(data) => return self.send(mio, &data, None).await,
             Pmt::Any(a) => {
                 if let Some((data, mcs)) = a.downcast_ref::<(Vec<u8>, Mcs)>() {
-                    if data.len() > MAX_PAYLOAD_SIZE {
-                        warn!(
-                            "WLAN Mac: TX frame too large ({}, max {}). Dropping.",
-                            data.len(),
-                            MAX_PAYLOAD_SIZE
-                        );
-                    } else {
-                        let len = self.generate_mac_data_frame(data);
-                        debug!("mac frame {:?}", &self.current_frame[0..len]);
-                        let mut vec = vec![0; len];
-                        vec.copy_from_slice(&self.current_frame[0..len]);
-                        mio.post("tx", Pmt::Any(Box::new((vec, Some(*mcs)))))
-                            .await?;
-                    }
+                    return self.send(mio, data, Some(*mcs)).await;
                 }
             }
             Pmt::Finished => {
@@ -83,9 +74,46 @@ impl Mac {
         Ok(Pmt::Null)
     }
 
-    fn generate_mac_data_frame(&mut self, data: &[u8]) -> usize {
-        self.current_frame[22..24].copy_from_slice(&(self.sequence_number << 4).to_le_bytes());
+    /// Post the frame(s) of a payload, or return an error string if it is too large
+    async fn send(
+        &mut self,
+        mio: &mut MessageOutputs,
+        data: &[u8],
+        mcs: Option<Mcs>,
+    ) -> Result<Pmt> {
+        let max = if self.fragment {
+            MAX_FRAGMENTS * MAX_PAYLOAD_SIZE
+        } else {
+            MAX_PAYLOAD_SIZE
+        };
+        if data.len() > max {
+            let msg = format!(
+                "WLAN Mac: TX payload too large ({} bytes, max {})",
+                data.len(),
+                max
+            );
+            warn!("{}. Dropping.", msg);
+            return Ok(Pmt::String(msg));
+        }
+
+        let n_fragments = data.len().div_ceil(MAX_PAYLOAD_SIZE).max(1);
+        for i in 0..n_fragments {
+            let chunk = &data[(i * MAX_PAYLOAD_SIZE).min(data.len())
+                ..((i + 1) * MAX_PAYLOAD_SIZE).min(data.len())];
+            let len = self.generate_mac_data_frame(chunk, i as u16, i + 1 < n_fragments);
+            debug!("mac frame {:?}", &self.current_frame[0..len]);
+            let vec = self.current_frame[0..len].to_vec();
+            mio.post("tx", Pmt::Any(Box::new((vec, mcs)))).await?;
+        }
         self.sequence_number = (self.sequence_number + 1) % (1 << 12);
+        Ok(Pmt::Null)
+    }
+
+    fn generate_mac_data_frame(&mut self, data: &[u8], fragment: u16, more: bool) -> usize {
+        // more fragments flag
+        self.current_frame[1] = if more { 0x04 } else { 0x00 };
+        self.current_frame[22..24]
+            .copy_from_slice(&((self.sequence_number << 4) | fragment).to_le_bytes());
 
         let len = data.len() + 24;
 
@@ -105,3 +133,93 @@ impl Mac {
 }
 
 impl Kernel for Mac {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+
+    fn mac() -> Mac {
+        Mac::new([0x42; 6], [0x23; 6], [0xff; 6])
+    }
+
+    fn frames(mocker: &Mocker<Mac>) -> Vec<Vec<u8>> {
+        mocker.messages()[0]
+            .iter()
+            .map(|p| match p {
+                Pmt::Any(a) => a
+                    .downcast_ref::<(Vec<u8>, Option<Mcs>)>()
+                    .unwrap()
+                    .0
+                    .clone(),
+                p => panic!("unexpected {p:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn payload_at_limit() {
+        let mut mocker = Mocker::new(mac());
+        let data = vec![7; MAX_PAYLOAD_SIZE];
+        assert_eq!(
+            mocker.post("tx", Pmt::Blob(data.clone())).unwrap(),
+            Pmt::Null
+        );
+        mocker.run();
+
+        let frames = frames(&mocker);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].len(), MAX_PSDU_SIZE);
+        assert_eq!(&frames[0][24..MAX_PAYLOAD_SIZE + 24], &data[..]);
+        assert_eq!(frames[0][1], 0);
+    }
+
+    #[test]
+    fn payload_over_limit() {
+        let mut mocker = Mocker::new(mac());
+        let data = vec![7; MAX_PAYLOAD_SIZE + 1];
+        let Pmt::String(e) = mocker.post("tx", Pmt::Blob(data.clone())).unwrap() else {
+            panic!("expected an error string");
+        };
+        assert!(e.contains("1501 bytes, max 1500"), "{e}");
+        let any = Pmt::Any(Box::new((data, Mcs::Qpsk_1_2)));
+        assert!(matches!(mocker.post("tx", any).unwrap(), Pmt::String(_)));
+        mocker.run();
+        assert!(mocker.messages()[0].is_empty());
+    }
+
+    #[test]
+    fn payload_fragmented() {
+        let mut mocker = Mocker::new(mac().with_fragmentation());
+        let data: Vec<u8> = (0..2 * MAX_PAYLOAD_SIZE + 10).map(|i| i as u8).collect();
+        assert_eq!(
+            mocker.post("tx", Pmt::Blob(vec![1, 2, 3])).unwrap(),
+            Pmt::Null
+        );
+        assert_eq!(
+            mocker.post("tx", Pmt::Blob(data.clone())).unwrap(),
+            Pmt::Null
+        );
+        mocker.run();
+
+        let frames = frames(&mocker);
+        assert_eq!(frames.len(), 4);
+        let mut payload = Vec::new();
+        for (i, f) in frames[1..].iter().enumerate() {
+            let seq_ctrl = u16::from_le_bytes([f[22], f[23]]);
+            // second MSDU, fragment i
+            assert_eq!(seq_ctrl, (1 << 4) | i as u16);
+            assert_eq!(f[1] & 0x04 != 0, i < 2);
+            let crc = crc32fast::hash(&f[..f.len() - 4]);
+            assert_eq!(&f[f.len() - 4..], &crc.to_le_bytes());
+            payload.extend_from_slice(&f[24..f.len() - 4]);
+        }
+        assert_eq!(payload, data);
+
+        let too_large = vec![0; MAX_FRAGMENTS * MAX_PAYLOAD_SIZE + 1];
+        assert!(matches!(
+            mocker.post("tx", Pmt::Blob(too_large)).unwrap(),
+            Pmt::String(_)
+        ));
+    }
+}
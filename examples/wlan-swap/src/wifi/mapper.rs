@@ -69,6 +69,13 @@ impl Signal {
     }
 }
 
+/// Map coded bits onto the OFDM subcarriers, adding the pilots.
+///
+/// Each input byte holds the bits of one data subcarrier, as grouped by the encoder for the MCS
+/// of the frame. [`Mapper::new`] uses the 802.11 constellations, while
+/// [`Mapper::with_constellation`] maps the data symbols of frames with `bits_per_symbol` bits
+/// per subcarrier with custom points. The SIGNAL field and frames of other modulations keep the
+/// standard mapping, so that receivers can still detect and decode them.
 #[derive(Block)]
 pub struct Mapper<I = DefaultCpuReader<u8>, O = DefaultCpuWriter<Complex32>>
 where
@@ -83,6 +90,8 @@ where
     current_mod: Modulation,
     current_len: usize,
     index: usize,
+    /// Custom points with the number of bits per symbol they apply to
+    constellation: Option<(Vec<Complex32>, usize)>,
 }
 
 impl<I, O> Mapper<I, O>
@@ -102,10 +111,31 @@ where
             current_mod: Modulation::Bpsk,
             current_len: 0,
             index: 0,
+            constellation: None,
         }
     }
 
-    fn map(input: &[u8; 48], output: &mut [Complex32; 64], modulation: Modulation, index: usize) {
+    /// Map the data symbols of frames with `bits_per_symbol` bits per subcarrier to `points`,
+    /// indexed by the bits
+    pub fn with_constellation(points: Vec<Complex32>, bits_per_symbol: usize) -> Self {
+        assert!(
+            [1, 2, 4, 6].contains(&bits_per_symbol),
+            "no 802.11 modulation has {bits_per_symbol} bits per symbol"
+        );
+        assert_eq!(points.len(), 1 << bits_per_symbol);
+        Self {
+            constellation: Some((points, bits_per_symbol)),
+            ..Self::new()
+        }
+    }
+
+    fn map(
+        input: &[u8; 48],
+        output: &mut [Complex32; 64],
+        modulation: Modulation,
+        points: Option<&[Complex32]>,
+        index: usize,
+    ) {
         // dc
         output[32] = Complex32::new(0.0, 0.0);
         // guard
@@ -127,7 +157,10 @@ where
             .enumerate()
         {
             // debug!("data {} {} mapped {}", i, c, modulation.map(input[i]));
-            output[c] = modulation.map(input[i]);
+            output[c] = match points {
+                Some(points) => points[input[i] as usize],
+                None => modulation.map(input[i]),
+            };
         }
     }
 }
@@ -185,6 +218,7 @@ where
                     &self.signal.signal_interleaved,
                     (&mut output[0..64]).try_into().unwrap(),
                     Modulation::Bpsk,
+                    None,
                     0,
                 );
                 o += 1;
@@ -205,6 +239,11 @@ where
 
         let n = std::cmp::min(input.len() / 48, (output.len() / 64) - o);
 
+        // custom points, if they apply to the modulation of the frame
+        let points = match &self.constellation {
+            Some((points, bits)) if *bits == self.current_mod.n_bpsc() => Some(points.as_slice()),
+            _ => None,
+        };
         for i in 0..n {
             self.index += 1;
             Self::map(
@@ -213,6 +252,7 @@ where
                     .try_into()
                     .unwrap(),
                 self.current_mod,
+                points,
                 self.index,
             );
         }
@@ -227,3 +267,54 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wifi::Mcs;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+    use futuresdr::runtime::mocker::Writer;
+
+    #[test]
+    fn custom_constellation() {
+        let points = vec![
+            Complex32::new(1.0, 0.0),
+            Complex32::new(0.0, 1.0),
+            Complex32::new(-1.0, 0.0),
+            Complex32::new(0.0, -1.0),
+        ];
+        // one QPSK symbol with the bit pairs 0, 1, 2, 3, 0, 1, ...
+        let frame = FrameParam::new(Mcs::Qpsk_1_2, 1);
+        assert_eq!(frame.n_symbols(), 1);
+        let input: Vec<u8> = (0..48).map(|i| (i % 4) as u8).collect();
+
+        let mut block = Mapper::<Reader<_>, Writer<_>>::with_constellation(points.clone(), 2);
+        block.input().set_with_tags(
+            input,
+            vec![ItemTag {
+                index: 0,
+                tag: Tag::NamedAny("wifi_start".to_string(), Box::new(frame)),
+            }],
+        );
+        block.output().reserve(128);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let (output, _) = mocker.output.get();
+        assert_eq!(output.len(), 128);
+
+        let data: Vec<Complex32> = (6..11)
+            .chain(12..25)
+            .chain(26..32)
+            .chain(33..39)
+            .chain(40..53)
+            .chain(54..59)
+            .map(|c| output[64 + c])
+            .collect();
+        let expected: Vec<Complex32> = (0..48).map(|i| points[i % 4]).collect();
+        assert_eq!(data, expected);
+
+        // the SIGNAL field stays BPSK
+        assert!(output[6..11].iter().all(|c| c.im == 0.0 && c.re.abs() == 1.0));
+    }
+}
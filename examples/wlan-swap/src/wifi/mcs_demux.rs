@@ -0,0 +1,103 @@
+use futuresdr::prelude::*;
+
+use super::Mcs;
+use super::Modulation;
+
+/// Route frame status messages by their MCS.
+///
+/// Expects a `Pmt::MapStrPmt` on `frame` with an `mcs` field holding the MCS name (e.g.,
+/// `"Qpsk_1_2"`, parsed with [`Mcs::parse`]) and forwards the message unchanged to the output
+/// of its modulation: `bpsk`, `qpsk`, `qam16`, or `qam64`. Messages without a valid `mcs`
+/// field go to `other`. `Pmt::Finished` is forwarded to all outputs.
+#[derive(Block)]
+#[message_inputs(frame)]
+#[message_outputs(bpsk, qpsk, qam16, qam64, other)]
+#[null_kernel]
+pub struct McsDemux;
+
+impl McsDemux {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn port(p: &Pmt) -> &'static str {
+        let mcs = match p {
+            Pmt::MapStrPmt(map) => match map.get("mcs") {
+                Some(Pmt::String(s)) => Mcs::parse(s).ok(),
+                _ => None,
+            },
+            _ => None,
+        };
+        match mcs.map(|m| m.modulation()) {
+            Some(Modulation::Bpsk) => "bpsk",
+            Some(Modulation::Qpsk) => "qpsk",
+            Some(Modulation::Qam16) => "qam16",
+            Some(Modulation::Qam64) => "qam64",
+            None => "other",
+        }
+    }
+
+    async fn frame(
+        &mut self,
+        io: &mut WorkIo,
+        mio: &mut MessageOutputs,
+        _meta: &mut BlockMeta,
+        p: Pmt,
+    ) -> Result<Pmt> {
+        if matches!(p, Pmt::Finished) {
+            for port in ["bpsk", "qpsk", "qam16", "qam64", "other"] {
+                mio.post(port, Pmt::Finished).await?;
+            }
+            io.finished = true;
+            return Ok(Pmt::Ok);
+        }
+
+        mio.post(Self::port(&p), p).await?;
+        Ok(Pmt::Ok)
+    }
+}
+
+impl Default for McsDemux {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futuresdr::runtime::mocker::Mocker;
+    use std::collections::HashMap;
+
+    fn frame(mcs: Pmt, seq: u64) -> Pmt {
+        Pmt::MapStrPmt(HashMap::from([
+            ("mcs".to_string(), mcs),
+            ("seq".to_string(), Pmt::U64(seq)),
+        ]))
+    }
+
+    #[test]
+    fn routes_by_modulation() {
+        let mut mocker = Mocker::new(McsDemux::new());
+        let frames = [
+            frame(Pmt::String("Qpsk_1_2".to_string()), 0),
+            frame(Pmt::String("qam64-3-4".to_string()), 1),
+            frame(Pmt::String("Bpsk_3_4".to_string()), 2),
+            frame(Pmt::String("qam16_1_2".to_string()), 3),
+            frame(Pmt::String("Qpsk_3_4".to_string()), 4),
+            frame(Pmt::U32(5), 5),
+            Pmt::Blob(vec![1, 2, 3]),
+        ];
+        for f in frames.iter() {
+            assert_eq!(mocker.post("frame", f.clone()).unwrap(), Pmt::Ok);
+        }
+        mocker.run();
+
+        let [bpsk, qpsk, qam16, qam64, other] = mocker.messages().try_into().unwrap();
+        assert_eq!(bpsk, vec![frames[2].clone()]);
+        assert_eq!(qpsk, vec![frames[0].clone(), frames[4].clone()]);
+        assert_eq!(qam16, vec![frames[3].clone()]);
+        assert_eq!(qam64, vec![frames[1].clone()]);
+        assert_eq!(other, vec![frames[5].clone(), frames[6].clone()]);
+    }
+}
@@ -15,7 +15,13 @@ mod encoder;
 pub use encoder::Encoder;
 
 mod frame_equalizer;
+pub use frame_equalizer::CHANNEL_ESTIMATE_LEN;
+pub use frame_equalizer::decode_channel_estimate;
+pub use frame_equalizer::encode_channel_estimate;
 pub use frame_equalizer::FrameEqualizer;
+pub use frame_equalizer::LABELED_SYMBOL_SIZE;
+pub use frame_equalizer::decode_labeled_symbols;
+pub use frame_equalizer::encode_labeled_symbols;
 
 mod mac;
 pub use mac::Mac;
@@ -23,6 +29,9 @@ pub use mac::Mac;
 mod mapper;
 pub use mapper::Mapper;
 
+mod mcs_demux;
+pub use mcs_demux::McsDemux;
+
 mod moving_average;
 pub use moving_average::MovingAverage;
 
@@ -41,7 +50,8 @@ pub use viterbi_decoder::ViterbiDecoder;
 pub const MAX_PAYLOAD_SIZE: usize = 1500;
 pub const MAX_PSDU_SIZE: usize = MAX_PAYLOAD_SIZE + 28; // MAC, CRC
 pub const MAX_SYM: usize = ((16 + 8 * MAX_PSDU_SIZE + 6) / 24) + 1;
-pub const MAX_ENCODED_BITS: usize = (16 + 8 * MAX_PSDU_SIZE + 6) * 2 + 288;
+// margin for padding to full symbols (< 2 * 216 coded bits) and Viterbi traceback (<= 16 * 24 + 4)
+pub const MAX_ENCODED_BITS: usize = (16 + 8 * MAX_PSDU_SIZE + 6) * 2 + 1024;
 
 #[derive(Clone, Copy, Debug)]
 pub enum Modulation {
@@ -61,6 +71,10 @@ impl Modulation {
             Modulation::Qam64 => 6,
         }
     }
+    /// ideal symbol locations, indexed by the mapped bits
+    pub fn constellation(&self) -> Vec<Complex32> {
+        (0..1u16 << self.n_bpsc()).map(|i| self.map(i as u8)).collect()
+    }
     pub fn map(&self, i: u8) -> Complex32 {
         match self {
             Modulation::Bpsk => {
@@ -214,9 +228,31 @@ impl Modulation {
             }
         }
     }
+
+    /// Append the max-log LLRs of the bits of `i` to `out`, in the bit order of [`demap`](Self::demap)
+    ///
+    /// `LLR_k = (min |i - s0|² - min |i - s1|²) / noise_var`, where `s0` (`s1`) ranges over the
+    /// constellation points with bit `k` cleared (set). A positive LLR favors a one. With the
+    /// unit-power constellation, `noise_var` is the inverse of the linear SNR.
+    pub fn llr(&self, i: &Complex32, noise_var: f32, out: &mut Vec<f32>) {
+        let n_bpsc = self.n_bpsc();
+        let mut min0 = [f32::INFINITY; 6];
+        let mut min1 = [f32::INFINITY; 6];
+        for b in 0..1u8 << n_bpsc {
+            let d = (i - self.map(b)).norm_sqr();
+            for k in 0..n_bpsc {
+                if b & (1 << k) == 0 {
+                    min0[k] = min0[k].min(d);
+                } else {
+                    min1[k] = min1[k].min(d);
+                }
+            }
+        }
+        out.extend((0..n_bpsc).map(|k| (min0[k] - min1[k]) / noise_var));
+    }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[allow(non_camel_case_types)]
 pub enum Mcs {
     Bpsk_1_2,
@@ -251,32 +287,48 @@ impl Mcs {
         }
     }
 
-    // coded bits per symbol
-    pub fn n_cbps(&self) -> usize {
+    /// All MCS, ordered by data rate
+    pub fn all() -> &'static [Mcs] {
+        &[
+            Mcs::Bpsk_1_2,
+            Mcs::Bpsk_3_4,
+            Mcs::Qpsk_1_2,
+            Mcs::Qpsk_3_4,
+            Mcs::Qam16_1_2,
+            Mcs::Qam16_3_4,
+            Mcs::Qam64_2_3,
+            Mcs::Qam64_3_4,
+        ]
+    }
+
+    /// coded bits per subcarrier symbol
+    pub fn bits_per_symbol(&self) -> usize {
+        self.modulation().n_bpsc()
+    }
+
+    /// convolutional code rate as (numerator, denominator)
+    pub fn coding_rate(&self) -> (u8, u8) {
         match self {
-            Mcs::Bpsk_1_2 => 48,
-            Mcs::Bpsk_3_4 => 48,
-            Mcs::Qpsk_1_2 => 96,
-            Mcs::Qpsk_3_4 => 96,
-            Mcs::Qam16_1_2 => 192,
-            Mcs::Qam16_3_4 => 192,
-            Mcs::Qam64_2_3 => 288,
-            Mcs::Qam64_3_4 => 288,
+            Mcs::Bpsk_1_2 | Mcs::Qpsk_1_2 | Mcs::Qam16_1_2 => (1, 2),
+            Mcs::Qam64_2_3 => (2, 3),
+            Mcs::Bpsk_3_4 | Mcs::Qpsk_3_4 | Mcs::Qam16_3_4 | Mcs::Qam64_3_4 => (3, 4),
         }
     }
 
+    /// data rate in bit/s, i.e., data bits per OFDM symbol (64 samples + 16 samples cyclic prefix)
+    pub fn data_rate(&self, sample_rate: f64) -> f64 {
+        self.n_dbps() as f64 * sample_rate / 80.0
+    }
+
+    // coded bits per symbol
+    pub fn n_cbps(&self) -> usize {
+        48 * self.bits_per_symbol()
+    }
+
     // data bits per symbol
     pub fn n_dbps(&self) -> usize {
-        match self {
-            Mcs::Bpsk_1_2 => 24,
-            Mcs::Bpsk_3_4 => 36,
-            Mcs::Qpsk_1_2 => 48,
-            Mcs::Qpsk_3_4 => 72,
-            Mcs::Qam16_1_2 => 96,
-            Mcs::Qam16_3_4 => 144,
-            Mcs::Qam64_2_3 => 192,
-            Mcs::Qam64_3_4 => 216,
-        }
+        let (k, n) = self.coding_rate();
+        self.n_cbps() * k as usize / n as usize
     }
     // rate field for signal field
     pub fn rate_field(&self) -> u8 {
@@ -309,6 +361,15 @@ impl Mcs {
     }
 }
 
+impl std::str::FromStr for Mcs {
+    type Err = String;
+
+    /// Parse an MCS name, see [`Mcs::parse`]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Mcs::parse(s)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct FrameParam {
     mcs: Mcs,
@@ -556,3 +617,52 @@ pub const LONG: [Complex32; 64] = [
     Complex32::new(0.0, 0.0),
     Complex32::new(0.0, 0.0),
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn constellation() {
+        for m in [Modulation::Bpsk, Modulation::Qpsk, Modulation::Qam16, Modulation::Qam64] {
+            let points = m.constellation();
+            assert_eq!(points.len(), 1 << m.n_bpsc());
+            for (i, p) in points.iter().enumerate() {
+                assert_eq!(m.demap(p) as usize, i);
+            }
+            let power = points.iter().map(|p| p.norm_sqr()).sum::<f32>() / points.len() as f32;
+            assert!((power - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn llr_signs() {
+        for m in [Modulation::Bpsk, Modulation::Qpsk, Modulation::Qam16, Modulation::Qam64] {
+            for (bits, p) in m.constellation().iter().enumerate() {
+                let mut llrs = Vec::new();
+                m.llr(p, 0.01, &mut llrs);
+                assert_eq!(llrs.len(), m.n_bpsc());
+                for (k, l) in llrs.iter().enumerate() {
+                    let bit = (bits >> k) & 1 == 1;
+                    assert_eq!(*l > 0.0, bit, "{m:?} point {bits} bit {k}: {l}");
+                    // the nearest point with the other bit value is at least one grid step away
+                    assert!(l.abs() > 1.0, "{m:?} point {bits} bit {k}: {l}");
+                }
+            }
+        }
+        // halfway between two BPSK points, both are equally likely
+        let mut llrs = Vec::new();
+        Modulation::Bpsk.llr(&Complex32::new(0.0, 0.3), 1.0, &mut llrs);
+        assert_eq!(llrs, [0.0]);
+    }
+
+    #[test]
+    fn data_rates() {
+        let rates: Vec<f64> = Mcs::all().iter().map(|m| m.data_rate(20e6)).collect();
+        assert_eq!(rates, vec![6e6, 9e6, 12e6, 18e6, 24e6, 36e6, 48e6, 54e6]);
+        let n_dbps: Vec<usize> = Mcs::all().iter().map(|m| m.n_dbps()).collect();
+        assert_eq!(n_dbps, vec![24, 36, 48, 72, 96, 144, 192, 216]);
+        assert_eq!(Mcs::Qam64_2_3.coding_rate(), (2, 3));
+        assert_eq!(Mcs::Qam16_3_4.bits_per_symbol(), 4);
+    }
+}
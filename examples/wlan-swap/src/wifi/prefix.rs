@@ -47,6 +47,16 @@ where
             pad_tail,
         }
     }
+
+    /// Zero samples before each frame
+    pub fn pad_front(&self) -> usize {
+        self.pad_front
+    }
+
+    /// Zero samples after each frame
+    pub fn pad_tail(&self) -> usize {
+        self.pad_tail
+    }
 }
 
 impl<I, O> Kernel for Prefix<I, O>
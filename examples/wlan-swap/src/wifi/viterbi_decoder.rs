@@ -6,8 +6,15 @@ use super::Mcs;
 
 const TRACEBACK_MAX: usize = 24;
 
+/// Soft-less Viterbi decoder for the 802.11a/g convolutional code.
+///
+/// The traceback depth is the number of bytes the decoder looks ahead before deciding on a
+/// byte. A deeper traceback yields more reliable decisions, especially for punctured codes,
+/// but each decoded byte traces back through more survivor paths (`depth` steps) and the
+/// decoder lags `depth` bytes behind its input. By default, the depth is chosen per MCS.
 pub struct ViterbiDecoder {
     frame_param: FrameParam,
+    traceback: Option<usize>,
     n_traceback: usize,
     store_pos: usize,
 
@@ -28,6 +35,7 @@ impl ViterbiDecoder {
     pub fn new() -> Self {
         ViterbiDecoder {
             frame_param: FrameParam::new(Mcs::Bpsk_1_2, 0),
+            traceback: None,
             n_traceback: 0,
             store_pos: 0,
 
@@ -45,6 +53,18 @@ impl ViterbiDecoder {
         }
     }
 
+    /// Create a decoder with a fixed traceback depth (in bytes, `1..=24`) for all MCS
+    pub fn with_traceback(depth: usize) -> Self {
+        assert!(
+            (1..=TRACEBACK_MAX).contains(&depth),
+            "traceback depth must be in 1..={TRACEBACK_MAX}"
+        );
+        ViterbiDecoder {
+            traceback: Some(depth),
+            ..Self::new()
+        }
+    }
+
     fn reset(&mut self, param: FrameParam) {
         self.frame_param = param;
 
@@ -65,21 +85,19 @@ impl ViterbiDecoder {
         self.mmresult.fill(0);
         self.ppresult.fill([0; 64]);
 
-        match self.frame_param.mcs() {
-            Mcs::Bpsk_1_2 | Mcs::Qpsk_1_2 | Mcs::Qam16_1_2 => {
-                self.n_traceback = 5;
-            }
-            Mcs::Bpsk_3_4 | Mcs::Qpsk_3_4 | Mcs::Qam16_3_4 | Mcs::Qam64_3_4 => {
-                self.n_traceback = 10;
-            }
-            Mcs::Qam64_2_3 => {
-                self.n_traceback = 9;
-            }
-        }
+        self.n_traceback = match (self.traceback, self.frame_param.mcs()) {
+            (Some(depth), _) => depth,
+            (None, Mcs::Bpsk_1_2 | Mcs::Qpsk_1_2 | Mcs::Qam16_1_2) => 5,
+            (None, Mcs::Bpsk_3_4 | Mcs::Qpsk_3_4 | Mcs::Qam16_3_4 | Mcs::Qam64_3_4) => 10,
+            (None, Mcs::Qam64_2_3) => 9,
+        };
     }
 
     pub fn depuncture(&mut self, in_bits: &[u8]) {
-        if self.n_traceback == 5 {
+        if matches!(
+            self.frame_param.mcs(),
+            Mcs::Bpsk_1_2 | Mcs::Qpsk_1_2 | Mcs::Qam16_1_2
+        ) {
             self.depunctured[0..in_bits.len()].copy_from_slice(in_bits);
         } else {
             let pattern = self.frame_param.mcs.depuncture_pattern();
@@ -104,6 +122,12 @@ impl ViterbiDecoder {
                 }
             }
         }
+
+        // The traceback reads past the end of the frame. The trellis is terminated by the
+        // tail bits, so continue it with zeros.
+        let n_coded = 2 * self.frame_param.n_data_bits();
+        let end = std::cmp::min(n_coded + 16 * self.n_traceback + 4, MAX_ENCODED_BITS);
+        self.depunctured[n_coded..end].fill(0);
     }
 
     fn viterbi_butterfly2_generic(&mut self, symbols: &[u8; 4]) {
@@ -545,3 +569,78 @@ const PARTAB: [u8; 256] = [
     0, 1, 1, 0, 1, 0, 0, 1, 1, 0, 0, 1, 0, 1, 1, 0, 1, 0, 0, 1, 0, 1, 1, 0, 0, 1, 1, 0, 1, 0, 0, 1,
     1, 0, 0, 1, 0, 1, 1, 0, 0, 1, 1, 0, 1, 0, 0, 1, 0, 1, 1, 0, 1, 0, 0, 1, 1, 0, 0, 1, 0, 1, 1, 0,
 ];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bit errors after decoding a noisy QPSK 3/4 frame
+    fn bit_errors(decoder: &mut ViterbiDecoder) -> usize {
+        let frame = FrameParam::new(Mcs::Qpsk_3_4, 1000);
+        let n_data_bits = frame.n_data_bits();
+        let n_payload = n_data_bits - frame.n_pad() - 6;
+
+        let mut lcg: u32 = 12345;
+        let mut rand = move || {
+            lcg = lcg.wrapping_mul(1103515245).wrapping_add(12345);
+            lcg >> 16
+        };
+
+        // data + zero tail + pad
+        let mut bits = vec![0u8; n_data_bits];
+        for b in bits.iter_mut().take(n_payload) {
+            *b = (rand() & 1) as u8;
+        }
+
+        // encode and puncture (rate 3/4)
+        let mut state = 0;
+        let mut coded = Vec::new();
+        for (i, b) in bits.iter().enumerate() {
+            state = ((state << 1) & 0x7e) | *b as u32;
+            let out = [
+                (state & 0o155).count_ones() as u8 % 2,
+                (state & 0o117).count_ones() as u8 % 2,
+            ];
+            for (k, o) in out.iter().enumerate() {
+                let m = (2 * i + k) % 6;
+                if !(m == 3 || m == 4) {
+                    coded.push(*o);
+                }
+            }
+        }
+
+        // flip 2% of the bits
+        for c in coded.iter_mut() {
+            if rand() % 100 < 2 {
+                *c ^= 1;
+            }
+        }
+
+        let mut in_bits = [0u8; MAX_ENCODED_BITS];
+        in_bits[0..coded.len()].copy_from_slice(&coded);
+        let mut out_bits = [0u8; MAX_ENCODED_BITS];
+        decoder.decode(frame, &in_bits, &mut out_bits);
+
+        bits[0..n_payload]
+            .iter()
+            .zip(out_bits.iter())
+            .filter(|(a, b)| a != b)
+            .count()
+    }
+
+    #[test]
+    fn noiseless_default() {
+        let frame = FrameParam::new(Mcs::Qpsk_1_2, 10);
+        let in_bits = [0u8; MAX_ENCODED_BITS];
+        let mut out_bits = [1u8; MAX_ENCODED_BITS];
+        ViterbiDecoder::new().decode(frame.clone(), &in_bits, &mut out_bits);
+        assert!(out_bits[0..frame.n_data_bits()].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn deeper_traceback_lowers_ber() {
+        let short = bit_errors(&mut ViterbiDecoder::with_traceback(2));
+        let long = bit_errors(&mut ViterbiDecoder::with_traceback(24));
+        assert!(long < short, "errors: depth 2 {short}, depth 24 {long}");
+    }
+}
@@ -1,4 +1,7 @@
 use futuresdr::prelude::*;
+use std::collections::HashMap;
+
+use super::mac::check_crc;
 
 const CHIP_MAPPING: [u32; 16] = [
     1618456172, 1309113062, 1826650030, 1724778362, 778887287, 2061946375, 2007919840, 125494990,
@@ -22,7 +25,10 @@ fn decode(seq: u32, threshold: u32) -> Option<u8> {
 enum State {
     Search,
     PreambleFound,
-    SearchSfd,
+    SearchSfd {
+        /// chip errors in the first SFD symbol
+        errors: u32,
+    },
     SearchHeader {
         byte: Option<u8>,
     },
@@ -36,17 +42,45 @@ enum State {
 struct Correlator {
     shift_reg: u32,
     threshold: u32,
+    /// Number of chips compared per preamble and SFD symbol, the most recent ones
+    window: u32,
 }
 impl Correlator {
+    /// Number of chips compared per symbol
+    const CHIPS: u32 = 30;
+
+    fn errors(&self, index: usize) -> u32 {
+        // skip the most recent chip, like `decode`
+        let mask = ((1u32 << self.window) - 1) << 1;
+        ((self.shift_reg ^ CHIP_MAPPING[index]) & mask).count_ones()
+    }
+
     fn matching(&self, index: usize) -> bool {
-        let ones =
-            ((self.shift_reg & 0x7FFFFFFE) ^ (CHIP_MAPPING[index] & 0x7FFFFFFE)).count_ones();
-        ones < self.threshold
+        self.errors(index) < self.threshold
     }
 }
 
+/// ZigBee (IEEE 802.15.4 O-QPSK) chip decoder.
+///
+/// Posts the MAC header and payload of each frame on `out`, with the FCS stripped. Right before
+/// that, `frame_ok` reports whether the FCS matched (`Pmt::Bool`). The received chips are posted
+/// on `symbols` for a constellation view.
+///
+/// For threshold tuning, `sync_events` reports each detected SFD as `Pmt::MapStrPmt` with the
+/// `index` of its first chip in the input stream (`Pmt::U64`) and the correlation `score`
+/// (`Pmt::F32`), i.e., the fraction of matching SFD chips.
+///
+/// Preamble and SFD are detected if fewer than `threshold` chips of each symbol differ, out of
+/// a window of the last 30 chips by default. A shorter window (see [`Decoder::with_params`])
+/// tolerates corrupted chips at the start of a symbol, at the cost of more false detections.
+/// Header and payload are always decoded over 30 chips.
+///
+/// With [`with_dedup_consecutive`](Decoder::with_dedup_consecutive), a frame identical to the
+/// previous one is dropped (including its `frame_ok`) if it ends less than the chips of its
+/// full PPDU after the previous one. Such a frame shares chips with the previous detection,
+/// e.g., due to overlapping correlation peaks, while a real repeat is sent after it.
 #[derive(Block)]
-#[message_outputs(out, symbols)]
+#[message_outputs(out, frame_ok, symbols, sync_events)]
 pub struct Decoder<I = DefaultCpuReader<f32>>
 where
     I: CpuBufferReader<Item = f32>,
@@ -57,24 +91,53 @@ where
     chip_count: u32,
     state: State,
     syms: Vec<Complex32>,
+    /// index of the next input sample
+    items: u64,
+    dedup_consecutive: bool,
+    /// last frame (with FCS) and the index after its last chip, if deduplicating
+    last_frame: Option<(Vec<u8>, u64)>,
 }
 
 impl<I> Decoder<I>
 where
     I: CpuBufferReader<Item = f32>,
 {
+    /// Default and maximum preamble correlation window in chips
+    pub const MAX_WINDOW: usize = Correlator::CHIPS as usize;
+
     pub fn new(threshold: u32) -> Self {
+        Self::with_params(threshold, Self::MAX_WINDOW)
+    }
+
+    /// Decoder with a preamble correlation window of `window` chips, at most
+    /// [`MAX_WINDOW`](Self::MAX_WINDOW)
+    pub fn with_params(threshold: u32, window: usize) -> Self {
+        assert!(
+            (1..=Self::MAX_WINDOW).contains(&window),
+            "correlation window must be in [1, {}] chips",
+            Self::MAX_WINDOW
+        );
         Self {
             input: I::default(),
             correlator: Correlator {
                 threshold,
                 shift_reg: 0,
+                window: window as u32,
             },
             state: State::Search,
             chip_count: 0,
             syms: Vec::new(),
+            items: 0,
+            dedup_consecutive: false,
+            last_frame: None,
         }
     }
+
+    /// Drop frames that repeat the previous one within its own length (disabled by default)
+    pub fn with_dedup_consecutive(mut self, enabled: bool) -> Self {
+        self.dedup_consecutive = enabled;
+        self
+    }
 }
 
 impl<I> Kernel for Decoder<I>
@@ -90,7 +153,7 @@ where
         let inbuf = self.input.slice().to_vec();
         let inbuf_len = inbuf.len();
 
-        for v in inbuf.into_iter() {
+        for (k, v) in inbuf.into_iter().enumerate() {
             // Accumulate symbol for constellation view (as complex with real part only)
             self.syms.push(Complex32::new(v, 0.0));
             
@@ -113,15 +176,29 @@ where
                 State::PreambleFound => {
                     if self.chip_count == 0 {
                         if self.correlator.matching(7) {
-                            self.state = State::SearchSfd;
+                            self.state = State::SearchSfd {
+                                errors: self.correlator.errors(7),
+                            };
                         } else if !self.correlator.matching(0) {
                             self.state = State::Search;
                         }
                     }
                 }
-                State::SearchSfd => {
+                State::SearchSfd { errors } => {
                     if self.chip_count == 0 {
                         if self.correlator.matching(10) {
+                            let errors = *errors + self.correlator.errors(10);
+                            let score = 1.0 - errors as f32 / (2 * self.correlator.window) as f32;
+                            // the SFD spans two symbols, ending with this sample
+                            let index = self.items + k as u64 + 1 - 64;
+                            mio.post(
+                                "sync_events",
+                                Pmt::MapStrPmt(HashMap::from([
+                                    ("index".to_string(), Pmt::U64(index)),
+                                    ("score".to_string(), Pmt::F32(score)),
+                                ])),
+                            )
+                            .await?;
                             self.state = State::SearchHeader { byte: None };
                         } else {
                             self.state = State::Search;
@@ -135,9 +212,10 @@ where
                         {
                             if let Some(o) = byte {
                                 let len = (i << 4) | *o;
-                                if len < 128 {
+                                // header and payload plus FCS
+                                if (3..128).contains(&len) {
                                     self.state = State::Decode {
-                                        len: (len as usize).saturating_sub(2),
+                                        len: len as usize,
                                         data: Vec::new(),
                                         byte: None,
                                     };
@@ -163,7 +241,22 @@ where
                                 *byte = None;
                                 if data.len() == *len {
                                     // info!("decoded frame");
-                                    mio.post("out", Pmt::Blob(std::mem::take(data))).await?;
+                                    let mut frame = std::mem::take(data);
+                                    let end = self.items + k as u64 + 1;
+                                    // preamble, SFD, and length byte, then the frame
+                                    let ppdu_chips = (6 + frame.len() as u64) * 64;
+                                    let duplicate =
+                                        self.last_frame.as_ref().is_some_and(|(last, last_end)| {
+                                            *last == frame && end - last_end < ppdu_chips
+                                        });
+                                    if self.dedup_consecutive {
+                                        self.last_frame = Some((frame.clone(), end));
+                                    }
+                                    if !duplicate {
+                                        mio.post("frame_ok", Pmt::Bool(check_crc(&frame))).await?;
+                                        frame.truncate(frame.len() - 2);
+                                        mio.post("out", Pmt::Blob(frame)).await?;
+                                    }
                                     self.state = State::Search;
                                 }
                             } else {
@@ -188,7 +281,158 @@ where
         }
 
         self.input.consume(inbuf_len);
+        self.items += inbuf_len as u64;
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zigbee::mac::calc_crc;
+    use futuresdr::runtime::mocker::Mocker;
+    use futuresdr::runtime::mocker::Reader;
+
+    /// MAC header (frame control, sequence number, PAN ID, addresses) and payload
+    fn mpdu() -> Vec<u8> {
+        let mut mpdu = vec![0x41, 0x88, 0x07, 0xaa, 0x1a, 0xff, 0xff, 0x44, 0x33];
+        mpdu.extend_from_slice(b"FutureSDR");
+        mpdu
+    }
+
+    /// Chips of a PPDU (preamble, SFD, length, MPDU, FCS) as antipodal soft values
+    fn chips(mpdu: &[u8], fcs: u16) -> Vec<f32> {
+        let mut bytes = vec![0, 0, 0, 0, 0xa7, (mpdu.len() + 2) as u8];
+        bytes.extend_from_slice(mpdu);
+        bytes.extend_from_slice(&fcs.to_le_bytes());
+
+        let mut chips = Vec::new();
+        for b in bytes {
+            for nibble in [b & 0xf, b >> 4] {
+                let c = CHIP_MAPPING[nibble as usize];
+                chips.extend((0..32).rev().map(|k| if c & (1 << k) != 0 { 1.0 } else { -1.0 }));
+            }
+        }
+        chips
+    }
+
+    fn decode_frames(chips: Vec<f32>) -> (Vec<Pmt>, Vec<Pmt>) {
+        let mut block = Decoder::<Reader<f32>>::new(10);
+        block.input().set(chips);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let [out, frame_ok, _, _] = mocker.messages().try_into().unwrap();
+        (out, frame_ok)
+    }
+
+    #[test]
+    fn frame_ok() {
+        let mpdu = mpdu();
+        let fcs = calc_crc(&mpdu);
+
+        let (out, frame_ok) = decode_frames(chips(&mpdu, fcs));
+        assert_eq!(out, vec![Pmt::Blob(mpdu.clone())]);
+        assert_eq!(frame_ok, vec![Pmt::Bool(true)]);
+
+        let mut flipped = mpdu.clone();
+        flipped[12] ^= 0x10;
+        let (out, frame_ok) = decode_frames(chips(&flipped, fcs));
+        assert_eq!(out, vec![Pmt::Blob(flipped)]);
+        assert_eq!(frame_ok, vec![Pmt::Bool(false)]);
+    }
+
+    #[test]
+    fn sync_events() {
+        let mpdu = mpdu();
+        let offset = 1000;
+        let mut input = vec![-1.0; offset];
+        input.extend(chips(&mpdu, calc_crc(&mpdu)));
+        // flip one SFD chip
+        input[offset + 256 + 40] *= -1.0;
+
+        let mut block = Decoder::<Reader<f32>>::new(10);
+        block.input().set(input);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let [out, _, _, sync_events] = mocker.messages().try_into().unwrap();
+        assert_eq!(out.len(), 1);
+
+        // preamble of 8 symbols
+        let [Pmt::MapStrPmt(event)] = &sync_events[..] else {
+            panic!("expected one sync event, got {sync_events:?}");
+        };
+        assert_eq!(event["index"], Pmt::U64((offset + 8 * 32) as u64));
+        let Pmt::F32(score) = event["score"] else {
+            panic!("score is not an f32");
+        };
+        assert!((score - 59.0 / 60.0).abs() < 1e-6, "score {score}");
+    }
+
+    #[test]
+    fn dedup_consecutive() {
+        let mpdu = mpdu();
+        let frame = chips(&mpdu, calc_crc(&mpdu));
+        // a second detection of the frame, starting with its last two preamble symbols
+        let mut doubled = frame.clone();
+        doubled.extend_from_slice(&frame[6 * 32..]);
+        // a real repeat with its own preamble
+        let mut repeated = frame.clone();
+        repeated.extend_from_slice(&frame);
+
+        let run = |dedup: bool, input: Vec<f32>| {
+            let mut block = Decoder::<Reader<f32>>::new(10).with_dedup_consecutive(dedup);
+            block.input().set(input);
+            let mut mocker = Mocker::new(block);
+            mocker.run();
+            let [out, frame_ok, _, _] = mocker.messages().try_into().unwrap();
+            assert_eq!(out.len(), frame_ok.len());
+            out
+        };
+        assert_eq!(run(false, doubled.clone()).len(), 2);
+        assert_eq!(run(true, doubled), vec![Pmt::Blob(mpdu.clone())]);
+        assert_eq!(run(true, repeated).len(), 2);
+    }
+
+    fn sync_events_with(decoder: Decoder<Reader<f32>>, input: Vec<f32>) -> (Vec<Pmt>, Vec<Pmt>) {
+        let mut block = decoder;
+        block.input().set(input);
+        let mut mocker = Mocker::new(block);
+        mocker.run();
+        let [out, _, _, sync_events] = mocker.messages().try_into().unwrap();
+        (out, sync_events)
+    }
+
+    #[test]
+    fn shorter_window_detects_more() {
+        // four corrupted chips at the start of each preamble and SFD symbol
+        let mpdu = mpdu();
+        let mut frame = chips(&mpdu, calc_crc(&mpdu));
+        for symbol in 0..10 {
+            for chip in 1..5 {
+                frame[32 * symbol + chip] *= -1.0;
+            }
+        }
+        let (out, events) = sync_events_with(Decoder::with_params(4, 30), frame.clone());
+        assert!(out.is_empty());
+        assert!(events.is_empty());
+        let (out, events) = sync_events_with(Decoder::with_params(4, 24), frame);
+        assert_eq!(out, vec![Pmt::Blob(mpdu)]);
+        assert_eq!(events.len(), 1);
+
+        // random chips only produce false detections with a short window
+        let mut state = 0x2545f491u32;
+        let noise: Vec<f32> = (0..100_000)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                if state & 1 == 1 { 1.0 } else { -1.0 }
+            })
+            .collect();
+        let (_, events) = sync_events_with(Decoder::with_params(4, 30), noise.clone());
+        assert!(events.is_empty());
+        let (_, events) = sync_events_with(Decoder::with_params(4, 12), noise);
+        assert!(!events.is_empty());
+    }
+}
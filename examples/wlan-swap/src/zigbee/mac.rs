@@ -8,6 +8,33 @@ const DESTINATION_PAN: u16 = 0x1aaa;
 const DESTINATION_ADDRESS: u16 = 0xffff;
 const SOURCE_ADDRESS: u16 = 0x3344;
 
+/// IEEE 802.15.4 FCS (CRC-16/KERMIT)
+pub(super) fn calc_crc(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+
+    for b in data.iter() {
+        for k in 0..8 {
+            let bit = if b & (1 << k) != 0 {
+                1 ^ (crc & 1)
+            } else {
+                crc & 1
+            };
+            crc >>= 1;
+            if bit != 0 {
+                crc ^= 1 << 15;
+                crc ^= 1 << 10;
+                crc ^= 1 << 3;
+            }
+        }
+    }
+    crc
+}
+
+/// Check a frame that ends with its FCS
+pub(super) fn check_crc(data: &[u8]) -> bool {
+    calc_crc(data) == 0
+}
+
 #[derive(Block)]
 #[message_inputs(rx, tx, stats)]
 #[message_outputs(rxed, rftap)]
@@ -59,31 +86,6 @@ where
         }
     }
 
-    fn calc_crc(data: &[u8]) -> u16 {
-        let mut crc: u16 = 0;
-
-        for b in data.iter() {
-            for k in 0..8 {
-                let bit = if b & (1 << k) != 0 {
-                    1 ^ (crc & 1)
-                } else {
-                    crc & 1
-                };
-                crc >>= 1;
-                if bit != 0 {
-                    crc ^= 1 << 15;
-                    crc ^= 1 << 10;
-                    crc ^= 1 << 3;
-                }
-            }
-        }
-        crc
-    }
-
-    fn check_crc(data: &[u8]) -> bool {
-        Self::calc_crc(data) == 0
-    }
-
     async fn rx(
         &mut self,
         io: &mut WorkIo,
@@ -93,7 +95,7 @@ where
     ) -> Result<Pmt> {
         match p {
             Pmt::Blob(data) => {
-                if Self::check_crc(&data) && data.len() > 2 {
+                if check_crc(&data) && data.len() > 2 {
                     info!("received frame, crc correct, payload length {}", data.len());
                     let mut rftap = vec![0; data.len() + 12];
                     rftap[0..4].copy_from_slice("RFta".as_bytes());
@@ -230,7 +232,7 @@ where
                         );
                     }
 
-                    let crc = Self::calc_crc(&self.current_frame[5..14 + v.len()]);
+                    let crc = calc_crc(&self.current_frame[5..14 + v.len()]);
                     self.current_frame[14 + v.len()] = crc.to_le_bytes()[0];
                     self.current_frame[15 + v.len()] = crc.to_le_bytes()[1];
 
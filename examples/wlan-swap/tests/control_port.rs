@@ -0,0 +1,32 @@
+use futuresdr::runtime::Runtime;
+use std::net::Ipv4Addr;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::time::Duration;
+use std::time::Instant;
+
+use wlan::loader::set_control_bind;
+
+#[test]
+fn runtime_binds_requested_port() {
+    // pick a free port
+    let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let bind = set_control_bind(Ipv4Addr::LOCALHOST.into(), port);
+    assert_eq!(bind.port(), port);
+
+    let _rt = Runtime::new();
+
+    // the control server starts in the background
+    let start = Instant::now();
+    while TcpStream::connect(bind).is_err() {
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "control server not listening on {bind}"
+        );
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
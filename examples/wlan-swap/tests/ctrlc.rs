@@ -0,0 +1,29 @@
+#![cfg(unix)]
+
+use futuresdr::blocks::NullSink;
+use futuresdr::blocks::NullSource;
+use futuresdr::prelude::*;
+use std::time::Duration;
+
+use wlan::loader::run_with_ctrlc;
+
+#[test]
+fn sigint_terminates_flowgraph() -> Result<()> {
+    let mut fg = Flowgraph::new();
+    let src = NullSource::<u8>::new();
+    let snk = NullSink::<u8>::new();
+    connect!(fg, src > snk);
+
+    std::thread::spawn(|| {
+        std::thread::sleep(Duration::from_millis(300));
+        unsafe {
+            libc::raise(libc::SIGINT);
+        }
+    });
+
+    // returning the flowgraph means it was terminated, not killed with the process
+    let rt = Runtime::new();
+    assert!(run_with_ctrlc(&rt, fg).is_ok());
+    assert!(snk.get().unwrap().n_received() > 0);
+    Ok(())
+}
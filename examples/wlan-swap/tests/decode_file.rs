@@ -0,0 +1,26 @@
+use std::process::Command;
+
+#[test]
+fn summary_of_sample_capture() {
+    let output = Command::new(env!("CARGO_BIN_EXE_decode_file"))
+        .arg("captures/zigbee_rx_file.toml")
+        .env("FUTURESDR_LOG_LEVEL", "warn")
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let summary: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    for key in ["frames", "fcs_ok", "fcs_fail", "invalid"] {
+        assert!(summary[key].is_u64(), "{key} missing in {summary}");
+    }
+    assert!(summary["mcs"].is_object());
+
+    // four frames, the third one with an invalid FCS
+    assert_eq!(summary["frames"], 4);
+    assert_eq!(summary["fcs_ok"], 3);
+    assert_eq!(summary["fcs_fail"], 1);
+}
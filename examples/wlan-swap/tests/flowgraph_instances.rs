@@ -0,0 +1,110 @@
+use anyhow::Result;
+use futuresdr::async_io::block_on;
+use futuresdr::runtime::Runtime;
+use std::sync::mpsc;
+
+use wlan::loader::ControlCommand;
+use wlan::loader::FlowgraphInstances;
+use wlan::loader::receive_commands;
+
+const NULLSTREAM: &str = "flowgraphs/nullstream.toml";
+
+#[test]
+fn two_labelled_instances() -> Result<()> {
+    let rt = Runtime::new();
+    let mut instances = FlowgraphInstances::new(&rt);
+
+    instances.load("wifi", NULLSTREAM)?;
+    instances.load("zigbee", NULLSTREAM)?;
+    assert_eq!(instances.labels(), vec!["wifi", "zigbee"]);
+
+    let mut wifi = instances.handle("wifi").unwrap();
+    let mut zigbee = instances.handle("zigbee").unwrap();
+
+    // swapping one instance terminates it and leaves the other one running
+    instances.apply(&ControlCommand::parse(&format!("load:wifi:{NULLSTREAM}")).unwrap())?;
+    assert_eq!(instances.len(), 2);
+    assert!(block_on(wifi.description()).is_err());
+    let _ = block_on(zigbee.description())?;
+
+    instances.apply(&ControlCommand::parse("stop:wifi").unwrap())?;
+    assert_eq!(instances.labels(), vec!["zigbee"]);
+    assert!(instances.apply(&ControlCommand::parse("stop:wifi").unwrap()).is_err());
+    let _ = block_on(zigbee.description())?;
+
+    instances.stop_all();
+    assert!(instances.is_empty());
+    Ok(())
+}
+
+#[test]
+fn rapid_switches_load_last_target() -> Result<()> {
+    let rt = Runtime::new();
+    let mut instances = FlowgraphInstances::new(&rt);
+
+    // the first two targets do not exist, so applying either of them would fail
+    let (tx, rx) = mpsc::channel();
+    tx.send("load:wifi:flowgraphs/missing_1.toml".to_string())?;
+    tx.send("load:wifi:flowgraphs/missing_2.toml".to_string())?;
+    tx.send(format!("load:wifi:{NULLSTREAM}"))?;
+
+    let cmds = receive_commands(&rx).unwrap();
+    assert_eq!(cmds.len(), 1);
+    for cmd in &cmds {
+        instances.apply(cmd)?;
+    }
+    assert_eq!(instances.labels(), vec!["wifi"]);
+    assert_eq!(instances.path("wifi"), Some(NULLSTREAM));
+
+    drop(tx);
+    assert!(receive_commands(&rx).is_none());
+    instances.stop_all();
+    Ok(())
+}
+
+#[test]
+fn finished_instance_is_reported() -> Result<()> {
+    let path = std::env::temp_dir().join(format!("wlan_finished_{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        r#"
+[[blocks]]
+name = "src"
+type = "FileSource"
+dtype = "Complex32"
+[[blocks.parameters]]
+name = "path"
+type = "string"
+value = "captures/zigbee_sample.cf32"
+
+[[blocks]]
+name = "snk"
+type = "NullSink"
+dtype = "Complex32"
+
+[[connections]]
+from = "src"
+to = "snk"
+"#,
+    )?;
+    let path = path.to_str().unwrap();
+
+    let rt = Runtime::new();
+    let mut instances = FlowgraphInstances::new(&rt);
+    let (tx, rx) = mpsc::channel();
+    instances.notify_finished(tx);
+    instances.load("file", path)?;
+
+    let msg = rx.recv_timeout(std::time::Duration::from_secs(10))?;
+    assert_eq!(msg, "finished:file");
+    instances.apply(&ControlCommand::parse(&msg).unwrap())?;
+    assert!(instances.is_empty());
+
+    // a stopped instance is not reported
+    instances.load("file", path)?;
+    instances.stop("file");
+    assert!(rx.recv_timeout(std::time::Duration::from_millis(500)).is_err());
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
@@ -0,0 +1,123 @@
+use anyhow::Result;
+use futuresdr::async_io::block_on;
+use futuresdr::blocks::Apply;
+use futuresdr::blocks::Head;
+use futuresdr::blocks::MessagePipe;
+use futuresdr::futures::StreamExt;
+use futuresdr::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand_distr::Distribution;
+use rand_distr::Normal;
+
+use wlan::zigbee::ClockRecoveryMm;
+use wlan::zigbee::Decoder;
+use wlan::zigbee::IqDelay;
+use wlan::zigbee::Mac;
+use wlan::zigbee::modulator;
+
+const N_FRAMES: usize = 10;
+/// Samples per byte: two nibbles of 16 chips with 4 samples each
+const SAMPLES_PER_BYTE: usize = 128;
+/// Preamble, length, header, and CRC
+const FRAME_OVERHEAD: usize = 16;
+/// Frame control, sequence number, PAN ID, and addresses
+const MAC_HEADER: usize = 9;
+/// Zeros that IqDelay inserts before and after each burst
+const BURST_PADDING: usize = 40000;
+
+fn payload(i: usize) -> Vec<u8> {
+    format!("zigbee frame {i:02}").into_bytes()
+}
+
+#[test]
+fn loopback_recovers_payload() -> Result<()> {
+    let mut fg = Flowgraph::new();
+
+    // ========================================
+    // TRANSMITTER
+    // ========================================
+    let mac: Mac = Mac::new();
+    let mac = fg.add_block(mac);
+    let modulator = modulator(&mut fg);
+    let iq_delay: IqDelay = IqDelay::new();
+    let iq_delay = fg.add_block(iq_delay);
+
+    fg.connect_dyn(&mac, "output", modulator, "input")?;
+    fg.connect_dyn(modulator, "output", &iq_delay, "input")?;
+
+    // ========================================
+    // CHANNEL
+    // ========================================
+    // stop in the middle of the last frame, so the flowgraph terminates
+    let frame_len = 2 * BURST_PADDING + (payload(0).len() + FRAME_OVERHEAD) * SAMPLES_PER_BYTE;
+    let head = fg.add_block(Head::<Complex32>::new(
+        ((N_FRAMES - 1) * frame_len + frame_len / 2) as u64,
+    ));
+    fg.connect_dyn(&iq_delay, "output", &head, "input")?;
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let normal = Normal::new(0.0f32, 0.05).unwrap();
+    let noise = fg.add_block(Apply::<_, _, _>::new(move |i: &Complex32| -> Complex32 {
+        i + Complex32::new(normal.sample(&mut rng), normal.sample(&mut rng))
+    }));
+    fg.connect_dyn(&head, "output", &noise, "input")?;
+
+    // ========================================
+    // RECEIVER
+    // ========================================
+    let mut last: Complex32 = Complex32::new(0.0, 0.0);
+    let mut iir: f32 = 0.0;
+    let alpha = 0.00016;
+    let avg = fg.add_block(Apply::<_, _, _>::new(move |i: &Complex32| -> f32 {
+        let phase = (last.conj() * i).arg();
+        last = *i;
+        iir = (1.0 - alpha) * iir + alpha * phase;
+        phase - iir
+    }));
+
+    let mm: ClockRecoveryMm = ClockRecoveryMm::new(2.0, 0.000225, 0.5, 0.03, 0.0002);
+    let mm = fg.add_block(mm);
+    let decoder: Decoder = Decoder::new(12);
+    let decoder = fg.add_block(decoder);
+
+    fg.connect_dyn(&noise, "output", &avg, "input")?;
+    fg.connect_dyn(&avg, "output", &mm, "input")?;
+    fg.connect_dyn(&mm, "output", &decoder, "input")?;
+
+    let (tx_frame, mut rx_frame) = mpsc::channel::<Pmt>(N_FRAMES + 1);
+    let pipe = fg.add_block(MessagePipe::new(tx_frame));
+    fg.connect_message(&decoder, "out", &pipe, "in")?;
+
+    let mac: BlockId = mac.into();
+    let rt = Runtime::new();
+    let (task, mut handle) = rt.start_sync(fg)?;
+    for i in 0..N_FRAMES {
+        block_on(handle.call(mac, "tx", Pmt::Blob(payload(i))))?;
+    }
+
+    // the decoder finishes once the head is reached, MessagePipe forwards that but keeps running
+    let mut received = Vec::new();
+    while let Some(p) = block_on(rx_frame.next()) {
+        match p {
+            Pmt::Blob(data) => received.push(data),
+            Pmt::Finished => break,
+            p => panic!("unexpected message {p:?}"),
+        }
+    }
+    block_on(handle.terminate())?;
+    block_on(task)?;
+
+    // the decoder strips the CRC (reporting it on `frame_ok`, which is not checked here), so
+    // noise in the burst padding can produce spurious short frames
+    let frames: Vec<_> = received
+        .iter()
+        .filter(|d| d.len() == MAC_HEADER + payload(0).len())
+        .collect();
+    assert_eq!(frames.len(), N_FRAMES - 1, "decoded {} frames", frames.len());
+    for (i, data) in frames.iter().enumerate() {
+        assert_eq!(data[2] as usize, i, "sequence number");
+        assert_eq!(&data[MAC_HEADER..], &payload(i)[..]);
+    }
+    Ok(())
+}
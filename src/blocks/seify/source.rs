@@ -3,6 +3,7 @@ use seify::Device;
 use seify::DeviceTrait;
 use seify::Direction::Rx;
 use seify::RxStreamer;
+use std::collections::HashMap;
 use std::time::Duration;
 
 use crate::blocks::seify::Config;
@@ -23,10 +24,13 @@ use crate::prelude::*;
 ///     - `"cmd"`: `Pmt` encoded `Config` to apply to all channels at once
 ///     - `"terminate"`: `Pmt::Ok` to terminate the block
 ///     - `"config"`: `u32`, `u64`, `usize` (channel id) returns the `Config` for the specified channel as a `Pmt::MapStrPmt`
-/// * Message outputs: None
+///     - `"overflows"`: returns the number of overflows reported by the driver as `u64`
+/// * Message outputs:
+///     - `"stats"`: `Pmt::MapStrPmt` with the `"overflows"` count (`u64`), posted after each overflow
 #[derive(Block)]
 #[blocking]
 #[message_inputs(freq, gain, sample_rate, cmd, terminate, config, overflows)]
+#[message_outputs(stats)]
 #[type_name(SeifySource)]
 pub struct Source<D, OUT = DefaultCpuWriter<Complex32>>
 where
@@ -186,6 +190,24 @@ where
     }
 }
 
+/// Read from the streamer, counting overflows
+///
+/// An overflow is not fatal: it is counted and reported as a read of zero samples.
+fn read<S: RxStreamer>(
+    streamer: &mut S,
+    bufs: &mut [&mut [Complex32]],
+    overflows: &mut u64,
+) -> std::result::Result<usize, seify::Error> {
+    match streamer.read(bufs, 500_000) {
+        Err(seify::Error::Overflow) => {
+            *overflows += 1;
+            warn!("Seify Source Overflow");
+            Ok(0)
+        }
+        r => r,
+    }
+}
+
 #[doc(hidden)]
 impl<D, OUT> Kernel for Source<D, OUT>
 where
@@ -195,7 +217,7 @@ where
     async fn work(
         &mut self,
         io: &mut WorkIo,
-        _mio: &mut MessageOutputs,
+        mio: &mut MessageOutputs,
         _meta: &mut BlockMeta,
     ) -> Result<()> {
         let mut bufs: Vec<&mut [Complex32]> = self.outputs.iter_mut().map(|b| b.slice()).collect();
@@ -207,13 +229,14 @@ where
             return Ok(());
         }
 
-        match streamer.read(&mut bufs, 500_000) {
+        let overflows = self.overflows;
+        match read(streamer, &mut bufs, &mut self.overflows) {
             Ok(len) => {
                 self.outputs.iter_mut().for_each(|o| o.produce(len));
-            }
-            Err(seify::Error::Overflow) => {
-                self.overflows += 1;
-                warn!("Seify Source Overflow");
+                if self.overflows != overflows {
+                    let stats = HashMap::from([("overflows".to_string(), Pmt::U64(self.overflows))]);
+                    mio.post("stats", Pmt::MapStrPmt(stats)).await?;
+                }
             }
             Err(e) => {
                 error!("Seify Source Error: {:?}", e);
@@ -240,3 +263,55 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Streamer that fails every other read with an overflow
+    struct FlakyStreamer {
+        reads: usize,
+    }
+
+    impl RxStreamer for FlakyStreamer {
+        fn mtu(&self) -> std::result::Result<usize, seify::Error> {
+            Ok(1500)
+        }
+
+        fn activate_at(&mut self, _time_ns: Option<i64>) -> std::result::Result<(), seify::Error> {
+            Ok(())
+        }
+
+        fn deactivate_at(
+            &mut self,
+            _time_ns: Option<i64>,
+        ) -> std::result::Result<(), seify::Error> {
+            Ok(())
+        }
+
+        fn read(
+            &mut self,
+            buffers: &mut [&mut [Complex32]],
+            _timeout_us: i64,
+        ) -> std::result::Result<usize, seify::Error> {
+            self.reads += 1;
+            if self.reads % 2 == 0 {
+                Err(seify::Error::Overflow)
+            } else {
+                Ok(buffers[0].len())
+            }
+        }
+    }
+
+    #[test]
+    fn counts_overflows() {
+        let mut streamer = FlakyStreamer { reads: 0 };
+        let mut buf = [Complex32::new(0.0, 0.0); 16];
+        let mut overflows = 0;
+        let lens: Vec<usize> = (0..5)
+            .map(|_| read(&mut streamer, &mut [&mut buf[..]], &mut overflows).unwrap())
+            .collect();
+        assert_eq!(lens, vec![16, 0, 16, 0, 16]);
+        assert_eq!(overflows, 2);
+    }
+}
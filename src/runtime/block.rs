@@ -5,6 +5,11 @@ use std::any::Any;
 use std::fmt;
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use web_time::Instant;
 
 use futuresdr::channel::mpsc;
 use futuresdr::channel::mpsc::Sender;
@@ -39,6 +44,14 @@ pub trait Block: Send + Any {
     fn id(&self) -> BlockId;
 
     // ##### Stream Ports
+    /// Names of the stream inputs
+    fn stream_inputs(&self) -> Vec<String> {
+        Vec::new()
+    }
+    /// Names of the stream outputs
+    fn stream_outputs(&self) -> Vec<String> {
+        Vec::new()
+    }
     /// Get dyn reference to stream input
     fn stream_input(&mut self, name: &str) -> Option<&mut dyn BufferReader>;
     /// Connect dyn BufferReader by downcasting it
@@ -51,6 +64,10 @@ pub trait Block: Send + Any {
     // ##### Message Ports
     /// Message inputs of the block
     fn message_inputs(&self) -> &'static [&'static str];
+    /// Message outputs of the block
+    fn message_outputs(&self) -> &'static [&'static str] {
+        &[]
+    }
     /// Connect message output port
     fn connect(
         &mut self,
@@ -70,6 +87,45 @@ pub trait Block: Send + Any {
     ///
     /// Blocking blocks will be spawned in a separate thread.
     fn is_blocking(&self) -> bool;
+    /// Measure the wall time spent in `work()`, returning the shared counters
+    ///
+    /// `None` if the block does not support profiling.
+    fn profile(&mut self) -> Option<Arc<WorkStats>> {
+        None
+    }
+}
+
+/// Number of `work()` calls of a block and the wall time spent in them (see [`Block::profile`])
+///
+/// The wall time includes the time a `work()` call is suspended in an `await` or preempted, so
+/// it is an upper bound of the CPU time.
+#[derive(Debug, Default)]
+pub struct WorkStats {
+    calls: AtomicU64,
+    wall_nanos: AtomicU64,
+}
+
+impl WorkStats {
+    /// Number of `work()` calls
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::Relaxed)
+    }
+    /// Total wall time spent in `work()`
+    pub fn wall_time(&self) -> Duration {
+        Duration::from_nanos(self.wall_nanos.load(Ordering::Relaxed))
+    }
+    /// Average wall time per `work()` call, zero if there was none
+    pub fn average_wall_time(&self) -> Duration {
+        match self.calls() {
+            0 => Duration::ZERO,
+            n => Duration::from_nanos(self.wall_nanos.load(Ordering::Relaxed) / n),
+        }
+    }
+    fn record(&self, elapsed: Duration) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.wall_nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
 }
 
 impl fmt::Debug for dyn Block {
@@ -93,6 +149,8 @@ pub struct WrappedKernel<K: Kernel> {
     pub inbox: mpsc::Receiver<BlockMessage>,
     /// Sending-side of Inbox
     pub inbox_tx: mpsc::Sender<BlockMessage>,
+    /// Wall time spent in `work()`, if profiled
+    work_stats: Option<Arc<WorkStats>>,
 }
 
 impl<K: KernelInterface + Kernel + Send + 'static> WrappedKernel<K> {
@@ -110,6 +168,7 @@ impl<K: KernelInterface + Kernel + Send + 'static> WrappedKernel<K> {
             id,
             inbox: rx,
             inbox_tx: tx,
+            work_stats: None,
         }
     }
 
@@ -120,6 +179,7 @@ impl<K: KernelInterface + Kernel + Send + 'static> WrappedKernel<K> {
             mio,
             kernel,
             inbox,
+            work_stats,
             ..
         } = self;
 
@@ -294,7 +354,12 @@ impl<K: KernelInterface + Kernel + Send + 'static> WrappedKernel<K> {
 
             // ================== work
             work_io.call_again = false;
-            if let Err(e) = kernel.work(&mut work_io, mio, meta).await {
+            let start = work_stats.as_ref().map(|_| Instant::now());
+            let res = kernel.work(&mut work_io, mio, meta).await;
+            if let (Some(stats), Some(start)) = (work_stats.as_ref(), start) {
+                stats.record(start.elapsed());
+            }
+            if let Err(e) = res {
                 error!("{}: Error in work(). Terminating. ({:?})", instance_name, e);
                 return Err(Error::RuntimeError(e.to_string()));
             }
@@ -320,6 +385,12 @@ impl<K: KernelInterface + Kernel + Send + 'static> Block for WrappedKernel<K> {
     }
 
     // ##### Stream Ports
+    fn stream_inputs(&self) -> Vec<String> {
+        self.kernel.stream_inputs()
+    }
+    fn stream_outputs(&self) -> Vec<String> {
+        self.kernel.stream_outputs()
+    }
     fn stream_input(&mut self, name: &str) -> Option<&mut dyn BufferReader> {
         self.kernel.stream_input(name)
     }
@@ -335,6 +406,9 @@ impl<K: KernelInterface + Kernel + Send + 'static> Block for WrappedKernel<K> {
     fn message_inputs(&self) -> &'static [&'static str] {
         K::message_inputs()
     }
+    fn message_outputs(&self) -> &'static [&'static str] {
+        K::message_outputs()
+    }
     fn connect(
         &mut self,
         src_port: &PortId,
@@ -357,6 +431,9 @@ impl<K: KernelInterface + Kernel + Send + 'static> Block for WrappedKernel<K> {
     fn is_blocking(&self) -> bool {
         K::is_blocking()
     }
+    fn profile(&mut self) -> Option<Arc<WorkStats>> {
+        Some(self.work_stats.get_or_insert_with(Default::default).clone())
+    }
 
     // ##### KERNEL
     async fn run(&mut self, mut main_inbox: Sender<FlowgraphMessage>) {
@@ -35,6 +35,11 @@ impl MessageOutput {
         self.handlers.push((port, sender));
     }
 
+    /// Whether the port is connected to any downstream message input
+    pub fn is_connected(&self) -> bool {
+        !self.handlers.is_empty()
+    }
+
     /// Notify connected downstream message ports that we are finished
     pub async fn notify_finished(&mut self) {
         for (port_id, sender) in self.handlers.iter_mut() {
@@ -58,6 +63,26 @@ impl MessageOutput {
                 .await;
         }
     }
+
+    /// Post data to the connected downstream ports that have room for it, without waiting
+    ///
+    /// `delivered` is the number of ports, in the order of connection, that already received
+    /// the message. It is advanced past the ports that accept the message now, so a later call
+    /// continues with the first port that was busy. Returns `true` once all ports received the
+    /// message. As with [`post`](Self::post), disconnected ports count as received.
+    pub fn try_post(&mut self, p: &Pmt, delivered: &mut usize) -> bool {
+        for (port_id, sender) in self.handlers.iter_mut().skip(*delivered) {
+            let m = BlockMessage::Call {
+                port_id: port_id.clone(),
+                data: p.clone(),
+            };
+            match sender.try_send(m) {
+                Err(e) if e.is_full() => return false,
+                _ => *delivered += 1,
+            }
+        }
+        true
+    }
 }
 
 /// Message Outputs
@@ -82,6 +107,32 @@ impl MessageOutputs {
             .await;
         Ok(())
     }
+    /// Post data to the connected downstream ports that have room for it, without waiting
+    ///
+    /// See [`MessageOutput::try_post`] for `delivered` and the return value.
+    pub fn try_post(
+        &mut self,
+        id: impl Into<PortId>,
+        p: &Pmt,
+        delivered: &mut usize,
+    ) -> Result<bool, Error> {
+        let id = id.into();
+        Ok(self
+            .output_mut(&id)
+            .ok_or(Error::InvalidMessagePort(BlockPortCtx::None, id))?
+            .try_post(p, delivered))
+    }
+    /// Whether the port is connected to any downstream message input
+    ///
+    /// Allows to skip preparing messages that nobody receives.
+    pub fn is_connected(&self, id: impl Into<PortId>) -> Result<bool, Error> {
+        let id = id.into();
+        self.outputs
+            .iter()
+            .find(|item| item.name() == id.name())
+            .map(MessageOutput::is_connected)
+            .ok_or(Error::InvalidMessagePort(BlockPortCtx::None, id))
+    }
     /// Connect Message Output Port
     pub fn connect(
         &mut self,
@@ -42,6 +42,7 @@ mod tag;
 mod work_io;
 
 pub use block::Block;
+pub use block::WorkStats;
 pub use block::WrappedKernel;
 pub use block_meta::BlockMeta;
 pub use flowgraph::BlockRef;